@@ -637,33 +637,49 @@ export const platformHelpers_exports = {
     reader.readAsArrayBuffer(b);
   },
 
-  idbOpen(name: string, version: bigint | null, callbackId: bigint) {
-    const req = indexedDB.open(
-      name,
-      version !== undefined && version !== null ? Number(version) : undefined,
-    );
-    req.onsuccess = () => {
-      if (
-        globalThis.__wasmExports &&
-        globalThis.__wasmExports["tairitsu-browser:full/idb-callbacks@0.2.0"]
-      ) {
-        globalThis.__wasmExports["tairitsu-browser:full/idb-callbacks@0.2.0"]?.on_idb_open?.(
-          callbackId,
-          globalThis.__storeElement?.(req.result) ?? 0n,
-        );
-      }
-    };
-    req.onerror = () => {
-      if (
-        globalThis.__wasmExports &&
-        globalThis.__wasmExports["tairitsu-browser:full/idb-callbacks@0.2.0"]
-      ) {
-        globalThis.__wasmExports["tairitsu-browser:full/idb-callbacks@0.2.0"]?.on_idb_error?.(
-          callbackId,
-          req.error?.message || "Open failed",
-        );
-      }
+  idbOpen(name: string, version: bigint | null, storeName: string | null, callbackId: bigint) {
+    const hasStore = (database: IDBDatabase) =>
+      storeName === undefined ||
+      storeName === null ||
+      database.objectStoreNames.contains(storeName);
+    const open = (requested: number | undefined) => {
+      const req = indexedDB.open(name, requested);
+      req.onupgradeneeded = () => {
+        if (!hasStore(req.result)) {
+          req.result.createObjectStore(storeName as string);
+        }
+      };
+      req.onsuccess = () => {
+        const database = req.result;
+        if (!hasStore(database)) {
+          // Object stores can only be created while upgrading to a newer version
+          database.close();
+          open(database.version + 1);
+          return;
+        }
+        if (
+          globalThis.__wasmExports &&
+          globalThis.__wasmExports["tairitsu-browser:full/idb-callbacks@0.2.0"]
+        ) {
+          globalThis.__wasmExports["tairitsu-browser:full/idb-callbacks@0.2.0"]?.on_idb_open?.(
+            callbackId,
+            globalThis.__storeElement?.(database) ?? 0n,
+          );
+        }
+      };
+      req.onerror = () => {
+        if (
+          globalThis.__wasmExports &&
+          globalThis.__wasmExports["tairitsu-browser:full/idb-callbacks@0.2.0"]
+        ) {
+          globalThis.__wasmExports["tairitsu-browser:full/idb-callbacks@0.2.0"]?.on_idb_error?.(
+            callbackId,
+            req.error?.message || "Open failed",
+          );
+        }
+      };
     };
+    open(version !== undefined && version !== null ? Number(version) : undefined);
     return 0n;
   },
 
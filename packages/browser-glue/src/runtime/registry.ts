@@ -19,6 +19,12 @@ import { history_exports } from "./history";
 import { observers_exports } from "./observers";
 import { mutationObserver_exports } from "./mutationObserver";
 import { event_exports } from "./event";
+import {
+  storage_exports,
+  storageEvent_exports,
+  windowLocalStorage_exports,
+  windowSessionStorage_exports,
+} from "./storage";
 
 export const INTERFACES = {
   "@tairitsu-glue/document": document_exports,
@@ -42,6 +48,10 @@ export const INTERFACES = {
   "@tairitsu-glue/observers": observers_exports,
   "@tairitsu-glue/mutation-observer": mutationObserver_exports,
   "@tairitsu-glue/event": event_exports,
+  "@tairitsu-glue/storage": storage_exports,
+  "@tairitsu-glue/window-local-storage": windowLocalStorage_exports,
+  "@tairitsu-glue/window-session-storage": windowSessionStorage_exports,
+  "@tairitsu-glue/storage-event": storageEvent_exports,
 };
 
 export function generateModuleCode(exports: Record<string, (...args: any[]) => any>) {
@@ -0,0 +1,81 @@
+// Storage interfaces - provide localStorage / sessionStorage and `storage`
+// events for WIT browser world
+
+// The two Web Storage areas are fixed, so they get fixed handles instead of
+// a handle table. Handle 0 (the global `window`) is the only receiver of
+// `get-local-storage` / `get-session-storage`.
+const LOCAL_STORAGE = 1n;
+const SESSION_STORAGE = 2n;
+
+function lookupStorage(handle: bigint): Storage {
+  if (handle === LOCAL_STORAGE) return window.localStorage;
+  if (handle === SESSION_STORAGE) return window.sessionStorage;
+  throw new Error("storage handle " + handle + " not found");
+}
+
+function storageHandle(area: Storage | null): bigint | undefined {
+  if (area === window.localStorage) return LOCAL_STORAGE;
+  if (area === window.sessionStorage) return SESSION_STORAGE;
+  return undefined;
+}
+
+function lookupStorageEvent(eventHandle: bigint): StorageEvent | undefined {
+  const ev = globalThis.__eventHandles?.get(eventHandle);
+  return ev instanceof StorageEvent ? ev : undefined;
+}
+
+export const storage_exports = {
+  getLength(self: bigint): number {
+    return lookupStorage(self).length;
+  },
+  key(self: bigint, index: number): string | undefined {
+    return lookupStorage(self).key(index) ?? undefined;
+  },
+  getItem(self: bigint, key: string): string | undefined {
+    return lookupStorage(self).getItem(key) ?? undefined;
+  },
+  setItem(self: bigint, key: string, value: string) {
+    try {
+      lookupStorage(self).setItem(key, value);
+    } catch (e) {
+      // Quota exceeded or storage disabled (e.g. private browsing).
+      console.warn("[tairitsu] storage.setItem failed:", e);
+    }
+  },
+  removeItem(self: bigint, key: string) {
+    lookupStorage(self).removeItem(key);
+  },
+  clear(self: bigint) {
+    lookupStorage(self).clear();
+  },
+};
+
+export const windowLocalStorage_exports = {
+  getLocalStorage(_self: bigint): bigint {
+    return LOCAL_STORAGE;
+  },
+};
+
+export const windowSessionStorage_exports = {
+  getSessionStorage(_self: bigint): bigint {
+    return SESSION_STORAGE;
+  },
+};
+
+export const storageEvent_exports = {
+  getKey(eventHandle: bigint): string | undefined {
+    return lookupStorageEvent(eventHandle)?.key ?? undefined;
+  },
+  getOldValue(eventHandle: bigint): string | undefined {
+    return lookupStorageEvent(eventHandle)?.oldValue ?? undefined;
+  },
+  getNewValue(eventHandle: bigint): string | undefined {
+    return lookupStorageEvent(eventHandle)?.newValue ?? undefined;
+  },
+  getUrl(eventHandle: bigint): string {
+    return lookupStorageEvent(eventHandle)?.url ?? "";
+  },
+  getStorageArea(eventHandle: bigint): bigint | undefined {
+    return storageHandle(lookupStorageEvent(eventHandle)?.storageArea ?? null);
+  },
+};
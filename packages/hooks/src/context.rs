@@ -1,3 +1,16 @@
+//! Context values scoped to the component tree.
+//!
+//! [`provide_context`] called while a component renders makes the value
+//! available to that component and its descendants; [`use_context`] finds
+//! the nearest provider above the active component, so sibling subtrees can
+//! provide different values of the same type. Each `#[component]` function
+//! renders in its own [`runtime::with_scope`] scope, so this also holds for
+//! components nested in one render. Providers are removed when their
+//! component is cleaned up.
+//!
+//! Outside of any component, contexts are provided at the root and are
+//! visible everywhere.
+
 use std::{
     any::{Any, TypeId},
     cell::RefCell,
@@ -5,6 +18,8 @@ use std::{
     rc::Rc,
 };
 
+use tairitsu_vdom::runtime::{self, ComponentId};
+
 thread_local! {
     static CONTEXT: RefCell<HashMap<TypeId, Rc<dyn Any>>> = RefCell::new(HashMap::new());
 }
@@ -45,13 +60,34 @@ impl<T: 'static> Clone for Context<T> {
     }
 }
 
+/// Provide a context value to the active component and its descendants.
+///
+/// Re-providing a type from the same component updates the existing
+/// context in place, so consumers holding it see the new value. Outside of
+/// a component the value is provided at the root.
 pub fn provide_context<T: 'static>(value: T) -> Context<T> {
-    let context = Context::new(value);
-    CONTEXT.with(|ctx| {
-        ctx.borrow_mut()
-            .insert(TypeId::of::<T>(), Rc::new(context.clone()));
-    });
-    context
+    let type_id = TypeId::of::<T>();
+    let Some(component) = runtime::current_scope() else {
+        let context = Context::new(value);
+        CONTEXT.with(|ctx| {
+            ctx.borrow_mut().insert(type_id, Rc::new(context.clone()));
+        });
+        return context;
+    };
+
+    let existing = runtime::context_slot(component, type_id)
+        .and_then(|value| value.downcast_ref::<Context<T>>().cloned());
+    match existing {
+        Some(context) => {
+            context.set(value);
+            context
+        }
+        None => {
+            let context = Context::new(value);
+            runtime::provide_context_slot(component, type_id, Rc::new(context.clone()));
+            context
+        }
+    }
 }
 
 /// Provide a context value that is automatically removed when the returned
@@ -69,23 +105,28 @@ pub fn provide_context_scoped<T: 'static>(value: T) -> ContextGuard {
     ContextGuard::new::<T>()
 }
 
-/// Remove a specific context type from the registry.
+/// Remove a context type provided by the active component, or from the
+/// root outside of a component.
 /// Returns `true` if the context was present and removed.
 pub fn drop_context<T: 'static>() -> bool {
-    CONTEXT.with(|ctx| ctx.borrow_mut().remove(&TypeId::of::<T>()).is_some())
+    remove_context(runtime::current_scope(), TypeId::of::<T>())
 }
 
-/// Clear all contexts from the registry. Useful in tests and during cleanup.
+/// Clear all contexts, at the root and in every component. Useful in tests
+/// and during cleanup.
 pub fn clear_all_contexts() {
     CONTEXT.with(|ctx| ctx.borrow_mut().clear());
+    runtime::reset_context_slots();
 }
 
+/// Get the context of type `T` from the nearest provider: the active
+/// component, then its ancestors, then the root.
 pub fn use_context<T: 'static + Clone>() -> Option<Context<T>> {
-    CONTEXT.with(|ctx| {
-        ctx.borrow()
-            .get(&TypeId::of::<T>())
-            .and_then(|value| value.downcast_ref::<Context<T>>().cloned())
-    })
+    let type_id = TypeId::of::<T>();
+    runtime::current_scope()
+        .and_then(|component| runtime::find_context_slot(component, type_id))
+        .or_else(|| CONTEXT.with(|ctx| ctx.borrow().get(&type_id).cloned()))
+        .and_then(|value| value.downcast_ref::<Context<T>>().cloned())
 }
 
 pub fn consume_context<T: 'static + Clone>() -> Option<T> {
@@ -103,16 +144,25 @@ pub fn consume_context_expect<T: 'static + Clone>() -> T {
     })
 }
 
-/// A guard that removes a context type from the registry when dropped.
-/// Created by [`provide_context_scoped`].
+fn remove_context(component: Option<ComponentId>, type_id: TypeId) -> bool {
+    match component {
+        Some(component) => runtime::remove_context_slot(component, type_id),
+        None => CONTEXT.with(|ctx| ctx.borrow_mut().remove(&type_id).is_some()),
+    }
+}
+
+/// A guard that removes a context type from where it was provided when
+/// dropped. Created by [`provide_context_scoped`].
 pub struct ContextGuard {
     type_id: Option<std::any::TypeId>,
+    component: Option<ComponentId>,
 }
 
 impl ContextGuard {
     fn new<T: 'static>() -> Self {
         Self {
             type_id: Some(TypeId::of::<T>()),
+            component: runtime::current_scope(),
         }
     }
 }
@@ -120,9 +170,7 @@ impl ContextGuard {
 impl Drop for ContextGuard {
     fn drop(&mut self) {
         if let Some(type_id) = self.type_id.take() {
-            CONTEXT.with(|ctx| {
-                ctx.borrow_mut().remove(&type_id);
-            });
+            remove_context(self.component, type_id);
         }
     }
 }
@@ -207,4 +255,59 @@ mod tests {
         assert!(use_context::<i32>().is_none());
         assert!(use_context::<String>().is_none());
     }
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Theme(&'static str);
+
+    fn component(parent: Option<ComponentId>) -> ComponentId {
+        let register = || runtime::use_component(tairitsu_vdom::VNode::empty);
+        match parent {
+            Some(parent) => runtime::with_component(parent, register),
+            None => register(),
+        }
+    }
+
+    fn theme_in(component: ComponentId) -> Option<Theme> {
+        runtime::with_component(component, consume_context::<Theme>)
+    }
+
+    #[test]
+    fn test_sibling_subtrees_provide_separately() {
+        clear_all_contexts();
+        let app = component(None);
+        let light = component(Some(app));
+        let dark = component(Some(app));
+        let nested = component(Some(dark));
+
+        runtime::with_component(app, || provide_context(Theme("default")));
+        runtime::with_component(light, || provide_context(Theme("light")));
+        runtime::with_component(dark, || provide_context(Theme("dark")));
+
+        assert_eq!(theme_in(light), Some(Theme("light")));
+        assert_eq!(theme_in(nested), Some(Theme("dark")));
+        assert_eq!(theme_in(app), Some(Theme("default")));
+        // Component providers are not visible outside the tree
+        assert_eq!(consume_context::<Theme>(), None);
+
+        // Re-providing updates the context consumers already hold
+        let held = runtime::with_component(nested, use_context::<Theme>).unwrap();
+        runtime::with_component(dark, || provide_context(Theme("darker")));
+        assert_eq!(*held.get(), Theme("darker"));
+
+        runtime::cleanup_component(dark);
+        assert_eq!(theme_in(light), Some(Theme("light")));
+        assert_eq!(theme_in(dark), None);
+
+        runtime::with_component(light, || {
+            let _guard = provide_context_scoped(7u8);
+            assert_eq!(consume_context::<u8>(), Some(7));
+        });
+        assert_eq!(runtime::with_component(light, consume_context::<u8>), None);
+
+        provide_context(Theme("root"));
+        let orphan = component(None);
+        assert_eq!(theme_in(orphan), Some(Theme("root")));
+        clear_all_contexts();
+        assert_eq!(theme_in(light), None);
+    }
 }
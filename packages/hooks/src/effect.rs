@@ -1,7 +1,26 @@
-use tairitsu_vdom::{create_effect, EffectHandle};
+use std::{any::TypeId, cell::RefCell, rc::Rc};
+
+use tairitsu_vdom::{create_effect, create_effect_with_cleanup, runtime, EffectHandle};
 
 pub type Effect = EffectHandle;
 
+/// Tie an effect's lifetime to the rendering component, if any.
+///
+/// The effect is stopped when the component re-renders (the render creates
+/// it again) or is cleaned up.
+fn track(handle: Effect) -> Effect {
+    if let Some(id) = runtime::active_component_id() {
+        runtime::register_effect_handle(id, handle.clone());
+    }
+    handle
+}
+
+/// Run an effect that re-runs whenever a signal it reads changes.
+///
+/// The effect lives until the returned handle is
+/// [stopped](EffectHandle::stop); re-rendering or cleaning up the component
+/// does not stop it. Use [`use_effect_with_cleanup`] for an effect scoped to
+/// the component.
 pub fn use_effect<F>(effect: F) -> Effect
 where
     F: FnMut() + 'static,
@@ -9,11 +28,83 @@ where
     create_effect(effect)
 }
 
+/// Run an effect that returns a teardown closure.
+///
+/// The teardown runs before the effect re-runs, when the returned handle is
+/// [stopped](EffectHandle::stop), and when the component re-renders or
+/// unmounts.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use_effect_with_cleanup(move || {
+///     let id = platform.set_interval(tick, 1000);
+///     move || platform.clear_interval(id)
+/// });
+/// ```
+pub fn use_effect_with_cleanup<F, C>(effect: F) -> Effect
+where
+    F: FnMut() -> C + 'static,
+    C: FnOnce() + 'static,
+{
+    track(create_effect_with_cleanup(effect))
+}
+
+/// Run an effect only when `deps` changes between renders.
+///
+/// Signal reads are not tracked; the effect re-runs when a render passes
+/// deps that differ from the previous ones, after the previous run's
+/// teardown. The last teardown runs when the component is cleaned up or the
+/// returned handle is stopped. Outside of a component the effect runs on
+/// every call.
+///
+/// Each call site keeps its own deps, so call it unconditionally and at
+/// most once per site and render.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use_effect_with_deps(user_id, |id| {
+///     let subscription = feed.subscribe(*id);
+///     move || subscription.cancel()
+/// });
+/// ```
+pub fn use_effect_with_deps<D, F, C>(deps: D, effect: F) -> Effect
+where
+    D: PartialEq + 'static,
+    F: FnOnce(&D) -> C + 'static,
+    C: FnOnce() + 'static,
+{
+    let Some(component) = runtime::active_component_id() else {
+        return EffectHandle::with_cleanup(effect(&deps));
+    };
+
+    let key = format!("use_effect_with_deps:{:?}", TypeId::of::<F>());
+    let slot: Rc<RefCell<Option<(D, Effect)>>> =
+        runtime::hook_slot(component, &key, || Rc::new(RefCell::new(None)));
+
+    let previous = slot.borrow_mut().take();
+    if let Some((previous_deps, handle)) = previous {
+        if previous_deps == deps && !handle.is_stopped() {
+            *slot.borrow_mut() = Some((previous_deps, handle.clone()));
+            return handle;
+        }
+        handle.stop();
+    }
+
+    let handle = EffectHandle::with_cleanup(effect(&deps));
+    runtime::register_persistent_effect_handle(component, handle.clone());
+    *slot.borrow_mut() = Some((deps, handle.clone()));
+    handle
+}
+
 #[cfg(test)]
 mod tests {
-    use std::cell::RefCell;
+    use std::cell::{Cell, RefCell};
     use std::rc::Rc;
 
+    use tairitsu_vdom::{Signal, VNode};
+
     use super::*;
 
     #[test]
@@ -27,4 +118,92 @@ mod tests {
 
         assert_eq!(*counter.borrow(), 1);
     }
+
+    fn logger() -> (Rc<RefCell<Vec<String>>>, impl Fn(String) + Clone) {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let push = {
+            let log = log.clone();
+            move |entry: String| log.borrow_mut().push(entry)
+        };
+        (log, push)
+    }
+
+    #[test]
+    fn test_use_effect_with_cleanup_unmount() {
+        let (log, push) = logger();
+        let component = runtime::use_component(VNode::empty);
+
+        let effect = runtime::with_component(component, || {
+            use_effect_with_cleanup(move || {
+                push("subscribe".to_string());
+                let push = push.clone();
+                move || push("unsubscribe".to_string())
+            })
+        });
+        assert_eq!(*log.borrow(), vec!["subscribe"]);
+
+        runtime::cleanup_component(component);
+        assert!(effect.is_stopped());
+        assert_eq!(*log.borrow(), vec!["subscribe", "unsubscribe"]);
+    }
+
+    #[test]
+    fn test_rerender_stops_only_scoped_effects() {
+        let (log, push) = logger();
+        let tick = Signal::new(0);
+        let renders = Rc::new(Cell::new(0));
+        let component = {
+            let (tick, renders) = (tick.clone(), renders.clone());
+            runtime::use_component(move || {
+                renders.set(renders.get() + 1);
+                let render = renders.get();
+                let (scoped, push_scoped) = (tick.clone(), push.clone());
+                use_effect_with_cleanup(move || {
+                    push_scoped(format!("scoped {render}: {}", scoped.get()));
+                    || {}
+                });
+                let (plain, push_plain) = (tick.clone(), push.clone());
+                use_effect(move || push_plain(format!("plain {render}: {}", plain.get())));
+                VNode::empty()
+            })
+        };
+
+        runtime::mark_dirty(component);
+        runtime::flush_render();
+        runtime::mark_dirty(component);
+        runtime::flush_render();
+        assert!(renders.get() >= 2);
+
+        tick.set(1);
+        let log = log.borrow();
+        assert!(!log.contains(&"scoped 1: 1".to_string()));
+        assert!(log.contains(&"plain 1: 1".to_string()));
+        runtime::cleanup_component(component);
+    }
+
+    #[test]
+    fn test_use_effect_with_deps_reruns_on_change() {
+        let (log, push) = logger();
+        let component = runtime::use_component(VNode::empty);
+        let render = |deps: u32| {
+            let push = push.clone();
+            runtime::with_component(component, move || {
+                use_effect_with_deps(deps, move |d| {
+                    push(format!("run {d}"));
+                    let d = *d;
+                    move || push(format!("cleanup {d}"))
+                })
+            })
+        };
+
+        render(1);
+        render(1);
+        render(2);
+        runtime::cleanup_component(component);
+
+        assert_eq!(
+            *log.borrow(),
+            vec!["run 1", "cleanup 1", "run 2", "cleanup 2"]
+        );
+    }
 }
@@ -0,0 +1,64 @@
+use std::any::TypeId;
+
+use tairitsu_vdom::{runtime, ErrorBoundary, RenderError, VNode};
+
+/// Get an [`ErrorBoundary`] that survives re-renders of the component.
+///
+/// Render the subtree through [`ErrorBoundary::render`]; panics in it, in
+/// components created inside it and in their effects show `fallback` until
+/// the boundary is reset. Outside of a component a new boundary is returned
+/// on every call.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let boundary = use_error_boundary(|error, boundary| {
+///     let boundary = boundary.clone();
+///     el("button")
+///         .on_click(move |_| boundary.reset())
+///         .child(txt(&format!("{} — retry", error.message)))
+///         .into()
+/// });
+/// boundary.render(|| chart(data))
+/// ```
+pub fn use_error_boundary<F>(fallback: F) -> ErrorBoundary
+where
+    F: Fn(&RenderError, &ErrorBoundary) -> VNode + 'static,
+{
+    let Some(component) = runtime::active_component_id() else {
+        return ErrorBoundary::new(fallback);
+    };
+
+    let key = format!("use_error_boundary:{:?}", TypeId::of::<F>());
+    runtime::hook_slot(component, &key, || ErrorBoundary::new(fallback))
+}
+
+#[cfg(test)]
+mod tests {
+    use tairitsu_vdom::txt;
+
+    use super::*;
+
+    #[test]
+    fn test_use_error_boundary_keeps_error_across_renders() {
+        let component = runtime::use_component(VNode::empty);
+        let render = || {
+            runtime::with_component(component, || {
+                let boundary = use_error_boundary(|error, _| txt(&error.message));
+                (
+                    boundary.clone(),
+                    boundary.render(|| panic!("broken widget")),
+                )
+            })
+        };
+
+        let (first, _) = render();
+        let (second, vnode) = render();
+        assert!(second.has_error());
+        assert_eq!(vnode, txt("broken widget"));
+
+        first.reset();
+        assert!(!second.has_error());
+        runtime::cleanup_component(component);
+    }
+}
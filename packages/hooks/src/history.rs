@@ -0,0 +1,434 @@
+//! Undo/redo history for stores and signals.
+//!
+//! A [`History`] wraps a [`Store`], [`ReactiveSignal`] or [`Signal`] and
+//! records the previous value on every [`set`](History::set) or
+//! [`update`](History::update) made through it. Changes made directly on
+//! the wrapped value are not recorded.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let document = History::new(Store::new(Document::default()))
+//!     .with_limit(200)
+//!     .coalesce(Duration::from_millis(500));
+//!
+//! // Typing quickly produces a single undo step.
+//! document.update(|d| d.text.push('a'));
+//! document.update(|d| d.text.push('b'));
+//!
+//! // Several edits as one step, notifying signal subscribers once.
+//! document.transaction(|| {
+//!     document.update(|d| d.selection = None);
+//!     document.update(|d| d.text.clear());
+//! });
+//!
+//! let can_undo = document.can_undo(); // a Signal<bool> for the toolbar
+//! document.undo();
+//! ```
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use tairitsu_vdom::{batch, Signal};
+
+use crate::{signal::ReactiveSignal, store::Store};
+
+/// Undo steps kept by default
+pub const DEFAULT_HISTORY_LIMIT: usize = 100;
+
+/// A value whose changes a [`History`] can record and revert
+pub trait HistoryTarget<T>: 'static {
+    fn get(&self) -> T;
+    /// Read the value without tracking it as a reactive dependency
+    fn peek(&self) -> T;
+    fn set(&self, value: T);
+}
+
+impl<T: Clone + 'static> HistoryTarget<T> for Store<T> {
+    fn get(&self) -> T {
+        Store::get(self)
+    }
+
+    fn peek(&self) -> T {
+        Store::get(self)
+    }
+
+    fn set(&self, value: T) {
+        Store::set(self, value);
+    }
+}
+
+impl<T: Clone + 'static> HistoryTarget<T> for ReactiveSignal<T> {
+    fn get(&self) -> T {
+        ReactiveSignal::get(self)
+    }
+
+    fn peek(&self) -> T {
+        ReactiveSignal::peek(self)
+    }
+
+    fn set(&self, value: T) {
+        ReactiveSignal::set(self, value);
+    }
+}
+
+impl<T: Clone + 'static> HistoryTarget<T> for Signal<T> {
+    fn get(&self) -> T {
+        Signal::get(self)
+    }
+
+    fn peek(&self) -> T {
+        Signal::peek(self)
+    }
+
+    fn set(&self, value: T) {
+        Signal::set(self, value);
+    }
+}
+
+/// Undo/redo stacks around a [`HistoryTarget`]
+///
+/// Clones share the same stacks.
+pub struct History<T: Clone + 'static> {
+    inner: Rc<HistoryInner<T>>,
+}
+
+struct HistoryInner<T> {
+    target: Box<dyn HistoryTarget<T>>,
+    undo: RefCell<VecDeque<T>>,
+    redo: RefCell<Vec<T>>,
+    limit: Cell<usize>,
+    coalesce: Cell<Option<Duration>>,
+    /// When the newest undo step last changed, while it is open to coalescing
+    last_edit: Cell<Option<Instant>>,
+    transaction_depth: Cell<usize>,
+    /// Value before the first change of the open transaction
+    transaction_start: RefCell<Option<T>>,
+    can_undo: Signal<bool>,
+    can_redo: Signal<bool>,
+}
+
+impl<T: Clone + 'static> Clone for History<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Rc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T: Clone + 'static> History<T> {
+    /// Record changes made to `target` through the returned history
+    pub fn new(target: impl HistoryTarget<T>) -> Self {
+        Self {
+            inner: Rc::new(HistoryInner {
+                target: Box::new(target),
+                undo: RefCell::new(VecDeque::new()),
+                redo: RefCell::new(Vec::new()),
+                limit: Cell::new(DEFAULT_HISTORY_LIMIT),
+                coalesce: Cell::new(None),
+                last_edit: Cell::new(None),
+                transaction_depth: Cell::new(0),
+                transaction_start: RefCell::new(None),
+                can_undo: Signal::new(false),
+                can_redo: Signal::new(false),
+            }),
+        }
+    }
+
+    /// Keep at most `limit` undo steps, dropping the oldest ones
+    pub fn with_limit(self, limit: usize) -> Self {
+        self.inner.limit.set(limit);
+        self.trim();
+        self
+    }
+
+    /// Merge changes made within `window` of the previous one into the same
+    /// undo step
+    ///
+    /// A step stops growing after [`seal`](Self::seal), undo or redo.
+    pub fn coalesce(self, window: Duration) -> Self {
+        self.inner.coalesce.set(Some(window));
+        self
+    }
+
+    pub fn get(&self) -> T {
+        self.inner.target.get()
+    }
+
+    /// Read the value without tracking it as a reactive dependency
+    pub fn peek(&self) -> T {
+        self.inner.target.peek()
+    }
+
+    /// Set a new value, recording the current one for undo
+    pub fn set(&self, value: T) {
+        self.record();
+        self.inner.target.set(value);
+    }
+
+    /// Change the value in place, recording the current one for undo
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        let mut value = self.peek();
+        f(&mut value);
+        self.set(value);
+    }
+
+    /// Run `f` as a single undo step
+    ///
+    /// Changes inside `f` run in a [`batch`], so signal subscribers are
+    /// notified once at the end. Transactions can be nested; the outermost
+    /// one records the step, also when `f` panics.
+    pub fn transaction<R>(&self, f: impl FnOnce() -> R) -> R {
+        let depth = &self.inner.transaction_depth;
+        depth.set(depth.get() + 1);
+        let _end = EndTransaction(self);
+        batch(f)
+    }
+
+    /// Revert the newest undo step; returns whether there was one
+    pub fn undo(&self) -> bool {
+        let Some(previous) = self.inner.undo.borrow_mut().pop_back() else {
+            return false;
+        };
+        self.inner.redo.borrow_mut().push(self.peek());
+        self.restore(previous);
+        true
+    }
+
+    /// Re-apply the newest undone step; returns whether there was one
+    pub fn redo(&self) -> bool {
+        let Some(next) = self.inner.redo.borrow_mut().pop() else {
+            return false;
+        };
+        self.inner.undo.borrow_mut().push_back(self.peek());
+        self.trim();
+        self.restore(next);
+        true
+    }
+
+    /// Whether [`undo`](Self::undo) would do anything, as a reactive signal
+    pub fn can_undo(&self) -> Signal<bool> {
+        self.inner.can_undo.clone()
+    }
+
+    /// Whether [`redo`](Self::redo) would do anything, as a reactive signal
+    pub fn can_redo(&self) -> Signal<bool> {
+        self.inner.can_redo.clone()
+    }
+
+    /// Start a new undo step on the next change, even within the coalescing
+    /// window
+    pub fn seal(&self) {
+        self.inner.last_edit.set(None);
+    }
+
+    /// Forget all undo and redo steps
+    pub fn clear(&self) {
+        self.inner.undo.borrow_mut().clear();
+        self.inner.redo.borrow_mut().clear();
+        self.seal();
+        self.refresh_signals();
+    }
+
+    /// Remember the current value before it changes
+    fn record(&self) {
+        let inner = &self.inner;
+        if inner.transaction_depth.get() > 0 {
+            let mut start = inner.transaction_start.borrow_mut();
+            if start.is_none() {
+                *start = Some(self.peek());
+            }
+            return;
+        }
+        self.push(self.peek());
+    }
+
+    /// Add an undo step holding `previous`, unless it extends the newest
+    /// step under coalescing
+    fn push(&self, previous: T) {
+        let inner = &self.inner;
+        let now = Instant::now();
+        let coalesced = match (inner.coalesce.get(), inner.last_edit.get()) {
+            (Some(window), Some(last)) => now.duration_since(last) <= window,
+            _ => false,
+        };
+        if inner.coalesce.get().is_some() {
+            inner.last_edit.set(Some(now));
+        }
+
+        if !coalesced {
+            inner.undo.borrow_mut().push_back(previous);
+            self.trim();
+        }
+        inner.redo.borrow_mut().clear();
+        self.refresh_signals();
+    }
+
+    fn restore(&self, value: T) {
+        self.seal();
+        self.inner.target.set(value);
+        self.refresh_signals();
+    }
+
+    fn trim(&self) {
+        let limit = self.inner.limit.get();
+        let mut undo = self.inner.undo.borrow_mut();
+        while undo.len() > limit {
+            undo.pop_front();
+        }
+    }
+
+    fn refresh_signals(&self) {
+        let can_undo = !self.inner.undo.borrow().is_empty();
+        let can_redo = !self.inner.redo.borrow().is_empty();
+        if self.inner.can_undo.peek() != can_undo {
+            self.inner.can_undo.set(can_undo);
+        }
+        if self.inner.can_redo.peek() != can_redo {
+            self.inner.can_redo.set(can_redo);
+        }
+    }
+}
+
+/// Closes a [`History::transaction`] level and records the step once the
+/// outermost one ends
+struct EndTransaction<'a, T: Clone + 'static>(&'a History<T>);
+
+impl<T: Clone + 'static> Drop for EndTransaction<'_, T> {
+    fn drop(&mut self) {
+        let inner = &self.0.inner;
+        inner
+            .transaction_depth
+            .set(inner.transaction_depth.get() - 1);
+
+        if inner.transaction_depth.get() == 0 {
+            let start = inner.transaction_start.borrow_mut().take();
+            if let Some(start) = start {
+                self.0.push(start);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tairitsu_vdom::create_effect;
+
+    use super::*;
+
+    #[test]
+    fn test_undo_redo_with_bounded_stack() {
+        let store = Store::new(0);
+        let history = History::new(store.clone()).with_limit(2);
+        let (can_undo, can_redo) = (history.can_undo(), history.can_redo());
+        assert!(!can_undo.get());
+
+        for value in 1..=3 {
+            history.set(value);
+        }
+        assert!(can_undo.get());
+
+        assert!(history.undo());
+        assert!(history.undo());
+        assert_eq!(store.get(), 1);
+        // The step back to 0 fell off the bounded stack.
+        assert!(!history.undo());
+        assert!(!can_undo.get() && can_redo.get());
+
+        assert!(history.redo());
+        assert_eq!(store.get(), 2);
+        history.update(|v| *v *= 10);
+        assert_eq!(store.get(), 20);
+        assert!(!can_redo.get());
+        assert!(!history.redo());
+    }
+
+    #[test]
+    fn test_transaction_is_one_batched_step() {
+        let signal = Signal::new(0);
+        let history = History::new(signal.clone());
+        let runs = Rc::new(Cell::new(0));
+        let _effect = {
+            let (signal, runs) = (signal.clone(), runs.clone());
+            create_effect(move || {
+                signal.get();
+                runs.set(runs.get() + 1);
+            })
+        };
+
+        history.transaction(|| {
+            history.set(1);
+            history.transaction(|| history.set(2));
+            history.set(3);
+        });
+        assert_eq!(runs.get(), 2);
+
+        assert!(history.undo());
+        assert_eq!(signal.get(), 0);
+        assert!(!history.undo());
+    }
+
+    #[test]
+    fn test_rapid_edits_coalesce_until_sealed() {
+        let store = Store::new(String::new());
+        let history = History::new(store.clone()).coalesce(Duration::from_secs(60));
+
+        for c in "abc".chars() {
+            history.update(|text| text.push(c));
+        }
+        history.seal();
+        history.update(|text| text.push('!'));
+
+        assert!(history.undo());
+        assert_eq!(store.get(), "abc");
+        assert!(history.undo());
+        assert_eq!(store.get(), "");
+        assert!(!history.undo());
+    }
+
+    #[test]
+    fn test_edits_inside_an_effect_do_not_subscribe_it() {
+        let signal = Signal::new(0);
+        let history = History::new(signal.clone());
+        let trigger = Signal::new(0);
+        let runs = Rc::new(Cell::new(0));
+        let _effect = {
+            let (history, trigger, runs) = (history.clone(), trigger.clone(), runs.clone());
+            create_effect(move || {
+                trigger.get();
+                runs.set(runs.get() + 1);
+                history.update(|v| *v += 1);
+            })
+        };
+
+        trigger.set(1);
+        assert_eq!((runs.get(), signal.get()), (2, 2));
+        // Neither the value nor the undo state became dependencies.
+        assert!(history.undo());
+        signal.set(10);
+        assert_eq!(runs.get(), 2);
+    }
+
+    #[test]
+    fn test_panicking_transaction_still_ends() {
+        let store = Store::new(0);
+        let history = History::new(store.clone());
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            history.transaction(|| {
+                history.set(1);
+                panic!("edit failed");
+            })
+        }));
+        assert!(result.is_err());
+
+        history.set(2);
+        assert!(history.undo());
+        assert_eq!(store.get(), 1);
+        assert!(history.undo());
+        assert_eq!(store.get(), 0);
+    }
+}
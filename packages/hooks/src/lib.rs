@@ -6,11 +6,14 @@
 //! # Available Hooks
 //!
 //! - **State**: [`use_signal`], [`use_state`], [`use_ref`]
-//! - **Side Effects**: [`use_effect`], [`use_memo`], [`use_callback`]
-//! - **Async**: [`use_suspense`], [`use_resource`]
+//! - **Side Effects**: [`use_effect`], [`use_effect_with_cleanup`], [`use_effect_with_deps`],
+//!   [`use_memo`], [`use_callback`]
+//! - **Async**: [`use_suspense`], [`use_resource`], [`use_query`], [`use_mutation`]
 //! - **DOM**: [`use_dom_ref`], [`use_element_ref`]
 //! - **Animation**: [`use_animation`], [`use_simple_animation`]
 //! - **Context**: [`provide_context`], [`use_context`]
+//! - **Errors**: [`use_error_boundary`]
+//! - **History**: [`History`] undo/redo for stores and signals
 //! - **Timer**: [`use_interval`]
 //! - **Interaction**: [`use_interaction_state`]
 
@@ -21,14 +24,20 @@ pub mod dom_ref;
 pub mod dynamic;
 pub mod effect;
 pub mod element_ref;
+pub mod error_boundary;
+pub mod history;
 pub mod interval;
 pub mod memo;
+pub mod query;
 pub mod ref_;
 pub mod signal;
 pub mod state;
 pub mod state_machine;
+pub mod storage;
 pub mod store;
 pub mod suspense;
+#[cfg(not(target_family = "wasm"))]
+pub mod testing;
 
 pub use animation::{
     use_animation, use_simple_animation, AnimationCallback, AnimationConfig, AnimationDirection,
@@ -41,11 +50,18 @@ pub use context::{
 };
 pub use dom_ref::{use_dom_ref, DomRef};
 pub use dynamic::{use_dynamic_text, use_dynamic_text_fn};
-pub use effect::use_effect;
+pub use effect::{use_effect, use_effect_with_cleanup, use_effect_with_deps, Effect};
 pub use element_ref::{use_element_ref, ElementRef};
+pub use error_boundary::use_error_boundary;
+pub use history::{History, HistoryTarget, DEFAULT_HISTORY_LIMIT};
 pub use interval::{use_interval, IntervalHandle};
 pub use memo::{use_memo, use_memo_with, use_memo_with_deps, Memo};
 pub use provide_context as use_context_provider;
+pub use query::{
+    clear_queries, dehydrate_queries, get_query_data, hydrate_queries, hydrate_queries_json,
+    invalidate, set_query_data, use_mutation, use_query, use_query_with, Mutation, MutationState,
+    Query, QueryOptions, QUERY_CACHE_SCRIPT_ID,
+};
 pub use ref_::{use_ref, UseRef};
 pub use signal::{use_signal, use_standalone_signal, ReactiveSignal, StandaloneSignal};
 pub use state::use_state;
@@ -53,10 +69,15 @@ pub use state_machine::{
     use_interaction_state, ButtonStateMachine, InteractionCallback, InteractionEvent,
     InteractionState,
 };
+pub use storage::{
+    register_local_storage, DefaultInitial, IdbStorage, Initial, InitialState, LocalStorage,
+    LocalStorageFuncs, MemoryStorage, PersistedStoreBuilder, StorageBackend, StorageEventHandler,
+    WatchGuard,
+};
 pub use store::{register_store, Store, StoreId};
 pub use suspense::{
-    resource_state, use_resource, use_suspense, Resource, ResourceState, ResourceStatus, Suspense,
-    SuspenseBoundary,
+    pending_resources, resource_state, use_resource, use_suspense, Resource, ResourceState,
+    ResourceStatus, Suspense, SuspenseBoundary,
 };
 
 // Re-export Event types from vdom for convenience
@@ -0,0 +1,1231 @@
+//! Cached queries and mutations on top of the resource registry.
+//!
+//! [`use_query`] fetches data by key into a cache shared by every component
+//! on the thread:
+//!
+//! - **Dedup**: components asking for the same key while a fetch is in
+//!   flight share it.
+//! - **Stale-while-revalidate**: data older than
+//!   [`stale_time`](QueryOptions::with_stale_time) is still returned while a
+//!   background fetch refreshes it.
+//! - **Retries**: failed fetches are retried with exponential backoff.
+//! - **Invalidation**: [`invalidate`] marks a key stale and refetches it if
+//!   a mounted component uses it. Invalidating during a fetch refetches once
+//!   that fetch settles, so its possibly outdated result is not kept as
+//!   fresh.
+//!
+//! [`use_mutation`] runs writes, optionally applying an optimistic update to
+//! cached data that is rolled back if the write fails.
+//!
+//! Queries register with the same registry as [`use_resource`], so
+//! [`Suspense`](crate::Suspense) boundaries show their fallback until a
+//! query has data and [`pending_resources`](crate::pending_resources) counts
+//! them.
+//!
+//! # Server-rendered data
+//!
+//! Cached data can be moved between threads or from the server to the
+//! client as JSON with [`dehydrate_queries`] and [`hydrate_queries`].
+//! `tairitsu_ssr::data_fetcher::Cache` uses these to embed the server's
+//! query cache in the page under [`QUERY_CACHE_SCRIPT_ID`]. The client does
+//! not read it on its own: pass the script's text to
+//! [`hydrate_queries_json`] before hydrating, so queries start with the
+//! server's data instead of fetching it again.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! fn todo_list() -> VNode {
+//!     let todos = use_query_with(
+//!         "todos",
+//!         QueryOptions::new().with_stale_time(Duration::from_secs(30)),
+//!         || async { api::fetch_todos().await },
+//!     );
+//!     let add = use_mutation(|title: String| async move { api::add_todo(title).await })
+//!         .optimistic("todos", |todos: &mut Vec<Todo>, title: &String| {
+//!             todos.push(Todo::draft(title))
+//!         });
+//!
+//!     match todos.read() {
+//!         ResourceState::Loading => txt("Loading..."),
+//!         ResourceState::Ready(todos) => render_todos(&todos, move |title| add.mutate(title)),
+//!         ResourceState::Error(err) => txt(&format!("Error: {}", err)),
+//!     }
+//! }
+//! ```
+//!
+//! [`use_resource`]: crate::use_resource
+
+use std::{
+    any::{Any, TypeId},
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    fmt,
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    rc::Rc,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use serde::{de::DeserializeOwned, Serialize};
+use tairitsu_vdom::{runtime, EffectHandle, Signal};
+use tracing::debug;
+
+use crate::suspense::{
+    register_shared_resource, track_resource_access, unregister_shared_resource, ResourceId,
+    ResourceState, ResourceStateOp, ResourceStatusCell,
+};
+
+/// Id of the `<script type="application/json">` element carrying a
+/// server's query cache
+///
+/// Read by the caller and passed to [`hydrate_queries_json`].
+pub const QUERY_CACHE_SCRIPT_ID: &str = "__TAIRITSU_QUERIES__";
+
+/// Longest wait between two retries
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Options for [`use_query_with`]
+#[derive(Clone, Copy, Debug)]
+pub struct QueryOptions {
+    /// How long fetched data counts as fresh
+    pub stale_time: Duration,
+    /// Retries after a failed fetch
+    pub retry: u32,
+    /// Wait before the first retry, doubled for each following one
+    pub retry_delay: Duration,
+}
+
+impl Default for QueryOptions {
+    fn default() -> Self {
+        Self {
+            stale_time: Duration::ZERO,
+            retry: 3,
+            retry_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+impl QueryOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep data fresh for `stale_time`; fresh data is not refetched when
+    /// another component starts using the query
+    pub fn with_stale_time(mut self, stale_time: Duration) -> Self {
+        self.stale_time = stale_time;
+        self
+    }
+
+    pub fn with_retry(mut self, retry: u32) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn with_retry_delay(mut self, retry_delay: Duration) -> Self {
+        self.retry_delay = retry_delay;
+        self
+    }
+}
+
+/// Serialize a type-erased cache value back to JSON
+type EncodeFn = fn(&dyn Any) -> Option<Vec<u8>>;
+
+/// Undo an optimistic update
+type RollbackFn = Box<dyn FnOnce()>;
+
+/// Apply an optimistic update for a mutation argument
+type OptimisticFn<A> = Rc<dyn Fn(&A) -> RollbackFn>;
+
+type MutateFn<A, T> = Rc<dyn Fn(A) -> Pin<Box<dyn Future<Output = anyhow::Result<T>> + Send>>>;
+
+thread_local! {
+    static QUERY_CACHE: RefCell<HashMap<String, QueryEntry>> = RefCell::new(HashMap::new());
+    /// Bumped by [`clear_queries`] so fetches started before it are ignored
+    static CACHE_GENERATION: Cell<u64> = const { Cell::new(0) };
+}
+
+struct QueryEntry {
+    data: Option<Rc<dyn Any>>,
+    encode: Option<EncodeFn>,
+    /// JSON from [`hydrate_queries`], decoded on first typed read
+    raw: Option<Vec<u8>>,
+    error: Option<String>,
+    updated_at: Option<Instant>,
+    stale_time: Duration,
+    invalidated: bool,
+    fetching: bool,
+    /// Invalidated while a fetch was in flight; fetch again once it settles
+    refetch_pending: bool,
+    /// Fetcher of the most recently mounted [`use_query`] for this key,
+    /// dropped when the last component using the key unmounts
+    refetch: Option<Rc<dyn Fn()>>,
+    /// Mounted components using this key
+    subscribers: usize,
+    /// Bumped on every change so readers re-render
+    version: Signal<u64>,
+    resource_id: ResourceId,
+    status: ResourceStatusCell,
+}
+
+impl QueryEntry {
+    fn new() -> Self {
+        let (resource_id, status) = register_shared_resource(ResourceStateOp::Ready);
+        Self {
+            data: None,
+            encode: None,
+            raw: None,
+            error: None,
+            updated_at: None,
+            stale_time: Duration::ZERO,
+            invalidated: false,
+            fetching: false,
+            refetch_pending: false,
+            refetch: None,
+            subscribers: 0,
+            version: Signal::new(0),
+            resource_id,
+            status,
+        }
+    }
+
+    fn has_data(&self) -> bool {
+        self.data.is_some() || self.raw.is_some()
+    }
+
+    fn is_stale(&self) -> bool {
+        match self.updated_at {
+            Some(updated_at) => self.invalidated || updated_at.elapsed() >= self.stale_time,
+            None => true,
+        }
+    }
+
+    fn set_status(&self, op: ResourceStateOp) {
+        *self.status.lock().unwrap_or_else(|e| e.into_inner()) = op;
+    }
+
+    fn store<T: Serialize + 'static>(&mut self, value: T) {
+        self.data = Some(Rc::new(value));
+        self.encode = Some(encode::<T>);
+        self.raw = None;
+        self.mark_fresh();
+    }
+
+    fn mark_fresh(&mut self) {
+        self.error = None;
+        self.updated_at = Some(Instant::now());
+        self.invalidated = false;
+        self.set_status(ResourceStateOp::Ready);
+    }
+}
+
+impl Drop for QueryEntry {
+    fn drop(&mut self) {
+        unregister_shared_resource(self.resource_id);
+    }
+}
+
+fn encode<T: Serialize + 'static>(value: &dyn Any) -> Option<Vec<u8>> {
+    serde_json::to_vec(value.downcast_ref::<T>()?).ok()
+}
+
+fn with_entry<R>(key: &str, f: impl FnOnce(&mut QueryEntry) -> R) -> R {
+    QUERY_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        f(cache.entry(key.to_string()).or_insert_with(QueryEntry::new))
+    })
+}
+
+fn with_existing_entry<R>(key: &str, f: impl FnOnce(&mut QueryEntry) -> R) -> Option<R> {
+    QUERY_CACHE.with(|cache| cache.borrow_mut().get_mut(key).map(f))
+}
+
+fn cache_generation() -> u64 {
+    CACHE_GENERATION.with(Cell::get)
+}
+
+/// The cached value of `key` as `T`, decoding hydrated JSON if needed
+fn cached<T: Clone + DeserializeOwned + 'static>(key: &str) -> Option<T> {
+    with_existing_entry(key, |entry| {
+        if entry.data.is_none() {
+            let decoded = serde_json::from_slice::<T>(entry.raw.as_deref()?);
+            match decoded {
+                Ok(value) => entry.data = Some(Rc::new(value)),
+                Err(err) => {
+                    debug!("query {:?}: hydrated data does not decode: {}", key, err);
+                    return None;
+                }
+            }
+        }
+        entry.data.as_ref()?.downcast_ref::<T>().cloned()
+    })
+    .flatten()
+}
+
+/// Set `signal`, notifying readers unless results are being applied in the
+/// middle of a render
+fn publish<V: Clone + 'static>(signal: &Signal<V>, value: V) {
+    if settling::is_applying() {
+        *signal.write() = value;
+    } else {
+        signal.set(value);
+    }
+}
+
+fn bump(version: &Signal<u64>) {
+    let next = *version.write() + 1;
+    publish(version, next);
+}
+
+fn status_of<T>(result: &Result<T, String>) -> ResourceStateOp {
+    match result {
+        Ok(_) => ResourceStateOp::Ready,
+        Err(_) => ResourceStateOp::Error,
+    }
+}
+
+/// A query returned by [`use_query`]
+///
+/// Reading it inside a render subscribes the component to the query's key.
+pub struct Query<T> {
+    key: String,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for Query<T> {
+    fn clone(&self) -> Self {
+        Self {
+            key: self.key.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for Query<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Query").field("key", &self.key).finish()
+    }
+}
+
+impl<T: Clone + DeserializeOwned + 'static> Query<T> {
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Current state of the query
+    ///
+    /// Cached data is `Ready` even while it is being revalidated or after a
+    /// revalidation failed; `Error` is only returned when there is no data.
+    pub fn read(&self) -> ResourceState<T> {
+        settling::apply_finished();
+        let (version, resource_id) = with_entry(&self.key, |e| (e.version.clone(), e.resource_id));
+        track_resource_access(resource_id);
+        version.get();
+
+        if let Some(value) = cached::<T>(&self.key) {
+            return ResourceState::Ready(value);
+        }
+        match self.error() {
+            Some(err) => ResourceState::Error(err),
+            None => ResourceState::Loading,
+        }
+    }
+
+    pub fn data(&self) -> Option<T> {
+        settling::apply_finished();
+        cached(&self.key)
+    }
+
+    /// Error of the latest fetch, if it failed
+    pub fn error(&self) -> Option<String> {
+        with_existing_entry(&self.key, |e| e.error.clone()).flatten()
+    }
+
+    /// Whether a fetch for this key is in flight
+    pub fn is_fetching(&self) -> bool {
+        settling::apply_finished();
+        with_existing_entry(&self.key, |e| e.fetching).unwrap_or(false)
+    }
+
+    /// Fetch again now, even if the data is fresh
+    pub fn refetch(&self) {
+        invalidate(&self.key);
+    }
+}
+
+/// Fetch `key` with `fetcher` into the shared query cache, with default
+/// [`QueryOptions`].
+///
+/// See the [module documentation](self) for caching behaviour.
+pub fn use_query<T, F, Fut>(key: impl Into<String>, fetcher: F) -> Query<T>
+where
+    T: Clone + Serialize + DeserializeOwned + Send + 'static,
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = anyhow::Result<T>> + Send + 'static,
+{
+    use_query_with(key, QueryOptions::default(), fetcher)
+}
+
+/// [`use_query`] with custom options.
+///
+/// The fetcher runs when a component first uses the query and the cache has
+/// no fresh data for `key`, and again whenever the key is invalidated.
+/// Outside of a component every call counts as a first use.
+pub fn use_query_with<T, F, Fut>(
+    key: impl Into<String>,
+    options: QueryOptions,
+    fetcher: F,
+) -> Query<T>
+where
+    T: Clone + Serialize + DeserializeOwned + Send + 'static,
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = anyhow::Result<T>> + Send + 'static,
+{
+    let key = key.into();
+    settling::apply_finished();
+
+    let component = runtime::active_component_id();
+    let mounted = component.is_some_and(|component| {
+        let slot = format!("use_query:{}:{:?}", key, TypeId::of::<F>());
+        runtime::hook_slot(component, &slot, || Rc::new(Cell::new(false))).replace(true)
+    });
+
+    if !mounted {
+        if let Some(component) = component {
+            subscribe(component, &key);
+        }
+        let refetch: Rc<dyn Fn()> = {
+            let key = key.clone();
+            let fetcher = Arc::new(fetcher);
+            Rc::new(move || start_fetch(&key, &fetcher, options))
+        };
+        let should_fetch = with_entry(&key, |entry| {
+            entry.stale_time = options.stale_time;
+            entry.refetch = Some(Rc::clone(&refetch));
+            !entry.has_data() || entry.is_stale()
+        });
+        if should_fetch {
+            refetch();
+        }
+    }
+
+    Query {
+        key,
+        _marker: PhantomData,
+    }
+}
+
+/// Count `component` as a user of `key` until it unmounts
+fn subscribe(component: runtime::ComponentId, key: &str) {
+    with_entry(key, |entry| entry.subscribers += 1);
+    let key = key.to_string();
+    let handle = EffectHandle::with_cleanup(move || {
+        with_existing_entry(&key, |entry| {
+            entry.subscribers = entry.subscribers.saturating_sub(1);
+            if entry.subscribers == 0 {
+                entry.refetch = None;
+            }
+        });
+    });
+    runtime::register_persistent_effect_handle(component, handle);
+}
+
+/// Start fetching `key` unless a fetch is already in flight
+fn start_fetch<T, F, Fut>(key: &str, fetcher: &Arc<F>, options: QueryOptions)
+where
+    T: Clone + Serialize + Send + 'static,
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = anyhow::Result<T>> + Send + 'static,
+{
+    let status = with_entry(key, |entry| {
+        if entry.fetching {
+            return None;
+        }
+        entry.fetching = true;
+        if !entry.has_data() {
+            entry.set_status(ResourceStateOp::Loading);
+        }
+        Some(Arc::clone(&entry.status))
+    });
+    let Some(status) = status else {
+        return;
+    };
+
+    let key = key.to_string();
+    let generation = cache_generation();
+    let future = fetch_with_retry(Arc::clone(fetcher), options);
+    settling::spawn(future, status, move |result: Result<T, String>| {
+        if generation != cache_generation() {
+            debug!(
+                "query {:?}: dropping a result fetched before the cache was cleared",
+                key
+            );
+            return;
+        }
+        let (version, refetch) = with_entry(&key, |entry| {
+            entry.fetching = false;
+            match result {
+                Ok(value) => entry.store(value),
+                Err(err) => {
+                    debug!("query {:?} failed: {}", key, err);
+                    entry.error = Some(err);
+                }
+            }
+            // The result may predate the invalidation, so it stays stale
+            let refetch = std::mem::take(&mut entry.refetch_pending);
+            entry.invalidated |= refetch;
+            (
+                entry.version.clone(),
+                entry.refetch.clone().filter(|_| refetch),
+            )
+        });
+        bump(&version);
+        if let Some(refetch) = refetch {
+            refetch();
+        }
+    });
+}
+
+async fn fetch_with_retry<T, F, Fut>(fetcher: Arc<F>, options: QueryOptions) -> Result<T, String>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = anyhow::Result<T>>,
+{
+    let mut delay = options.retry_delay;
+    let mut attempt = 0;
+    loop {
+        match fetcher().await {
+            Ok(value) => return Ok(value),
+            Err(err) if attempt >= options.retry => return Err(err.to_string()),
+            Err(err) => {
+                attempt += 1;
+                debug!(
+                    "fetch attempt {} failed, retrying in {:?}: {}",
+                    attempt, delay, err
+                );
+                settling::sleep(delay).await;
+                delay = (delay * 2).min(MAX_RETRY_DELAY);
+            }
+        }
+    }
+}
+
+/// Mark `key` stale and refetch it if a component uses it.
+///
+/// If a fetch is in flight, the refetch starts once it settles.
+pub fn invalidate(key: &str) {
+    let refetch = with_existing_entry(key, |entry| {
+        entry.invalidated = true;
+        if entry.fetching {
+            entry.refetch_pending = true;
+            return None;
+        }
+        entry.refetch.clone()
+    })
+    .flatten();
+    if let Some(refetch) = refetch {
+        refetch();
+    }
+}
+
+/// Replace the cached data of `key`, notifying components that read it.
+pub fn set_query_data<T: Serialize + 'static>(key: &str, value: T) {
+    let version = with_entry(key, |entry| {
+        entry.store(value);
+        entry.version.clone()
+    });
+    bump(&version);
+}
+
+/// The cached data of `key`, if there is any of type `T`.
+pub fn get_query_data<T: Clone + DeserializeOwned + 'static>(key: &str) -> Option<T> {
+    settling::apply_finished();
+    cached(key)
+}
+
+/// Drop every cached query on this thread.
+///
+/// Servers call this between requests so that data does not leak from one
+/// page into the next. Fetches and mutations still in flight are not
+/// written back into the cache when they finish.
+pub fn clear_queries() {
+    CACHE_GENERATION.with(|generation| generation.set(generation.get() + 1));
+    let entries = QUERY_CACHE.with(|cache| std::mem::take(&mut *cache.borrow_mut()));
+    drop(entries);
+}
+
+/// Cached data of every query as JSON, keyed by query key.
+pub fn dehydrate_queries() -> Vec<(String, Vec<u8>)> {
+    settling::apply_finished();
+    QUERY_CACHE.with(|cache| {
+        cache
+            .borrow()
+            .iter()
+            .filter_map(|(key, entry)| {
+                let json = match (&entry.data, entry.encode) {
+                    (Some(data), Some(encode)) => encode(data.as_ref()),
+                    _ => entry.raw.clone(),
+                };
+                Some((key.clone(), json?))
+            })
+            .collect()
+    })
+}
+
+/// Seed the query cache with JSON data, e.g. from [`dehydrate_queries`] on
+/// the server.
+///
+/// Hydrated data counts as fetched now; it is decoded when a query first
+/// reads it.
+pub fn hydrate_queries(entries: impl IntoIterator<Item = (String, Vec<u8>)>) {
+    let versions: Vec<_> = entries
+        .into_iter()
+        .map(|(key, json)| {
+            with_entry(&key, |entry| {
+                entry.data = None;
+                entry.encode = None;
+                entry.raw = Some(json);
+                entry.mark_fresh();
+                entry.version.clone()
+            })
+        })
+        .collect();
+    for version in &versions {
+        bump(version);
+    }
+}
+
+/// [`hydrate_queries`] from a JSON object mapping query keys to data, as
+/// embedded by the server under [`QUERY_CACHE_SCRIPT_ID`].
+pub fn hydrate_queries_json(json: &str) -> anyhow::Result<()> {
+    let queries: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(json).context("query cache is not a JSON object")?;
+    hydrate_queries(
+        queries
+            .into_iter()
+            .map(|(key, value)| (key, value.to_string().into_bytes())),
+    );
+    Ok(())
+}
+
+/// State of a [`Mutation`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum MutationState<T> {
+    /// Not run yet, or [reset](Mutation::reset).
+    Idle,
+    /// The latest run is in flight.
+    Pending,
+    /// The latest run succeeded.
+    Success(T),
+    /// The latest run failed; optimistic updates were rolled back.
+    Error(String),
+}
+
+/// A write returned by [`use_mutation`]
+pub struct Mutation<A, T: Clone + 'static> {
+    inner: Rc<MutationInner<A, T>>,
+}
+
+struct MutationInner<A, T: Clone + 'static> {
+    run: RefCell<MutateFn<A, T>>,
+    state: Signal<MutationState<T>>,
+    optimistic: RefCell<Vec<(String, OptimisticFn<A>)>>,
+    invalidates: RefCell<Vec<String>>,
+    resource_id: ResourceId,
+    status: ResourceStatusCell,
+}
+
+impl<A, T: Clone + 'static> Drop for MutationInner<A, T> {
+    fn drop(&mut self) {
+        unregister_shared_resource(self.resource_id);
+    }
+}
+
+impl<A, T: Clone + 'static> Clone for Mutation<A, T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Rc::clone(&self.inner),
+        }
+    }
+}
+
+impl<A: 'static, T: Clone + Send + 'static> Mutation<A, T> {
+    fn new(run: MutateFn<A, T>) -> Self {
+        let (resource_id, status) = register_shared_resource(ResourceStateOp::Ready);
+        Self {
+            inner: Rc::new(MutationInner {
+                run: RefCell::new(run),
+                state: Signal::new(MutationState::Idle),
+                optimistic: RefCell::new(Vec::new()),
+                invalidates: RefCell::new(Vec::new()),
+                resource_id,
+                status,
+            }),
+        }
+    }
+
+    /// Apply `update` to the cached data of `key` as soon as the mutation
+    /// starts
+    ///
+    /// The previous data is restored if the mutation fails, and `key` is
+    /// invalidated if it succeeds. Keys without cached data are left alone.
+    /// Calling this again for the same key replaces the update.
+    pub fn optimistic<Q>(
+        self,
+        key: impl Into<String>,
+        update: impl Fn(&mut Q, &A) + 'static,
+    ) -> Self
+    where
+        Q: Clone + Serialize + DeserializeOwned + 'static,
+    {
+        let key = key.into();
+        let apply: OptimisticFn<A> = {
+            let key = key.clone();
+            Rc::new(move |arg| {
+                let previous = cached::<Q>(&key);
+                if let Some(mut value) = previous.clone() {
+                    update(&mut value, arg);
+                    set_query_data(&key, value);
+                }
+                let key = key.clone();
+                Box::new(move || {
+                    if let Some(previous) = previous {
+                        set_query_data(&key, previous);
+                    }
+                })
+            })
+        };
+
+        {
+            let mut optimistic = self.inner.optimistic.borrow_mut();
+            optimistic.retain(|(existing, _)| *existing != key);
+            optimistic.push((key, apply));
+        }
+        self
+    }
+
+    /// Invalidate `key` after the mutation succeeds
+    pub fn invalidates(self, key: impl Into<String>) -> Self {
+        let key = key.into();
+        {
+            let mut invalidates = self.inner.invalidates.borrow_mut();
+            if !invalidates.contains(&key) {
+                invalidates.push(key);
+            }
+        }
+        self
+    }
+
+    /// Run the mutation with `arg`
+    pub fn mutate(&self, arg: A) {
+        let optimistic = self.inner.optimistic.borrow().clone();
+        let rollbacks: Vec<RollbackFn> = optimistic.iter().map(|(_, apply)| apply(&arg)).collect();
+        let keys: Vec<String> = optimistic
+            .into_iter()
+            .map(|(key, _)| key)
+            .chain(self.inner.invalidates.borrow().iter().cloned())
+            .collect();
+
+        let run = Rc::clone(&self.inner.run.borrow());
+        let future = run(arg);
+
+        *self.inner.status.lock().unwrap_or_else(|e| e.into_inner()) = ResourceStateOp::Loading;
+        publish(&self.inner.state, MutationState::Pending);
+
+        let state = self.inner.state.clone();
+        let status = Arc::clone(&self.inner.status);
+        let generation = cache_generation();
+        let future = async move { future.await.map_err(|err| err.to_string()) };
+        settling::spawn(future, status, move |result: Result<T, String>| {
+            // Rolling back or invalidating would bring back cleared data
+            let cleared = generation != cache_generation();
+            match result {
+                Ok(value) => {
+                    publish(&state, MutationState::Success(value));
+                    if !cleared {
+                        for key in &keys {
+                            invalidate(key);
+                        }
+                    }
+                }
+                Err(err) => {
+                    debug!("mutation failed, rolling back: {}", err);
+                    if !cleared {
+                        for rollback in rollbacks.into_iter().rev() {
+                            rollback();
+                        }
+                    }
+                    publish(&state, MutationState::Error(err));
+                }
+            }
+        });
+    }
+
+    pub fn state(&self) -> MutationState<T> {
+        settling::apply_finished();
+        self.inner.state.get()
+    }
+
+    pub fn is_pending(&self) -> bool {
+        matches!(self.state(), MutationState::Pending)
+    }
+
+    /// Go back to [`MutationState::Idle`]
+    pub fn reset(&self) {
+        publish(&self.inner.state, MutationState::Idle);
+    }
+}
+
+/// Create a mutation that runs `mutate` when [`Mutation::mutate`] is called.
+///
+/// Inside a component the mutation and its state survive re-renders; the
+/// latest `mutate` closure is used.
+pub fn use_mutation<A, T, F, Fut>(mutate: F) -> Mutation<A, T>
+where
+    A: 'static,
+    T: Clone + Send + 'static,
+    F: Fn(A) -> Fut + 'static,
+    Fut: Future<Output = anyhow::Result<T>> + Send + 'static,
+{
+    settling::apply_finished();
+    let run: MutateFn<A, T> = Rc::new(move |arg| Box::pin(mutate(arg)));
+
+    let Some(component) = runtime::active_component_id() else {
+        return Mutation::new(run);
+    };
+
+    let key = format!("use_mutation:{:?}", TypeId::of::<F>());
+    let init = Rc::clone(&run);
+    let mutation = runtime::hook_slot(component, &key, move || Mutation::new(init));
+    *mutation.inner.run.borrow_mut() = run;
+    mutation
+}
+
+/// Running fetches off the render path and applying their results.
+///
+/// Native targets run each fetch on its own thread with a tokio runtime,
+/// like `use_resource`, and apply results on the UI thread the next time
+/// the query cache is read. WASM targets poll fetches on the UI thread from
+/// platform timers and apply results as soon as they finish.
+#[cfg(not(target_family = "wasm"))]
+mod settling {
+    use std::{
+        cell::{Cell, RefCell},
+        future::Future,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use super::{status_of, ResourceStatusCell};
+
+    type Finisher = Box<dyn FnMut() -> bool>;
+
+    thread_local! {
+        static FINISHERS: RefCell<Vec<Finisher>> = const { RefCell::new(Vec::new()) };
+        static APPLYING: Cell<bool> = const { Cell::new(false) };
+    }
+
+    pub(super) fn spawn<T: Send + 'static>(
+        future: impl Future<Output = Result<T, String>> + Send + 'static,
+        status: ResourceStatusCell,
+        on_done: impl FnOnce(Result<T, String>) + 'static,
+    ) {
+        let slot = Arc::new(Mutex::new(None));
+        let thread_slot = Arc::clone(&slot);
+
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("failed to build tokio runtime");
+            let result = rt.block_on(future);
+            let op = status_of(&result);
+
+            // Fill the slot first, so the result is there once
+            // `pending_resources` stops counting the fetch.
+            *thread_slot.lock().unwrap_or_else(|e| e.into_inner()) = Some(result);
+            *status.lock().unwrap_or_else(|e| e.into_inner()) = op;
+        });
+
+        let mut on_done = Some(on_done);
+        FINISHERS.with(|finishers| {
+            finishers.borrow_mut().push(Box::new(move || {
+                let Some(result) = slot.lock().unwrap_or_else(|e| e.into_inner()).take() else {
+                    return false;
+                };
+                if let Some(on_done) = on_done.take() {
+                    on_done(result);
+                }
+                true
+            }));
+        });
+    }
+
+    /// Apply the results of fetches that have finished
+    ///
+    /// This usually runs while a component renders, so readers are not
+    /// notified; they see the new data when they render.
+    pub(super) fn apply_finished() {
+        let finishers = FINISHERS.with(|finishers| std::mem::take(&mut *finishers.borrow_mut()));
+        if finishers.is_empty() {
+            return;
+        }
+
+        let was_applying = APPLYING.with(|applying| applying.replace(true));
+        let mut waiting: Vec<Finisher> = finishers
+            .into_iter()
+            .filter_map(|mut finish| (!finish()).then_some(finish))
+            .collect();
+        APPLYING.with(|applying| applying.set(was_applying));
+
+        FINISHERS.with(|finishers| {
+            let mut finishers = finishers.borrow_mut();
+            waiting.append(&mut finishers);
+            *finishers = waiting;
+        });
+    }
+
+    pub(super) fn is_applying() -> bool {
+        APPLYING.with(|applying| applying.get())
+    }
+
+    pub(super) async fn sleep(duration: Duration) {
+        tokio::time::sleep(duration).await;
+    }
+}
+
+#[cfg(target_family = "wasm")]
+mod settling {
+    use std::{
+        cell::{Cell, RefCell},
+        collections::HashMap,
+        future::Future,
+        pin::Pin,
+        rc::Rc,
+        task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+        time::Duration,
+    };
+
+    use tairitsu_vdom::dom_ops;
+
+    use super::{status_of, ResourceStatusCell};
+
+    type Task = Pin<Box<dyn Future<Output = ()>>>;
+
+    thread_local! {
+        static TASKS: RefCell<HashMap<usize, Task>> = RefCell::new(HashMap::new());
+        static NEXT_TASK: Cell<usize> = const { Cell::new(0) };
+    }
+
+    pub(super) fn spawn<T: 'static>(
+        future: impl Future<Output = Result<T, String>> + 'static,
+        status: ResourceStatusCell,
+        on_done: impl FnOnce(Result<T, String>) + 'static,
+    ) {
+        let id = NEXT_TASK.with(|next| {
+            let id = next.get();
+            next.set(id + 1);
+            id
+        });
+        let task = async move {
+            let result = future.await;
+            *status.lock().unwrap_or_else(|e| e.into_inner()) = status_of(&result);
+            on_done(result);
+        };
+        TASKS.with(|tasks| tasks.borrow_mut().insert(id, Box::pin(task)));
+        // Never poll inside the caller, which is usually a render.
+        schedule(id);
+    }
+
+    /// Results are applied as fetches finish, so there is nothing to do
+    pub(super) fn apply_finished() {}
+
+    pub(super) fn is_applying() -> bool {
+        false
+    }
+
+    pub(super) fn sleep(duration: Duration) -> Delay {
+        Delay {
+            ms: duration.as_millis().min(i32::MAX as u128) as i32,
+            done: Rc::new(Cell::new(false)),
+            started: false,
+        }
+    }
+
+    fn schedule(id: usize) {
+        dom_ops::set_timeout(Box::new(move || poll(id)), 0);
+    }
+
+    fn poll(id: usize) {
+        let Some(mut task) = TASKS.with(|tasks| tasks.borrow_mut().remove(&id)) else {
+            return;
+        };
+        let waker = task_waker(id);
+        if task
+            .as_mut()
+            .poll(&mut Context::from_waker(&waker))
+            .is_pending()
+        {
+            TASKS.with(|tasks| tasks.borrow_mut().insert(id, task));
+        }
+    }
+
+    // The waker data is the task id itself; it is never dereferenced.
+    const VTABLE: RawWakerVTable =
+        RawWakerVTable::new(clone_waker, wake_task, wake_task, drop_waker);
+
+    fn task_waker(id: usize) -> Waker {
+        // SAFETY: the vtable functions only use the data pointer as an id.
+        unsafe { Waker::from_raw(RawWaker::new(id as *const (), &VTABLE)) }
+    }
+
+    fn clone_waker(data: *const ()) -> RawWaker {
+        RawWaker::new(data, &VTABLE)
+    }
+
+    fn wake_task(data: *const ()) {
+        schedule(data as usize);
+    }
+
+    fn drop_waker(_: *const ()) {}
+
+    /// Resolves after a platform timeout
+    pub(super) struct Delay {
+        ms: i32,
+        done: Rc<Cell<bool>>,
+        started: bool,
+    }
+
+    impl Future for Delay {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.done.get() {
+                return Poll::Ready(());
+            }
+            if !self.started {
+                self.started = true;
+                let done = Rc::clone(&self.done);
+                let waker = cx.waker().clone();
+                dom_ops::set_timeout(
+                    Box::new(move || {
+                        done.set(true);
+                        waker.wake();
+                    }),
+                    self.ms,
+                );
+            }
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    fn wait_until(mut done: impl FnMut() -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !done() {
+            assert!(Instant::now() < deadline, "timed out waiting for queries");
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    fn counting_fetcher(
+        calls: &Arc<AtomicUsize>,
+    ) -> impl Fn() -> std::future::Ready<anyhow::Result<usize>> + Send + Sync + 'static {
+        let calls = Arc::clone(calls);
+        move || std::future::ready(Ok(calls.fetch_add(1, Ordering::SeqCst) + 1))
+    }
+
+    #[test]
+    fn test_queries_share_one_fetch_per_key() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let first = use_query("count", counting_fetcher(&calls));
+        let second = use_query("count", counting_fetcher(&calls));
+        assert!(first.read().is_loading());
+
+        wait_until(|| first.read().is_ready());
+        assert_eq!(second.read(), ResourceState::Ready(1));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(get_query_data::<usize>("count"), Some(1));
+    }
+
+    #[test]
+    fn test_failed_fetches_are_retried() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let fetcher = {
+            let calls = Arc::clone(&calls);
+            move || {
+                let attempt = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                async move {
+                    anyhow::ensure!(attempt >= 3, "attempt {} failed", attempt);
+                    Ok(attempt)
+                }
+            }
+        };
+        let options = QueryOptions::new().with_retry_delay(Duration::from_millis(1));
+        let query = use_query_with("flaky", options, fetcher);
+
+        wait_until(|| !query.read().is_loading());
+        assert_eq!(query.read(), ResourceState::Ready(3));
+
+        let failing = use_query_with("down", options.with_retry(0), || async {
+            Err::<u8, _>(anyhow::anyhow!("unreachable"))
+        });
+        wait_until(|| !failing.read().is_loading());
+        assert_eq!(failing.read(), ResourceState::Error("unreachable".into()));
+    }
+
+    #[test]
+    fn test_invalidate_keeps_stale_data_while_revalidating() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let query = use_query("stale", counting_fetcher(&calls));
+        wait_until(|| query.read().is_ready());
+
+        invalidate("stale");
+        assert!(query.is_fetching());
+        assert_eq!(query.read(), ResourceState::Ready(1));
+
+        wait_until(|| query.read() == ResourceState::Ready(2));
+        assert!(!query.is_fetching());
+    }
+
+    #[test]
+    fn test_invalidate_during_fetch_refetches_after_it_settles() {
+        let server = Arc::new(AtomicUsize::new(1));
+        let requests = Arc::new(AtomicUsize::new(0));
+        let released = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let fetcher = {
+            let (server, requests) = (Arc::clone(&server), Arc::clone(&requests));
+            let released = Arc::clone(&released);
+            move || {
+                // The response reflects the server at request time.
+                let value = server.load(Ordering::SeqCst);
+                requests.fetch_add(1, Ordering::SeqCst);
+                let released = Arc::clone(&released);
+                async move {
+                    while !released.load(Ordering::SeqCst) {
+                        std::thread::sleep(Duration::from_millis(1));
+                    }
+                    Ok(value)
+                }
+            }
+        };
+        let query = use_query("mutated", fetcher);
+        wait_until(|| requests.load(Ordering::SeqCst) == 1);
+
+        server.store(2, Ordering::SeqCst);
+        invalidate("mutated");
+        assert!(query.is_fetching());
+        released.store(true, Ordering::SeqCst);
+
+        wait_until(|| !query.is_fetching());
+        assert_eq!(query.read(), ResourceState::Ready(2));
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_clear_queries_drops_in_flight_results() {
+        use crate::suspense::{pending_resources, resource_state};
+
+        let requests = Arc::new(AtomicUsize::new(0));
+        let released = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let fetcher = {
+            let (requests, released) = (Arc::clone(&requests), Arc::clone(&released));
+            move || {
+                requests.fetch_add(1, Ordering::SeqCst);
+                let released = Arc::clone(&released);
+                async move {
+                    while !released.load(Ordering::SeqCst) {
+                        std::thread::sleep(Duration::from_millis(1));
+                    }
+                    Ok(1usize)
+                }
+            }
+        };
+        let _query = use_query("cleared", fetcher);
+        let mutation = use_mutation(|_: ()| async { Ok(()) });
+        wait_until(|| requests.load(Ordering::SeqCst) == 1);
+        let (resource_id, status) = with_existing_entry("cleared", |entry| {
+            (entry.resource_id, Arc::clone(&entry.status))
+        })
+        .unwrap();
+        assert_eq!(pending_resources(), 1);
+
+        clear_queries();
+        assert_eq!(resource_state(resource_id), None);
+        assert_eq!(pending_resources(), 0);
+
+        released.store(true, Ordering::SeqCst);
+        wait_until(|| *status.lock().unwrap() != ResourceStateOp::Loading);
+        assert_eq!(get_query_data::<usize>("cleared"), None);
+
+        let resource_id = mutation.inner.resource_id;
+        assert!(resource_state(resource_id).is_some());
+        drop(mutation);
+        assert_eq!(resource_state(resource_id), None);
+    }
+
+    #[test]
+    fn test_unmounted_queries_are_not_refetched() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let component = runtime::use_component(tairitsu_vdom::VNode::empty);
+        runtime::with_component(component, || {
+            use_query("unmounted", counting_fetcher(&calls))
+        });
+        wait_until(|| get_query_data::<usize>("unmounted").is_some());
+
+        runtime::cleanup_component(component);
+        invalidate("unmounted");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_optimistic_update_rolls_back_on_failure() {
+        set_query_data("todos", vec!["write docs".to_string()]);
+        let add = use_mutation(|_title: String| async { Err::<(), _>(anyhow::anyhow!("offline")) })
+            .optimistic("todos", |todos: &mut Vec<String>, title: &String| {
+                todos.push(title.clone())
+            });
+
+        add.mutate("ship it".into());
+        assert!(add.is_pending());
+        assert_eq!(
+            get_query_data::<Vec<String>>("todos").unwrap(),
+            ["write docs", "ship it"]
+        );
+
+        wait_until(|| !add.is_pending());
+        assert_eq!(add.state(), MutationState::Error("offline".into()));
+        assert_eq!(
+            get_query_data::<Vec<String>>("todos").unwrap(),
+            ["write docs"]
+        );
+    }
+
+    #[test]
+    fn test_dehydrated_queries_hydrate_another_cache() {
+        set_query_data("user", (7u32, "Ada".to_string()));
+        let entries = dehydrate_queries();
+        clear_queries();
+        assert_eq!(get_query_data::<(u32, String)>("user"), None);
+
+        hydrate_queries(entries);
+        assert_eq!(
+            get_query_data::<(u32, String)>("user"),
+            Some((7, "Ada".to_string()))
+        );
+
+        hydrate_queries_json(r#"{"settings":{"theme":"dark"}}"#).unwrap();
+        let settings: serde_json::Value = get_query_data("settings").unwrap();
+        assert_eq!(settings["theme"], "dark");
+    }
+}
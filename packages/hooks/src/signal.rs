@@ -42,6 +42,11 @@ impl<T: Clone + 'static> ReactiveSignal<T> {
         self.signal.get()
     }
 
+    /// Get the current value without tracking the signal as a dependency.
+    pub fn peek(&self) -> T {
+        self.signal.peek()
+    }
+
     /// Set a new value and trigger re-render.
     pub fn set(&self, value: T) {
         self.signal.set(value);
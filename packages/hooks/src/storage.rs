@@ -0,0 +1,810 @@
+//! Storage backends for persisted stores.
+//!
+//! A [`StorageBackend`] keeps string values by key; [`Store::persisted`]
+//! uses one to save a store's state and load it back on the next start.
+//! Three backends are provided:
+//!
+//! - [`LocalStorage`] — the browser's `localStorage`, kept in sync across
+//!   tabs through `storage` events
+//! - [`IdbStorage`] — an IndexedDB object store on any [`IdbOps`] platform
+//! - [`MemoryStorage`] — an in-memory map for tests and native targets
+//!
+//! # Schema versions
+//!
+//! State is saved as `{"version": N, "state": ...}`. When a store is built
+//! with a newer [`version`](PersistedStoreBuilder::version), the saved
+//! state is passed through the [migrations](PersistedStoreBuilder::migrate)
+//! for each version in between and written back. State that cannot be
+//! migrated or deserialized is ignored and the store keeps its initial
+//! value.
+//!
+//! # Example
+//!
+//! ```ignore
+//! #[derive(Clone, Default, Serialize, Deserialize)]
+//! struct Settings {
+//!     theme: String,
+//!     font_size: u32,
+//! }
+//!
+//! let settings = Store::<Settings>::persisted_builder("settings", LocalStorage)
+//!     .version(2)
+//!     .migrate(1, |mut state| {
+//!         state["font_size"] = 14.into();
+//!         Ok(state)
+//!     })
+//!     .build();
+//!
+//! settings.update(|s| s.theme = "dark".into()); // saved right away
+//! ```
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    marker::PhantomData,
+    rc::Rc,
+};
+
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use tairitsu_vdom::{IdbOps, StorageEvent};
+use tracing::warn;
+
+use crate::store::Store;
+
+/// Callback receiving a key's new value, or `None` if it was removed
+type ChangeFn = Rc<dyn Fn(Option<String>)>;
+
+/// Handler for `storage` events on `window`
+pub type StorageEventHandler = Box<dyn Fn(&StorageEvent)>;
+
+/// A schema migration from one version to the next
+type MigrationFn = Box<dyn Fn(Value) -> Result<Value>>;
+
+/// Watchers of keys, by watch id
+type Watchers = Vec<(usize, String, ChangeFn)>;
+
+/// Stops a [`StorageBackend::watch`] when dropped
+#[must_use = "the watch stops when the guard is dropped"]
+pub struct WatchGuard {
+    unwatch: Option<Box<dyn FnOnce()>>,
+}
+
+impl WatchGuard {
+    /// Run `unwatch` when the guard is dropped
+    pub fn new(unwatch: impl FnOnce() + 'static) -> Self {
+        Self {
+            unwatch: Some(Box::new(unwatch)),
+        }
+    }
+
+    /// A guard for a watch that needs no cleanup
+    pub fn none() -> Self {
+        Self { unwatch: None }
+    }
+}
+
+impl Drop for WatchGuard {
+    fn drop(&mut self) {
+        if let Some(unwatch) = self.unwatch.take() {
+            unwatch();
+        }
+    }
+}
+
+/// Key-value storage for [`Store::persisted`]
+pub trait StorageBackend: 'static {
+    /// Read `key` and pass its value to `on_load`
+    ///
+    /// Synchronous backends call `on_load` before returning.
+    fn load(&self, key: &str, on_load: Box<dyn FnOnce(Option<String>)>);
+
+    fn save(&self, key: &str, value: &str);
+
+    fn remove(&self, key: &str);
+
+    /// Call `on_change` whenever `key` is changed from outside, e.g. by
+    /// another tab, until the returned guard is dropped
+    ///
+    /// Changes made through this backend are not reported. The default
+    /// implementation never calls `on_change`.
+    fn watch(&self, key: &str, on_change: Box<dyn Fn(Option<String>)>) -> WatchGuard {
+        let _ = (key, on_change);
+        WatchGuard::none()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// MemoryStorage
+// ---------------------------------------------------------------------------
+
+/// In-memory storage; clones share the same map
+///
+/// [`write_external`](Self::write_external) simulates a change made by
+/// another tab.
+#[derive(Clone, Default)]
+pub struct MemoryStorage {
+    inner: Rc<RefCell<MemoryInner>>,
+}
+
+#[derive(Default)]
+struct MemoryInner {
+    values: HashMap<String, String>,
+    watchers: Watchers,
+    next_watch: usize,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &str) -> Option<String> {
+        self.inner.borrow().values.get(key).cloned()
+    }
+
+    /// Change `key` as another tab would, notifying watchers
+    pub fn write_external(&self, key: &str, value: Option<&str>) {
+        let watchers: Vec<ChangeFn> = {
+            let mut inner = self.inner.borrow_mut();
+            match value {
+                Some(value) => inner.values.insert(key.to_string(), value.to_string()),
+                None => inner.values.remove(key),
+            };
+            inner
+                .watchers
+                .iter()
+                .filter(|(_, watched, _)| watched == key)
+                .map(|(_, _, on_change)| on_change.clone())
+                .collect()
+        };
+        for on_change in watchers {
+            on_change(value.map(str::to_string));
+        }
+    }
+}
+
+impl StorageBackend for MemoryStorage {
+    fn load(&self, key: &str, on_load: Box<dyn FnOnce(Option<String>)>) {
+        on_load(self.get(key));
+    }
+
+    fn save(&self, key: &str, value: &str) {
+        self.inner
+            .borrow_mut()
+            .values
+            .insert(key.to_string(), value.to_string());
+    }
+
+    fn remove(&self, key: &str) {
+        self.inner.borrow_mut().values.remove(key);
+    }
+
+    fn watch(&self, key: &str, on_change: Box<dyn Fn(Option<String>)>) -> WatchGuard {
+        let id = {
+            let mut inner = self.inner.borrow_mut();
+            let id = inner.next_watch;
+            inner.next_watch += 1;
+            inner
+                .watchers
+                .push((id, key.to_string(), Rc::from(on_change)));
+            id
+        };
+        let inner = Rc::downgrade(&self.inner);
+        WatchGuard::new(move || {
+            if let Some(inner) = inner.upgrade() {
+                inner
+                    .borrow_mut()
+                    .watchers
+                    .retain(|(watch, ..)| *watch != id);
+            }
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// LocalStorage
+// ---------------------------------------------------------------------------
+
+/// Function pointers backing [`LocalStorage`]
+///
+/// Filled in by the platform layer during bootstrap.
+#[derive(Clone, Copy)]
+pub struct LocalStorageFuncs {
+    pub get_item: fn(&str) -> Option<String>,
+    pub set_item: fn(&str, &str),
+    pub remove_item: fn(&str),
+    /// Call the handler for every `storage` event on `window`
+    pub listen: fn(StorageEventHandler) -> Result<()>,
+}
+
+thread_local! {
+    static LOCAL_STORAGE_FUNCS: Cell<Option<LocalStorageFuncs>> = const { Cell::new(None) };
+    static LOCAL_STORAGE_WATCHERS: RefCell<Watchers> = const { RefCell::new(Vec::new()) };
+    static LOCAL_STORAGE_NEXT_WATCH: Cell<usize> = const { Cell::new(0) };
+    static LOCAL_STORAGE_LISTENING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Register the platform's `localStorage` functions
+pub fn register_local_storage(funcs: LocalStorageFuncs) {
+    LOCAL_STORAGE_FUNCS.with(|f| f.set(Some(funcs)));
+    if LOCAL_STORAGE_WATCHERS.with(|watchers| !watchers.borrow().is_empty()) {
+        LocalStorage::listen();
+    }
+}
+
+/// The browser's `localStorage`
+///
+/// Until the platform calls [`register_local_storage`] (e.g. during SSR or
+/// on native targets) it behaves as an empty storage that drops writes.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LocalStorage;
+
+impl LocalStorage {
+    fn funcs() -> Option<LocalStorageFuncs> {
+        LOCAL_STORAGE_FUNCS.with(Cell::get)
+    }
+
+    /// Subscribe to `storage` events once the platform is registered
+    fn listen() {
+        let Some(funcs) = Self::funcs() else {
+            return;
+        };
+        if LOCAL_STORAGE_LISTENING.with(|listening| listening.replace(true)) {
+            return;
+        }
+        if let Err(err) = (funcs.listen)(Box::new(Self::on_storage_event)) {
+            warn!("Failed to listen for storage events: {:#}", err);
+            // The next watch tries again.
+            LOCAL_STORAGE_LISTENING.with(|listening| listening.set(false));
+        }
+    }
+
+    fn on_storage_event(event: &StorageEvent) {
+        let watchers: Vec<ChangeFn> = LOCAL_STORAGE_WATCHERS.with(|watchers| {
+            watchers
+                .borrow()
+                .iter()
+                // A `None` key means the whole storage was cleared.
+                .filter(|(_, key, _)| event.key.as_ref().is_none_or(|changed| changed == key))
+                .map(|(_, _, on_change)| on_change.clone())
+                .collect()
+        });
+        for on_change in watchers {
+            on_change(event.new_value.clone());
+        }
+    }
+}
+
+impl StorageBackend for LocalStorage {
+    fn load(&self, key: &str, on_load: Box<dyn FnOnce(Option<String>)>) {
+        on_load(Self::funcs().and_then(|funcs| (funcs.get_item)(key)));
+    }
+
+    fn save(&self, key: &str, value: &str) {
+        if let Some(funcs) = Self::funcs() {
+            (funcs.set_item)(key, value);
+        }
+    }
+
+    fn remove(&self, key: &str) {
+        if let Some(funcs) = Self::funcs() {
+            (funcs.remove_item)(key);
+        }
+    }
+
+    fn watch(&self, key: &str, on_change: Box<dyn Fn(Option<String>)>) -> WatchGuard {
+        let id = LOCAL_STORAGE_NEXT_WATCH.with(|next| next.replace(next.get() + 1));
+        LOCAL_STORAGE_WATCHERS.with(|watchers| {
+            watchers
+                .borrow_mut()
+                .push((id, key.to_string(), Rc::from(on_change)));
+        });
+        Self::listen();
+        WatchGuard::new(move || {
+            let _ = LOCAL_STORAGE_WATCHERS.try_with(|watchers| {
+                watchers.borrow_mut().retain(|(watch, ..)| *watch != id);
+            });
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// IdbStorage
+// ---------------------------------------------------------------------------
+
+/// An IndexedDB object store
+///
+/// The database is opened when the backend is created, and upgraded to
+/// create the object store if it does not have it yet; operations issued
+/// before it is open are queued. IndexedDB has no change events, so
+/// [`watch`](StorageBackend::watch) is not supported.
+pub struct IdbStorage<P: IdbOps> {
+    inner: Rc<IdbInner<P>>,
+}
+
+type IdbOp<P> = Box<dyn FnOnce(&IdbInner<P>, Option<u64>)>;
+
+struct IdbInner<P: IdbOps> {
+    platform: P,
+    store_name: String,
+    db: Cell<IdbDatabase>,
+    pending: RefCell<Vec<IdbOp<P>>>,
+}
+
+#[derive(Clone, Copy)]
+enum IdbDatabase {
+    Opening,
+    Open(u64),
+    Failed,
+}
+
+impl<P: IdbOps> Clone for IdbStorage<P> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Rc::clone(&self.inner),
+        }
+    }
+}
+
+impl<P: IdbOps> IdbStorage<P> {
+    /// Use `store_name` in the IndexedDB database `database`
+    pub fn new(platform: P, database: &str, store_name: &str) -> Self {
+        let inner = Rc::new(IdbInner {
+            platform,
+            store_name: store_name.to_string(),
+            db: Cell::new(IdbDatabase::Opening),
+            pending: RefCell::new(Vec::new()),
+        });
+
+        let opened = inner.clone();
+        let database_name = database.to_string();
+        inner.platform.idb_open(
+            database,
+            None,
+            Some(store_name),
+            Box::new(move |result| {
+                let db = match result {
+                    Ok(db) => IdbDatabase::Open(db),
+                    Err(err) => {
+                        warn!("Failed to open IndexedDB {}: {}", database_name, err);
+                        IdbDatabase::Failed
+                    }
+                };
+                opened.db.set(db);
+                let pending = std::mem::take(&mut *opened.pending.borrow_mut());
+                for op in pending {
+                    opened.run(op);
+                }
+            }),
+        );
+
+        Self { inner }
+    }
+}
+
+impl<P: IdbOps> IdbInner<P> {
+    /// Run `op` with the database handle, or `None` if it failed to open
+    fn run(&self, op: IdbOp<P>) {
+        match self.db.get() {
+            IdbDatabase::Opening => self.pending.borrow_mut().push(op),
+            IdbDatabase::Open(db) => op(self, Some(db)),
+            IdbDatabase::Failed => op(self, None),
+        }
+    }
+}
+
+fn log_idb_error(action: &'static str, key: String) -> Box<dyn FnOnce(Result<(), String>)> {
+    Box::new(move |result| {
+        if let Err(err) = result {
+            warn!("Failed to {} IndexedDB key {}: {}", action, key, err);
+        }
+    })
+}
+
+impl<P: IdbOps> StorageBackend for IdbStorage<P> {
+    fn load(&self, key: &str, on_load: Box<dyn FnOnce(Option<String>)>) {
+        let key = key.to_string();
+        self.inner.run(Box::new(move |inner, db| {
+            let Some(db) = db else {
+                return on_load(None);
+            };
+            let on_result = {
+                let key = key.clone();
+                Box::new(move |result: Result<Option<String>, String>| {
+                    on_load(result.unwrap_or_else(|err| {
+                        warn!("Failed to read IndexedDB key {}: {}", key, err);
+                        None
+                    }))
+                })
+            };
+            inner
+                .platform
+                .idb_get(db, &inner.store_name, &key, on_result);
+        }));
+    }
+
+    fn save(&self, key: &str, value: &str) {
+        let (key, value) = (key.to_string(), value.to_string());
+        self.inner.run(Box::new(move |inner, db| {
+            if let Some(db) = db {
+                inner.platform.idb_put(
+                    db,
+                    &inner.store_name,
+                    &value,
+                    Some(&key),
+                    log_idb_error("write", key.clone()),
+                );
+            }
+        }));
+    }
+
+    fn remove(&self, key: &str) {
+        let key = key.to_string();
+        self.inner.run(Box::new(move |inner, db| {
+            if let Some(db) = db {
+                inner.platform.idb_delete(
+                    db,
+                    &inner.store_name,
+                    &key,
+                    log_idb_error("delete", key.clone()),
+                );
+            }
+        }));
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Persisted stores
+// ---------------------------------------------------------------------------
+
+/// The saved form of a store's state
+#[derive(Serialize, Deserialize)]
+struct Envelope<S> {
+    version: u32,
+    state: S,
+}
+
+/// Builder for a [`Store`] saved to a [`StorageBackend`]
+///
+/// Created by [`Store::persisted_builder`]. `I` tracks whether
+/// [`initial`](Self::initial) was called; without it, building requires
+/// `T: Default`.
+pub struct PersistedStoreBuilder<T, I = DefaultInitial> {
+    key: String,
+    backend: Rc<dyn StorageBackend>,
+    initial: I,
+    version: u32,
+    migrations: HashMap<u32, MigrationFn>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+/// Initial state of a [`PersistedStoreBuilder`]
+pub trait InitialState<T> {
+    fn into_state(self) -> T;
+}
+
+/// `T::default()`, the initial state unless one is given
+pub struct DefaultInitial;
+
+impl<T: Default> InitialState<T> for DefaultInitial {
+    fn into_state(self) -> T {
+        T::default()
+    }
+}
+
+/// Initial state given to [`PersistedStoreBuilder::initial`]
+pub struct Initial<T>(T);
+
+impl<T> InitialState<T> for Initial<T> {
+    fn into_state(self) -> T {
+        self.0
+    }
+}
+
+impl<T> PersistedStoreBuilder<T>
+where
+    T: Clone + Serialize + DeserializeOwned + 'static,
+{
+    pub(crate) fn new(key: String, backend: Rc<dyn StorageBackend>) -> Self {
+        Self {
+            key,
+            backend,
+            initial: DefaultInitial,
+            version: 1,
+            migrations: HashMap::new(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T, I> PersistedStoreBuilder<T, I>
+where
+    T: Clone + Serialize + DeserializeOwned + 'static,
+{
+    /// State used until saved state is loaded, and when it is removed
+    ///
+    /// Defaults to `T::default()`.
+    pub fn initial(self, initial: T) -> PersistedStoreBuilder<T, Initial<T>> {
+        PersistedStoreBuilder {
+            key: self.key,
+            backend: self.backend,
+            initial: Initial(initial),
+            version: self.version,
+            migrations: self.migrations,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Current schema version, 1 by default
+    pub fn version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Convert state saved with schema version `from` to version `from + 1`
+    pub fn migrate(
+        mut self,
+        from: u32,
+        migration: impl Fn(Value) -> Result<Value> + 'static,
+    ) -> Self {
+        self.migrations.insert(from, Box::new(migration));
+        self
+    }
+
+    /// Create the store and load the saved state
+    ///
+    /// Synchronous backends load before this returns; otherwise the saved
+    /// state replaces the initial one when it arrives, unless the store was
+    /// changed in the meantime. The store stops watching the backend once
+    /// every clone of it is dropped.
+    pub fn build(self) -> Store<T>
+    where
+        I: InitialState<T>,
+    {
+        let initial = self.initial.into_state();
+        let store = Store::new(initial.clone());
+        let persisted = Rc::new(Persisted {
+            key: self.key,
+            backend: self.backend,
+            initial,
+            version: self.version,
+            migrations: self.migrations,
+            applying: Cell::new(false),
+            changed: Cell::new(false),
+        });
+
+        // The backend only holds the store weakly, so the watch can end
+        // when the store is dropped.
+        let watch = {
+            let (watcher, store) = (persisted.clone(), store.downgrade());
+            persisted.backend.watch(
+                &persisted.key,
+                Box::new(move |saved| {
+                    if let Some(store) = store.upgrade() {
+                        watcher.apply(&store, saved.as_deref());
+                    }
+                }),
+            )
+        };
+
+        {
+            let persisted = persisted.clone();
+            store.subscribe(move |state: T| {
+                // Owned by the store's subscribers, so dropped with them
+                let _watch = &watch;
+                if persisted.applying.get() {
+                    return;
+                }
+                persisted.changed.set(true);
+                persisted.save(&state);
+            });
+        }
+
+        {
+            let (loader, store) = (persisted.clone(), store.downgrade());
+            let on_load = Box::new(move |saved: Option<String>| {
+                let Some(store) = store.upgrade() else {
+                    return;
+                };
+                if let Some(saved) = saved {
+                    if !loader.changed.get() {
+                        loader.apply(&store, Some(&saved));
+                    }
+                }
+            });
+            persisted.backend.load(&persisted.key, on_load);
+        }
+
+        store
+    }
+}
+
+/// Shared state of a persisted store's subscriptions
+struct Persisted<T> {
+    key: String,
+    backend: Rc<dyn StorageBackend>,
+    initial: T,
+    version: u32,
+    migrations: HashMap<u32, MigrationFn>,
+    /// Set while saved state is written into the store, so it isn't saved back
+    applying: Cell<bool>,
+    /// Whether the store changed before the saved state was loaded
+    changed: Cell<bool>,
+}
+
+impl<T> Persisted<T>
+where
+    T: Clone + Serialize + DeserializeOwned + 'static,
+{
+    fn save(&self, state: &T) {
+        let envelope = Envelope {
+            version: self.version,
+            state,
+        };
+        match serde_json::to_string(&envelope) {
+            Ok(json) => self.backend.save(&self.key, &json),
+            Err(err) => warn!("Failed to serialize store {}: {}", self.key, err),
+        }
+    }
+
+    /// Put saved state into the store, or the initial state if `None`
+    fn apply(&self, store: &Store<T>, saved: Option<&str>) {
+        let (state, migrated) = match saved {
+            None => (self.initial.clone(), false),
+            Some(saved) => match self.decode(saved) {
+                Ok(decoded) => decoded,
+                Err(err) => {
+                    warn!("Ignoring saved state of store {}: {:#}", self.key, err);
+                    return;
+                }
+            },
+        };
+
+        self.applying.set(true);
+        store.set(state.clone());
+        self.applying.set(false);
+
+        if migrated {
+            self.save(&state);
+        }
+    }
+
+    /// Parse saved state, migrating it to the current version
+    ///
+    /// Returns the state and whether it was migrated.
+    fn decode(&self, saved: &str) -> Result<(T, bool)> {
+        let envelope: Envelope<Value> =
+            serde_json::from_str(saved).context("Saved state is not a versioned envelope")?;
+        if envelope.version > self.version {
+            anyhow::bail!(
+                "Saved with schema version {}, newer than {}",
+                envelope.version,
+                self.version
+            );
+        }
+
+        let mut state = envelope.state;
+        for from in envelope.version..self.version {
+            let migration = self
+                .migrations
+                .get(&from)
+                .with_context(|| format!("No migration from schema version {}", from))?;
+            state = migration(state)
+                .with_context(|| format!("Migration from schema version {} failed", from))?;
+        }
+
+        let state = serde_json::from_value(state).context("Saved state does not match the type")?;
+        Ok((state, envelope.version != self.version))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tairitsu_vdom::MockPlatform;
+
+    use super::*;
+
+    #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Settings {
+        theme: String,
+        font_size: u32,
+    }
+
+    fn dark() -> Settings {
+        Settings {
+            theme: "dark".to_string(),
+            font_size: 14,
+        }
+    }
+
+    #[test]
+    fn test_persisted_store_round_trip_and_cross_tab_sync() {
+        let storage = MemoryStorage::new();
+        let store = Store::<Settings>::persisted("settings", storage.clone());
+        assert_eq!(store.get(), Settings::default());
+
+        store.set(dark());
+        let saved = storage.get("settings").unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&saved).unwrap(),
+            serde_json::json!({"version": 1, "state": {"theme": "dark", "font_size": 14}})
+        );
+
+        let reloaded = Store::<Settings>::persisted("settings", storage.clone());
+        assert_eq!(reloaded.get(), dark());
+
+        storage.write_external(
+            "settings",
+            Some(r#"{"version": 1, "state": {"theme": "light", "font_size": 12}}"#),
+        );
+        assert_eq!(reloaded.get().theme, "light");
+        storage.write_external("settings", Some("not json"));
+        assert_eq!(reloaded.get().theme, "light");
+        storage.write_external("settings", None);
+        assert_eq!(reloaded.get(), Settings::default());
+    }
+
+    #[test]
+    fn test_persisted_store_migrates_old_versions() {
+        let storage = MemoryStorage::new();
+        storage.save("settings", r#"{"version": 1, "state": {"theme": "dark"}}"#);
+
+        let store = Store::<Settings>::persisted_builder("settings", storage.clone())
+            .version(2)
+            .migrate(1, |mut state| {
+                state["font_size"] = 14.into();
+                Ok(state)
+            })
+            .build();
+        assert_eq!(store.get(), dark());
+        assert!(storage.get("settings").unwrap().contains(r#""version":2"#));
+
+        // Without a migration path the saved state is ignored.
+        let store = Store::<Settings>::persisted_builder("settings", storage)
+            .version(3)
+            .initial(Settings {
+                theme: "light".to_string(),
+                font_size: 12,
+            })
+            .build();
+        assert_eq!(store.get().theme, "light");
+    }
+
+    #[test]
+    fn test_dropped_store_stops_watching() {
+        #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+        struct Volume(u8);
+
+        let storage = MemoryStorage::new();
+        let store = Store::persisted_builder("volume", storage.clone())
+            .initial(Volume(5))
+            .build();
+        let watching = || storage.inner.borrow().watchers.len();
+        assert_eq!(watching(), 1);
+
+        let clone = store.clone();
+        drop(store);
+        storage.write_external("volume", Some(r#"{"version": 1, "state": 9}"#));
+        assert_eq!(clone.get(), Volume(9));
+
+        drop(clone);
+        assert_eq!(watching(), 0);
+    }
+
+    #[test]
+    fn test_idb_storage_loads_asynchronously() {
+        let platform = MockPlatform::new();
+        let storage = IdbStorage::new(platform.clone(), "app", "stores");
+
+        let store = Store::<Settings>::persisted("settings", storage.clone());
+        store.set(dark());
+        platform.advance_time(0);
+
+        let reloaded = Store::<Settings>::persisted("settings", storage);
+        assert_eq!(reloaded.get(), Settings::default());
+        platform.advance_time(0);
+        assert_eq!(reloaded.get(), dark());
+    }
+}
@@ -28,7 +28,15 @@
 //! }
 //! ```
 
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    rc::{Rc, Weak},
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::storage::{PersistedStoreBuilder, StorageBackend};
 
 /// Unique identifier for a store instance
 pub type StoreId = usize;
@@ -199,6 +207,61 @@ impl<T: Clone + 'static> Store<T> {
     pub fn subscriber_count(&self) -> usize {
         self.subscribers.borrow().len()
     }
+
+    /// A reference that does not keep the store alive
+    pub(crate) fn downgrade(&self) -> WeakStore<T> {
+        WeakStore {
+            id: self.id,
+            state: Rc::downgrade(&self.state),
+            subscribers: Rc::downgrade(&self.subscribers),
+        }
+    }
+}
+
+/// A [`Store`] reference that does not keep it alive
+pub(crate) struct WeakStore<T: Clone + 'static> {
+    id: StoreId,
+    state: Weak<RefCell<T>>,
+    subscribers: Weak<RefCell<HashMap<usize, SubscriberFn<T>>>>,
+}
+
+impl<T: Clone + 'static> WeakStore<T> {
+    /// The store, unless every clone of it was dropped
+    pub(crate) fn upgrade(&self) -> Option<Store<T>> {
+        Some(Store {
+            id: self.id,
+            state: self.state.upgrade()?,
+            subscribers: self.subscribers.upgrade()?,
+        })
+    }
+}
+
+impl<T: Clone + Serialize + DeserializeOwned + 'static> Store<T> {
+    /// Create a store saved to `backend` under `key`
+    ///
+    /// The state starts as `T::default()`, is replaced by the saved state
+    /// once loaded, and is saved on every change. See
+    /// [`storage`](crate::storage) for the available backends.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let settings = Store::<Settings>::persisted("settings", LocalStorage);
+    /// ```
+    pub fn persisted(key: impl Into<String>, backend: impl StorageBackend) -> Self
+    where
+        T: Default,
+    {
+        Self::persisted_builder(key, backend).build()
+    }
+
+    /// Configure a persisted store's initial state and schema migrations
+    pub fn persisted_builder(
+        key: impl Into<String>,
+        backend: impl StorageBackend,
+    ) -> PersistedStoreBuilder<T> {
+        PersistedStoreBuilder::new(key.into(), Rc::new(backend))
+    }
 }
 
 impl<T: Clone + 'static> AnyStore for Store<T> {
@@ -122,7 +122,7 @@ struct ResourceRegistry {
 /// Tracks only the state kind (no associated data).
 /// The actual typed data lives in `ResourceInner.thread_safe_state`.
 #[derive(Clone, Copy, PartialEq, Eq)]
-enum ResourceStateOp {
+pub(crate) enum ResourceStateOp {
     Loading,
     Ready,
     Error,
@@ -140,6 +140,15 @@ impl ResourceRegistry {
         }
     }
 
+    /// Forget every resource and boundary; IDs are not reused.
+    #[cfg(not(target_family = "wasm"))]
+    fn reset(&mut self) {
+        *self = Self {
+            next_id: self.next_id,
+            ..Self::new()
+        };
+    }
+
     fn register_resource(&mut self) -> ResourceId {
         let id = self.next_id;
         self.next_id += 1;
@@ -241,6 +250,42 @@ pub fn track_resource_dependency(resource_id: ResourceId, component_id: runtime:
     });
 }
 
+/// Registry status of a resource, writable from the thread that fetches it.
+pub(crate) type ResourceStatusCell = Arc<std::sync::Mutex<ResourceStateOp>>;
+
+/// Register a resource whose status is driven by the caller.
+///
+/// Used by queries and mutations so that Suspense boundaries and
+/// [`pending_resources`] see them like `use_resource` resources.
+pub(crate) fn register_shared_resource(
+    initial: ResourceStateOp,
+) -> (ResourceId, ResourceStatusCell) {
+    RESOURCE_REGISTRY.with(|registry| {
+        let mut reg = registry.borrow_mut();
+        let id = reg.register_resource();
+        let status = Arc::clone(&reg.resources[&id]);
+        *status.lock().unwrap_or_else(|e| e.into_inner()) = initial;
+        (id, status)
+    })
+}
+
+/// Forget a resource registered with [`register_shared_resource`].
+pub(crate) fn unregister_shared_resource(id: ResourceId) {
+    // Cached queries may be dropped while thread locals are torn down
+    let _ = RESOURCE_REGISTRY.try_with(|registry| {
+        let mut reg = registry.borrow_mut();
+        reg.resources.remove(&id);
+        reg.resource_dependencies.remove(&id);
+    });
+}
+
+/// Record a read of `resource_id` in the active suspense boundary.
+pub(crate) fn track_resource_access(resource_id: ResourceId) {
+    RESOURCE_REGISTRY.with(|registry| {
+        registry.borrow_mut().track_access(resource_id);
+    });
+}
+
 /// Check if any tracked resources are still loading.
 fn has_loading_resources(boundary_id: runtime::ComponentId) -> bool {
     RESOURCE_REGISTRY.with(|registry| {
@@ -422,7 +467,11 @@ where
     let component_id = runtime::use_component(VNode::empty);
 
     // Register the resource in the global registry
-    let resource_id = RESOURCE_REGISTRY.with(|registry| registry.borrow_mut().register_resource());
+    let (resource_id, registry_state) = RESOURCE_REGISTRY.with(|registry| {
+        let mut reg = registry.borrow_mut();
+        let id = reg.register_resource();
+        (id, Arc::clone(&reg.resources[&id]))
+    });
 
     // Track the dependency
     track_resource_dependency(resource_id, component_id);
@@ -465,31 +514,24 @@ where
                     Ok(value) => ResourceState::Ready(value),
                     Err(err) => ResourceState::Error(err.to_string()),
                 };
-                *thread_safe_state.lock().unwrap_or_else(|e| e.into_inner()) = new_state.clone();
-
-                let registry_state = match new_state {
+                let op = match &new_state {
                     ResourceState::Loading => ResourceStateOp::Loading,
                     ResourceState::Ready(_) => ResourceStateOp::Ready,
                     ResourceState::Error(_) => ResourceStateOp::Error,
                 };
+                *thread_safe_state.lock().unwrap_or_else(|e| e.into_inner()) = new_state;
 
-                let affected = RESOURCE_REGISTRY.with(|registry| {
-                    registry
-                        .borrow_mut()
-                        .update_resource(resource_id, registry_state)
-                });
-
-                for comp_id in affected {
-                    runtime::mark_dirty(comp_id);
-                }
-                runtime::flush_render();
+                // The registry and runtime are thread-local to the UI thread,
+                // so only the shared state is updated here; the owning thread
+                // observes it on its next render or via `pending_resources`.
+                *registry_state.lock().unwrap_or_else(|e| e.into_inner()) = op;
             }
         });
     }
 
     #[cfg(target_family = "wasm")]
     {
-        let _ = fetcher;
+        let _ = (fetcher, registry_state);
         tracing::warn!(
             "use_resource: async execution not supported on WASM targets without wasm-bindgen-futures. Resource will remain in Loading state."
         );
@@ -622,6 +664,32 @@ pub fn resource_state(id: ResourceId) -> Option<ResourceStatus> {
     })
 }
 
+/// Number of resources registered on this thread that are still loading.
+///
+/// Background fetches update this as they complete, so tests can poll it to
+/// wait for async data (see `testing::Screen::await_resources`).
+pub fn pending_resources() -> usize {
+    RESOURCE_REGISTRY.with(|registry| {
+        registry
+            .borrow()
+            .resources
+            .values()
+            .filter(|state| {
+                *state.lock().unwrap_or_else(|e| e.into_inner()) == ResourceStateOp::Loading
+            })
+            .count()
+    })
+}
+
+/// Forget all resources and suspense boundaries registered on this thread.
+///
+/// Used by `testing::Screen` so one test's resources do not count towards
+/// the next one's [`pending_resources`].
+#[cfg(not(target_family = "wasm"))]
+pub(crate) fn reset_resource_tracking() {
+    RESOURCE_REGISTRY.with(|registry| registry.borrow_mut().reset());
+}
+
 /// Suspense boundary state for tracking pending resources.
 ///
 /// This is a simplified version for backward compatibility.
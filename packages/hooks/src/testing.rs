@@ -0,0 +1,412 @@
+//! Testing-library style helpers for native component tests.
+//!
+//! [`render`] registers a component with the reactive runtime, renders it
+//! into a [`MockPlatform`] and returns a [`Screen`] for querying the patched
+//! tree and firing events. Renders scheduled by signal writes run on the
+//! mock's animation frame; every `fire_*` and [`Screen::advance_timers`] call
+//! flushes them, so assertions always see the up-to-date tree.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use tairitsu_hooks::testing::render;
+//! use tairitsu_macros::rsx;
+//! use tairitsu_vdom::Signal;
+//!
+//! let count = Signal::new(0);
+//! let screen = render({
+//!     let count = count.clone();
+//!     move || {
+//!         let value = count.get();
+//!         let label = format!("Clicked {} times", value);
+//!         let count = count.clone();
+//!         rsx! {
+//!             button { onclick: move |_| count.set(value + 1), "{label}" }
+//!         }
+//!     }
+//! });
+//!
+//! screen.fire_click(&screen.get_by_role("button"));
+//! screen.get_by_text("Clicked 1 times");
+//! ```
+//!
+//! The runtime's callbacks are thread-local, so only one [`Screen`] should be
+//! alive per test thread.
+
+use std::{
+    cell::Cell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use tairitsu_vdom::{
+    mock::MockPlatform, runtime, DomOps, GenericEvent, MockElement, Patch, QueryOps, TimerOps,
+    VNode,
+};
+
+use crate::suspense::{pending_resources, reset_resource_tracking};
+
+/// How long [`Screen::await_resources`] waits before giving up.
+pub const RESOURCE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Render `render_fn` as a root component into a fresh [`MockPlatform`].
+pub fn render<F>(render_fn: F) -> Screen
+where
+    F: FnMut() -> VNode + 'static,
+{
+    let platform = MockPlatform::new();
+    let component = runtime::use_component(render_fn);
+    let root_is_fragment = Rc::new(Cell::new(false));
+
+    let scheduler = platform.clone();
+    runtime::set_schedule_callback(move |render| {
+        scheduler.request_animation_frame(Box::new(move |_| render()));
+    });
+
+    let target = platform.clone();
+    let fragment = root_is_fragment.clone();
+    runtime::set_apply_patches_callback(move |id, patches| {
+        if id != component {
+            return;
+        }
+        // Patches address the node rendered for the root VNode; a fragment
+        // root has no node of its own, so its children live in the body.
+        let body = target.body();
+        let initial = matches!(patches.first(), Some(Patch::CreateNode { .. }));
+        let root = if initial || fragment.get() {
+            body
+        } else {
+            target.children(&body).first().copied().unwrap_or(body)
+        };
+        target.apply_patches(&root, &patches);
+        fragment.set(matches!(
+            runtime::get_current_vnode(id),
+            Some(VNode::Fragment(_))
+        ));
+    });
+
+    runtime::mark_dirty(component);
+    runtime::flush_render();
+
+    Screen {
+        platform,
+        component,
+    }
+}
+
+/// A rendered component and the mock document it lives in.
+///
+/// Dropping the screen cleans up the component's effects and subscriptions,
+/// uninstalls its runtime callbacks and forgets all tracked resources.
+pub struct Screen {
+    platform: MockPlatform,
+    component: runtime::ComponentId,
+}
+
+impl Screen {
+    /// The underlying platform, for lower-level queries and presets.
+    pub fn platform(&self) -> &MockPlatform {
+        &self.platform
+    }
+
+    /// The runtime ID of the rendered root component.
+    pub fn component_id(&self) -> runtime::ComponentId {
+        self.component
+    }
+
+    /// The element the component is rendered into.
+    pub fn container(&self) -> MockElement {
+        self.platform.body()
+    }
+
+    /// Serialized HTML of the rendered tree.
+    pub fn html(&self) -> String {
+        self.platform.html()
+    }
+
+    /// Text content of `element` and its descendants.
+    pub fn text(&self, element: &MockElement) -> String {
+        self.platform.text_content(element)
+    }
+
+    /// Value of an attribute on `element`.
+    pub fn attribute(&self, element: &MockElement, name: &str) -> Option<String> {
+        self.platform.get_attribute(element, name)
+    }
+
+    // -- Queries --------------------------------------------------------------
+
+    /// First element matching a CSS selector.
+    pub fn query_selector(&self, selector: &str) -> Option<MockElement> {
+        self.platform.query_selector(selector)
+    }
+
+    /// All innermost elements whose trimmed text equals `text`.
+    pub fn get_all_by_text(&self, text: &str) -> Vec<MockElement> {
+        self.platform.get_all_by_text(text)
+    }
+
+    /// The element whose trimmed text equals `text`, if any.
+    pub fn query_by_text(&self, text: &str) -> Option<MockElement> {
+        self.platform.get_by_text(text)
+    }
+
+    /// The element whose trimmed text equals `text`.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the current HTML if no element matches.
+    pub fn get_by_text(&self, text: &str) -> MockElement {
+        self.query_by_text(text)
+            .unwrap_or_else(|| self.fail(&format!("no element with text {:?}", text)))
+    }
+
+    /// All elements with the given ARIA role, explicit or implicit.
+    pub fn get_all_by_role(&self, role: &str) -> Vec<MockElement> {
+        self.platform
+            .query_all("*")
+            .into_iter()
+            .filter(|el| self.role_of(el).as_deref() == Some(role))
+            .collect()
+    }
+
+    /// The first element with the given ARIA role, if any.
+    pub fn query_by_role(&self, role: &str) -> Option<MockElement> {
+        self.get_all_by_role(role).into_iter().next()
+    }
+
+    /// The first element with the given ARIA role.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the current HTML if no element has the role.
+    pub fn get_by_role(&self, role: &str) -> MockElement {
+        self.query_by_role(role)
+            .unwrap_or_else(|| self.fail(&format!("no element with role {:?}", role)))
+    }
+
+    /// The element with the given role whose accessible name is `name`.
+    ///
+    /// The name is taken from `aria-label`, then the text content, then
+    /// `alt` and `title`.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the current HTML if no element matches.
+    pub fn get_by_role_named(&self, role: &str, name: &str) -> MockElement {
+        self.get_all_by_role(role)
+            .into_iter()
+            .find(|el| self.accessible_name(el) == name)
+            .unwrap_or_else(|| {
+                self.fail(&format!("no element with role {:?} named {:?}", role, name))
+            })
+    }
+
+    fn role_of(&self, element: &MockElement) -> Option<String> {
+        if let Some(role) = self.attribute(element, "role") {
+            return role.split_whitespace().next().map(str::to_string);
+        }
+        let tag = self.platform.tag_name(element)?;
+        implicit_role(&tag, |name| self.attribute(element, name)).map(str::to_string)
+    }
+
+    fn accessible_name(&self, element: &MockElement) -> String {
+        if let Some(label) = self.attribute(element, "aria-label") {
+            return label.trim().to_string();
+        }
+        let text = self.text(element);
+        if !text.trim().is_empty() {
+            return text.trim().to_string();
+        }
+        self.attribute(element, "alt")
+            .or_else(|| self.attribute(element, "title"))
+            .unwrap_or_default()
+    }
+
+    fn fail(&self, message: &str) -> ! {
+        panic!("{}\n\nRendered HTML:\n{}", message, self.html())
+    }
+
+    // -- Events and time ------------------------------------------------------
+
+    /// Click `element` and flush resulting renders.
+    ///
+    /// Returns `false` if a handler called `prevent_default`.
+    pub fn fire_click(&self, element: &MockElement) -> bool {
+        let result = self.platform.click(element);
+        self.flush();
+        result
+    }
+
+    /// Set `element`'s value, fire `input` and flush resulting renders.
+    pub fn fire_input(&self, element: &MockElement, value: &str) -> bool {
+        let result = self.platform.input(element, value);
+        self.flush();
+        result
+    }
+
+    /// Set `element`'s value, fire `change` and flush resulting renders.
+    pub fn fire_change(&self, element: &MockElement, value: &str) -> bool {
+        let result = self.platform.change(element, value);
+        self.flush();
+        result
+    }
+
+    /// Fire `keydown` with `key` and flush resulting renders.
+    pub fn fire_key_down(&self, element: &MockElement, key: &str) -> bool {
+        let result = self.platform.key_down(element, key);
+        self.flush();
+        result
+    }
+
+    /// Fire `submit` and flush resulting renders.
+    pub fn fire_submit(&self, element: &MockElement) -> bool {
+        let result = self.platform.submit(element);
+        self.flush();
+        result
+    }
+
+    /// Fire an event without a typed payload and flush resulting renders.
+    pub fn fire(&self, element: &MockElement, event: &str) -> bool {
+        let result = self.platform.dispatch_event(element, event, |init| {
+            Box::new(
+                GenericEvent::new()
+                    .event_type(event)
+                    .event_handle(init.handle.clone()),
+            )
+        });
+        self.flush();
+        result
+    }
+
+    /// Advance the virtual clock, running due timers and animation frames,
+    /// then flush renders.
+    pub fn advance_timers(&self, ms: u64) {
+        self.platform.advance_time(ms);
+        self.flush();
+    }
+
+    /// Apply any pending renders now.
+    pub fn flush(&self) {
+        runtime::flush_render();
+    }
+
+    /// Wait for every `use_resource`, `use_query` and mutation fetch to
+    /// settle, then re-render.
+    ///
+    /// Zero-delay timers are drained while waiting, so mock platform
+    /// callbacks (IndexedDB, file readers, …) also complete.
+    ///
+    /// # Panics
+    ///
+    /// Panics if resources are still loading after [`RESOURCE_TIMEOUT`].
+    pub fn await_resources(&self) {
+        let deadline = Instant::now() + RESOURCE_TIMEOUT;
+        loop {
+            self.advance_timers(0);
+            let pending = pending_resources();
+            if pending == 0 {
+                break;
+            }
+            if Instant::now() >= deadline {
+                self.fail(&format!(
+                    "{} resource(s) still loading after {:?}",
+                    pending, RESOURCE_TIMEOUT
+                ));
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        runtime::mark_dirty(self.component);
+        self.flush();
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        runtime::cleanup_component(self.component);
+        runtime::clear_callbacks();
+        reset_resource_tracking();
+    }
+}
+
+/// The implicit ARIA role of an element, per HTML-AAM.
+fn implicit_role(tag: &str, attribute: impl Fn(&str) -> Option<String>) -> Option<&'static str> {
+    let role = match tag {
+        "a" | "area" => return attribute("href").map(|_| "link"),
+        "input" => match attribute("type").as_deref().unwrap_or("text") {
+            "checkbox" => "checkbox",
+            "radio" => "radio",
+            "button" | "submit" | "reset" | "image" => "button",
+            "range" => "slider",
+            "number" => "spinbutton",
+            "search" => "searchbox",
+            "hidden" => return None,
+            _ => "textbox",
+        },
+        "select" if attribute("multiple").is_some() => "listbox",
+        "select" => "combobox",
+        "button" => "button",
+        "textarea" => "textbox",
+        "option" => "option",
+        "img" => "img",
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => "heading",
+        "ul" | "ol" | "menu" => "list",
+        "li" => "listitem",
+        "nav" => "navigation",
+        "main" => "main",
+        "header" => "banner",
+        "footer" => "contentinfo",
+        "aside" => "complementary",
+        "article" => "article",
+        "form" => "form",
+        "dialog" => "dialog",
+        "table" => "table",
+        "tr" => "row",
+        "td" => "cell",
+        "th" => "columnheader",
+        "progress" => "progressbar",
+        "hr" => "separator",
+        _ => return None,
+    };
+    Some(role)
+}
+
+#[cfg(test)]
+mod tests {
+    use tairitsu_vdom::{VNode, VText};
+
+    use super::*;
+    use crate::suspense::{register_shared_resource, ResourceStateOp};
+
+    #[test]
+    fn test_dropped_screen_leaves_no_state_behind() {
+        let screen = render(|| VNode::Text(VText::new("first")));
+        // A fetch that never settles before the screen goes away.
+        register_shared_resource(ResourceStateOp::Loading);
+        assert_eq!(pending_resources(), 1);
+        drop(screen);
+
+        assert_eq!(pending_resources(), 0);
+        let screen = render(|| VNode::Text(VText::new("second")));
+        screen.await_resources();
+        assert_eq!(screen.html(), "second");
+    }
+
+    #[test]
+    fn test_implicit_role() {
+        let none = |_: &str| None;
+        assert_eq!(implicit_role("button", none), Some("button"));
+        assert_eq!(implicit_role("h3", none), Some("heading"));
+        assert_eq!(implicit_role("a", none), None);
+        assert_eq!(
+            implicit_role("a", |n| (n == "href").then(|| "/".to_string())),
+            Some("link")
+        );
+        assert_eq!(implicit_role("input", none), Some("textbox"));
+        assert_eq!(
+            implicit_role("input", |n| (n == "type").then(|| "checkbox".to_string())),
+            Some("checkbox")
+        );
+        assert_eq!(implicit_role("div", none), None);
+    }
+}
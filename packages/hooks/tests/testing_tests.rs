@@ -0,0 +1,168 @@
+use tairitsu_hooks::{
+    consume_context, provide_context, testing::render, use_resource, ResourceState,
+};
+use tairitsu_macros::{component, rsx};
+use tairitsu_vdom::{Signal, TimerOps, VNode, VText};
+
+#[test]
+fn test_render_and_query() {
+    let screen = render(|| {
+        rsx! {
+            nav {
+                h1 { "Dashboard" }
+                a { href: "/settings", "Settings" }
+                button { "aria-label": "Close menu", "×" }
+            }
+        }
+    });
+
+    assert_eq!(screen.text(&screen.get_by_role("heading")), "Dashboard");
+    let link = screen.get_by_role_named("link", "Settings");
+    assert_eq!(
+        screen.attribute(&link, "href").as_deref(),
+        Some("/settings")
+    );
+    screen.get_by_role_named("button", "Close menu");
+    assert!(screen.query_by_text("Missing").is_none());
+}
+
+#[test]
+fn test_fire_click_rerenders_component() {
+    let count = Signal::new(0);
+    let screen = render({
+        let count = count.clone();
+        move || {
+            let value = count.get();
+            let count = count.clone();
+            let label = format!("Clicked {} times", value);
+            rsx! {
+                div {
+                    p { "{label}" }
+                    button { onclick: move |_| count.set(value + 1), "Increment" }
+                }
+            }
+        }
+    });
+
+    screen.get_by_text("Clicked 0 times");
+    screen.fire_click(&screen.get_by_role("button"));
+    screen.fire_click(&screen.get_by_role("button"));
+
+    screen.get_by_text("Clicked 2 times");
+    assert_eq!(count.get(), 2);
+}
+
+#[test]
+fn test_fragment_root_rerenders() {
+    let items = Signal::new(vec!["a"]);
+    let screen = render({
+        let items = items.clone();
+        move || {
+            VNode::Fragment(
+                items
+                    .get()
+                    .into_iter()
+                    .map(|item| rsx! { p { "{item}" } })
+                    .collect(),
+            )
+        }
+    });
+
+    assert_eq!(screen.html(), "<p>a</p>");
+    items.set(vec!["a", "b"]);
+    screen.flush();
+    assert_eq!(screen.html(), "<p>a</p><p>b</p>");
+}
+
+#[test]
+fn test_advance_timers() {
+    let status = Signal::new("waiting");
+    let screen = render({
+        let status = status.clone();
+        move || rsx! { span { "{status.get()}" } }
+    });
+
+    let setter = status.clone();
+    screen
+        .platform()
+        .set_timeout(Box::new(move || setter.set("done")), 1000);
+
+    screen.advance_timers(999);
+    screen.get_by_text("waiting");
+    screen.advance_timers(1);
+    screen.get_by_text("done");
+}
+
+#[test]
+fn test_await_resources() {
+    let resource = use_resource(|| async { Ok::<_, anyhow::Error>("loaded".to_string()) });
+    let screen = render(move || {
+        let text = match resource.read() {
+            ResourceState::Loading => "loading".to_string(),
+            ResourceState::Ready(value) => value,
+            ResourceState::Error(err) => err,
+        };
+        VNode::Text(VText::new(&text))
+    });
+
+    screen.await_resources();
+    assert_eq!(screen.html(), "loaded");
+}
+
+#[test]
+#[should_panic(expected = "no element with text")]
+fn test_get_by_text_panics_with_html() {
+    let screen = render(|| rsx! { p { "present" } });
+    screen.get_by_text("absent");
+}
+
+#[derive(Clone)]
+struct Theme(&'static str);
+
+#[component]
+fn ThemedPanel(
+    #[props(default)] theme: &'static str,
+    #[props(default)] prefix: &'static str,
+) -> VNode {
+    provide_context(Theme(theme));
+    rsx! { section { ThemeLabel { prefix: prefix } } }
+}
+
+#[component]
+fn ThemeLabel(
+    #[props(default)] prefix: &'static str,
+    #[props(default)] suffix: &'static str,
+) -> VNode {
+    let theme = consume_context::<Theme>().map_or("none", |theme| theme.0);
+    let label = format!("{}{}{}", prefix, theme, suffix);
+    rsx! { span { "{label}" } }
+}
+
+#[test]
+fn test_sibling_components_provide_separate_contexts() {
+    let count = Signal::new(0);
+    let screen = render({
+        let count = count.clone();
+        move || {
+            provide_context(Theme("default"));
+            let value = count.get();
+            let count = count.clone();
+            rsx! {
+                div {
+                    ThemedPanel { theme: "light" }
+                    ThemedPanel { theme: "dark" }
+                    ThemeLabel { prefix: "app:" }
+                    button { onclick: move |_| count.set(value + 1), "Rerender" }
+                }
+            }
+        }
+    });
+
+    let expected = "<div><section><span>light</span></section>\
+                    <section><span>dark</span></section>\
+                    <span>app:default</span><button>Rerender</button></div>";
+    assert_eq!(screen.html(), expected);
+
+    screen.fire_click(&screen.get_by_role("button"));
+    assert_eq!(screen.html(), expected);
+}
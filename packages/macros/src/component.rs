@@ -194,6 +194,15 @@ fn expand_component_impl(mut input: ItemFn) -> Result<TokenStream2> {
         }
     }
 
+    // Render the body in its own runtime scope, so contexts it provides
+    // stay within its subtree
+    let scope_return = match &input.sig.output {
+        syn::ReturnType::Type(_, ty) if !matches!(**ty, syn::Type::ImplTrait(_)) => {
+            quote! { -> #ty }
+        }
+        _ => quote! {},
+    };
+
     // Create the function
     let original_fn = if uses_existing_props {
         let props_type = existing_props_name
@@ -205,7 +214,7 @@ fn expand_component_impl(mut input: ItemFn) -> Result<TokenStream2> {
             #[allow(unused_variables)]
             #[allow(clippy::needless_update)]
             #fn_vis fn #fn_name(props: #props_type) #fn_return {
-                #fn_block
+                tairitsu_vdom::runtime::with_scope(move || #scope_return #fn_block)
             }
         }
     } else {
@@ -229,8 +238,10 @@ fn expand_component_impl(mut input: ItemFn) -> Result<TokenStream2> {
             #[allow(unused_variables)]
             #[allow(clippy::needless_update)]
             #fn_vis fn #fn_name(props: #props_name) #fn_return {
-                #(#prop_bindings)*
-                #fn_block
+                tairitsu_vdom::runtime::with_scope(move || #scope_return {
+                    #(#prop_bindings)*
+                    #fn_block
+                })
             }
         }
     };
@@ -1,5 +1,6 @@
 mod component;
 mod props_dsl;
+mod routable;
 mod rsx;
 mod scss;
 mod scss_include;
@@ -182,6 +183,35 @@ pub fn svg(input: TokenStream) -> TokenStream {
     expand_svg(input)
 }
 
+/// Derives `tairitsu_web::router::Routable` for an enum of typed routes
+///
+/// Each variant carries a `#[route("...")]` pattern. `:name` segments are
+/// parsed into the variant's field of the same name with `FromStr` and
+/// written back with `ToString`; every field must appear in the pattern.
+/// `*` segments are not supported, since `to_path` could not write them
+/// back; use a `:param` field instead.
+///
+/// The generated impl refers to `tairitsu_web`, which needs its `router`
+/// feature.
+///
+/// # Example
+/// ```ignore
+/// #[derive(Routable)]
+/// enum AppRoute {
+///     #[route("/")]
+///     Home,
+///     #[route("/users/:id")]
+///     User { id: u64 },
+/// }
+///
+/// assert!(matches!(AppRoute::from_path("/users/7?tab=1"), Some(AppRoute::User { id: 7 })));
+/// assert_eq!(AppRoute::User { id: 7 }.to_path(), "/users/7");
+/// ```
+#[proc_macro_derive(Routable, attributes(route))]
+pub fn derive_routable(input: TokenStream) -> TokenStream {
+    routable::expand_routable(input)
+}
+
 /// Derives WitCommand trait for an enum, automatically generating Response type and command routing
 ///
 /// # Example
@@ -0,0 +1,319 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitStr, Result, Variant};
+
+/// A parsed segment of a `#[route("...")]` pattern
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum PatternSegment {
+    /// Literal segment that must match exactly
+    Static(String),
+    /// `:name` segment bound to the field `name`
+    Param(String),
+}
+
+/// Split a route pattern into segments, ignoring empty ones
+///
+/// `*` segments are rejected: `to_path` would have no value to write back.
+pub(crate) fn parse_pattern(pattern: &LitStr) -> Result<Vec<PatternSegment>> {
+    pattern
+        .value()
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            if s == "*" {
+                Err(syn::Error::new_spanned(
+                    pattern,
+                    "`*` segments are not supported by #[derive(Routable)]; \
+                     capture the segment with a `:param` field instead",
+                ))
+            } else if let Some(name) = s.strip_prefix(':') {
+                Ok(PatternSegment::Param(name.to_string()))
+            } else {
+                Ok(PatternSegment::Static(s.to_string()))
+            }
+        })
+        .collect()
+}
+
+pub fn expand_routable(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = match syn::parse(input) {
+        Ok(input) => input,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    match expand_routable_impl(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand_routable_impl(input: DeriveInput) -> Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let Data::Enum(data_enum) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "#[derive(Routable)] can only be applied to enums",
+        ));
+    };
+
+    let mut patterns = Vec::new();
+    let mut match_blocks = Vec::new();
+    let mut path_arms = Vec::new();
+
+    for variant in &data_enum.variants {
+        let pattern = route_attr(variant)?;
+        let segments = parse_pattern(&pattern)?;
+        check_fields(variant, &pattern, &segments)?;
+
+        patterns.push(pattern.value());
+        match_blocks.push(expand_match_block(variant, &segments));
+        path_arms.push(expand_path_arm(variant, &segments));
+    }
+
+    Ok(quote! {
+        tairitsu_web::__require_router!();
+
+        impl #impl_generics tairitsu_web::router::Routable for #name #ty_generics #where_clause {
+            fn from_path(path: &str) -> ::std::option::Option<Self> {
+                let (__path, _, _) = tairitsu_web::router::split_url(path);
+                let __segments: ::std::vec::Vec<&str> =
+                    __path.split('/').filter(|s| !s.is_empty()).collect();
+                #(#match_blocks)*
+                ::std::option::Option::None
+            }
+
+            fn to_path(&self) -> ::std::string::String {
+                match self {
+                    #(#path_arms),*
+                }
+            }
+
+            fn patterns() -> &'static [&'static str] {
+                &[#(#patterns),*]
+            }
+        }
+    })
+}
+
+/// Read the `#[route("...")]` attribute of a variant
+fn route_attr(variant: &Variant) -> Result<LitStr> {
+    let mut found = None;
+    for attr in &variant.attrs {
+        if attr.path().is_ident("route") {
+            if found.is_some() {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "duplicate #[route] attribute",
+                ));
+            }
+            found = Some(attr.parse_args::<LitStr>()?);
+        }
+    }
+    found.ok_or_else(|| {
+        syn::Error::new_spanned(
+            &variant.ident,
+            "missing #[route(\"/path\")] attribute on Routable variant",
+        )
+    })
+}
+
+/// Ensure every `:param` has a field and every field has a `:param`
+fn check_fields(variant: &Variant, pattern: &LitStr, segments: &[PatternSegment]) -> Result<()> {
+    let params: Vec<&str> = segments
+        .iter()
+        .filter_map(|s| match s {
+            PatternSegment::Param(name) => Some(name.as_str()),
+            _ => None,
+        })
+        .collect();
+
+    let fields: Vec<String> = match &variant.fields {
+        Fields::Named(named) => named
+            .named
+            .iter()
+            .filter_map(|f| f.ident.as_ref().map(|i| i.to_string()))
+            .collect(),
+        Fields::Unit => Vec::new(),
+        Fields::Unnamed(_) => {
+            return Err(syn::Error::new_spanned(
+                &variant.fields,
+                "Routable variants must be unit or have named fields matching `:params`",
+            ));
+        }
+    };
+
+    for param in &params {
+        if !fields.iter().any(|f| f == param) {
+            return Err(syn::Error::new_spanned(
+                pattern,
+                format!("route parameter `:{}` has no matching field", param),
+            ));
+        }
+    }
+    for field in &fields {
+        if !params.contains(&field.as_str()) {
+            return Err(syn::Error::new_spanned(
+                &variant.fields,
+                format!(
+                    "field `{}` does not appear in the route as `:{}`",
+                    field, field
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Generate a labeled block returning the variant if `segments` matches
+fn expand_match_block(variant: &Variant, segments: &[PatternSegment]) -> TokenStream2 {
+    let ident = &variant.ident;
+    let len = segments.len();
+
+    let checks = segments
+        .iter()
+        .enumerate()
+        .map(|(i, segment)| match segment {
+            PatternSegment::Static(lit) => quote! {
+                if __segments[#i] != #lit {
+                    break 'variant;
+                }
+            },
+            PatternSegment::Param(name) => {
+                let field = syn::Ident::new(name, proc_macro2::Span::call_site());
+                quote! {
+                    let ::std::result::Result::Ok(#field) =
+                        tairitsu_web::router::query::decode_path_segment(__segments[#i]).parse()
+                    else {
+                        break 'variant;
+                    };
+                }
+            }
+        });
+
+    let construct = match &variant.fields {
+        Fields::Named(named) => {
+            let names = named.named.iter().map(|f| &f.ident);
+            quote! { Self::#ident { #(#names),* } }
+        }
+        _ => quote! { Self::#ident },
+    };
+
+    quote! {
+        'variant: {
+            if __segments.len() != #len {
+                break 'variant;
+            }
+            #(#checks)*
+            return ::std::option::Option::Some(#construct);
+        }
+    }
+}
+
+/// Generate the `to_path` arm for a variant
+fn expand_path_arm(variant: &Variant, segments: &[PatternSegment]) -> TokenStream2 {
+    let ident = &variant.ident;
+
+    let pattern = match &variant.fields {
+        Fields::Named(named) => {
+            let names = named.named.iter().map(|f| &f.ident);
+            quote! { Self::#ident { #(#names),* } }
+        }
+        _ => quote! { Self::#ident },
+    };
+
+    if segments.is_empty() {
+        return quote! { #pattern => ::std::string::String::from("/") };
+    }
+
+    let pushes = segments.iter().map(|segment| match segment {
+        PatternSegment::Static(lit) => quote! {
+            __path.push('/');
+            __path.push_str(#lit);
+        },
+        PatternSegment::Param(name) => {
+            let field = syn::Ident::new(name, proc_macro2::Span::call_site());
+            quote! {
+                __path.push('/');
+                __path.push_str(&tairitsu_web::router::query::encode_component(
+                    &::std::string::ToString::to_string(#field),
+                ));
+            }
+        }
+    });
+
+    quote! {
+        #pattern => {
+            let mut __path = ::std::string::String::new();
+            #(#pushes)*
+            __path
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn test_parse_pattern() {
+        assert_eq!(
+            parse_pattern(&parse_quote!("/users/:id/posts")).unwrap(),
+            vec![
+                PatternSegment::Static("users".to_string()),
+                PatternSegment::Param("id".to_string()),
+                PatternSegment::Static("posts".to_string()),
+            ]
+        );
+        assert!(parse_pattern(&parse_quote!("/")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_expand_rejects_wildcard() {
+        let input: DeriveInput = parse_quote! {
+            enum Route {
+                #[route("/files/*")]
+                Files,
+            }
+        };
+        let err = expand_routable_impl(input).unwrap_err();
+        assert!(err.to_string().contains("`*` segments are not supported"));
+    }
+
+    #[test]
+    fn test_expand_rejects_missing_field() {
+        let input: DeriveInput = parse_quote! {
+            enum Route {
+                #[route("/users/:id")]
+                User,
+            }
+        };
+        let err = expand_routable_impl(input).unwrap_err();
+        assert!(err.to_string().contains("`:id` has no matching field"));
+    }
+
+    #[test]
+    fn test_expand_rejects_unused_field() {
+        let input: DeriveInput = parse_quote! {
+            enum Route {
+                #[route("/users")]
+                Users { page: u32 },
+            }
+        };
+        let err = expand_routable_impl(input).unwrap_err();
+        assert!(err.to_string().contains("field `page`"));
+    }
+
+    #[test]
+    fn test_expand_requires_route_attr() {
+        let input: DeriveInput = parse_quote! {
+            enum Route {
+                Home,
+            }
+        };
+        assert!(expand_routable_impl(input).is_err());
+    }
+}
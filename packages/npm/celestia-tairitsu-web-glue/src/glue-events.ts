@@ -111,6 +111,12 @@ globalThis.__nextEventHandle = globalThis.__nextEventHandle || 1n;
 // to WASM to prevent infinite recursion when a handler triggers the same event type.
 globalThis.__dispatchingEvents = globalThis.__dispatchingEvents || new Set<string>();
 
+// Handle 0 is reserved for the global `window`, which never lives in the
+// element table. Used for `popstate` / `hashchange` subscriptions.
+function lookupEventTarget(target: bigint): any {
+  return target === 0n ? window : globalThis.__lookupElement(target);
+}
+
 export const eventTarget_exports = {
   /**
    * Add an event listener to an element.
@@ -121,7 +127,7 @@ export const eventTarget_exports = {
    */
   addEventListener(target: bigint, eventType: string, useCapture: boolean): bigint | string {
     try {
-      const element = globalThis.__lookupElement(target);
+      const element = lookupEventTarget(target);
 
       const listener = function (event: Event) {
         if (globalThis.__wasmExports) {
@@ -216,7 +222,7 @@ export const eventTarget_exports = {
    */
   removeEventListener(target: bigint, eventType: string, listenerHandle: bigint): void {
     try {
-      const element = globalThis.__lookupElement(target);
+      const element = lookupEventTarget(target);
       const listenerInfo = globalThis.__listenerHandles.get(listenerHandle);
 
       if (listenerInfo && listenerInfo.element === element && listenerInfo.type === eventType) {
@@ -3,7 +3,12 @@
 //! This module provides a generic container implementation that can run any WASM component.
 //! Users need to implement WIT interface bindings and initialization themselves.
 
+#[cfg(feature = "dynamic")]
+use std::{collections::HashSet, sync::Arc, time::Duration};
+use std::{future::Future, pin::Pin};
+
 use anyhow::{Context as AnyhowContext, Result};
+use bytes::Bytes;
 
 use wasmtime::{
     component::{Component, Linker},
@@ -13,8 +18,15 @@ use wasmtime::{
 use wasmtime_wasi::{ResourceTable, WasiCtx, WasiCtxBuilder, WasiCtxView, WasiView};
 
 #[cfg(feature = "dynamic")]
-use crate::dynamic::host_imports::HostImportRegistry;
-use crate::Image;
+use wasmtime::component::{Instance, InstancePre, Resource, ResourceAny, Val};
+
+#[cfg(feature = "dynamic")]
+use crate::{dynamic::host_imports::HostImportRegistry, epoch::MAX_TICKS, Timeout};
+use crate::{
+    limits::{ContainerLimits, LimitConfig, LimitKind},
+    logs::LogSource,
+    CapturedOutput, ContainerLogs, Image, LogEntry, Snapshot, WasiPolicy, WitLoader,
+};
 
 /// Base trait for host state
 ///
@@ -22,50 +34,114 @@ use crate::Image;
 pub trait HostStateImpl: WasiView + Send + 'static {
     /// Get user-defined state
     fn as_any_mut(&mut self) -> &mut dyn std::any::Any;
+
+    /// Resource limiter backing [`ContainerBuilder::with_memory_limit`] and
+    /// friends
+    ///
+    /// Return `None` (the default) if the state has no [`ContainerLimits`];
+    /// building a container with limits then fails.
+    fn limits(&mut self) -> Option<&mut ContainerLimits> {
+        None
+    }
+
+    /// Buffer holding the guest's stdout and stderr, see [`Container::logs`]
+    ///
+    /// Return `None` (the default) if the state does not capture output.
+    fn logs(&self) -> Option<&ContainerLogs> {
+        None
+    }
 }
 
 /// Default host state implementation
 ///
-/// Provides basic WASI support, users can extend through inheritance or composition
+/// Provides basic WASI support, users can extend through inheritance or composition.
+/// Guest stdout and stderr are captured into the container's logs, see
+/// [`Container::logs`].
 pub struct HostState {
     wasi: WasiCtx,
     table: ResourceTable,
+    limits: ContainerLimits,
+    logs: ContainerLogs,
+    captured: CapturedOutput,
 }
 
 impl HostState {
     /// Create a new host state with no inherited capabilities
     ///
-    /// The returned state has no stdio, network, or filesystem access by default.
+    /// The returned state has no stdin, network, or filesystem access by default,
+    /// and its stdout and stderr only reach [`Container::logs`].
     /// Use [`HostState::with_wasi`] or [`HostState::default`] if you need
     /// to customise WASI capabilities explicitly.
     pub fn new() -> Result<Self> {
-        let wasi = WasiCtxBuilder::new().build();
-
-        let table = ResourceTable::new();
-
-        Ok(Self { wasi, table })
+        Ok(Self::default())
     }
 
     /// Create host state with custom WASI configuration
+    ///
+    /// Stdout and stderr go to the container's logs unless `f` redirects them.
     pub fn with_wasi<F>(f: F) -> Result<Self>
     where
         F: FnOnce(&mut WasiCtxBuilder) -> &mut WasiCtxBuilder,
     {
-        let mut builder = WasiCtxBuilder::new();
+        let logs = ContainerLogs::default();
+        let mut builder = wasi_builder(&logs);
         f(&mut builder);
-        let wasi = builder.build();
+        Ok(Self::from_builder(builder, logs, CapturedOutput::default()))
+    }
+
+    /// Create host state granting the capabilities of a [`WasiPolicy`]
+    ///
+    /// # Example
+    /// ```ignore
+    /// let policy = WasiPolicy::from_toml_file("plugins/echo.toml")?;
+    /// let state = HostState::with_policy(&policy)?;
+    /// ```
+    pub fn with_policy(policy: &WasiPolicy) -> Result<Self> {
+        Self::with_policy_and_logs(policy, ContainerLogs::default())
+    }
+
+    /// Like [`with_policy`](Self::with_policy), logging into an existing buffer
+    ///
+    /// Output the policy captures in its own buffers does not reach `logs`.
+    pub fn with_policy_and_logs(policy: &WasiPolicy, logs: ContainerLogs) -> Result<Self> {
+        let mut builder = wasi_builder(&logs);
+        let captured = policy.apply(&mut builder)?;
+        Ok(Self::from_builder(builder, logs, captured))
+    }
 
-        let table = ResourceTable::new();
+    /// Guest stdout and stderr captured by the policy's buffers
+    pub fn captured_output(&self) -> &CapturedOutput {
+        &self.captured
+    }
 
-        Ok(Self { wasi, table })
+    fn from_builder(
+        mut builder: WasiCtxBuilder,
+        logs: ContainerLogs,
+        captured: CapturedOutput,
+    ) -> Self {
+        Self {
+            wasi: builder.build(),
+            table: ResourceTable::new(),
+            limits: ContainerLimits::default(),
+            logs,
+            captured,
+        }
     }
 }
 
+/// A WASI builder sending stdout and stderr to `logs`
+fn wasi_builder(logs: &ContainerLogs) -> WasiCtxBuilder {
+    let mut builder = WasiCtxBuilder::new();
+    builder
+        .stdout(logs.stream(LogSource::Stdout))
+        .stderr(logs.stream(LogSource::Stderr));
+    builder
+}
+
 impl Default for HostState {
     fn default() -> Self {
-        let wasi = WasiCtxBuilder::new().build();
-        let table = ResourceTable::new();
-        Self { wasi, table }
+        let logs = ContainerLogs::default();
+        Self::from_builder(wasi_builder(&logs), logs, CapturedOutput::default())
     }
 }
 
@@ -82,6 +158,14 @@ impl HostStateImpl for HostState {
     fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
         self
     }
+
+    fn limits(&mut self) -> Option<&mut ContainerLimits> {
+        Some(&mut self.limits)
+    }
+
+    fn logs(&self) -> Option<&ContainerLogs> {
+        Some(&self.logs)
+    }
 }
 
 /// Handle to a guest instance
@@ -183,6 +267,22 @@ impl<'a, T: HostStateImpl> GuestHandlerContext<'a, T> {
     }
 }
 
+/// Boxed, `Send` future returned by async guest initializers and host imports.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Synchronous guest initializer stored by [`ContainerBuilder`].
+type GuestInitializer<T> = Box<
+    dyn for<'a> FnOnce(GuestHandlerContext<'a, T>) -> Result<GuestInstance, anyhow::Error> + Send,
+>;
+
+/// Async guest initializer stored by [`ContainerBuilder`].
+type AsyncGuestInitializer<T> = Box<
+    dyn for<'a> FnOnce(
+            GuestHandlerContext<'a, T>,
+        ) -> BoxFuture<'a, Result<GuestInstance, anyhow::Error>>
+        + Send,
+>;
+
 /// Container builder
 ///
 /// Used to configure and create a WASM container instance.
@@ -212,20 +312,26 @@ impl<'a, T: HostStateImpl> GuestHandlerContext<'a, T> {
 ///     })
 ///     .build()?;
 /// ```
+///
+/// # Async mode
+///
+/// [`build_async`](ContainerBuilder::build_async) produces a container whose
+/// guest calls are futures ([`Container::call_guest_raw_desc_async`],
+/// [`Container::call_guest_binary_async`]) instead of blocking the calling
+/// thread. WASI and async host imports are linked with their async bindings.
 pub struct ContainerBuilder<T: HostStateImpl> {
     image: Image,
     host_state: T,
     fuel_limit: Option<u64>,
     epoch_deadline: Option<u64>,
+    limits: LimitConfig,
     #[allow(clippy::type_complexity)]
     host_linker_init: Option<Box<dyn FnOnce(&mut Linker<T>) -> Result<(), anyhow::Error> + Send>>,
-    #[allow(clippy::type_complexity)]
-    guest_initializer: Option<
-        Box<
-            dyn for<'a> FnOnce(GuestHandlerContext<'a, T>) -> Result<GuestInstance, anyhow::Error>
-                + Send,
-        >,
-    >,
+    guest_initializer: Option<GuestInitializer<T>>,
+    async_guest_initializer: Option<AsyncGuestInitializer<T>>,
+    snapshot: Option<Bytes>,
+    #[cfg(feature = "dynamic")]
+    host_imports: Option<HostImportRegistry>,
 }
 
 impl<T: HostStateImpl> ContainerBuilder<T>
@@ -239,12 +345,34 @@ where
             host_state: T::default(),
             fuel_limit: None,
             epoch_deadline: None,
+            limits: LimitConfig::default(),
             host_linker_init: None,
             guest_initializer: None,
+            async_guest_initializer: None,
+            snapshot: None,
+            #[cfg(feature = "dynamic")]
+            host_imports: None,
         }
     }
 }
 
+impl ContainerBuilder<HostState> {
+    /// Grant the guest the capabilities of a [`WasiPolicy`]
+    ///
+    /// Replaces the host state with [`HostState::with_policy`].
+    ///
+    /// # Example
+    /// ```ignore
+    /// let container = Container::builder(image)
+    ///     .with_wasi_policy(&WasiPolicy::from_toml_file("plugin.toml")?)?
+    ///     .with_guest_initializer(|ctx| { /* ... */ })
+    ///     .build()?;
+    /// ```
+    pub fn with_wasi_policy(self, policy: &WasiPolicy) -> Result<Self> {
+        Ok(self.with_host_state(HostState::with_policy(policy)?))
+    }
+}
+
 impl<T: HostStateImpl> ContainerBuilder<T> {
     /// Use custom host state
     pub fn with_host_state(mut self, state: T) -> Self {
@@ -273,12 +401,44 @@ impl<T: HostStateImpl> ContainerBuilder<T> {
     ///
     /// The deadline counts down each time [`Engine::increment_epoch`] is called.
     /// When it reaches zero the guest traps, allowing the host to implement
-    /// time-based timeouts.
+    /// time-based timeouts. [`Image::start_epoch_ticker`] increments the epoch
+    /// from a background thread; for a timeout on a single call see
+    /// [`Container::call_guest_raw_desc_with_timeout`].
     pub fn with_epoch_deadline(mut self, deadline: u64) -> Self {
         self.epoch_deadline = Some(deadline);
         self
     }
 
+    /// Limit each linear memory of the guest to `bytes`.
+    ///
+    /// Growing a memory past the limit fails (`memory.grow` returns -1).
+    /// If the guest then traps, the container enters
+    /// [`ContainerState::Error`] with [`ErrorCause::ResourceLimit`].
+    ///
+    /// Limits require a host state that provides [`HostStateImpl::limits`],
+    /// as the default [`HostState`] does.
+    pub fn with_memory_limit(mut self, bytes: usize) -> Self {
+        self.limits.memory_size = Some(bytes);
+        self
+    }
+
+    /// Limit each table of the guest to `elements` entries.
+    ///
+    /// Behaves like [`with_memory_limit`](Self::with_memory_limit) for
+    /// `table.grow`.
+    pub fn with_table_limit(mut self, elements: usize) -> Self {
+        self.limits.table_elements = Some(elements);
+        self
+    }
+
+    /// Limit the number of core and component instances in the store.
+    ///
+    /// Exceeding it makes instantiation, and so the build, fail.
+    pub fn with_instance_limit(mut self, instances: usize) -> Self {
+        self.limits.instances = Some(instances);
+        self
+    }
+
     /// Register additional host imports with the linker before guest instantiation.
     ///
     /// This is called after WASI is added to the linker but before the guest
@@ -302,6 +462,28 @@ impl<T: HostStateImpl> ContainerBuilder<T> {
         self
     }
 
+    /// Serve the guest's imports from a [`HostImportRegistry`]
+    ///
+    /// The registry is linked after [`with_host_linker`](Self::with_host_linker)
+    /// runs and before the guest is instantiated, and is also available to
+    /// [`Container::call_host_import_raw_desc`] afterwards.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let registry = HostImportRegistry::from_wit(&loader, "example:host/math")
+    ///     .handler("add", |(a, b): (u32, u32)| Ok(a + b))
+    ///     .build(&image)?;
+    /// let container = Container::builder(image)
+    ///     .with_host_imports(registry)
+    ///     .with_guest_initializer(|ctx| { /* ... */ })
+    ///     .build()?;
+    /// ```
+    #[cfg(feature = "dynamic")]
+    pub fn with_host_imports(mut self, registry: HostImportRegistry) -> Self {
+        self.host_imports = Some(registry);
+        self
+    }
+
     /// Set guest instance initializer
     ///
     /// # Arguments
@@ -331,55 +513,232 @@ impl<T: HostStateImpl> ContainerBuilder<T> {
         self
     }
 
+    /// Set async guest instance initializer
+    ///
+    /// Used by [`build_async`](ContainerBuilder::build_async). The returned
+    /// future may borrow the context, so instantiation can be awaited with
+    /// `instantiate_async`.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let container = Container::builder(image)
+    ///     .with_async_guest_initializer(|ctx| {
+    ///         Box::pin(async move {
+    ///             let instance = ctx
+    ///                 .linker
+    ///                 .instantiate_async(&mut *ctx.store, ctx.component)
+    ///                 .await?;
+    ///             Ok(GuestInstance::new_dynamic(instance))
+    ///         })
+    ///     })
+    ///     .build_async()
+    ///     .await?;
+    /// ```
+    pub fn with_async_guest_initializer<F>(mut self, f: F) -> Self
+    where
+        F: for<'a> FnOnce(
+                GuestHandlerContext<'a, T>,
+            ) -> BoxFuture<'a, Result<GuestInstance, anyhow::Error>>
+            + Send
+            + 'static,
+    {
+        self.async_guest_initializer = Some(Box::new(f));
+        self
+    }
+
+    /// Restore guest state from a [`Container::snapshot`] once the guest is
+    /// instantiated
+    ///
+    /// [`build`](Self::build) fails if the snapshot is malformed, was taken
+    /// from a different image, or is rejected by the guest's `load-state`
+    /// export. Snapshots are not supported by
+    /// [`build_async`](Self::build_async).
+    ///
+    /// # Example
+    /// ```ignore
+    /// let container = Container::builder(image)
+    ///     .from_snapshot(std::fs::read("plugin.snap")?)
+    ///     .with_guest_initializer(|ctx| { /* ... */ })
+    ///     .build()?;
+    /// ```
+    pub fn from_snapshot(mut self, snapshot: impl Into<Bytes>) -> Self {
+        self.snapshot = Some(snapshot.into());
+        self
+    }
+
     /// Build container
-    pub fn build(self) -> Result<Container<T>> {
-        let mut store = Store::new(self.image.engine(), self.host_state);
+    pub fn build(mut self) -> Result<Container<T>> {
+        if self.async_guest_initializer.is_some() {
+            anyhow::bail!("An async guest initializer is set. Use build_async() instead.");
+        }
+        // Check the envelope before paying for instantiation
+        let snapshot = self.snapshot.take();
+        if let Some(snapshot) = &snapshot {
+            Snapshot::decode(snapshot)?.check_image(&self.image)?;
+        }
+        let initializer = self.guest_initializer.take().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Guest initializer is required. Use with_guest_initializer() to set it."
+            )
+        })?;
+
+        #[cfg(feature = "dynamic")]
+        let (epoch_deadline, configure) = (self.epoch_deadline, self.store_configuration());
+        #[cfg(feature = "dynamic")]
+        let host_imports = self.host_imports.clone();
+        let (mut store, mut linker, image) = self.prepare(false)?;
+        let ctx = GuestHandlerContext::new(&mut linker, &mut store, image.component());
+        let guest_instance = initializer(ctx)?;
+
+        #[cfg(feature = "dynamic")]
+        let respawn = dynamic_respawn(&linker, &image, &guest_instance, false, configure);
+
+        let mut container = Container::from_parts(store, image, guest_instance, false);
+        #[cfg(feature = "dynamic")]
+        {
+            container = container.with_recovery(epoch_deadline, respawn);
+            if let Some(registry) = host_imports {
+                container.with_host_import_registry(registry);
+            }
+        }
+        if let Some(snapshot) = snapshot {
+            AnyhowContext::context(
+                container.restore(&snapshot),
+                "Failed to restore container snapshot",
+            )?;
+        }
+
+        Ok(container)
+    }
 
-        if let Some(fuel) = self.fuel_limit {
-            store
-                .set_fuel(fuel)
-                .context("Failed to set fuel limit (ensure Image was created with consume_fuel(true) in Config)")?;
+    /// Build container in async mode
+    ///
+    /// Runs the async guest initializer if one is set, otherwise the
+    /// synchronous one.
+    pub async fn build_async(mut self) -> Result<Container<T>> {
+        if self.snapshot.is_some() {
+            anyhow::bail!("Snapshots can only be restored by build(), not build_async()");
         }
-        if let Some(deadline) = self.epoch_deadline {
-            store.set_epoch_deadline(deadline);
+        let async_initializer = self.async_guest_initializer.take();
+        let initializer = self.guest_initializer.take();
+        if async_initializer.is_none() && initializer.is_none() {
+            anyhow::bail!(
+                "Guest initializer is required. Use with_async_guest_initializer() to set it."
+            );
         }
 
+        #[cfg(feature = "dynamic")]
+        let (epoch_deadline, configure) = (self.epoch_deadline, self.store_configuration());
+        #[cfg(feature = "dynamic")]
+        let host_imports = self.host_imports.clone();
+        let (mut store, mut linker, image) = self.prepare(true)?;
+        let ctx = GuestHandlerContext::new(&mut linker, &mut store, image.component());
+        let guest_instance = match (async_initializer, initializer) {
+            (Some(init), _) => init(ctx).await?,
+            (None, Some(init)) => init(ctx)?,
+            (None, None) => unreachable!("checked above"),
+        };
+
+        #[cfg(feature = "dynamic")]
+        let respawn = dynamic_respawn(&linker, &image, &guest_instance, true, configure);
+
+        let container = Container::from_parts(store, image, guest_instance, true);
+        #[cfg(feature = "dynamic")]
+        let container = {
+            let mut container = container.with_recovery(epoch_deadline, respawn);
+            if let Some(registry) = host_imports {
+                container.with_host_import_registry(registry);
+            }
+            container
+        };
+
+        Ok(container)
+    }
+
+    /// Applies this builder's limits to the fresh store of a respawned guest
+    #[cfg(feature = "dynamic")]
+    fn store_configuration(&self) -> impl Fn(&mut Store<T>) -> Result<()> + Send + Sync + 'static {
+        let (fuel_limit, epoch_deadline, limits) =
+            (self.fuel_limit, self.epoch_deadline, self.limits);
+        move |store| configure_store(store, fuel_limit, epoch_deadline, limits)
+    }
+
+    /// Create the store and linker shared by both build modes.
+    fn prepare(self, async_support: bool) -> Result<(Store<T>, Linker<T>, Image)> {
+        let mut store = Store::new(self.image.engine(), self.host_state);
+        configure_store(
+            &mut store,
+            self.fuel_limit,
+            self.epoch_deadline,
+            self.limits,
+        )?;
+
         let mut linker = Linker::new(self.image.engine());
-        wasmtime_wasi::p2::add_to_linker_sync(&mut linker)
-            .context("Failed to add WASI to linker")?;
+        add_wasi_to_linker(&mut linker, async_support)?;
 
         // Apply custom host linker configuration (e.g. registering host imports).
         if let Some(linker_init) = self.host_linker_init {
             linker_init(&mut linker).context("Failed to configure host linker")?;
         }
+        #[cfg(feature = "dynamic")]
+        if let Some(registry) = &self.host_imports {
+            let mut imports = registry.list_imports();
+            imports.sort_unstable();
+            if let Some(name) = imports
+                .into_iter()
+                .find(|name| !async_support && registry.is_async(name) == Some(true))
+            {
+                anyhow::bail!("Host import {} is async. Use build_async() instead.", name);
+            }
+            registry.add_to_linker(&mut linker)?;
+        }
 
-        // Clone the component for type introspection
-        // Component is reference-counted internally, so this is cheap
-        let component = self.image.component().clone();
-
-        let guest_instance = if let Some(initializer) = self.guest_initializer {
-            let ctx = GuestHandlerContext::new(&mut linker, &mut store, &component);
-            initializer(ctx)?
-        } else {
-            return Err(anyhow::anyhow!(
-                "Guest initializer is required. Use with_guest_initializer() to set it."
-            ));
-        };
+        Ok((store, linker, self.image))
+    }
+}
 
-        // Extract dynamic instance from guest_instance if available
-        #[cfg(feature = "dynamic")]
-        let dynamic_instance = guest_instance.get_dynamic_instance_ref().cloned();
+/// Apply per-container limits to a freshly created store.
+pub(crate) fn configure_store<T: HostStateImpl>(
+    store: &mut Store<T>,
+    fuel_limit: Option<u64>,
+    epoch_deadline: Option<u64>,
+    limits: LimitConfig,
+) -> Result<()> {
+    if let Some(fuel) = fuel_limit {
+        store.set_fuel(fuel).context(
+            "Failed to set fuel limit (ensure Image was created with consume_fuel(true) in Config)",
+        )?;
+    }
+    if let Some(deadline) = epoch_deadline {
+        store.set_epoch_deadline(deadline);
+    }
+    if !limits.is_empty() {
+        let slot = store.data_mut().limits().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Memory, table and instance limits require a host state that implements HostStateImpl::limits"
+            )
+        })?;
+        *slot = ContainerLimits::new(limits);
+        store.limiter(|state| {
+            state
+                .limits()
+                .expect("host state provided limits when the store was configured")
+        });
+    }
+    Ok(())
+}
 
-        Ok(Container {
-            store,
-            guest: guest_instance,
-            state: ContainerState::Created,
-            #[cfg(feature = "dynamic")]
-            dynamic_instance,
-            #[cfg(feature = "dynamic")]
-            host_imports: None,
-        })
+/// Add WASI preview2 to `linker`, with async bindings for async containers.
+pub(crate) fn add_wasi_to_linker<T: HostStateImpl>(
+    linker: &mut Linker<T>,
+    async_support: bool,
+) -> Result<()> {
+    if async_support {
+        wasmtime_wasi::p2::add_to_linker_async(linker).context("Failed to add WASI to linker")?;
+    } else {
+        wasmtime_wasi::p2::add_to_linker_sync(linker).context("Failed to add WASI to linker")?;
     }
+    Ok(())
 }
 
 /// Lifecycle state of a [`Container`].
@@ -391,8 +750,60 @@ pub enum ContainerState {
     Running,
     /// Container was explicitly stopped via [`Container::stop`].
     Stopped,
-    /// An unrecoverable error occurred. Contains the error message.
-    Error(String),
+    /// An unrecoverable error occurred.
+    Error(ContainerError),
+}
+
+/// Why a container entered [`ContainerState::Error`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCause {
+    /// The guest ran into a limit set with
+    /// [`ContainerBuilder::with_memory_limit`] or
+    /// [`ContainerBuilder::with_table_limit`]
+    ResourceLimit(LimitKind),
+    /// The guest trapped (including running out of fuel or epoch deadline)
+    Trap,
+    /// Any other unrecoverable error
+    Other,
+}
+
+/// The error recorded in [`ContainerState::Error`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContainerError {
+    pub cause: ErrorCause,
+    pub message: String,
+}
+
+impl ContainerError {
+    pub fn new(cause: ErrorCause, message: impl Into<String>) -> Self {
+        Self {
+            cause,
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for ContainerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.cause {
+            ErrorCause::ResourceLimit(kind) => {
+                write!(f, "{} limit exceeded: {}", kind, self.message)
+            }
+            ErrorCause::Trap | ErrorCause::Other => f.write_str(&self.message),
+        }
+    }
+}
+
+impl From<String> for ContainerError {
+    fn from(message: String) -> Self {
+        Self::new(ErrorCause::Other, message)
+    }
+}
+
+impl From<&str> for ContainerError {
+    fn from(message: &str) -> Self {
+        Self::new(ErrorCause::Other, message)
+    }
 }
 
 /// A Container represents a running instance of an Image
@@ -401,8 +812,25 @@ pub enum ContainerState {
 ///
 /// # Async usage
 ///
-/// All guest-call methods are synchronous. To call them from an async context
-/// (e.g. a tokio web-handler), wrap the container in `Arc<Mutex<…>>` and use
+/// Containers built with [`ContainerBuilder::build_async`] expose
+/// [`call_guest_raw_desc_async`](Container::call_guest_raw_desc_async) and
+/// [`call_guest_binary_async`](Container::call_guest_binary_async), which
+/// yield to the executor while the guest runs or awaits async host imports,
+/// so one runtime thread can drive many containers:
+///
+/// ```ignore
+/// let image = Image::from_component(component_binary)?;
+///
+/// let mut container = Container::builder(image)
+///     .with_async_guest_initializer(|ctx| { /* ... */ })
+///     .build_async()
+///     .await?;
+/// let result = container.call_guest_raw_desc_async("handle", payload).await?;
+/// ```
+///
+/// The synchronous guest-call methods are only available on containers built
+/// with [`ContainerBuilder::build`]. To call those from an async context,
+/// wrap the container in `Arc<Mutex<…>>` and use
 /// [`tokio::task::spawn_blocking`]:
 ///
 /// ```ignore
@@ -419,9 +847,33 @@ pub enum ContainerState {
 /// `Container<T>` is `Send` when `T: Send` (the default [`HostState`] satisfies
 /// this). It is **not** `Sync`, so always guard with a `Mutex`.
 pub struct Container<T: HostStateImpl = HostState> {
-    store: Store<T>,
+    /// Always set, except while [`reinstantiate`](Self::reinstantiate) moves
+    /// the host state into a fresh store
+    store: Option<Store<T>>,
+    /// Image the container was instantiated from, kept for type introspection
+    /// and its epoch ticker
+    image: Image,
     guest: GuestInstance,
     state: ContainerState,
+    /// Whether the container was built with [`ContainerBuilder::build_async`]
+    async_support: bool,
+    /// Number of guest calls made, and how many of them failed
+    calls: u64,
+    failed_calls: u64,
+
+    /// Deadline set with [`ContainerBuilder::with_epoch_deadline`], restored
+    /// after a call with a timeout
+    #[cfg(feature = "dynamic")]
+    epoch_deadline: Option<u64>,
+
+    /// Recreates the guest in a new store, carrying over the host state,
+    /// after a timeout traps it
+    ///
+    /// Only set for guests built as dynamic instances or instantiated from a
+    /// [`ContainerTemplate`](crate::pool::ContainerTemplate); other containers
+    /// stay in the error state after a timeout.
+    #[cfg(feature = "dynamic")]
+    respawn: Option<Respawn<T>>,
 
     /// Dynamic instance for runtime function invocation (duplicated from guest for easier access)
     #[cfg(feature = "dynamic")]
@@ -430,6 +882,101 @@ pub struct Container<T: HostStateImpl = HostState> {
     /// Host import registry for dynamic host function invocation
     #[cfg(feature = "dynamic")]
     host_imports: Option<HostImportRegistry>,
+
+    /// Reps of the `Resource(n)` tokens handed out in call results
+    #[cfg(feature = "dynamic")]
+    resource_tokens: HashSet<u32>,
+}
+
+/// Message for the store, which is only taken while being replaced
+const STORE_PRESENT: &str = "container store is present";
+
+/// Instantiates a fresh guest in a new store holding the given host state
+///
+/// A trap poisons the whole store, so the guest cannot be recreated in the
+/// old one. The new store is returned even if instantiation fails. Async
+/// containers instantiate with `instantiate_async`.
+#[cfg(feature = "dynamic")]
+pub(crate) enum Respawn<T: 'static> {
+    Sync(Arc<dyn Fn(T) -> Respawned<T> + Send + Sync>),
+    Async(Arc<dyn Fn(T) -> BoxFuture<'static, Respawned<T>> + Send + Sync>),
+}
+
+/// The new store and the guest instantiated in it
+#[cfg(feature = "dynamic")]
+type Respawned<T> = (Store<T>, Result<GuestInstance>);
+
+#[cfg(feature = "dynamic")]
+impl<T: 'static> Clone for Respawn<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Sync(respawn) => Self::Sync(respawn.clone()),
+            Self::Async(respawn) => Self::Async(respawn.clone()),
+        }
+    }
+}
+
+#[cfg(feature = "dynamic")]
+impl<T: HostStateImpl> Respawn<T> {
+    /// Instantiate `pre` in stores set up by `configure`, wrapping each
+    /// instance with `guest`
+    pub(crate) fn new(
+        pre: InstancePre<T>,
+        async_support: bool,
+        configure: impl Fn(&mut Store<T>) -> Result<()> + Send + Sync + 'static,
+        guest: impl Fn(&mut Store<T>, &Instance) -> Result<GuestInstance> + Send + Sync + 'static,
+    ) -> Self {
+        let parts = Arc::new((pre, configure, guest));
+        if !async_support {
+            return Self::Sync(Arc::new(move |state| {
+                let (pre, configure, guest) = &*parts;
+                let mut store = Store::new(pre.component().engine(), state);
+                let result = configure(&mut store).and_then(|_| {
+                    let instance = pre
+                        .instantiate(&mut store)
+                        .context("Failed to instantiate component")?;
+                    guest(&mut store, &instance)
+                });
+                (store, result)
+            }));
+        }
+        Self::Async(Arc::new(move |state| {
+            let parts = parts.clone();
+            Box::pin(async move {
+                let (pre, configure, guest) = &*parts;
+                let mut store = Store::new(pre.component().engine(), state);
+                let result = async {
+                    configure(&mut store)?;
+                    let instance = pre
+                        .instantiate_async(&mut store)
+                        .await
+                        .context("Failed to instantiate component")?;
+                    guest(&mut store, &instance)
+                }
+                .await;
+                (store, result)
+            })
+        }))
+    }
+}
+
+/// Lets a raw instance be recreated after a timeout traps it
+#[cfg(feature = "dynamic")]
+fn dynamic_respawn<T: HostStateImpl>(
+    linker: &Linker<T>,
+    image: &Image,
+    guest: &GuestInstance,
+    async_support: bool,
+    configure: impl Fn(&mut Store<T>) -> Result<()> + Send + Sync + 'static,
+) -> Option<Respawn<T>> {
+    guest.get_dynamic_instance_ref()?;
+    let pre = linker.instantiate_pre(image.component()).ok()?;
+    Some(Respawn::new(
+        pre,
+        async_support,
+        configure,
+        |_, instance| Ok(GuestInstance::new_dynamic(*instance)),
+    ))
 }
 
 impl Container {
@@ -440,14 +987,63 @@ impl Container {
 }
 
 impl<T: HostStateImpl> Container<T> {
+    pub(crate) fn from_parts(
+        store: Store<T>,
+        image: Image,
+        guest: GuestInstance,
+        async_support: bool,
+    ) -> Self {
+        // Extract dynamic instance from guest_instance if available
+        #[cfg(feature = "dynamic")]
+        let dynamic_instance = guest.get_dynamic_instance_ref().cloned();
+
+        Self {
+            store: Some(store),
+            image,
+            guest,
+            state: ContainerState::Created,
+            async_support,
+            calls: 0,
+            failed_calls: 0,
+            #[cfg(feature = "dynamic")]
+            epoch_deadline: None,
+            #[cfg(feature = "dynamic")]
+            respawn: None,
+            #[cfg(feature = "dynamic")]
+            dynamic_instance,
+            #[cfg(feature = "dynamic")]
+            host_imports: None,
+            #[cfg(feature = "dynamic")]
+            resource_tokens: HashSet::new(),
+        }
+    }
+
+    /// What [`call_guest_raw_desc_with_timeout`](Self::call_guest_raw_desc_with_timeout)
+    /// needs to restore the container after a timeout
+    #[cfg(feature = "dynamic")]
+    pub(crate) fn with_recovery(
+        mut self,
+        epoch_deadline: Option<u64>,
+        respawn: Option<Respawn<T>>,
+    ) -> Self {
+        self.epoch_deadline = epoch_deadline;
+        self.respawn = respawn;
+        self
+    }
+
     /// Get mutable reference to Store
     pub fn store_mut(&mut self) -> &mut Store<T> {
-        &mut self.store
+        self.store.as_mut().expect(STORE_PRESENT)
     }
 
     /// Get immutable reference to Store
     pub fn store(&self) -> &Store<T> {
-        &self.store
+        self.store.as_ref().expect(STORE_PRESENT)
+    }
+
+    /// Get reference to the image the container was built from
+    pub fn image(&self) -> &Image {
+        &self.image
     }
 
     /// Get reference to guest instance
@@ -462,31 +1058,76 @@ impl<T: HostStateImpl> Container<T> {
 
     /// Get mutable reference to host state
     pub fn host_state_mut(&mut self) -> &mut T {
-        self.store.data_mut()
+        self.store_mut().data_mut()
     }
 
     /// Get immutable reference to host state
     pub fn host_state(&self) -> &T {
-        self.store.data()
+        self.store().data()
     }
 
-    /// Return the current lifecycle state of the container.
-    pub fn state(&self) -> &ContainerState {
-        &self.state
+    /// Whether the container was built in async mode.
+    ///
+    /// Async containers accept only the `*_async` guest-call methods.
+    pub fn is_async(&self) -> bool {
+        self.async_support
     }
 
-    /// Stop the container.
+    /// Guest stdout and stderr lines, oldest first (similar to docker logs)
     ///
-    /// After stopping, subsequent guest calls will return an error.
-    /// This transitions the state to [`ContainerState::Stopped`].
-    pub fn stop(&mut self) {
-        self.state = ContainerState::Stopped;
+    /// Empty if the host state does not capture output, see
+    /// [`HostStateImpl::logs`].
+    pub fn logs(&self) -> Vec<LogEntry> {
+        self.host_state()
+            .logs()
+            .map(ContainerLogs::entries)
+            .unwrap_or_default()
     }
 
-    /// Call a guest function by name with JSON payload
+    /// Stream guest output lines as they are written
     ///
-    /// This is a convenience method for dynamic invocation.
-    /// Requires that the guest instance implements the appropriate interface.
+    /// Returns `None` if the host state does not capture output.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let lines = container.subscribe_logs().unwrap();
+    /// std::thread::spawn(move || {
+    ///     for entry in lines {
+    ///         eprintln!("[{}] {}", entry.level, entry.message);
+    ///     }
+    /// });
+    /// ```
+    pub fn subscribe_logs(&self) -> Option<std::sync::mpsc::Receiver<LogEntry>> {
+        self.host_state().logs().map(ContainerLogs::subscribe)
+    }
+
+    /// Return the current lifecycle state of the container.
+    pub fn state(&self) -> &ContainerState {
+        &self.state
+    }
+
+    /// Number of guest calls made through the dynamic call methods.
+    pub(crate) fn call_count(&self) -> u64 {
+        self.calls
+    }
+
+    /// Number of those guest calls that returned an error.
+    pub(crate) fn failed_call_count(&self) -> u64 {
+        self.failed_calls
+    }
+
+    /// Stop the container.
+    ///
+    /// After stopping, subsequent guest calls will return an error.
+    /// This transitions the state to [`ContainerState::Stopped`].
+    pub fn stop(&mut self) {
+        self.state = ContainerState::Stopped;
+    }
+
+    /// Call a guest function by name with JSON payload
+    ///
+    /// This is a convenience method for dynamic invocation.
+    /// Requires that the guest instance implements the appropriate interface.
     ///
     /// # Arguments
     /// * `function_name` - Name of the function to call
@@ -534,26 +1175,155 @@ impl<T: HostStateImpl> Container<T> {
         function_name: &str,
         raw_desc_payload: &str,
     ) -> Result<String> {
-        match &self.state {
-            ContainerState::Stopped => {
-                anyhow::bail!("Container is stopped");
-            }
-            ContainerState::Error(e) => {
-                anyhow::bail!("Container is in error state: {}", e);
+        self.ensure_callable(false)?;
+
+        let result = self.call_guest_raw_desc_inner(function_name, raw_desc_payload);
+        self.record_outcome(&result, is_trap);
+        result
+    }
+
+    /// Call a guest function like [`call_guest_raw_desc`](Container::call_guest_raw_desc),
+    /// trapping it if it runs longer than `timeout`
+    ///
+    /// Requires an image created with
+    /// [`Config::epoch_interruption(true)`](wasmtime::Config::epoch_interruption)
+    /// and a running [`Image::start_epoch_ticker`]; the timeout is rounded up
+    /// to whole ticks. On expiry the error is a [`Timeout`]
+    /// (`e.downcast_ref::<Timeout>()`), the guest is re-instantiated and the
+    /// container keeps its previous state, so later calls work as usual.
+    /// The trap poisons the store, so the guest is re-instantiated in a new
+    /// one that keeps the host state; guest state from before the timeout is
+    /// lost.
+    ///
+    /// Afterwards the store's epoch deadline is reset to the one set with
+    /// [`ContainerBuilder::with_epoch_deadline`], or none.
+    ///
+    /// # Example
+    /// ```ignore
+    /// match container.call_guest_raw_desc_with_timeout("run", "()", Duration::from_secs(1)) {
+    ///     Err(e) if e.downcast_ref::<Timeout>().is_some() => { /* retry or report */ }
+    ///     result => println!("{}", result?),
+    /// }
+    /// ```
+    #[cfg(feature = "dynamic")]
+    pub fn call_guest_raw_desc_with_timeout(
+        &mut self,
+        function_name: &str,
+        raw_desc_payload: &str,
+        timeout: Duration,
+    ) -> Result<String> {
+        let previous_state = self.state.clone();
+        self.start_timeout(timeout)?;
+        let result = self.call_guest_raw_desc(function_name, raw_desc_payload);
+        self.reset_epoch_deadline();
+
+        match result {
+            Err(e) if is_interrupt(&e) => {
+                let recovered = self.reinstantiate();
+                self.finish_timeout(e, recovered, previous_state, function_name, timeout)
             }
-            _ => {}
+            result => result,
         }
+    }
 
-        let result = self.call_guest_raw_desc_inner(function_name, raw_desc_payload);
-        match &result {
-            Ok(_) => self.state = ContainerState::Running,
-            Err(e) => {
-                let is_wasm_fatal = e.downcast_ref::<wasmtime::Trap>().is_some();
-                if is_wasm_fatal {
-                    self.state = ContainerState::Error(e.to_string());
-                }
+    /// Async variant of
+    /// [`call_guest_raw_desc_with_timeout`](Container::call_guest_raw_desc_with_timeout)
+    ///
+    /// Only available on containers built with [`ContainerBuilder::build_async`].
+    #[cfg(feature = "dynamic")]
+    pub async fn call_guest_raw_desc_with_timeout_async(
+        &mut self,
+        function_name: &str,
+        raw_desc_payload: &str,
+        timeout: Duration,
+    ) -> Result<String> {
+        let previous_state = self.state.clone();
+        self.start_timeout(timeout)?;
+        let result = self
+            .call_guest_raw_desc_async(function_name, raw_desc_payload)
+            .await;
+        self.reset_epoch_deadline();
+
+        match result {
+            Err(e) if is_interrupt(&e) => {
+                let recovered = self.reinstantiate_async().await;
+                self.finish_timeout(e, recovered, previous_state, function_name, timeout)
             }
+            result => result,
         }
+    }
+
+    /// Set the epoch deadline for a call that may take at most `timeout`
+    #[cfg(feature = "dynamic")]
+    fn start_timeout(&mut self, timeout: Duration) -> Result<()> {
+        let ticker = self.image.epoch_ticker().ok_or_else(|| {
+            anyhow::anyhow!("No epoch ticker is running. Call Image::start_epoch_ticker() first.")
+        })?;
+        self.store_mut()
+            .set_epoch_deadline(ticker.ticks_for(timeout));
+        Ok(())
+    }
+
+    /// Restore the deadline set with [`ContainerBuilder::with_epoch_deadline`]
+    #[cfg(feature = "dynamic")]
+    fn reset_epoch_deadline(&mut self) {
+        let deadline = self.epoch_deadline.unwrap_or(MAX_TICKS);
+        self.store_mut().set_epoch_deadline(deadline);
+    }
+
+    /// Turn an interrupted call into a [`Timeout`] once the guest has been
+    /// re-instantiated
+    #[cfg(feature = "dynamic")]
+    fn finish_timeout(
+        &mut self,
+        error: anyhow::Error,
+        recovered: Result<()>,
+        previous_state: ContainerState,
+        function_name: &str,
+        timeout: Duration,
+    ) -> Result<String> {
+        let timeout = Timeout {
+            function: function_name.to_string(),
+            timeout,
+        };
+        if let Err(respawn_error) = recovered {
+            let message = format!("{error}; recovering from the timeout failed: {respawn_error}");
+            self.state = ContainerState::Error(ContainerError::new(ErrorCause::Trap, message));
+            return Err(respawn_error.context(timeout));
+        }
+        self.reset_epoch_deadline();
+        self.state = previous_state;
+        Err(timeout.into())
+    }
+
+    /// Async variant of [`call_guest_raw_desc`](Container::call_guest_raw_desc)
+    ///
+    /// Only available on containers built with [`ContainerBuilder::build_async`].
+    ///
+    /// # Example
+    /// ```ignore
+    /// let result = container
+    ///     .call_guest_raw_desc_async("process", r#"Request { input: "hello", count: 42 }"#)
+    ///     .await?;
+    /// ```
+    #[cfg(feature = "dynamic")]
+    pub async fn call_guest_raw_desc_async(
+        &mut self,
+        function_name: &str,
+        raw_desc_payload: &str,
+    ) -> Result<String> {
+        self.ensure_callable(true)?;
+
+        let result = async {
+            let (func, param_types, num_results) = self.lookup_export(function_name)?;
+            let (args, owned) =
+                raw_desc_to_args(raw_desc_payload, &param_types, self.resource_table())?;
+            let results = self.invoke_async(func, &args, num_results).await?;
+            self.spend_resource_tokens(owned);
+            self.results_to_raw_desc_async(&results).await
+        }
+        .await;
+        self.record_outcome(&result, is_trap);
         result
     }
 
@@ -565,82 +1335,20 @@ impl<T: HostStateImpl> Container<T> {
     ) -> Result<String> {
         use wasmtime::component::Val;
 
-        use crate::dynamic::{ron_to_val, val_to_ron};
-
-        // Get the function (direct field access avoids borrow checker issues)
-        let instance = self
-            .dynamic_instance
-            .as_ref()
-            .ok_or_else(|| anyhow::anyhow!("Dynamic instance not available"))?;
-
-        let func = instance
-            .get_func(&mut self.store, function_name)
-            .ok_or_else(|| anyhow::anyhow!("Export function not found: {}", function_name))?;
-
-        // Get function type info
-        let func_ty = func.ty(&self.store);
-        let param_types: Vec<_> = func_ty.params().collect();
-        let result_types: Vec<_> = func_ty.results().collect();
+        let (func, param_types, num_results) = self.lookup_export(function_name)?;
 
         // Convert raw descriptor → Val
-        let mut args = Vec::new();
-
-        if param_types.len() == 1 {
-            // Single parameter - direct parsing
-            // Extract just the type from the (name, type) tuple
-            let param_type = &param_types[0].1;
-            args.push(ron_to_val(raw_desc_payload, param_type)?);
-        } else {
-            // Multiple parameters - parse as sequence/array
-            let ron_array = if raw_desc_payload.trim().starts_with('[') {
-                raw_desc_payload.to_string()
-            } else if raw_desc_payload.trim().starts_with('(') {
-                // RON treats tuples differently, convert to array syntax
-                raw_desc_payload.to_string()
-            } else {
-                format!("[{}]", raw_desc_payload)
-            };
-
-            use ron::Value as RonValue;
-            let ron_value: RonValue = ron::from_str(&ron_array)?;
-
-            if let RonValue::Seq(items) = ron_value {
-                if items.len() != param_types.len() {
-                    anyhow::bail!(
-                        "Parameter count mismatch: expected {}, got {}",
-                        param_types.len(),
-                        items.len()
-                    );
-                }
-                for (ron_val, (_param_name, param_type)) in
-                    items.into_iter().zip(param_types.iter())
-                {
-                    args.push(ron_value_to_val(ron_val, param_type)?);
-                }
-            } else {
-                anyhow::bail!(
-                    "Invalid raw descriptor payload for function with multiple parameters"
-                );
-            }
-        }
+        let (args, owned) =
+            raw_desc_to_args(raw_desc_payload, &param_types, self.resource_table())?;
 
         // Call the function
-        let mut results = vec![Val::Bool(false); result_types.len()];
-        func.call(&mut self.store, &args, &mut results)
+        let mut results = vec![Val::Bool(false); num_results];
+        func.call(self.store_mut(), &args, &mut results)
             .context("Function call failed")?;
+        self.spend_resource_tokens(owned);
 
         // Convert Val → raw descriptor (RON)
-        let output_ron: Result<Vec<_>> = results.iter().map(val_to_ron).collect();
-        let output_ron = output_ron.context("Failed to convert result to RON")?;
-
-        // Format output based on return value count
-        let output = match output_ron.len() {
-            0 => "()".to_string(),
-            1 => output_ron[0].clone(),
-            _ => format!("({})", output_ron.join(", ")),
-        };
-
-        Ok(output)
+        self.results_to_raw_desc(&results)
     }
 
     /// Call a guest function by name with binary payload
@@ -667,6 +1375,62 @@ impl<T: HostStateImpl> Container<T> {
         function_name: &str,
         args: &[wasmtime::component::Val],
     ) -> Result<Vec<wasmtime::component::Val>> {
+        self.ensure_callable(false)?;
+
+        let result = self.call_guest_binary_inner(function_name, args);
+        self.record_outcome(&result, is_wasm_fatal);
+        result
+    }
+
+    /// Async variant of [`call_guest_binary`](Container::call_guest_binary)
+    ///
+    /// Only available on containers built with [`ContainerBuilder::build_async`].
+    ///
+    /// # Example
+    /// ```ignore
+    /// use wasmtime::component::Val;
+    /// let args = vec![Val::String("hello".to_string()), Val::U32(42)];
+    /// let results = container.call_guest_binary_async("process", &args).await?;
+    /// ```
+    #[cfg(feature = "dynamic")]
+    pub async fn call_guest_binary_async(
+        &mut self,
+        function_name: &str,
+        args: &[wasmtime::component::Val],
+    ) -> Result<Vec<wasmtime::component::Val>> {
+        self.ensure_callable(true)?;
+
+        let result = async {
+            let (func, _, num_results) = self.lookup_export(function_name)?;
+            self.invoke_async(func, args, num_results).await
+        }
+        .await;
+        self.record_outcome(&result, is_wasm_fatal);
+        result
+    }
+
+    #[cfg(feature = "dynamic")]
+    fn call_guest_binary_inner(
+        &mut self,
+        function_name: &str,
+        args: &[wasmtime::component::Val],
+    ) -> Result<Vec<wasmtime::component::Val>> {
+        use wasmtime::component::Val;
+
+        let (func, _, num_results) = self.lookup_export(function_name)?;
+
+        // Call the function
+        let mut results = vec![Val::Bool(false); num_results];
+        func.call(self.store_mut(), args, &mut results)
+            .context("Function call failed")?;
+
+        Ok(results)
+    }
+
+    /// Reject calls on stopped or failed containers, and calls whose mode
+    /// does not match how the container was built.
+    #[cfg(feature = "dynamic")]
+    fn ensure_callable(&self, async_call: bool) -> Result<()> {
         match &self.state {
             ContainerState::Stopped => {
                 anyhow::bail!("Container is stopped");
@@ -676,50 +1440,138 @@ impl<T: HostStateImpl> Container<T> {
             }
             _ => {}
         }
+        match (self.async_support, async_call) {
+            (true, false) => {
+                anyhow::bail!(
+                    "Container was built with build_async(); use the *_async call methods"
+                )
+            }
+            (false, true) => {
+                anyhow::bail!("Container was built with build(); async calls require build_async()")
+            }
+            _ => Ok(()),
+        }
+    }
 
-        let result = self.call_guest_binary_inner(function_name, args);
-        match &result {
+    /// Replace a trapped guest with a fresh instance in a new store that
+    /// keeps the host state.
+    #[cfg(feature = "dynamic")]
+    fn reinstantiate(&mut self) -> Result<()> {
+        let Respawn::Sync(respawn) = self.respawn()? else {
+            anyhow::bail!("Async containers are re-instantiated by the *_async call methods");
+        };
+        let state = self.store.take().expect(STORE_PRESENT).into_data();
+        let (store, guest) = respawn(state);
+        self.replace_guest(store, guest)
+    }
+
+    /// Async variant of [`reinstantiate`](Self::reinstantiate)
+    #[cfg(feature = "dynamic")]
+    async fn reinstantiate_async(&mut self) -> Result<()> {
+        let respawn = self.respawn()?;
+        let state = self.store.take().expect(STORE_PRESENT).into_data();
+        let (store, guest) = match respawn {
+            Respawn::Sync(respawn) => respawn(state),
+            Respawn::Async(respawn) => respawn(state).await,
+        };
+        self.replace_guest(store, guest)
+    }
+
+    #[cfg(feature = "dynamic")]
+    fn respawn(&self) -> Result<Respawn<T>> {
+        self.respawn.clone().ok_or_else(|| {
+            anyhow::anyhow!(
+                "The guest cannot be re-instantiated; build it with GuestInstance::new_dynamic or from a ContainerTemplate"
+            )
+        })
+    }
+
+    #[cfg(feature = "dynamic")]
+    fn replace_guest(&mut self, store: Store<T>, guest: Result<GuestInstance>) -> Result<()> {
+        self.store = Some(store);
+        self.forget_resource_tokens();
+        let guest = guest?;
+        self.dynamic_instance = guest.get_dynamic_instance_ref().cloned();
+        self.guest = guest;
+        Ok(())
+    }
+
+    /// Update the lifecycle state after a guest call.
+    #[cfg(feature = "dynamic")]
+    fn record_outcome<R>(&mut self, result: &Result<R>, is_fatal: fn(&anyhow::Error) -> bool) {
+        self.calls += 1;
+        let breached = self
+            .host_state_mut()
+            .limits()
+            .and_then(ContainerLimits::take_breached);
+        match result {
             Ok(_) => self.state = ContainerState::Running,
             Err(e) => {
-                let msg = e.to_string();
-                let is_wasm_fatal = msg.contains("trap")
-                    || msg.contains("out of memory")
-                    || msg.contains("fuel")
-                    || e.downcast_ref::<wasmtime::Error>().is_some()
-                    || e.downcast_ref::<wasmtime::Trap>().is_some();
-                if is_wasm_fatal {
-                    self.state = ContainerState::Error(msg);
+                self.failed_calls += 1;
+                if is_fatal(e) {
+                    let cause = match breached {
+                        Some(kind) => ErrorCause::ResourceLimit(kind),
+                        None if is_trap(e) => ErrorCause::Trap,
+                        None => ErrorCause::Other,
+                    };
+                    self.state = ContainerState::Error(ContainerError::new(cause, e.to_string()));
                 }
             }
         }
-        result
     }
 
+    /// Resolve an export, returning it with its parameter types and result count.
     #[cfg(feature = "dynamic")]
-    fn call_guest_binary_inner(
+    fn lookup_export(
         &mut self,
         function_name: &str,
-        args: &[wasmtime::component::Val],
-    ) -> Result<Vec<wasmtime::component::Val>> {
-        use wasmtime::component::Val;
-
+    ) -> Result<(
+        wasmtime::component::Func,
+        Vec<wasmtime::component::Type>,
+        usize,
+    )> {
         // Get the function (direct field access avoids borrow checker issues)
         let instance = self
             .dynamic_instance
             .as_ref()
             .ok_or_else(|| anyhow::anyhow!("Dynamic instance not available"))?;
-
-        let func = instance
-            .get_func(&mut self.store, function_name)
+        let store = self.store.as_mut().expect(STORE_PRESENT);
+
+        // `interface#function` names resolve through the exported instances
+        let mut index = None;
+        for segment in function_name.split(EXPORT_PATH_SEPARATOR) {
+            index = Some(
+                instance
+                    .get_export_index(&mut *store, index.as_ref(), segment)
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("Export function not found: {}", function_name)
+                    })?,
+            );
+        }
+        let func = index
+            .and_then(|index| instance.get_func(&mut *store, index))
             .ok_or_else(|| anyhow::anyhow!("Export function not found: {}", function_name))?;
 
         // Get function type info
-        let func_ty = func.ty(&self.store);
+        let func_ty = func.ty(&*store);
+        let param_types = func_ty.params().map(|(_name, ty)| ty).collect();
         let num_results = func_ty.results().count();
 
-        // Call the function
+        Ok((func, param_types, num_results))
+    }
+
+    #[cfg(feature = "dynamic")]
+    async fn invoke_async(
+        &mut self,
+        func: wasmtime::component::Func,
+        args: &[wasmtime::component::Val],
+        num_results: usize,
+    ) -> Result<Vec<wasmtime::component::Val>> {
+        use wasmtime::component::Val;
+
         let mut results = vec![Val::Bool(false); num_results];
-        func.call(&mut self.store, args, &mut results)
+        func.call_async(self.store_mut(), args, &mut results)
+            .await
             .context("Function call failed")?;
 
         Ok(results)
@@ -762,71 +1614,198 @@ impl<T: HostStateImpl> Container<T> {
         function_name: &str,
         raw_desc_payload: &str,
     ) -> Result<String> {
-        use crate::dynamic::{ron_to_val, val_to_ron};
-
         let registry = self
             .host_imports
             .as_ref()
             .ok_or_else(|| anyhow::anyhow!("Host import registry not initialized"))?;
 
-        // Get function signature
-        let (params, _results) = registry
-            .get_signature(function_name)
-            .ok_or_else(|| anyhow::anyhow!("Host import not found: {}", function_name))?;
-
         // Convert raw descriptor → Val
-        let mut args = Vec::new();
-        if params.len() == 1 {
-            args.push(ron_to_val(raw_desc_payload, &params[0])?);
-        } else {
-            // Multiple parameters - parse as sequence/array
-            let payload = raw_desc_payload.trim();
-            let ron_array = if payload.starts_with('[') || payload.starts_with('(') {
-                raw_desc_payload.to_string()
-            } else {
-                format!("[{}]", raw_desc_payload)
-            };
-
-            use ron::Value as RonValue;
-            let ron_value: RonValue = ron::from_str(&ron_array)?;
-
-            if let RonValue::Seq(items) = ron_value {
-                if items.len() != params.len() {
-                    anyhow::bail!(
-                        "Parameter count mismatch: expected {}, got {}",
-                        params.len(),
-                        items.len()
-                    );
-                }
-                for (ron_val, param_type) in items.into_iter().zip(params.iter()) {
-                    args.push(ron_value_to_val(ron_val, param_type)?);
-                }
-            } else {
-                anyhow::bail!(
-                    "Invalid raw descriptor payload for function with multiple parameters"
-                );
-            }
-        }
+        let table = self
+            .store
+            .as_mut()
+            .expect(STORE_PRESENT)
+            .data_mut()
+            .ctx()
+            .table;
+        let (args, owned) = host_import_args(registry, function_name, raw_desc_payload, table)?;
 
         // Call the function
         let result_vals = registry.call(function_name, &args)?;
+        self.spend_resource_tokens(owned);
 
         // Convert Val → raw descriptor (RON)
-        let output_ron: Result<Vec<_>> = result_vals.iter().map(val_to_ron).collect();
-        let output_ron = output_ron.context("Failed to convert result to RON")?;
-        let output = match output_ron.len() {
-            0 => "()".to_string(),
-            1 => output_ron[0].clone(),
-            _ => format!("({})", output_ron.join(", ")),
-        };
+        self.results_to_raw_desc(&result_vals)
+    }
+
+    /// Async variant of [`call_host_import_raw_desc`](Container::call_host_import_raw_desc)
+    ///
+    /// Awaits imports registered with [`HostImportRegistry::register_async`];
+    /// synchronous imports are called directly.
+    #[cfg(feature = "dynamic")]
+    pub async fn call_host_import_raw_desc_async(
+        &mut self,
+        function_name: &str,
+        raw_desc_payload: &str,
+    ) -> Result<String> {
+        let registry = self
+            .host_imports
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Host import registry not initialized"))?;
+
+        let table = self
+            .store
+            .as_mut()
+            .expect(STORE_PRESENT)
+            .data_mut()
+            .ctx()
+            .table;
+        let (args, owned) = host_import_args(registry, function_name, raw_desc_payload, table)?;
+        let result_vals = registry.call_async(function_name, &args).await?;
+        self.spend_resource_tokens(owned);
+        self.results_to_raw_desc_async(&result_vals).await
+    }
+
+    /// The host state's resource table, which backs `Resource(n)` tokens.
+    #[cfg(feature = "dynamic")]
+    fn resource_table(&mut self) -> &mut ResourceTable {
+        self.store_mut().data_mut().ctx().table
+    }
+
+    /// Release a `Resource(n)` token returned by a call
+    ///
+    /// A token stays valid, and keeps its resource alive, until it is passed
+    /// as an `own<T>` argument to a call that succeeds or released here;
+    /// passing it as `borrow<T>` keeps it. Releasing an owned resource runs its destructor.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let file = container.call_guest_raw_desc("open", r#""notes.txt""#)?;
+    /// container.call_guest_raw_desc("read", &file)?; // borrow<file>
+    /// container.drop_resource(&file)?;
+    /// ```
+    #[cfg(feature = "dynamic")]
+    pub fn drop_resource(&mut self, token: &str) -> Result<()> {
+        let resource = self.take_resource(token)?;
+        resource
+            .resource_drop(self.store_mut())
+            .context("Failed to drop resource")?;
+        Ok(())
+    }
+
+    /// Async variant of [`drop_resource`](Container::drop_resource)
+    ///
+    /// Only available on containers built with [`ContainerBuilder::build_async`].
+    #[cfg(feature = "dynamic")]
+    pub async fn drop_resource_async(&mut self, token: &str) -> Result<()> {
+        let resource = self.take_resource(token)?;
+        resource
+            .resource_drop_async(self.store_mut())
+            .await
+            .context("Failed to drop resource")?;
+        Ok(())
+    }
+
+    #[cfg(feature = "dynamic")]
+    fn take_resource(&mut self, token: &str) -> Result<ResourceAny> {
+        use crate::dynamic::{deserialize::resource_token_rep, take_resource_token};
 
-        Ok(output)
+        let rep = resource_token_rep(token)?;
+        let resource = take_resource_token(token, self.resource_table())?;
+        self.resource_tokens.remove(&rep);
+        Ok(resource)
+    }
+
+    /// Remove the tokens a successful call took as `own<T>` arguments
+    #[cfg(feature = "dynamic")]
+    fn spend_resource_tokens(&mut self, owned: Vec<u32>) {
+        let table = self.resource_table();
+        for rep in &owned {
+            let _ = table.delete(Resource::<ResourceAny>::new_own(*rep));
+        }
+        for rep in owned {
+            self.resource_tokens.remove(&rep);
+        }
+    }
+
+    /// Format call results as a raw descriptor, recording the resource tokens
+    /// they hand out
+    ///
+    /// Returns the tokens stored before a formatting error, which nobody
+    /// received and the caller must drop.
+    #[cfg(feature = "dynamic")]
+    fn format_results(
+        &mut self,
+        results: &[Val],
+    ) -> Result<String, (anyhow::Error, Vec<ResourceAny>)> {
+        let mut issued = Vec::new();
+        match vals_to_raw_desc(results, self.resource_table(), &mut issued) {
+            Ok(output) => {
+                self.resource_tokens.extend(issued);
+                Ok(output)
+            }
+            Err(e) => {
+                let table = self.resource_table();
+                let orphans = issued
+                    .into_iter()
+                    .filter_map(|rep| table.delete(Resource::<ResourceAny>::new_own(rep)).ok())
+                    .collect();
+                Err((e, orphans))
+            }
+        }
+    }
+
+    /// Convert call results into a raw descriptor (RON)
+    #[cfg(feature = "dynamic")]
+    fn results_to_raw_desc(&mut self, results: &[Val]) -> Result<String> {
+        match self.format_results(results) {
+            Ok(output) => Ok(output),
+            Err((e, orphans)) => {
+                for resource in orphans {
+                    // The formatting error is the one worth reporting
+                    let _ = resource.resource_drop(self.store_mut());
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Async variant of [`results_to_raw_desc`](Self::results_to_raw_desc)
+    #[cfg(feature = "dynamic")]
+    async fn results_to_raw_desc_async(&mut self, results: &[Val]) -> Result<String> {
+        match self.format_results(results) {
+            Ok(output) => Ok(output),
+            Err((e, orphans)) => {
+                for resource in orphans {
+                    let _ = resource.resource_drop_async(self.store_mut()).await;
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Forget the tokens issued by a store that has been replaced
+    ///
+    /// Their handles were released along with the old store.
+    #[cfg(feature = "dynamic")]
+    fn forget_resource_tokens(&mut self) {
+        let tokens = std::mem::take(&mut self.resource_tokens);
+        let table = self.resource_table();
+        for rep in tokens {
+            let handle = Resource::<ResourceAny>::new_own(rep);
+            // Tokens spent as `own<T>` may have had their slot reused
+            if table.get(&handle).is_ok() {
+                let _ = table.delete(handle);
+            }
+        }
     }
 
     /// List all guest export functions
     ///
-    /// Returns information about all functions exported by the WASM component.
-    /// Requires the `dynamic` feature to be enabled.
+    /// The list is built from the component's type information, so it covers
+    /// every exported function, including those inside exported interfaces.
+    /// Interface functions are named `interface#function` (for example
+    /// `example:math/calc#add`), which is also the name the `call_guest_*`
+    /// methods accept.
     ///
     /// # Returns
     /// Vector of export information including function names and types
@@ -840,65 +1819,26 @@ impl<T: HostStateImpl> Container<T> {
     ///     println!("  Results: {:?}", export.results);
     /// }
     /// ```
-    #[cfg(feature = "dynamic")]
-    pub fn list_guest_exports(&mut self) -> Result<Vec<ExportInfo>> {
-        if let Some(instance) = &self.dynamic_instance {
-            let mut exports = Vec::new();
-
-            // Try to get common export function names
-            // This is a pragmatic approach since we can't easily iterate all exports
-            // without knowing their names beforehand
-            let potential_exports = vec![
-                "init",
-                "process",
-                "getname",
-                "getversion",
-                "getfeatures",
-                "shutdown",
-                "notify",
-                "add",
-                "sub",
-                "mul",
-                "div",
-                "to-upper",
-                "to-lower",
-                "reverse",
-                "length",
-                "process-numbers",
-                "transform",
-            ];
-
-            for export_name in potential_exports {
-                // Try to get the function
-                if let Some(func) = instance.get_func(&mut self.store, export_name) {
-                    // Get function type information
-                    let func_ty = func.ty(&self.store);
-                    let mut params = Vec::new();
-                    let mut results = Vec::new();
-
-                    // Extract parameter types (they come as (name, type) tuples)
-                    for (param_name, param_type) in func_ty.params() {
-                        params.push((param_name.to_string(), param_type.clone()));
-                    }
-
-                    // Extract result types
-                    for result_type in func_ty.results() {
-                        results.push(result_type.clone());
-                    }
-
-                    exports.push(ExportInfo {
-                        name: export_name.to_string(),
-                        params,
-                        results,
-                    });
-                }
-            }
+    pub fn list_guest_exports(&self) -> Result<Vec<ExportInfo>> {
+        let engine = self.store().engine();
+        let mut exports = Vec::new();
+        for (name, item) in self.image.component().component_type().exports(engine) {
+            collect_exports(engine, None, name, item.ty, &mut exports);
+        }
+        Ok(exports)
+    }
 
-            Ok(exports)
-        } else {
-            // No dynamic instance available, return empty list
-            Ok(Vec::new())
+    /// List all guest export functions with documentation from WIT
+    ///
+    /// Same as [`list_guest_exports`](Self::list_guest_exports), with each
+    /// entry's `docs` filled from the matching function in `wit`, when the
+    /// WIT source documents it.
+    pub fn list_guest_exports_with_docs(&self, wit: &WitLoader) -> Result<Vec<ExportInfo>> {
+        let mut exports = self.list_guest_exports()?;
+        for export in &mut exports {
+            export.docs = wit.function_docs(export.interface.as_deref(), export.function_name());
         }
+        Ok(exports)
     }
 
     /// List all host import functions
@@ -944,9 +1884,65 @@ impl<T: HostStateImpl> Container<T> {
 /// Export function information
 #[derive(Debug, Clone)]
 pub struct ExportInfo {
+    /// Callable name: `function` at the top level, `interface#function` inside
+    /// an exported interface
     pub name: String,
+    /// Exported interface the function belongs to, if any
+    pub interface: Option<String>,
     pub params: Vec<(String, wasmtime::component::Type)>,
     pub results: Vec<wasmtime::component::Type>,
+    /// Documentation from WIT, see [`Container::list_guest_exports_with_docs`]
+    pub docs: Option<String>,
+}
+
+impl ExportInfo {
+    /// Function name without the interface prefix
+    pub fn function_name(&self) -> &str {
+        self.name
+            .rsplit_once(EXPORT_PATH_SEPARATOR)
+            .map_or(self.name.as_str(), |(_, function)| function)
+    }
+}
+
+/// Separates an exported interface from a function in export names
+const EXPORT_PATH_SEPARATOR: char = '#';
+
+/// Helper: add the functions in a component export item to `exports`
+///
+/// Exported instances are walked recursively, prefixing their functions with
+/// the instance path.
+fn collect_exports(
+    engine: &wasmtime::Engine,
+    interface: Option<&str>,
+    name: &str,
+    item: wasmtime::component::types::ComponentItem,
+    exports: &mut Vec<ExportInfo>,
+) {
+    use wasmtime::component::types::ComponentItem;
+
+    let path = match interface {
+        Some(interface) => format!("{interface}{EXPORT_PATH_SEPARATOR}{name}"),
+        None => name.to_string(),
+    };
+    match item {
+        ComponentItem::ComponentFunc(func) => exports.push(ExportInfo {
+            name: path,
+            interface: interface.map(str::to_string),
+            params: func
+                .params()
+                .map(|(param, ty)| (param.to_string(), ty))
+                .collect(),
+            results: func.results().collect(),
+            docs: None,
+        }),
+        ComponentItem::ComponentInstance(instance) => {
+            for (name, item) in instance.exports(engine) {
+                collect_exports(engine, Some(&path), name, item.ty, exports);
+            }
+        }
+        // Types, resources, modules and nested components are not callable
+        _ => {}
+    }
 }
 
 /// Import function information
@@ -957,16 +1953,124 @@ pub struct ImportInfo {
     pub results: Vec<wasmtime::component::Type>,
 }
 
-/// Helper: RON Value to Val (for use in Container)
+/// Helper: parse a raw descriptor payload into call arguments
+///
+/// A single parameter is parsed directly; multiple parameters are read as a
+/// sequence (`[a, b]`, `(a, b)` or a bare `a, b`), and no parameters as `()`,
+/// `[]` or an empty payload. `Resource(n)` tokens are resolved through
+/// `resources`; those passed as `own<T>` are returned alongside the arguments
+/// and stay in the table until the call succeeds.
+#[cfg(feature = "dynamic")]
+fn raw_desc_to_args(
+    raw_desc_payload: &str,
+    param_types: &[wasmtime::component::Type],
+    resources: &mut ResourceTable,
+) -> Result<(Vec<wasmtime::component::Val>, Vec<u32>)> {
+    use crate::dynamic::{deserialize::read_with_resources, parse_ron_for};
+    use ron::Value as RonValue;
+
+    let mut owned = Vec::new();
+    if param_types.len() == 1 {
+        let ron_val = parse_ron_for(raw_desc_payload, param_types)?;
+        let arg = read_with_resources(ron_val, &param_types[0], resources, &mut owned)?;
+        return Ok((vec![arg], owned));
+    }
+
+    let payload = raw_desc_payload.trim();
+    if param_types.is_empty() && matches!(payload, "" | "()" | "[]") {
+        return Ok((Vec::new(), owned));
+    }
+    let ron_array = if payload.starts_with('[') || payload.starts_with('(') {
+        // RON treats tuples differently, but both parse as a sequence
+        raw_desc_payload.to_string()
+    } else {
+        format!("[{}]", raw_desc_payload)
+    };
+
+    let RonValue::Seq(items) = parse_ron_for(&ron_array, param_types)? else {
+        anyhow::bail!("Invalid raw descriptor payload for function with multiple parameters");
+    };
+    if items.len() != param_types.len() {
+        anyhow::bail!(
+            "Parameter count mismatch: expected {}, got {}",
+            param_types.len(),
+            items.len()
+        );
+    }
+    let args = items
+        .into_iter()
+        .zip(param_types.iter())
+        .map(|(ron_val, param_type)| {
+            read_with_resources(ron_val, param_type, resources, &mut owned)
+        })
+        .collect::<Result<_>>()?;
+    Ok((args, owned))
+}
+
+/// Helper: look up a host import's signature and parse its arguments
+#[cfg(feature = "dynamic")]
+fn host_import_args(
+    registry: &HostImportRegistry,
+    function_name: &str,
+    raw_desc_payload: &str,
+    resources: &mut ResourceTable,
+) -> Result<(Vec<wasmtime::component::Val>, Vec<u32>)> {
+    let (params, _results) = registry
+        .get_signature(function_name)
+        .ok_or_else(|| anyhow::anyhow!("Host import not found: {}", function_name))?;
+
+    raw_desc_to_args(raw_desc_payload, &params, resources)
+}
+
+/// Helper: format call results as a raw descriptor (RON)
+///
+/// The rep of every resource token written is appended to `issued`.
+#[cfg(feature = "dynamic")]
+fn vals_to_raw_desc(
+    results: &[wasmtime::component::Val],
+    resources: &mut ResourceTable,
+    issued: &mut Vec<u32>,
+) -> Result<String> {
+    use crate::dynamic::serialize::write_with_resources;
+
+    let output_ron: Result<Vec<_>> = results
+        .iter()
+        .map(|val| write_with_resources(val, resources, issued))
+        .collect();
+    let output_ron = output_ron.context("Failed to convert result to RON")?;
+
+    // Format output based on return value count
+    Ok(match output_ron.len() {
+        0 => "()".to_string(),
+        1 => output_ron[0].clone(),
+        _ => format!("({})", output_ron.join(", ")),
+    })
+}
+
+/// Whether a raw-descriptor call failed with a WASM trap.
+#[cfg(feature = "dynamic")]
+fn is_trap(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<wasmtime::Trap>().is_some()
+}
+
+/// Whether a call trapped because its epoch deadline expired.
 #[cfg(feature = "dynamic")]
-fn ron_value_to_val(
-    ron_value: ron::Value,
-    target_type: &wasmtime::component::Type,
-) -> Result<wasmtime::component::Val> {
-    use crate::dynamic::ron_to_val;
-    // Convert ron::Value to RON string, then use ron_to_val
-    let ron_str = ron::to_string(&ron_value)?;
-    ron_to_val(&ron_str, target_type)
+fn is_interrupt(e: &anyhow::Error) -> bool {
+    matches!(
+        e.downcast_ref::<wasmtime::Trap>(),
+        Some(wasmtime::Trap::Interrupt)
+    )
+}
+
+/// Whether a binary call failed in a way that leaves the instance unusable.
+#[cfg(feature = "dynamic")]
+fn is_wasm_fatal(e: &anyhow::Error) -> bool {
+    let msg = e.to_string();
+    msg.contains("trap")
+        || msg.contains("out of memory")
+        || msg.contains("fuel")
+        || e.downcast_ref::<wasmtime::Error>().is_some()
+        || e.downcast_ref::<wasmtime::Trap>().is_some()
 }
 
 impl<T: HostStateImpl> std::fmt::Debug for Container<T> {
@@ -1057,12 +2161,12 @@ mod tests {
         let created = ContainerState::Created;
         let running = ContainerState::Running;
         let stopped = ContainerState::Stopped;
-        let error = ContainerState::Error("trap".to_string());
+        let error = ContainerState::Error("trap".into());
 
         assert_eq!(created, ContainerState::Created);
         assert_eq!(running, ContainerState::Running);
         assert_eq!(stopped, ContainerState::Stopped);
-        assert!(matches!(error, ContainerState::Error(msg) if msg == "trap"));
+        assert!(matches!(error, ContainerState::Error(e) if e.message == "trap"));
     }
 
     #[test]
@@ -1078,7 +2182,7 @@ mod tests {
 
     #[test]
     fn test_container_state_clone_debug() {
-        let state = ContainerState::Error("oom".to_string());
+        let state = ContainerState::Error("oom".into());
         let cloned = state.clone();
         assert_eq!(state, cloned);
         let debug = format!("{:?}", state);
@@ -1109,4 +2213,326 @@ mod tests {
             err_msg
         );
     }
+
+    #[test]
+    fn test_list_guest_exports_includes_interfaces() {
+        use crate::Image;
+        use wasmtime::component::Type;
+
+        let wat = r#"
+            (component
+              (core module $m
+                (func (export "ping") (result i32) i32.const 1)
+                (func (export "add") (param i32 i32) (result i32)
+                  local.get 0 local.get 1 i32.add))
+              (core instance $i (instantiate $m))
+              (func $ping (result u32) (canon lift (core func $i "ping")))
+              (func $add (param "a" u32) (param "b" u32) (result u32)
+                (canon lift (core func $i "add")))
+              (instance $calc (export "add" (func $add)))
+              (export "ping" (func $ping))
+              (export "example:math/calc" (instance $calc))
+            )
+        "#;
+        let binary = wat::parse_str(wat).expect("valid WAT");
+        let img = Image::from_component(binary.into()).expect("valid component");
+        let container = Container::builder(img)
+            .with_guest_initializer(|ctx| {
+                let instance = ctx.linker.instantiate(&mut *ctx.store, ctx.component)?;
+                #[cfg(feature = "dynamic")]
+                let guest = GuestInstance::new_dynamic(instance);
+                #[cfg(not(feature = "dynamic"))]
+                let guest = GuestInstance::new(instance);
+                Ok(guest)
+            })
+            .build()
+            .expect("build should succeed");
+
+        let exports = container.list_guest_exports().unwrap();
+        let names: Vec<_> = exports.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["ping", "example:math/calc#add"]);
+
+        let add = &exports[1];
+        assert_eq!(add.interface.as_deref(), Some("example:math/calc"));
+        assert_eq!(add.function_name(), "add");
+        let params: Vec<_> = add.params.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(params, ["a", "b"]);
+        assert!(matches!(add.results.as_slice(), [Type::U32]));
+
+        #[cfg(feature = "dynamic")]
+        {
+            let mut container = container;
+            let sum = container.call_guest_raw_desc("example:math/calc#add", "(2, 3)");
+            assert_eq!(sum.unwrap(), "5");
+        }
+    }
+
+    #[cfg(feature = "dynamic")]
+    #[test]
+    fn test_own_token_survives_failed_call() {
+        use crate::Image;
+
+        // `make()` returns a fresh resource, `take(r, n)` consumes it and
+        // returns `n`
+        let wat = r#"
+            (component
+              (type $r' (resource (rep i32)))
+              (export $r "r" (type $r'))
+              (core func $new (canon resource.new $r'))
+              (core module $m
+                (import "" "new" (func $new (param i32) (result i32)))
+                (func (export "make") (result i32) i32.const 7 call $new)
+                (func (export "take") (param i32 i32) (result i32) local.get 1))
+              (core instance $i (instantiate $m (with "" (instance (export "new" (func $new))))))
+              (func (export "make") (result (own $r)) (canon lift (core func $i "make")))
+              (func (export "take") (param "r" (own $r)) (param "n" u32) (result u32)
+                (canon lift (core func $i "take")))
+            )
+        "#;
+        let binary = wat::parse_str(wat).expect("valid WAT");
+        let img = Image::from_component(binary.into()).expect("valid component");
+        let mut container = Container::builder(img)
+            .with_guest_initializer(|ctx| {
+                let instance = ctx.linker.instantiate(&mut *ctx.store, ctx.component)?;
+                Ok(GuestInstance::new_dynamic(instance))
+            })
+            .build()
+            .unwrap();
+
+        let token = container.call_guest_raw_desc("make", "()").unwrap();
+        assert!(container
+            .call_guest_raw_desc("take", &format!("({}, \"five\")", token))
+            .is_err());
+        assert_eq!(container.resource_tokens.len(), 1);
+
+        let taken = container.call_guest_raw_desc("take", &format!("({}, 5)", token));
+        assert_eq!(taken.unwrap(), "5");
+        assert!(container.resource_tokens.is_empty());
+        assert!(container
+            .call_guest_raw_desc("take", &format!("({}, 5)", token))
+            .is_err());
+    }
+
+    #[test]
+    fn test_limits_set_error_cause() {
+        use crate::Image;
+
+        // `grow(pages)` traps if memory.grow fails; `boom()` always traps
+        let wat = r#"
+            (component
+              (core module $m
+                (memory 1)
+                (func (export "grow") (param i32) (result i32)
+                  (if (i32.eq (memory.grow (local.get 0)) (i32.const -1))
+                    (then unreachable))
+                  i32.const 0)
+                (func (export "boom") unreachable))
+              (core instance $i (instantiate $m))
+              (func $grow (param "pages" u32) (result u32)
+                (canon lift (core func $i "grow")))
+              (func $boom (canon lift (core func $i "boom")))
+              (export "grow" (func $grow))
+              (export "boom" (func $boom))
+            )
+        "#;
+        let binary = wat::parse_str(wat).expect("valid WAT");
+        let img = Image::from_component(binary.into()).expect("valid component");
+        let build = |builder: ContainerBuilder<HostState>| {
+            builder
+                .with_guest_initializer(|ctx| {
+                    let instance = ctx.linker.instantiate(&mut *ctx.store, ctx.component)?;
+                    #[cfg(feature = "dynamic")]
+                    let guest = GuestInstance::new_dynamic(instance);
+                    #[cfg(not(feature = "dynamic"))]
+                    let guest = GuestInstance::new(instance);
+                    Ok(guest)
+                })
+                .build()
+        };
+
+        assert!(build(Container::builder(img.clone()).with_instance_limit(0)).is_err());
+
+        #[cfg(feature = "dynamic")]
+        {
+            use crate::LimitKind;
+
+            let mut limited =
+                build(Container::builder(img.clone()).with_memory_limit(2 * 65536)).unwrap();
+            limited.call_guest_raw_desc("grow", "1").unwrap();
+            assert_eq!(limited.state(), &ContainerState::Running);
+            assert!(limited.call_guest_raw_desc("grow", "4").is_err());
+            assert!(matches!(
+                limited.state(),
+                ContainerState::Error(e) if e.cause == ErrorCause::ResourceLimit(LimitKind::Memory)
+            ));
+
+            let mut trapping = build(Container::builder(img)).unwrap();
+            assert!(trapping.call_guest_raw_desc("boom", "()").is_err());
+            assert!(matches!(
+                trapping.state(),
+                ContainerState::Error(e) if e.cause == ErrorCause::Trap
+            ));
+        }
+    }
+
+    /// A component whose `spin()` never returns and whose `count()`
+    /// increments and returns a global, with epoch interruption enabled
+    #[cfg(feature = "dynamic")]
+    fn counter_image() -> crate::Image {
+        let wat = r#"
+            (component
+              (core module $m
+                (global $n (mut i32) (i32.const 0))
+                (func (export "spin") (loop $l (br $l)))
+                (func (export "count") (result i32)
+                  (global.set $n (i32.add (global.get $n) (i32.const 1)))
+                  global.get $n))
+              (core instance $i (instantiate $m))
+              (func $spin (canon lift (core func $i "spin")))
+              (func $count (result u32) (canon lift (core func $i "count")))
+              (export "spin" (func $spin))
+              (export "count" (func $count))
+            )
+        "#;
+        let binary = wat::parse_str(wat).expect("valid WAT");
+        let mut config = wasmtime::Config::new();
+        config.epoch_interruption(true);
+        crate::Image::from_component_with_config(binary.into(), config).unwrap()
+    }
+
+    #[cfg(feature = "dynamic")]
+    #[test]
+    fn test_call_with_timeout_recovers() {
+        use std::time::Duration;
+
+        use crate::Timeout;
+
+        let img = counter_image();
+        let mut container = Container::builder(img.clone())
+            .with_guest_initializer(|ctx| {
+                let instance = ctx.linker.instantiate(&mut *ctx.store, ctx.component)?;
+                Ok(GuestInstance::new_dynamic(instance))
+            })
+            .build()
+            .unwrap();
+
+        let timeout = Duration::from_millis(50);
+        assert!(container
+            .call_guest_raw_desc_with_timeout("count", "()", timeout)
+            .is_err_and(|e| e.to_string().contains("epoch ticker")));
+
+        img.start_epoch_ticker(Duration::from_millis(5));
+        let count = container.call_guest_raw_desc_with_timeout("count", "()", timeout);
+        assert_eq!(count.unwrap(), "1");
+
+        let err = container
+            .call_guest_raw_desc_with_timeout("spin", "()", timeout)
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<Timeout>(),
+            Some(&Timeout {
+                function: "spin".to_string(),
+                timeout,
+            })
+        );
+        assert_eq!(container.state(), &ContainerState::Running);
+
+        // The guest was re-instantiated, so its global starts over
+        assert_eq!(container.call_guest_raw_desc("count", "()").unwrap(), "1");
+        img.stop_epoch_ticker();
+    }
+
+    #[cfg(feature = "dynamic")]
+    #[tokio::test]
+    async fn test_async_call_with_timeout_recovers() {
+        use std::time::Duration;
+
+        use crate::Timeout;
+
+        let img = counter_image();
+        let mut container = Container::builder(img.clone())
+            .with_async_guest_initializer(|ctx| {
+                Box::pin(async move {
+                    let instance = ctx
+                        .linker
+                        .instantiate_async(&mut *ctx.store, ctx.component)
+                        .await?;
+                    Ok(GuestInstance::new_dynamic(instance))
+                })
+            })
+            .build_async()
+            .await
+            .unwrap();
+        img.start_epoch_ticker(Duration::from_millis(5));
+
+        let timeout = Duration::from_millis(50);
+        let count = container
+            .call_guest_raw_desc_with_timeout_async("count", "()", timeout)
+            .await;
+        assert_eq!(count.unwrap(), "1");
+        let err = container
+            .call_guest_raw_desc_with_timeout_async("spin", "()", timeout)
+            .await
+            .unwrap_err();
+        assert!(err.downcast_ref::<Timeout>().is_some(), "{err}");
+        assert_eq!(container.state(), &ContainerState::Running);
+
+        let count = container.call_guest_raw_desc_async("count", "()").await;
+        assert_eq!(count.unwrap(), "1");
+        img.stop_epoch_ticker();
+    }
+
+    #[test]
+    fn test_build_rejects_async_initializer() {
+        use crate::Image;
+        let wasm = bytes::Bytes::from_static(b"\x00asm\x01\x00\x00\x00");
+        let img = match Image::new(wasm) {
+            Ok(img) => img,
+            Err(_) => return,
+        };
+        let result = Container::builder(img)
+            .with_async_guest_initializer(|_ctx| Box::pin(async { Ok(GuestInstance::new(())) }))
+            .build();
+        assert!(result.unwrap_err().to_string().contains("build_async"));
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "dynamic")]
+    async fn test_call_mode_must_match_build_mode() {
+        use crate::Image;
+        let wasm = bytes::Bytes::from_static(b"\x00asm\x01\x00\x00\x00");
+        let img = match Image::new(wasm) {
+            Ok(img) => img,
+            Err(_) => return,
+        };
+
+        let mut sync_container = Container::builder(img.clone())
+            .with_guest_initializer(|_ctx| Ok(GuestInstance::new(())))
+            .build()
+            .expect("build should succeed");
+        assert!(!sync_container.is_async());
+        let err = sync_container
+            .call_guest_binary_async("test_fn", &[])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("build_async"));
+
+        let mut async_container = Container::builder(img)
+            .with_async_guest_initializer(|_ctx| Box::pin(async { Ok(GuestInstance::new(())) }))
+            .build_async()
+            .await
+            .expect("build_async should succeed");
+        assert!(async_container.is_async());
+        let err = async_container
+            .call_guest_raw_desc("test_fn", "()")
+            .unwrap_err();
+        assert!(err.to_string().contains("_async"));
+
+        let err = async_container
+            .call_guest_raw_desc_async("test_fn", "()")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Dynamic instance not available"));
+        assert_eq!(async_container.state(), &ContainerState::Created);
+    }
 }
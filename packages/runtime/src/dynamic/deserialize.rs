@@ -7,7 +7,9 @@
 use anyhow::{anyhow, bail, Context, Result};
 use ron::Value as RonValue;
 
-use wasmtime::component::{Type, Val};
+use wasmtime::component::{Resource, ResourceAny, ResourceTable, Type, Val};
+
+use super::ident::{quote_bare_identifiers, same_name, Quoting};
 
 /// Convert RON string to Wasmtime Val (requires type information)
 ///
@@ -20,9 +22,14 @@ use wasmtime::component::{Type, Val};
 /// - List<T> including List<List<T>>
 /// - Tuple<T1, T2, ...> including Tuple with nested types
 /// - Record { fields } including nested fields
-/// - Variant cases with optional payloads
+/// - Variant cases with optional payloads (`LightBlue(true)`, `Circle`)
 /// - Result<T, E>
 /// - Option<T>
+/// - Enum cases as bare identifiers (`LightBlue` or `light-blue`)
+/// - Flags as a set of bare identifiers (`[Read, Write]`)
+///
+/// Resource handles need the container's table, see
+/// [`ron_to_val_with_resources`].
 ///
 /// # Examples
 ///
@@ -38,17 +45,130 @@ use wasmtime::component::{Type, Val};
 /// let val = ron_to_val("[[1, 2], [3, 4]]", &nested_type)?;
 /// ```
 pub fn ron_to_val(ron: &str, target_type: &Type) -> Result<Val> {
-    // Parse RON to generic value first
-    let ron_value: RonValue = ron::from_str(ron).context("Failed to parse RON")?;
+    ron_value_to_val(
+        parse_ron_for(ron, std::slice::from_ref(target_type))?,
+        target_type,
+    )
+}
+
+/// Convert RON string to Wasmtime Val, resolving `Resource(n)` tokens
+///
+/// Tokens are the handles [`val_to_ron_with_resources`](super::val_to_ron_with_resources)
+/// stored in `resources`. A token passed as `own<T>` is removed from the
+/// table, since ownership moves into the call; `borrow<T>` leaves it in place.
+pub fn ron_to_val_with_resources(
+    ron: &str,
+    target_type: &Type,
+    resources: &mut ResourceTable,
+) -> Result<Val> {
+    let ron_value = parse_ron_for(ron, std::slice::from_ref(target_type))?;
+    ron_value_to_val_with_resources(ron_value, target_type, resources)
+}
+
+/// Parse RON source into a generic value
+pub fn parse_ron(ron: &str) -> Result<RonValue> {
+    ron::from_str(ron).context("Failed to parse RON")
+}
+
+/// Parse RON source holding values of `types` into a generic value
+///
+/// `ron::Value` drops the names of bare identifiers, so when `types` contain
+/// records, enums, flags or variants, record field names and case names are
+/// read as strings (`{x: Red}` parses like `{"x": "Red"}`). Variant and
+/// result cases with a payload are read as single-entry maps (`Ok(1)` parses
+/// like `{"Ok": 1}`). Other payloads are parsed unchanged.
+pub fn parse_ron_for(ron: &str, types: &[Type]) -> Result<RonValue> {
+    let quoting = Quoting::for_types(types);
+    if quoting.is_none() {
+        return parse_ron(ron);
+    }
+    parse_ron(&quote_bare_identifiers(ron, quoting))
+}
+
+/// The table index in a `Resource(n)` token
+pub(crate) fn resource_token_rep(token: &str) -> Result<u32> {
+    resource::token_rep(parse_ron(token)?)
+}
 
-    ron_value_to_val(ron_value, target_type)
+/// Remove a `Resource(n)` token from `resources`, returning its handle
+///
+/// The handle must then be released with [`ResourceAny::resource_drop`] in
+/// the store it came from, which
+/// [`Container::drop_resource`](crate::Container::drop_resource) does.
+pub fn take_resource_token(token: &str, resources: &mut ResourceTable) -> Result<ResourceAny> {
+    let rep = resource_token_rep(token)?;
+    let handle = Resource::<ResourceAny>::new_own(rep);
+    // `delete` frees the slot before checking its type, so look first
+    resources
+        .get(&handle)
+        .context(format!("Unknown resource handle: {}", rep))?;
+    Ok(resources.delete(handle)?)
 }
 
 /// Convert RON Value to Wasmtime Val (direct, without string conversion)
 ///
 /// This is the core deserialization function that handles all types,
-/// including nested complex types.
+/// including nested complex types. Enum and flag names are expected as
+/// strings, as produced by [`parse_ron_for`].
 pub fn ron_value_to_val(ron_value: RonValue, target_type: &Type) -> Result<Val> {
+    convert(ron_value, target_type, &mut None)
+}
+
+/// [`ron_value_to_val`] with resource handle resolution, see
+/// [`ron_to_val_with_resources`]
+///
+/// `own<T>` tokens are only removed once the whole value has converted.
+pub fn ron_value_to_val_with_resources(
+    ron_value: RonValue,
+    target_type: &Type,
+    resources: &mut ResourceTable,
+) -> Result<Val> {
+    let mut owned = Vec::new();
+    let val = read_with_resources(ron_value, target_type, resources, &mut owned)?;
+    for rep in owned {
+        resources.delete(Resource::<ResourceAny>::new_own(rep))?;
+    }
+    Ok(val)
+}
+
+/// [`ron_value_to_val_with_resources`], leaving tokens passed as `own<T>` in
+/// the table and appending their reps to `owned`
+///
+/// The caller removes them once the call the value is passed to succeeds, so
+/// a failed conversion or call does not spend them.
+pub(crate) fn read_with_resources(
+    ron_value: RonValue,
+    target_type: &Type,
+    resources: &mut ResourceTable,
+    owned: &mut Vec<u32>,
+) -> Result<Val> {
+    convert(
+        ron_value,
+        target_type,
+        &mut Some(Tokens {
+            table: resources,
+            owned,
+        }),
+    )
+}
+
+/// Table resolving resource tokens, and the reps of the tokens passed as
+/// `own<T>`
+struct Tokens<'a> {
+    table: &'a mut ResourceTable,
+    owned: &'a mut Vec<u32>,
+}
+
+fn convert(
+    ron_value: RonValue,
+    target_type: &Type,
+    tokens: &mut Option<Tokens<'_>>,
+) -> Result<Val> {
+    if let Type::Own(_) | Type::Borrow(_) = target_type {
+        return resource::deserialize_resource(ron_value, target_type, tokens.as_mut());
+    }
+    let mut recurse = |ron: RonValue, ty: &Type| convert(ron, ty, tokens);
+
     match (ron_value, target_type) {
         // Delegate to type-specific handlers
         (ron, Type::Bool) => basic::deserialize_bool(ron),
@@ -66,12 +186,16 @@ pub fn ron_value_to_val(ron_value: RonValue, target_type: &Type) -> Result<Val>
         (ron, Type::String) => basic::deserialize_string(ron),
 
         // Complex types - delegate to complex module
-        (ron, Type::List(_)) => complex::deserialize_list(ron, target_type, ron_value_to_val),
-        (ron, Type::Tuple(_)) => complex::deserialize_tuple(ron, target_type, ron_value_to_val),
-        (ron, Type::Record(_)) => complex::deserialize_record(ron, target_type, ron_value_to_val),
-        (ron, Type::Variant(_)) => complex::deserialize_variant(ron, target_type, ron_value_to_val),
-        (ron, Type::Result(_)) => complex::deserialize_result(ron, target_type, ron_value_to_val),
-        (ron, Type::Option(_)) => complex::deserialize_option(ron, target_type, ron_value_to_val),
+        (ron, Type::List(_)) => complex::deserialize_list(ron, target_type, &mut recurse),
+        (ron, Type::Tuple(_)) => complex::deserialize_tuple(ron, target_type, &mut recurse),
+        (ron, Type::Record(_)) => complex::deserialize_record(ron, target_type, &mut recurse),
+        (ron, Type::Variant(_)) => complex::deserialize_variant(ron, target_type, &mut recurse),
+        (ron, Type::Result(_)) => complex::deserialize_result(ron, target_type, &mut recurse),
+        (ron, Type::Option(_)) => complex::deserialize_option(ron, target_type, &mut recurse),
+
+        // Named types - delegate to named module
+        (ron, Type::Enum(_)) => named::deserialize_enum(ron, target_type),
+        (ron, Type::Flags(_)) => named::deserialize_flags(ron, target_type),
 
         // Fallback - capture types before moving
         (ron, ty) => bail!(
@@ -223,7 +347,7 @@ mod complex {
     use super::*;
 
     /// Type alias for recursive deserializer function
-    pub type Deserializer = fn(RonValue, &Type) -> Result<Val>;
+    pub type Deserializer<'a> = &'a mut dyn FnMut(RonValue, &Type) -> Result<Val>;
 
     /// Deserialize List<T> including nested lists
     ///
@@ -293,6 +417,7 @@ mod complex {
     /// # Examples
     /// - `{x: 1, y: 2}` -> Record { x: U32, y: U32 }
     /// - `{data: [1, 2, 3]}` -> Record { data: List<U32> }
+    /// - `(max_size: 3)` -> Record { max-size: U32 }
     pub fn deserialize_record(
         ron: RonValue,
         target_type: &Type,
@@ -311,10 +436,16 @@ mod complex {
             let field_name = field.name;
             let field_type = field.ty;
 
-            // Find field value in map (RON stores keys as Strings)
+            // Find field value in map (RON stores keys as Strings), also
+            // accepting the snake_case names serde writes for `max-size`
+            let key = RonValue::String(field_name.to_string());
             let val = map
                 .iter()
-                .find(|(k, _)| *k == &RonValue::String(field_name.to_string()))
+                .find(|(k, _)| **k == key)
+                .or_else(|| {
+                    map.iter()
+                        .find(|(k, _)| matches!(k, RonValue::String(k) if same_name(k, field_name)))
+                })
                 .map(|(_, v)| v)
                 .ok_or_else(|| anyhow!("Missing field: {}", field_name))?;
 
@@ -330,38 +461,49 @@ mod complex {
     /// Deserialize Variant with optional payload
     ///
     /// # Examples
-    /// - `Some(42)` -> Variant::Some with U32
-    /// - `None` -> Variant::None (unit)
+    /// - `LightBlue(true)` / `{"light-blue": true}` -> case `light-blue`
+    ///   with Bool
+    /// - `"circle"` / bare `Circle` -> unit case `circle`
     pub fn deserialize_variant(
         ron: RonValue,
         target_type: &Type,
         deserialize: Deserializer,
     ) -> Result<Val> {
-        let (mut map, variant_type) = match ron {
-            RonValue::Map(map) => match target_type {
-                Type::Variant(variant_type) => (map, variant_type),
-                _ => bail!("Expected variant type, got {:?}", target_type),
-            },
+        let Type::Variant(variant_type) = target_type else {
+            bail!("Expected variant type, got {:?}", target_type);
+        };
+        let map = match ron {
+            RonValue::Map(map) => map,
+            RonValue::String(name) => {
+                // Unit case written as a bare identifier
+                let case = variant_type
+                    .cases()
+                    .find(|case| case.ty.is_none() && same_name(&name, case.name))
+                    .ok_or_else(|| anyhow!("Unknown unit variant case: {}", name))?;
+                return Ok(Val::Variant(case.name.to_string(), None));
+            }
             _ => bail!("Expected variant map, got {:?}", ron),
         };
 
-        // Try each case
-        for case in variant_type.cases() {
-            let case_name = case.name;
-            let key = RonValue::String(case_name.to_string());
-
-            if let Some(val_ron) = map.remove(&key) {
-                // Case has optional type
-                let case_val = match case.ty {
-                    Some(t) => Some(Box::new(deserialize(val_ron, &t)?)),
-                    None => None, // Unit variant
-                };
-
-                return Ok(Val::Variant(case_name.to_string(), case_val));
-            }
+        // `Case(payload)` is parsed as the single-entry map `{"Case": payload}`
+        if map.len() != 1 {
+            bail!("Expected a single variant case, got {:?}", map);
         }
-
-        bail!("No matching variant case found in: {:?}", map)
+        let (key, val_ron) = map.into_iter().next().expect("map has one entry");
+        let RonValue::String(name) = key else {
+            bail!("Expected variant case name, got {:?}", key);
+        };
+        let case = variant_type
+            .cases()
+            .find(|case| same_name(&name, case.name))
+            .ok_or_else(|| anyhow!("Unknown variant case: {}", name))?;
+
+        // Case has optional type
+        let case_val = match case.ty {
+            Some(t) => Some(Box::new(deserialize(val_ron, &t)?)),
+            None => None, // Unit variant
+        };
+        Ok(Val::Variant(case.name.to_string(), case_val))
     }
 
     /// Deserialize Result<T, E>
@@ -433,6 +575,131 @@ mod complex {
     }
 }
 
+// Enum and flags handlers
+mod named {
+    use ron::Value as RonValue;
+
+    use super::*;
+
+    /// Deserialize an enum case
+    ///
+    /// # Examples
+    /// - `LightBlue` / `"light-blue"` -> Enum("light-blue")
+    pub fn deserialize_enum(ron: RonValue, target_type: &Type) -> Result<Val> {
+        let Type::Enum(enum_type) = target_type else {
+            bail!("Expected enum type, got {:?}", target_type);
+        };
+        let name = match ron {
+            RonValue::String(name) => name,
+            _ => bail!("Expected enum case identifier, got {:?}", ron),
+        };
+
+        enum_type
+            .names()
+            .find(|case| same_name(&name, case))
+            .map(|case| Val::Enum(case.to_string()))
+            .ok_or_else(|| anyhow!("Unknown enum case: {}", name))
+    }
+
+    /// Deserialize a set of flags
+    ///
+    /// Set flags are returned in declaration order; duplicates are ignored.
+    ///
+    /// # Examples
+    /// - `[Read, Write]` -> Flags(["read", "write"])
+    /// - `[]` -> Flags([])
+    pub fn deserialize_flags(ron: RonValue, target_type: &Type) -> Result<Val> {
+        let Type::Flags(flags_type) = target_type else {
+            bail!("Expected flags type, got {:?}", target_type);
+        };
+        let items = match ron {
+            RonValue::Seq(items) => items,
+            _ => bail!("Expected flags set, got {:?}", ron),
+        };
+
+        let mut set = Vec::with_capacity(items.len());
+        for item in items {
+            let name = match item {
+                RonValue::String(name) => name,
+                other => bail!("Expected flag identifier, got {:?}", other),
+            };
+            let flag = flags_type
+                .names()
+                .find(|flag| same_name(&name, flag))
+                .ok_or_else(|| anyhow!("Unknown flag: {}", name))?;
+            set.push(flag);
+        }
+
+        let flags = flags_type
+            .names()
+            .filter(|flag| set.contains(flag))
+            .map(str::to_string)
+            .collect();
+        Ok(Val::Flags(flags))
+    }
+}
+
+// Resource handle handlers
+mod resource {
+    use ron::Value as RonValue;
+
+    use super::*;
+
+    /// The table index in a `Resource(n)` token
+    pub fn token_rep(ron: RonValue) -> Result<u32> {
+        // `Resource(3)` parses as a one-element sequence
+        let token = match ron {
+            RonValue::Seq(mut items) if items.len() == 1 => items.remove(0),
+            other => other,
+        };
+        let rep = match token {
+            RonValue::Number(n) => n.as_i64().context("Resource handle expected")?,
+            _ => bail!("Expected resource handle, got {:?}", token),
+        };
+        u32::try_from(rep).context(format!("Invalid resource handle: {}", rep))
+    }
+
+    /// Deserialize a `Resource(n)` token from the container's resource table
+    ///
+    /// # Examples
+    /// - `Resource(3)` -> the handle stored at index 3
+    pub fn deserialize_resource(
+        ron: RonValue,
+        target_type: &Type,
+        tokens: Option<&mut Tokens<'_>>,
+    ) -> Result<Val> {
+        let tokens = tokens.ok_or_else(|| {
+            anyhow!("Resource values require a ResourceTable, use ron_to_val_with_resources")
+        })?;
+
+        let rep = token_rep(ron)?;
+        let handle = Resource::<ResourceAny>::new_own(rep);
+        let resource = *tokens
+            .table
+            .get(&handle)
+            .context(format!("Unknown resource handle: {}", rep))?;
+
+        let expected = match target_type {
+            Type::Own(ty) | Type::Borrow(ty) => ty,
+            _ => bail!("Expected resource type, got {:?}", target_type),
+        };
+        if resource.ty() != *expected {
+            bail!("Resource handle {} has a different resource type", rep);
+        }
+
+        // Ownership moves into the call, so the token is spent once it
+        // succeeds
+        if let Type::Own(_) = target_type {
+            if tokens.owned.contains(&rep) {
+                bail!("Resource handle {} is passed as own more than once", rep);
+            }
+            tokens.owned.push(rep);
+        }
+
+        Ok(Val::Resource(resource))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
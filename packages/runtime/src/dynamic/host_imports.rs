@@ -3,23 +3,82 @@
 //! This module provides a registry for host functions that WASM components import,
 //! allowing dynamic invocation similar to guest exports.
 
-use anyhow::Result;
-use std::{collections::HashMap, sync::Arc};
+use anyhow::{Context, Result};
+use std::{collections::HashMap, future::Future, sync::Arc};
 
-use wasmtime::component::{Type, Val};
+use wasmtime::component::{Linker, Type, Val};
+
+use crate::{BoxFuture, HostStateImpl};
 
 /// Host import function registry
+///
+/// Clones share the registered handlers.
+#[derive(Clone)]
 pub struct HostImportRegistry {
+    /// Instance the imports belong to, or `None` for top-level imports
+    instance: Option<String>,
     imports: HashMap<String, HostImport>,
 }
 
 impl HostImportRegistry {
     pub fn new() -> Self {
         Self {
+            instance: None,
             imports: HashMap::new(),
         }
     }
 
+    /// Provide the imports as functions of the imported instance `name`
+    /// (e.g. `example:host/math`) instead of as top-level functions
+    pub fn with_instance(mut self, name: impl Into<String>) -> Self {
+        self.instance = Some(name.into());
+        self
+    }
+
+    /// Define every registered import in `linker`
+    ///
+    /// Async imports can only be called by guests of containers built with
+    /// [`build_async`](crate::container::ContainerBuilder::build_async).
+    /// Called by [`ContainerBuilder::with_host_imports`](crate::container::ContainerBuilder::with_host_imports)
+    /// before the guest is instantiated.
+    pub fn add_to_linker<T: HostStateImpl>(&self, linker: &mut Linker<T>) -> Result<()> {
+        let root = linker.root();
+        let mut linker = match &self.instance {
+            Some(name) => root.into_instance(name)?,
+            None => root,
+        };
+        for (name, import) in &self.imports {
+            match &import.handler {
+                HostImportHandler::Sync(handler) => {
+                    let (import, handler) = (name.clone(), handler.clone());
+                    linker
+                        .func_new(name, move |_store, _ty, args, results| {
+                            handler(args)
+                                .and_then(|values| copy_results(&import, values, results))
+                                .map_err(wasmtime::Error::from_anyhow)
+                        })
+                        .map_err(anyhow::Error::from)
+                        .with_context(|| format!("Failed to link host import {}", name))?;
+                }
+                HostImportHandler::Async(handler) => {
+                    let (import, handler) = (name.clone(), handler.clone());
+                    linker
+                        .func_new_async(name, move |_store, _ty, args, results| {
+                            let (import, call) = (import.clone(), handler(args.to_vec()));
+                            Box::new(async move {
+                                call.await
+                                    .and_then(|values| copy_results(&import, values, results))
+                                    .map_err(wasmtime::Error::from_anyhow)
+                            })
+                        })
+                        .map_err(anyhow::Error::from)
+                        .with_context(|| format!("Failed to link host import {}", name))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Register a host import function
     pub fn register<F>(&mut self, name: String, params: Vec<Type>, results: Vec<Type>, handler: F)
     where
@@ -28,19 +87,72 @@ impl HostImportRegistry {
         let import = HostImport {
             params,
             results,
-            handler: Arc::new(handler),
+            handler: HostImportHandler::Sync(Arc::new(handler)),
+        };
+        self.imports.insert(name, import);
+    }
+
+    /// Register an async host import function
+    ///
+    /// The handler receives owned arguments so the returned future can outlive
+    /// the call site. Guests reach async imports only in containers built with
+    /// [`build_async`](crate::container::ContainerBuilder::build_async); on the
+    /// host, invoke them through [`call_async`](HostImportRegistry::call_async).
+    pub fn register_async<F, Fut>(
+        &mut self,
+        name: String,
+        params: Vec<Type>,
+        results: Vec<Type>,
+        handler: F,
+    ) where
+        F: Fn(Vec<Val>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Vec<Val>>> + Send + 'static,
+    {
+        let import = HostImport {
+            params,
+            results,
+            handler: HostImportHandler::Async(Arc::new(
+                move |args| -> BoxFuture<'static, Result<Vec<Val>>> { Box::pin(handler(args)) },
+            )),
         };
         self.imports.insert(name, import);
     }
 
     /// Dynamically call a host import function
+    ///
+    /// Fails for imports registered with
+    /// [`register_async`](HostImportRegistry::register_async).
     pub fn call(&self, name: &str, args: &[Val]) -> Result<Vec<Val>> {
-        let import = self
-            .imports
+        match &self.get(name)?.handler {
+            HostImportHandler::Sync(handler) => handler(args),
+            HostImportHandler::Async(_) => {
+                anyhow::bail!("Host import {} is async, use call_async()", name)
+            }
+        }
+    }
+
+    /// Dynamically call a host import function, awaiting async handlers
+    ///
+    /// Synchronous handlers are called directly.
+    pub async fn call_async(&self, name: &str, args: &[Val]) -> Result<Vec<Val>> {
+        match &self.get(name)?.handler {
+            HostImportHandler::Sync(handler) => handler(args),
+            HostImportHandler::Async(handler) => handler(args.to_vec()).await,
+        }
+    }
+
+    /// Whether the import was registered with
+    /// [`register_async`](HostImportRegistry::register_async)
+    pub fn is_async(&self, name: &str) -> Option<bool> {
+        self.imports
             .get(name)
-            .ok_or_else(|| anyhow::anyhow!("Host import not found: {}", name))?;
+            .map(|i| matches!(i.handler, HostImportHandler::Async(_)))
+    }
 
-        (import.handler)(args)
+    fn get(&self, name: &str) -> Result<&HostImport> {
+        self.imports
+            .get(name)
+            .ok_or_else(|| anyhow::anyhow!("Host import not found: {}", name))
     }
 
     /// List all registered import functions
@@ -56,16 +168,52 @@ impl HostImportRegistry {
     }
 }
 
+impl std::fmt::Debug for HostImportRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HostImportRegistry")
+            .field("instance", &self.instance)
+            .field("imports", &self.list_imports())
+            .finish()
+    }
+}
+
 impl Default for HostImportRegistry {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// Write a handler's results into the slots wasmtime provides
+fn copy_results(name: &str, values: Vec<Val>, results: &mut [Val]) -> Result<()> {
+    if values.len() != results.len() {
+        anyhow::bail!(
+            "Host import {} returned {} values, expected {}",
+            name,
+            values.len(),
+            results.len()
+        );
+    }
+    for (slot, value) in results.iter_mut().zip(values) {
+        *slot = value;
+    }
+    Ok(())
+}
+
 /// Type alias for host import handler function to reduce type complexity
-type HostImportHandler = Arc<dyn Fn(&[Val]) -> Result<Vec<Val>> + Send + Sync>;
+type SyncHandler = Arc<dyn Fn(&[Val]) -> Result<Vec<Val>> + Send + Sync>;
+
+/// Type alias for async host import handler function
+type AsyncHandler = Arc<dyn Fn(Vec<Val>) -> BoxFuture<'static, Result<Vec<Val>>> + Send + Sync>;
+
+/// Handler of a registered host import
+#[derive(Clone)]
+enum HostImportHandler {
+    Sync(SyncHandler),
+    Async(AsyncHandler),
+}
 
 /// Host import function descriptor
+#[derive(Clone)]
 pub struct HostImport {
     params: Vec<Type>,
     results: Vec<Type>,
@@ -110,4 +258,99 @@ mod tests {
         let result = registry.call("add", &[Val::U32(10), Val::U32(32)]).unwrap();
         assert_eq!(result[0], Val::U32(42));
     }
+
+    #[tokio::test]
+    async fn test_host_import_call_async() {
+        let mut registry = HostImportRegistry::new();
+
+        registry.register_async(
+            "double".to_string(),
+            vec![Type::U32],
+            vec![Type::U32],
+            |args| async move {
+                match args.first() {
+                    Some(Val::U32(n)) => Ok(vec![Val::U32(n * 2)]),
+                    _ => Err(anyhow::anyhow!("Type error")),
+                }
+            },
+        );
+        registry.register("one".to_string(), vec![], vec![Type::U32], |_args| {
+            Ok(vec![Val::U32(1)])
+        });
+
+        assert_eq!(registry.is_async("double"), Some(true));
+        assert_eq!(registry.is_async("one"), Some(false));
+        assert_eq!(registry.is_async("missing"), None);
+
+        let result = registry
+            .call_async("double", &[Val::U32(21)])
+            .await
+            .unwrap();
+        assert_eq!(result[0], Val::U32(42));
+        let result = registry.call_async("one", &[]).await.unwrap();
+        assert_eq!(result[0], Val::U32(1));
+
+        let err = registry.call("double", &[Val::U32(21)]).unwrap_err();
+        assert!(err.to_string().contains("call_async"));
+    }
+
+    #[tokio::test]
+    async fn test_guest_calls_async_import() {
+        use crate::{Container, GuestInstance, Image};
+
+        let wat = r#"
+            (component
+              (import "double" (func $double (param "n" u32) (result u32)))
+              (core func $double (canon lower (func $double)))
+              (core module $m
+                (import "host" "double" (func $double (param i32) (result i32)))
+                (func (export "run") (param i32) (result i32)
+                  local.get 0
+                  call $double))
+              (core instance $host (export "double" (func $double)))
+              (core instance $i (instantiate $m (with "host" (instance $host))))
+              (func (export "run") (param "x" u32) (result u32)
+                (canon lift (core func $i "run")))
+            )
+        "#;
+        let binary = wat::parse_str(wat).expect("valid WAT");
+        let image = Image::from_component(binary.into()).expect("valid component");
+        let mut registry = HostImportRegistry::new();
+        registry.register_async(
+            "double".to_string(),
+            vec![Type::U32],
+            vec![Type::U32],
+            |args| async move {
+                tokio::task::yield_now().await;
+                match args.first() {
+                    Some(Val::U32(n)) => Ok(vec![Val::U32(n * 2)]),
+                    _ => Err(anyhow::anyhow!("Type error")),
+                }
+            },
+        );
+
+        let err = Container::builder(image.clone())
+            .with_host_imports(registry.clone())
+            .with_guest_initializer(|_ctx| Ok(GuestInstance::new(())))
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("build_async"), "{err}");
+
+        let mut container = Container::builder(image)
+            .with_host_imports(registry)
+            .with_async_guest_initializer(|ctx| {
+                Box::pin(async move {
+                    let instance = ctx
+                        .linker
+                        .instantiate_async(&mut *ctx.store, ctx.component)
+                        .await?;
+                    Ok(GuestInstance::new_dynamic(instance))
+                })
+            })
+            .build_async()
+            .await
+            .unwrap();
+        let result = container.call_guest_raw_desc_async("run", "21").await;
+        assert_eq!(result.unwrap(), "42");
+    }
 }
@@ -0,0 +1,386 @@
+//! Identifier handling for enum, flags and variant case names
+//!
+//! WIT case names are kebab-case (`light-blue`), which is not a valid RON
+//! identifier, so they are written in UpperCamelCase (`LightBlue`), matching
+//! the Rust types wit-bindgen generates. Parsing accepts any spelling that
+//! differs only in case and separators.
+//!
+//! `ron::Value` discards the name of a bare identifier (`Red` parses as
+//! `()`), so [`quote_bare_identifiers`] rewrites them into strings before
+//! parsing. It likewise drops the case of `LightBlue(true)`, so variant and
+//! result cases with a payload are rewritten as single-entry maps
+//! (`{"LightBlue": true}`). Only payloads whose target type has names to
+//! recover are rewritten, see [`Quoting::for_types`].
+
+use wasmtime::component::Type;
+
+/// Identifiers RON gives a meaning of its own.
+const KEYWORDS: &[&str] = &["true", "false", "Some", "None", "inf", "NaN"];
+
+/// Name of the opaque resource handle token (`Resource(n)`)
+const RESOURCE_TOKEN: &str = "Resource";
+
+/// Convert a WIT name to a RON identifier (`light-blue` → `LightBlue`)
+pub(crate) fn to_ron_ident(wit_name: &str) -> String {
+    wit_name
+        .split(['-', '_'])
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Whether `ident` names `wit_name`, ignoring case and `-` / `_` separators
+pub(crate) fn same_name(ident: &str, wit_name: &str) -> bool {
+    let normalize = |s: &str| {
+        s.chars()
+            .filter(|c| *c != '-' && *c != '_')
+            .flat_map(char::to_lowercase)
+            .collect::<String>()
+    };
+    normalize(ident) == normalize(wit_name)
+}
+
+/// Which bare identifiers [`quote_bare_identifiers`] rewrites
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Quoting {
+    /// Record field names written as map keys (`{x: 1}`)
+    pub map_keys: bool,
+    /// Enum, flag and unit variant case names in value position (`Red`)
+    pub names: bool,
+    /// Variant and result cases with a payload (`LightBlue(true)`)
+    pub cases: bool,
+}
+
+impl Quoting {
+    /// The rewrites a payload for `types` needs; none for types without
+    /// records or named cases
+    pub fn for_types(types: &[Type]) -> Self {
+        let mut quoting = Self::default();
+        for ty in types {
+            quoting.visit(ty);
+        }
+        quoting
+    }
+
+    pub fn is_none(self) -> bool {
+        self == Self::default()
+    }
+
+    fn visit(&mut self, ty: &Type) {
+        match ty {
+            Type::Record(record) => {
+                self.map_keys = true;
+                for field in record.fields() {
+                    self.visit(&field.ty);
+                }
+            }
+            Type::Variant(variant) => {
+                self.names = true;
+                self.cases = true;
+                for ty in variant.cases().filter_map(|case| case.ty) {
+                    self.visit(&ty);
+                }
+            }
+            Type::Enum(_) | Type::Flags(_) => self.names = true,
+            Type::List(list) => self.visit(&list.ty()),
+            Type::Tuple(tuple) => tuple.types().for_each(|ty| self.visit(&ty)),
+            Type::Option(option) => self.visit(&option.ty()),
+            Type::Result(result) => {
+                self.cases = true;
+                result
+                    .ok()
+                    .iter()
+                    .chain(&result.err())
+                    .for_each(|ty| self.visit(ty));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Rewrite bare identifiers in RON source as strings
+///
+/// Keywords, resource tokens, named structs (`Name(field: ...)`) and struct
+/// field names (`(field: ...)`) are always left alone. Map keys inside `{}`
+/// are quoted with [`Quoting::map_keys`], so `{x: 1}` reads as `{"x": 1}`.
+/// With [`Quoting::cases`] a case with a payload becomes a map, so `Ok(1)`
+/// reads as `{"Ok": 1}`; otherwise its name is left alone. Other identifiers
+/// are quoted with [`Quoting::names`]. Strings, chars, comments and
+/// attributes are copied verbatim.
+pub(crate) fn quote_bare_identifiers(src: &str, quoting: Quoting) -> String {
+    let chars: Vec<char> = src.chars().collect();
+    let mut out = String::with_capacity(src.len() + 16);
+    // Closing bracket of each open bracket; a case payload closes with `}`
+    let mut brackets = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            '"' => i = copy_quoted(&chars, i, '"', &mut out),
+            '\'' => i = copy_quoted(&chars, i, '\'', &mut out),
+            'r' if matches!(next, Some('"')) || raw_string_hashes(&chars, i + 1).is_some() => {
+                i = copy_raw_string(&chars, i, &mut out);
+            }
+            'r' if next == Some('#') => {
+                // Raw identifier (`r#type`) - never quoted
+                let end = ident_end(&chars, i + 2);
+                out.extend(&chars[i..end]);
+                i = end;
+            }
+            '/' if next == Some('/') => {
+                let end = find(&chars, i, "\n").map_or(chars.len(), |p| p + 1);
+                out.extend(&chars[i..end]);
+                i = end;
+            }
+            '/' if next == Some('*') => {
+                let end = find(&chars, i + 2, "*/").map_or(chars.len(), |p| p + 2);
+                out.extend(&chars[i..end]);
+                i = end;
+            }
+            '#' => {
+                // Attribute such as `#![enable(implicit_some)]`
+                let end = find(&chars, i, "]").map_or(chars.len(), |p| p + 1);
+                out.extend(&chars[i..end]);
+                i = end;
+            }
+            '(' | '[' | '{' => {
+                brackets.push(match c {
+                    '(' => ')',
+                    '[' => ']',
+                    _ => '}',
+                });
+                out.push(c);
+                i += 1;
+            }
+            ')' | ']' | '}' => {
+                out.push(brackets.pop().unwrap_or(c));
+                i += 1;
+            }
+            c if c.is_ascii_digit() => {
+                let mut end = i + 1;
+                while end < chars.len() {
+                    let d = chars[end];
+                    let exponent_sign = (d == '+' || d == '-')
+                        && matches!(chars[end - 1], 'e' | 'E')
+                        && !chars[i..end].contains(&'x');
+                    if d.is_ascii_alphanumeric() || d == '_' || d == '.' || exponent_sign {
+                        end += 1;
+                    } else {
+                        break;
+                    }
+                }
+                out.extend(&chars[i..end]);
+                i = end;
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let end = ident_end(&chars, i);
+                let ident: String = chars[i..end].iter().collect();
+                let open = skip_whitespace(&chars, end);
+                let following = chars.get(open);
+                if KEYWORDS.contains(&ident.as_str()) {
+                    out.push_str(&ident);
+                } else if following == Some(&'(') {
+                    if quoting.cases && ident != RESOURCE_TOKEN && !is_named_struct(&chars, open) {
+                        // `Case(payload)` -> `{"Case": payload}`
+                        out.push_str(&format!("{{\"{}\": ", ident));
+                        brackets.push('}');
+                        i = open + 1;
+                        continue;
+                    }
+                    out.push_str(&ident);
+                } else if following == Some(&':') {
+                    if quoting.map_keys && brackets.last() == Some(&'}') {
+                        out.push_str(&format!("\"{}\"", ident));
+                    } else {
+                        out.push_str(&ident);
+                    }
+                } else if quoting.names {
+                    out.push_str(&format!("\"{}\"", ident));
+                } else {
+                    out.push_str(&ident);
+                }
+                i = end;
+            }
+            _ => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+
+    out
+}
+
+fn ident_end(chars: &[char], start: usize) -> usize {
+    let mut end = start;
+    while end < chars.len() && (chars[end].is_ascii_alphanumeric() || chars[end] == '_') {
+        end += 1;
+    }
+    end
+}
+
+fn skip_whitespace(chars: &[char], start: usize) -> usize {
+    let mut end = start;
+    while end < chars.len() && chars[end].is_whitespace() {
+        end += 1;
+    }
+    end
+}
+
+/// Whether the `(` at `open` starts named fields (`(field: ...)`)
+fn is_named_struct(chars: &[char], open: usize) -> bool {
+    let start = skip_whitespace(chars, open + 1);
+    let end = ident_end(chars, start);
+    end > start && chars.get(skip_whitespace(chars, end)) == Some(&':')
+}
+
+fn find(chars: &[char], start: usize, pattern: &str) -> Option<usize> {
+    let pattern: Vec<char> = pattern.chars().collect();
+    (start..chars.len()).find(|&p| chars[p..].starts_with(&pattern))
+}
+
+/// Copy a `"..."` or `'...'` literal starting at `start`, honouring escapes.
+fn copy_quoted(chars: &[char], start: usize, quote: char, out: &mut String) -> usize {
+    let mut i = start + 1;
+    while i < chars.len() && chars[i] != quote {
+        i += if chars[i] == '\\' { 2 } else { 1 };
+    }
+    let end = (i + 1).min(chars.len());
+    out.extend(&chars[start..end]);
+    end
+}
+
+/// Number of `#`s if a raw string (`r#"`, `r##"`, ...) starts at `start`.
+fn raw_string_hashes(chars: &[char], start: usize) -> Option<usize> {
+    let hashes = chars[start.min(chars.len())..]
+        .iter()
+        .take_while(|c| **c == '#')
+        .count();
+    (hashes > 0 && chars.get(start + hashes) == Some(&'"')).then_some(hashes)
+}
+
+/// Copy a raw string literal starting at the `r` at `start`.
+fn copy_raw_string(chars: &[char], start: usize, out: &mut String) -> usize {
+    let hashes = raw_string_hashes(chars, start + 1).unwrap_or(0);
+    let terminator: String = std::iter::once('"')
+        .chain(std::iter::repeat_n('#', hashes))
+        .collect();
+    let body = start + 2 + hashes;
+    let end = find(chars, body, &terminator).map_or(chars.len(), |p| p + terminator.len());
+    out.extend(&chars[start..end]);
+    end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_ron_ident() {
+        assert_eq!(to_ron_ident("red"), "Red");
+        assert_eq!(to_ron_ident("light-blue"), "LightBlue");
+        assert_eq!(to_ron_ident("read_only"), "ReadOnly");
+    }
+
+    #[test]
+    fn test_same_name() {
+        assert!(same_name("LightBlue", "light-blue"));
+        assert!(same_name("light_blue", "light-blue"));
+        assert!(same_name("light-blue", "light-blue"));
+        assert!(!same_name("Blue", "light-blue"));
+    }
+
+    const ALL: Quoting = Quoting {
+        map_keys: true,
+        names: true,
+        cases: true,
+    };
+
+    #[test]
+    fn test_quote_bare_identifiers() {
+        assert_eq!(quote_bare_identifiers("Red", ALL), "\"Red\"");
+        assert_eq!(
+            quote_bare_identifiers("[Read, Write]", ALL),
+            "[\"Read\", \"Write\"]"
+        );
+        assert_eq!(quote_bare_identifiers("{x: Red}", ALL), "{\"x\": \"Red\"}");
+        assert_eq!(
+            quote_bare_identifiers("Point(x: 1, color: Red)", ALL),
+            "Point(x: 1, color: \"Red\")"
+        );
+        assert_eq!(
+            quote_bare_identifiers("(Some(true), None, -inf)", ALL),
+            "(Some(true), None, -inf)"
+        );
+        assert_eq!(quote_bare_identifiers("Resource(3)", ALL), "Resource(3)");
+    }
+
+    #[test]
+    fn test_quote_bare_identifiers_is_scoped() {
+        let keys = Quoting {
+            map_keys: true,
+            names: false,
+            cases: false,
+        };
+        assert_eq!(quote_bare_identifiers("{x: Red}", keys), "{\"x\": Red}");
+        let names = Quoting {
+            map_keys: false,
+            names: true,
+            cases: false,
+        };
+        assert_eq!(quote_bare_identifiers("{x: Red}", names), "{x: \"Red\"}");
+        assert_eq!(
+            quote_bare_identifiers("{x: Red}", Quoting::default()),
+            "{x: Red}"
+        );
+        assert_eq!(
+            quote_bare_identifiers("LightBlue(true)", names),
+            "LightBlue(true)"
+        );
+
+        assert!(Quoting::for_types(&[Type::U32, Type::String]).is_none());
+    }
+
+    #[test]
+    fn test_quote_bare_identifiers_rewrites_cases() {
+        assert_eq!(
+            quote_bare_identifiers("LightBlue(true)", ALL),
+            "{\"LightBlue\": true}"
+        );
+        assert_eq!(
+            quote_bare_identifiers("[Ok(Circle), Err ((1, 2))]", ALL),
+            "[{\"Ok\": \"Circle\"}, {\"Err\": (1, 2)}]"
+        );
+        assert_eq!(
+            quote_bare_identifiers("Sized((max_size: 3))", ALL),
+            "{\"Sized\": (max_size: 3)}"
+        );
+        assert_eq!(
+            quote_bare_identifiers("Boxed(Some(Resource(0)))", ALL),
+            "{\"Boxed\": Some(Resource(0))}"
+        );
+    }
+
+    #[test]
+    fn test_quote_bare_identifiers_skips_literals() {
+        assert_eq!(
+            quote_bare_identifiers(r#"("Red", 'x', r"Green", 1e-5, 0xFF)"#, ALL),
+            r#"("Red", 'x', r"Green", 1e-5, 0xFF)"#
+        );
+        assert_eq!(
+            quote_bare_identifiers("Red // Blue\n/* Green */", ALL),
+            "\"Red\" // Blue\n/* Green */"
+        );
+        assert_eq!(
+            quote_bare_identifiers("#![enable(implicit_some)]\nRed", ALL),
+            "#![enable(implicit_some)]\n\"Red\""
+        );
+    }
+}
@@ -19,22 +19,39 @@
 //! - Result (Ok/Err with optional payloads)
 //! - Option (Some/None)
 //!
+//! ## Named Types
+//! - Enum cases as bare identifiers: `light-blue` is written `LightBlue`
+//! - Flags as sets of bare identifiers: `[Read, Write]`
+//!
+//! ## Resources
+//! - `own<T>` / `borrow<T>` handles as opaque `Resource(n)` tokens, where `n`
+//!   indexes the container's `ResourceTable` (see [`val_to_ron_with_resources`]
+//!   and [`ron_to_val_with_resources`])
+//! - A token lives until it is passed as `own<T>` or released with
+//!   [`Container::drop_resource`](crate::Container::drop_resource)
+//!
 //! # Architecture
 //!
 //! This module is organized into submodules for better maintainability:
 //! - [`serialize`] - Val → RON conversion
 //! - [`deserialize`] - RON → Val conversion with full nesting support
 //! - [`host_imports`] - Host import function registry
+//! - [`wit_imports`] - Host imports registered from a WIT interface
 
 pub mod host_imports;
+mod ident;
+pub mod wit_imports;
 
 // Re-export modular serialization/deserialization
 pub mod deserialize;
 pub mod serialize;
 
 // Public API
-pub use deserialize::{ron_to_val, ron_value_to_val};
-pub use serialize::val_to_ron;
+pub use deserialize::{
+    parse_ron, parse_ron_for, ron_to_val, ron_to_val_with_resources, ron_value_to_val,
+    ron_value_to_val_with_resources, take_resource_token,
+};
+pub use serialize::{val_to_ron, val_to_ron_with_resources};
 
 #[cfg(test)]
 mod tests {
@@ -114,8 +131,67 @@ mod tests {
         assert!(matches!(result, Val::Float64(_)));
     }
 
-    // Note: Tests for complex types (list, tuple, option, result with nesting)
-    // are in integration_test.rs where we can extract types from actual
+    #[test]
+    fn test_resource_round_trip() {
+        use wasmtime::{
+            component::{Resource, ResourceAny, ResourceTable, ResourceType, Type},
+            Engine, Store,
+        };
+
+        struct Counter;
+        struct Other;
+
+        let engine = Engine::default();
+        let mut store = Store::new(&engine, ());
+        let resource =
+            ResourceAny::try_from_resource(Resource::<Counter>::new_own(7), &mut store).unwrap();
+        let mut table = ResourceTable::new();
+
+        assert!(val_to_ron(&Val::Resource(resource)).is_err());
+        let token = val_to_ron_with_resources(&Val::Resource(resource), &mut table).unwrap();
+        assert!(token.starts_with("Resource("), "token: {}", token);
+
+        let borrow = Type::Borrow(ResourceType::host::<Counter>());
+        let own = Type::Own(ResourceType::host::<Counter>());
+        let wrong = Type::Own(ResourceType::host::<Other>());
+
+        assert!(ron_to_val(&token, &borrow).is_err());
+        assert!(ron_to_val_with_resources(&token, &wrong, &mut table).is_err());
+        assert_eq!(
+            ron_to_val_with_resources(&token, &borrow, &mut table).unwrap(),
+            Val::Resource(resource)
+        );
+        // Passing the handle as `own` spends the token
+        assert_eq!(
+            ron_to_val_with_resources(&token, &own, &mut table).unwrap(),
+            Val::Resource(resource)
+        );
+        assert!(ron_to_val_with_resources(&token, &own, &mut table).is_err());
+
+        // A borrowed token stays in the table until it is taken and dropped
+        let token = val_to_ron_with_resources(&Val::Resource(resource), &mut table).unwrap();
+        ron_to_val_with_resources(&token, &borrow, &mut table).unwrap();
+        let taken = take_resource_token(&token, &mut table).unwrap();
+        assert_eq!(taken, resource);
+        taken.resource_drop(&mut store).unwrap();
+        assert!(take_resource_token(&token, &mut table).is_err());
+    }
+
+    #[test]
+    fn test_val_to_ron_enum_and_flags() {
+        assert_eq!(
+            val_to_ron(&Val::Enum("light-blue".to_string())).unwrap(),
+            "LightBlue"
+        );
+        assert_eq!(
+            val_to_ron(&Val::Flags(vec!["read".to_string(), "write".to_string()])).unwrap(),
+            "[Read, Write]"
+        );
+        assert_eq!(val_to_ron(&Val::Flags(vec![])).unwrap(), "[]");
+    }
+
+    // Note: Tests for complex types (list, tuple, option, result with nesting,
+    // enum, flags) are in integration_test.rs where we can extract types from actual
     // WASM component functions. The wasmtime 40 API doesn't provide
     // public constructors for List, Tuple, OptionType, ResultType.
 }
@@ -3,9 +3,11 @@
 //! This module provides conversion from Wasmtime Component Model `Val` types
 //! to RON (Rust Object Notation), with full support for nested complex types.
 
-use anyhow::{bail, Result};
+use anyhow::{anyhow, bail, Result};
 
-use wasmtime::component::Val;
+use wasmtime::component::{ResourceTable, Val};
+
+use super::ident::to_ron_ident;
 
 /// Convert Wasmtime Val to RON string
 ///
@@ -18,9 +20,14 @@ use wasmtime::component::Val;
 /// - List<T> including List<List<T>>
 /// - Tuple<T1, T2, ...> including nested tuples
 /// - Record { fields } including nested records
-/// - Variant cases with optional payloads
+/// - Variant cases with optional payloads (`LightBlue(true)`, `Circle`)
 /// - Result<T, E>
 /// - Option<T>
+/// - Enum cases as bare identifiers (`light-blue` -> `LightBlue`)
+/// - Flags as a set of bare identifiers (`[Read, Write]`)
+///
+/// Resource handles need the container's table, see
+/// [`val_to_ron_with_resources`].
 ///
 /// # Examples
 ///
@@ -40,6 +47,54 @@ use wasmtime::component::Val;
 /// assert_eq!(ron, "[[1, 2], [3, 4]]");
 /// ```
 pub fn val_to_ron(val: &Val) -> Result<String> {
+    write(val, &mut None)
+}
+
+/// Convert Wasmtime Val to RON string, storing resource handles in `resources`
+///
+/// Each `Val::Resource` is pushed into the table and written as an opaque
+/// `Resource(n)` token, which [`ron_to_val_with_resources`](super::ron_to_val_with_resources)
+/// resolves back to the same handle.
+pub fn val_to_ron_with_resources(val: &Val, resources: &mut ResourceTable) -> Result<String> {
+    write_with_resources(val, resources, &mut Vec::new())
+}
+
+/// [`val_to_ron_with_resources`], appending the rep of every token it stores
+/// to `issued`
+///
+/// Tokens stored before an error are still listed, so the caller can release
+/// them.
+pub(crate) fn write_with_resources(
+    val: &Val,
+    resources: &mut ResourceTable,
+    issued: &mut Vec<u32>,
+) -> Result<String> {
+    write(
+        val,
+        &mut Some(Tokens {
+            table: resources,
+            issued,
+        }),
+    )
+}
+
+/// Table receiving resource handles, and the reps of the tokens written
+struct Tokens<'a> {
+    table: &'a mut ResourceTable,
+    issued: &'a mut Vec<u32>,
+}
+
+fn write(val: &Val, tokens: &mut Option<Tokens<'_>>) -> Result<String> {
+    if let Val::Resource(resource) = val {
+        let tokens = tokens.as_mut().ok_or_else(|| {
+            anyhow!("Resource values require a ResourceTable, use val_to_ron_with_resources")
+        })?;
+        let rep = named::serialize_resource(*resource, tokens.table)?;
+        tokens.issued.push(rep);
+        return Ok(format!("Resource({})", rep));
+    }
+    let mut recurse = |val: &Val| write(val, tokens);
+
     match val {
         // Delegate to type-specific handlers
         Val::Bool(b) => basic::serialize_bool(*b),
@@ -57,12 +112,16 @@ pub fn val_to_ron(val: &Val) -> Result<String> {
         Val::String(s) => basic::serialize_string(s.as_str()),
 
         // Complex types - delegate to complex module
-        Val::List(items) => complex::serialize_list(items, val_to_ron),
-        Val::Tuple(items) => complex::serialize_tuple(items, val_to_ron),
-        Val::Record(fields) => complex::serialize_record(fields, val_to_ron),
-        Val::Variant(case_name, val) => complex::serialize_variant(case_name, val, val_to_ron),
-        Val::Result(r) => complex::serialize_result(r, val_to_ron),
-        Val::Option(o) => complex::serialize_option(o, val_to_ron),
+        Val::List(items) => complex::serialize_list(items, &mut recurse),
+        Val::Tuple(items) => complex::serialize_tuple(items, &mut recurse),
+        Val::Record(fields) => complex::serialize_record(fields, &mut recurse),
+        Val::Variant(case_name, val) => complex::serialize_variant(case_name, val, &mut recurse),
+        Val::Result(r) => complex::serialize_result(r, &mut recurse),
+        Val::Option(o) => complex::serialize_option(o, &mut recurse),
+
+        // Named types - delegate to named module
+        Val::Enum(case_name) => named::serialize_enum(case_name),
+        Val::Flags(flags) => named::serialize_flags(flags),
 
         _ => bail!("Unsupported Val type for RON conversion: {:?}", val),
     }
@@ -132,7 +191,7 @@ mod complex {
     use super::*;
 
     /// Type alias for recursive serializer function
-    pub type Serializer = fn(&Val) -> Result<String>;
+    pub type Serializer<'a> = &'a mut dyn FnMut(&Val) -> Result<String>;
 
     /// Serialize List<T> including nested lists
     ///
@@ -170,16 +229,16 @@ mod complex {
     /// Serialize Variant with optional payload
     ///
     /// # Examples
-    /// - Some(42) -> "Some(42)"
-    /// - None -> "None" (unit variant)
+    /// - Variant("light-blue", Some(true)) -> "LightBlue(true)"
+    /// - Variant("circle", None) -> "Circle" (unit case)
     pub fn serialize_variant(
         case_name: &str,
         val: &Option<Box<Val>>,
         serialize: Serializer,
     ) -> Result<String> {
         match val {
-            Some(v) => Ok(format!("{}({})", case_name, serialize(v)?)),
-            None => Ok(case_name.to_string()),
+            Some(v) => Ok(format!("{}({})", to_ron_ident(case_name), serialize(v)?)),
+            None => Ok(to_ron_ident(case_name)),
         }
     }
 
@@ -214,6 +273,41 @@ mod complex {
     }
 }
 
+// Enum, flags and resource serializers
+mod named {
+    use wasmtime::component::ResourceAny;
+
+    use super::*;
+
+    /// Serialize an enum case as a bare identifier
+    ///
+    /// # Examples
+    /// - Enum("light-blue") -> "LightBlue"
+    pub fn serialize_enum(case_name: &str) -> Result<String> {
+        Ok(to_ron_ident(case_name))
+    }
+
+    /// Serialize flags as a set of bare identifiers
+    ///
+    /// # Examples
+    /// - Flags(["read", "write"]) -> "[Read, Write]"
+    /// - Flags([]) -> "[]"
+    pub fn serialize_flags(flags: &[String]) -> Result<String> {
+        let flags: Vec<_> = flags.iter().map(|flag| to_ron_ident(flag)).collect();
+        Ok(format!("[{}]", flags.join(", ")))
+    }
+
+    /// Store a resource in `table`, returning the rep its token is written
+    /// with
+    ///
+    /// # Examples
+    /// - first resource stored -> 0, written "Resource(0)"
+    pub fn serialize_resource(resource: ResourceAny, table: &mut ResourceTable) -> Result<u32> {
+        let handle = table.push(resource)?;
+        Ok(handle.rep())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
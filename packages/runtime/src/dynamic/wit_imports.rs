@@ -0,0 +1,638 @@
+//! WIT-driven host import registration
+//!
+//! [`WitImports`] registers every function of a WIT interface parsed by a
+//! [`WitLoader`] with a [`HostImportRegistry`]. Handlers receive their
+//! arguments either as RON text or decoded with serde, and
+//! [`build`](WitImports::build) checks the handlers against the interface and
+//! the interface against the component's imports, so mistakes surface before
+//! the component is instantiated. Pass the registry to
+//! [`ContainerBuilder::with_host_imports`](crate::container::ContainerBuilder::with_host_imports)
+//! to serve the guest's calls.
+//!
+//! # Example
+//! ```ignore
+//! let loader = WitLoader::from_dir("./wit")?;
+//! let registry = HostImportRegistry::from_wit(&loader, "example:host/math")
+//!     .handler("add", |(a, b): (u32, u32)| Ok(a + b))
+//!     .ron_handler("describe", |args| Ok(format!("\"called with {args}\"")))
+//!     .build(&image)?;
+//! let container = Container::builder(image)
+//!     .with_host_imports(registry)
+//!     .with_guest_initializer(|ctx| { /* ... */ })
+//!     .build()?;
+//! ```
+
+use anyhow::{anyhow, bail, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{collections::HashMap, sync::Arc};
+
+use wasmtime::component::{
+    types::{ComponentFunc, ComponentItem},
+    Type, Val,
+};
+use wit_parser::{Handle, InterfaceId, Resolve, TypeDefKind};
+
+use super::{host_imports::HostImportRegistry, ident::to_ron_ident, ron_to_val, val_to_ron};
+use crate::{Image, WitLoader};
+
+/// Handler receiving a call's arguments and returning its result as RON
+type RonHandler = Arc<dyn Fn(&[Val]) -> Result<String> + Send + Sync>;
+
+/// Builder registering a WIT interface's functions as host imports
+///
+/// Created with [`HostImportRegistry::from_wit`].
+pub struct WitImports<'a> {
+    loader: &'a WitLoader,
+    interface: String,
+    handlers: HashMap<String, RonHandler>,
+}
+
+impl HostImportRegistry {
+    /// Register the functions of a WIT interface, see [`WitImports`]
+    ///
+    /// `interface` is the interface's full name
+    /// (`package:name/interface@version`) or its bare name.
+    pub fn from_wit<'a>(loader: &'a WitLoader, interface: &str) -> WitImports<'a> {
+        WitImports {
+            loader,
+            interface: interface.to_string(),
+            handlers: HashMap::new(),
+        }
+    }
+}
+
+impl WitImports<'_> {
+    /// Handle `function` with RON text
+    ///
+    /// The handler receives the arguments as a RON tuple, e.g. `(1, "two")`,
+    /// and returns the result as RON (`()` for functions without one).
+    pub fn ron_handler<F>(self, function: &str, handler: F) -> Self
+    where
+        F: Fn(&str) -> Result<String> + Send + Sync + 'static,
+    {
+        self.insert(function, move |args| {
+            let args = args.iter().map(val_to_ron).collect::<Result<Vec<_>>>()?;
+            handler(&format!("({})", args.join(", ")))
+        })
+    }
+
+    /// Handle `function` with serde-decoded arguments
+    ///
+    /// A single parameter is decoded into `A` directly; several parameters
+    /// are decoded into a tuple and none into `()`. The returned value is
+    /// converted to the function's result type, with records and variant
+    /// cases read as serde writes them (`(max_size: 3)`, `LightBlue(true)`).
+    pub fn handler<A, R, F>(self, function: &str, handler: F) -> Self
+    where
+        A: DeserializeOwned,
+        R: Serialize,
+        F: Fn(A) -> Result<R> + Send + Sync + 'static,
+    {
+        let name = function.to_string();
+        self.insert(function, move |args| {
+            let args = match args {
+                [arg] => to_serde_ron(arg)?,
+                _ => {
+                    let args = args.iter().map(to_serde_ron).collect::<Result<Vec<_>>>()?;
+                    format!("({})", args.join(", "))
+                }
+            };
+            let args = ron::from_str(&args).map_err(|e| {
+                anyhow!("Failed to decode arguments of host import {}: {}", name, e)
+            })?;
+            Ok(ron::to_string(&handler(args)?)?)
+        })
+    }
+
+    fn insert<F>(mut self, function: &str, handler: F) -> Self
+    where
+        F: Fn(&[Val]) -> Result<String> + Send + Sync + 'static,
+    {
+        self.handlers
+            .insert(function.to_string(), Arc::new(handler));
+        self
+    }
+
+    /// Check the handlers and register them
+    ///
+    /// Fails if a function of the interface has no handler, a handler names
+    /// a function the interface lacks, `image` does not import the interface,
+    /// or an imported function's signature differs from the WIT definition.
+    /// Functions the component does not import are not registered.
+    pub fn build(mut self, image: &Image) -> Result<HostImportRegistry> {
+        let loader = self.loader;
+        let resolve = &loader.resolve;
+        let (interface_id, interface_name) = self.find_interface()?;
+        let interface = &resolve.interfaces[interface_id];
+
+        let missing: Vec<&str> = interface
+            .functions
+            .keys()
+            .filter(|name| !self.handlers.contains_key(*name))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            bail!(
+                "Missing host import handlers for {}: {}",
+                interface_name,
+                missing.join(", ")
+            );
+        }
+        let mut unknown: Vec<&str> = self
+            .handlers
+            .keys()
+            .filter(|name| !interface.functions.contains_key(*name))
+            .map(String::as_str)
+            .collect();
+        if !unknown.is_empty() {
+            unknown.sort_unstable();
+            bail!(
+                "Interface {} has no functions named: {}",
+                interface_name,
+                unknown.join(", ")
+            );
+        }
+
+        let (instance, imported) = component_import(image, &interface_name)?;
+        let mut registry = HostImportRegistry::new().with_instance(instance);
+        for (name, func) in imported {
+            let Some(wit_func) = interface.functions.get(&name) else {
+                bail!(
+                    "Component imports {}#{}, which the WIT interface does not define",
+                    interface_name,
+                    name
+                );
+            };
+            check_signature(resolve, wit_func, &func)
+                .map_err(|e| anyhow!("Host import {}#{}: {}", interface_name, name, e))?;
+
+            let handler = self
+                .handlers
+                .remove(&name)
+                .expect("every interface function has a handler");
+            let params: Vec<Type> = func.params().map(|(_, ty)| ty).collect();
+            let results: Vec<Type> = func.results().collect();
+            let result_types = results.clone();
+            registry.register(name, params, results, move |args| {
+                call_ron_handler(&handler, args, &result_types)
+            });
+        }
+        Ok(registry)
+    }
+
+    /// The interface named by `self.interface` and its full name
+    fn find_interface(&self) -> Result<(InterfaceId, String)> {
+        let resolve = &self.loader.resolve;
+        resolve
+            .interfaces
+            .iter()
+            .find_map(|(id, iface)| {
+                let full_name = resolve.id_of(id)?;
+                (full_name == self.interface || iface.name.as_deref() == Some(&self.interface))
+                    .then_some((id, full_name))
+            })
+            .ok_or_else(|| anyhow!("WIT interface not found: {}", self.interface))
+    }
+}
+
+/// The name under which `image` imports `interface`, and the functions it
+/// imports from it
+///
+/// The component may name the interface with or without its version.
+fn component_import(
+    image: &Image,
+    interface: &str,
+) -> Result<(String, Vec<(String, ComponentFunc)>)> {
+    let engine = image.engine();
+    let unversioned = interface
+        .split_once('@')
+        .map_or(interface, |(name, _)| name);
+    let (name, instance) = image
+        .component()
+        .component_type()
+        .imports(engine)
+        .find_map(|(name, item)| match item.ty {
+            ComponentItem::ComponentInstance(instance)
+                if name == interface || name == unversioned =>
+            {
+                Some((name.to_string(), instance))
+            }
+            _ => None,
+        })
+        .ok_or_else(|| anyhow!("Component does not import interface {}", interface))?;
+
+    let funcs = instance
+        .exports(engine)
+        .filter_map(|(name, item)| match item.ty {
+            ComponentItem::ComponentFunc(func) => Some((name.to_string(), func)),
+            _ => None,
+        })
+        .collect();
+    Ok((name, funcs))
+}
+
+/// Check a component function type against its WIT definition
+fn check_signature(
+    resolve: &Resolve,
+    wit_func: &wit_parser::Function,
+    func: &ComponentFunc,
+) -> Result<()> {
+    let params: Vec<(&str, Type)> = func.params().collect();
+    if params.len() != wit_func.params.len() {
+        bail!(
+            "expected {} parameters, the component has {}",
+            wit_func.params.len(),
+            params.len()
+        );
+    }
+    for (wit_param, (name, ty)) in wit_func.params.iter().zip(params) {
+        if wit_param.name != name {
+            bail!(
+                "expected parameter `{}`, the component has `{}`",
+                wit_param.name,
+                name
+            );
+        }
+        if !type_matches(resolve, &wit_param.ty, &ty) {
+            bail!("parameter `{}` has a different type", name);
+        }
+    }
+
+    let results: Vec<Type> = func.results().collect();
+    let same_result = match (&wit_func.result, results.as_slice()) {
+        (None, []) => true,
+        (Some(wit_ty), [ty]) => type_matches(resolve, wit_ty, ty),
+        _ => false,
+    };
+    if !same_result {
+        bail!("the result has a different type");
+    }
+    Ok(())
+}
+
+/// Whether a WIT type and a component type describe the same values
+///
+/// Resources only need to agree on ownership, as their identity is not known
+/// until instantiation.
+fn type_matches(resolve: &Resolve, wit: &wit_parser::Type, ty: &Type) -> bool {
+    use wit_parser::Type as Wit;
+
+    let id = match (wit, ty) {
+        (Wit::Bool, Type::Bool)
+        | (Wit::U8, Type::U8)
+        | (Wit::U16, Type::U16)
+        | (Wit::U32, Type::U32)
+        | (Wit::U64, Type::U64)
+        | (Wit::S8, Type::S8)
+        | (Wit::S16, Type::S16)
+        | (Wit::S32, Type::S32)
+        | (Wit::S64, Type::S64)
+        | (Wit::F32, Type::Float32)
+        | (Wit::F64, Type::Float64)
+        | (Wit::Char, Type::Char)
+        | (Wit::String, Type::String) => return true,
+        (Wit::Id(id), _) => *id,
+        _ => return false,
+    };
+
+    let both = |wit: Option<&wit_parser::Type>, ty: Option<Type>| match (wit, ty) {
+        (None, None) => true,
+        (Some(wit), Some(ty)) => type_matches(resolve, wit, &ty),
+        _ => false,
+    };
+    match (&resolve.types[id].kind, ty) {
+        (TypeDefKind::Type(alias), _) => type_matches(resolve, alias, ty),
+        (TypeDefKind::List(wit), Type::List(list)) => type_matches(resolve, wit, &list.ty()),
+        (TypeDefKind::Option(wit), Type::Option(option)) => {
+            type_matches(resolve, wit, &option.ty())
+        }
+        (TypeDefKind::Result(wit), Type::Result(result)) => {
+            both(wit.ok.as_ref(), result.ok()) && both(wit.err.as_ref(), result.err())
+        }
+        (TypeDefKind::Tuple(wit), Type::Tuple(tuple)) => {
+            let types: Vec<Type> = tuple.types().collect();
+            wit.types.len() == types.len()
+                && wit
+                    .types
+                    .iter()
+                    .zip(&types)
+                    .all(|(wit, ty)| type_matches(resolve, wit, ty))
+        }
+        (TypeDefKind::Record(wit), Type::Record(record)) => {
+            let fields: Vec<_> = record.fields().collect();
+            wit.fields.len() == fields.len()
+                && wit.fields.iter().zip(fields).all(|(wit, field)| {
+                    wit.name == field.name && type_matches(resolve, &wit.ty, &field.ty)
+                })
+        }
+        (TypeDefKind::Variant(wit), Type::Variant(variant)) => {
+            let cases: Vec<_> = variant.cases().collect();
+            wit.cases.len() == cases.len()
+                && wit
+                    .cases
+                    .iter()
+                    .zip(cases)
+                    .all(|(wit, case)| wit.name == case.name && both(wit.ty.as_ref(), case.ty))
+        }
+        (TypeDefKind::Enum(wit), Type::Enum(enum_type)) => wit
+            .cases
+            .iter()
+            .map(|case| case.name.as_str())
+            .eq(enum_type.names()),
+        (TypeDefKind::Flags(wit), Type::Flags(flags)) => wit
+            .flags
+            .iter()
+            .map(|flag| flag.name.as_str())
+            .eq(flags.names()),
+        (TypeDefKind::Handle(Handle::Own(_)), Type::Own(_))
+        | (TypeDefKind::Handle(Handle::Borrow(_)), Type::Borrow(_)) => true,
+        _ => false,
+    }
+}
+
+/// Call a handler with component values
+fn call_ron_handler(handler: &RonHandler, args: &[Val], results: &[Type]) -> Result<Vec<Val>> {
+    let output = handler(args)?;
+    match results {
+        [] => Ok(Vec::new()),
+        [ty] => Ok(vec![ron_to_val(&output, ty)?]),
+        _ => bail!("Host imports return at most one value"),
+    }
+}
+
+/// Format a value as RON that serde can decode into the matching Rust type
+///
+/// Unlike [`val_to_ron`], records are written as structs with snake_case
+/// field names and variant cases as Rust-style identifiers.
+fn to_serde_ron(val: &Val) -> Result<String> {
+    let join = |vals: &[Val]| -> Result<String> {
+        Ok(vals
+            .iter()
+            .map(to_serde_ron)
+            .collect::<Result<Vec<_>>>()?
+            .join(", "))
+    };
+    let payload = |val: &Option<Box<Val>>| match val {
+        Some(val) => to_serde_ron(val),
+        None => Ok("()".to_string()),
+    };
+    Ok(match val {
+        Val::Record(fields) => {
+            let fields = fields
+                .iter()
+                .map(|(name, val)| {
+                    Ok(format!(
+                        "{}: {}",
+                        name.replace('-', "_"),
+                        to_serde_ron(val)?
+                    ))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            format!("({})", fields.join(", "))
+        }
+        Val::Variant(case, None) => to_ron_ident(case),
+        Val::Variant(case, Some(val)) => format!("{}({})", to_ron_ident(case), to_serde_ron(val)?),
+        Val::List(vals) => format!("[{}]", join(vals)?),
+        Val::Tuple(vals) => format!("({},)", join(vals)?),
+        Val::Option(Some(val)) => format!("Some({})", to_serde_ron(val)?),
+        Val::Result(Ok(val)) => format!("Ok({})", payload(val)?),
+        Val::Result(Err(val)) => format!("Err({})", payload(val)?),
+        _ => val_to_ron(val)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIT: &str = r#"
+        package example:host;
+
+        interface math {
+            record point { x: s32, y: s32 }
+
+            add: func(a: u32, b: u32) -> u32;
+            norm: func(p: point) -> u32;
+            log: func(msg: string);
+        }
+
+        interface config {
+            record cfg { max-size: u32 }
+            variant shape { light-blue(bool), circle }
+
+            defaults: func() -> cfg;
+            pick: func(n: u32) -> shape;
+        }
+    "#;
+
+    fn loader() -> WitLoader {
+        let mut resolve = Resolve::default();
+        resolve.push_str("host.wit", WIT).unwrap();
+        WitLoader { resolve }
+    }
+
+    /// A component importing `add` and `norm`, with `add`'s `b` typed `b_ty`
+    fn image(b_ty: &str) -> Image {
+        let wat = format!(
+            r#"
+            (component
+              (import "example:host/math" (instance
+                (type $point (record (field "x" s32) (field "y" s32)))
+                (export "point" (type $p (eq $point)))
+                (export "add" (func (param "a" u32) (param "b" {b_ty}) (result u32)))
+                (export "norm" (func (param "p" $p) (result u32)))
+              ))
+            )
+            "#
+        );
+        let binary = wat::parse_str(wat).expect("valid WAT");
+        Image::from_component(binary.into()).expect("valid component")
+    }
+
+    #[derive(serde::Deserialize)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    fn imports(loader: &WitLoader) -> WitImports<'_> {
+        HostImportRegistry::from_wit(loader, "example:host/math")
+            .handler("add", |(a, b): (u32, u32)| Ok(a + b))
+            .handler("norm", |p: Point| {
+                Ok(p.x.unsigned_abs() + p.y.unsigned_abs())
+            })
+    }
+
+    #[test]
+    fn test_register_wit_interface() {
+        let loader = loader();
+        let registry = imports(&loader)
+            .ron_handler("log", |_| Ok("()".to_string()))
+            .build(&image("u32"))
+            .unwrap();
+
+        let mut names = registry.list_imports();
+        names.sort_unstable();
+        assert_eq!(names, vec!["add", "norm"]);
+        assert_eq!(
+            registry.call("add", &[Val::U32(40), Val::U32(2)]).unwrap(),
+            vec![Val::U32(42)]
+        );
+        let point = Val::Record(vec![
+            ("x".to_string(), Val::S32(-3)),
+            ("y".to_string(), Val::S32(4)),
+        ]);
+        assert_eq!(registry.call("norm", &[point]).unwrap(), vec![Val::U32(7)]);
+    }
+
+    #[test]
+    fn test_missing_and_unknown_handlers() {
+        let loader = loader();
+        let err = imports(&loader).build(&image("u32")).unwrap_err();
+        assert!(err.to_string().contains("Missing host import handlers"));
+        assert!(err.to_string().ends_with(": log"), "{err}");
+
+        let err = imports(&loader)
+            .ron_handler("log", |_| Ok("()".to_string()))
+            .ron_handler("mul", |_| Ok("0".to_string()))
+            .build(&image("u32"))
+            .unwrap_err();
+        assert!(err.to_string().ends_with(": mul"), "{err}");
+
+        let err = HostImportRegistry::from_wit(&loader, "missing")
+            .build(&image("u32"))
+            .unwrap_err();
+        assert!(err.to_string().contains("not found"), "{err}");
+    }
+
+    #[test]
+    fn test_signature_mismatch() {
+        let loader = loader();
+        let err = imports(&loader)
+            .ron_handler("log", |_| Ok("()".to_string()))
+            .build(&image("u64"))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Host import example:host/math#add: parameter `b` has a different type"
+        );
+    }
+
+    #[test]
+    fn test_guest_calls_linked_import() {
+        let wat = r#"
+            (component
+              (import "example:host/math" (instance $math
+                (type $point (record (field "x" s32) (field "y" s32)))
+                (export "point" (type $p (eq $point)))
+                (export "add" (func (param "a" u32) (param "b" u32) (result u32)))
+              ))
+              (core func $add (canon lower (func $math "add")))
+              (core module $m
+                (import "host" "add" (func $add (param i32 i32) (result i32)))
+                (func (export "run") (param i32) (result i32)
+                  local.get 0
+                  i32.const 2
+                  call $add))
+              (core instance $host (export "add" (func $add)))
+              (core instance $i (instantiate $m (with "host" (instance $host))))
+              (func (export "run") (param "x" u32) (result u32)
+                (canon lift (core func $i "run")))
+            )
+        "#;
+        let binary = wat::parse_str(wat).expect("valid WAT");
+        let image = Image::from_component(binary.into()).expect("valid component");
+
+        let loader = loader();
+        let registry = imports(&loader)
+            .ron_handler("log", |_| Ok("()".to_string()))
+            .build(&image)
+            .unwrap();
+        let mut container = crate::Container::builder(image)
+            .with_host_imports(registry)
+            .with_guest_initializer(|ctx| {
+                let instance = ctx.linker.instantiate(&mut *ctx.store, ctx.component)?;
+                Ok(crate::GuestInstance::new_dynamic(instance))
+            })
+            .build()
+            .unwrap();
+
+        assert_eq!(container.call_guest_raw_desc("run", "40").unwrap(), "42");
+        assert_eq!(
+            container
+                .call_host_import_raw_desc("add", "(1, 2)")
+                .unwrap(),
+            "3"
+        );
+    }
+
+    #[derive(serde::Serialize)]
+    struct Cfg {
+        max_size: u32,
+    }
+
+    #[derive(serde::Serialize)]
+    enum Shape {
+        LightBlue(bool),
+        Circle,
+    }
+
+    #[test]
+    fn test_serde_results() {
+        let wat = r#"
+            (component
+              (import "example:host/config" (instance
+                (type $cfg (record (field "max-size" u32)))
+                (export "cfg" (type $c (eq $cfg)))
+                (type $shape (variant (case "light-blue" bool) (case "circle")))
+                (export "shape" (type $s (eq $shape)))
+                (export "defaults" (func (result $c)))
+                (export "pick" (func (param "n" u32) (result $s)))
+              ))
+            )
+        "#;
+        let binary = wat::parse_str(wat).expect("valid WAT");
+        let image = Image::from_component(binary.into()).expect("valid component");
+
+        let loader = loader();
+        let registry = HostImportRegistry::from_wit(&loader, "example:host/config")
+            .handler("defaults", |()| Ok(Cfg { max_size: 3 }))
+            .handler("pick", |n: u32| {
+                Ok(match n {
+                    0 => Shape::Circle,
+                    n => Shape::LightBlue(n > 1),
+                })
+            })
+            .build(&image)
+            .unwrap();
+
+        assert_eq!(
+            registry.call("defaults", &[]).unwrap(),
+            vec![Val::Record(vec![("max-size".to_string(), Val::U32(3))])]
+        );
+        assert_eq!(
+            registry.call("pick", &[Val::U32(2)]).unwrap(),
+            vec![Val::Variant(
+                "light-blue".to_string(),
+                Some(Box::new(Val::Bool(true)))
+            )]
+        );
+        assert_eq!(
+            registry.call("pick", &[Val::U32(0)]).unwrap(),
+            vec![Val::Variant("circle".to_string(), None)]
+        );
+    }
+
+    #[test]
+    fn test_serde_ron() {
+        let val = Val::Tuple(vec![
+            Val::Record(vec![("max-size".to_string(), Val::U32(1))]),
+            Val::Variant("light-blue".to_string(), Some(Box::new(Val::Bool(true)))),
+            Val::Result(Ok(None)),
+        ]);
+        assert_eq!(
+            to_serde_ron(&val).unwrap(),
+            "((max_size: 1), LightBlue(true), Ok(()),)"
+        );
+    }
+}
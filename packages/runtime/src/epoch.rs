@@ -0,0 +1,219 @@
+//! Epoch - Background epoch ticker and per-call timeouts
+//!
+//! Epoch interruption only fires when something calls
+//! [`Engine::increment_epoch`]. [`EpochTicker`] does that from a background
+//! thread at a fixed interval, so deadlines measured in ticks become
+//! wall-clock durations. An image owns at most one ticker, shared by all of
+//! its clones and the containers built from it; see
+//! [`Image::start_epoch_ticker`].
+//!
+//! [`Container::call_guest_raw_desc_with_timeout`](crate::Container::call_guest_raw_desc_with_timeout)
+//! turns an expired deadline into a [`Timeout`] error.
+
+use std::{
+    sync::{Arc, Condvar, Mutex, PoisonError},
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use wasmtime::Engine;
+
+use crate::Image;
+
+/// Upper bound for deadlines, leaving room for the engine's current epoch
+pub(crate) const MAX_TICKS: u64 = u64::MAX / 2;
+
+/// Handle to a thread that increments an engine's epoch at a fixed interval
+///
+/// Clones share the same thread, which stops once the last handle is
+/// dropped.
+#[derive(Clone)]
+pub struct EpochTicker {
+    inner: Arc<TickerInner>,
+}
+
+struct TickerInner {
+    interval: Duration,
+    stop: Arc<(Mutex<bool>, Condvar)>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl EpochTicker {
+    /// Start ticking `engine`'s epoch every `interval`.
+    ///
+    /// The engine must have
+    /// [`Config::epoch_interruption(true)`](wasmtime::Config::epoch_interruption)
+    /// set for deadlines to take effect.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is zero.
+    pub fn start(engine: &Engine, interval: Duration) -> Self {
+        assert!(!interval.is_zero(), "epoch tick interval must be non-zero");
+
+        let stop = Arc::new((Mutex::new(false), Condvar::new()));
+        let thread = std::thread::Builder::new()
+            .name("tairitsu-epoch-ticker".to_string())
+            .spawn({
+                let engine = engine.clone();
+                let stop = stop.clone();
+                move || tick(&engine, interval, &stop)
+            })
+            .expect("failed to spawn epoch ticker thread");
+
+        Self {
+            inner: Arc::new(TickerInner {
+                interval,
+                stop,
+                thread: Some(thread),
+            }),
+        }
+    }
+
+    /// Time between two epoch increments
+    pub fn interval(&self) -> Duration {
+        self.inner.interval
+    }
+
+    /// Epoch deadline, in ticks, that expires no earlier than `timeout`
+    ///
+    /// The ticker's phase is unknown, so one tick is added: the deadline
+    /// expires between `timeout` and `timeout + interval` from now.
+    /// Very long timeouts are capped so the deadline cannot overflow.
+    pub fn ticks_for(&self, timeout: Duration) -> u64 {
+        let interval = self.inner.interval.as_nanos();
+        let ticks = timeout.as_nanos().div_ceil(interval) + 1;
+        u64::try_from(ticks).map_or(MAX_TICKS, |ticks| ticks.min(MAX_TICKS))
+    }
+}
+
+impl std::fmt::Debug for EpochTicker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EpochTicker")
+            .field("interval", &self.inner.interval)
+            .finish()
+    }
+}
+
+impl Drop for TickerInner {
+    fn drop(&mut self) {
+        let (stopped, wake) = &*self.stop;
+        *stopped.lock().unwrap_or_else(PoisonError::into_inner) = true;
+        wake.notify_all();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn tick(engine: &Engine, interval: Duration, stop: &(Mutex<bool>, Condvar)) {
+    let (stopped, wake) = stop;
+    let mut guard = stopped.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut next = Instant::now() + interval;
+    while !*guard {
+        let now = Instant::now();
+        if now >= next {
+            engine.increment_epoch();
+            next += interval;
+            continue;
+        }
+        guard = wake
+            .wait_timeout(guard, next - now)
+            .unwrap_or_else(PoisonError::into_inner)
+            .0;
+    }
+}
+
+impl Image {
+    /// Start a background [`EpochTicker`] for this image's engine.
+    ///
+    /// The ticker is shared by every clone of the image and every container
+    /// built from it, and replaces any ticker started earlier. It keeps
+    /// running until [`stop_epoch_ticker`](Self::stop_epoch_ticker) is
+    /// called or the image, its containers and all returned handles are
+    /// dropped.
+    ///
+    /// The image must be created with
+    /// [`Config::epoch_interruption(true)`](wasmtime::Config::epoch_interruption).
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut config = tairitsu::Config::new();
+    /// config.epoch_interruption(true);
+    /// let image = Image::from_component_with_config(component_binary, config)?;
+    /// image.start_epoch_ticker(Duration::from_millis(10));
+    ///
+    /// let mut container = Container::builder(image)
+    ///     .with_guest_initializer(|ctx| { /* ... */ })
+    ///     .build()?;
+    /// match container.call_guest_raw_desc_with_timeout("run", "()", Duration::from_secs(1)) {
+    ///     Err(e) if e.downcast_ref::<Timeout>().is_some() => { /* still usable */ }
+    ///     other => { /* ... */ }
+    /// }
+    /// ```
+    pub fn start_epoch_ticker(&self, interval: Duration) -> EpochTicker {
+        let ticker = EpochTicker::start(self.engine(), interval);
+        let previous = self.epoch_ticker_slot().replace(ticker.clone());
+        // Join the old thread outside the lock
+        drop(previous);
+        ticker
+    }
+
+    /// The ticker started with [`start_epoch_ticker`](Self::start_epoch_ticker),
+    /// if it is running
+    pub fn epoch_ticker(&self) -> Option<EpochTicker> {
+        self.epoch_ticker_slot().clone()
+    }
+
+    /// Detach the image's ticker; its thread stops once no handles remain.
+    pub fn stop_epoch_ticker(&self) {
+        let ticker = self.epoch_ticker_slot().take();
+        drop(ticker);
+    }
+}
+
+/// A guest call exceeded its wall-clock timeout
+///
+/// Returned by
+/// [`Container::call_guest_raw_desc_with_timeout`](crate::Container::call_guest_raw_desc_with_timeout);
+/// match it with `error.downcast_ref::<Timeout>()`. The container stays
+/// usable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timeout {
+    /// The guest function that was called
+    pub function: String,
+    /// The timeout that expired
+    pub timeout: Duration,
+}
+
+impl std::fmt::Display for Timeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Guest function {} timed out after {:?}",
+            self.function, self.timeout
+        )
+    }
+}
+
+impl std::error::Error for Timeout {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ticks_for_rounds_up_and_adds_a_tick() {
+        let engine = Engine::new(wasmtime::Config::new().epoch_interruption(true)).unwrap();
+        let ticker = EpochTicker::start(&engine, Duration::from_millis(10));
+
+        assert_eq!(ticker.interval(), Duration::from_millis(10));
+        assert_eq!(ticker.ticks_for(Duration::ZERO), 1);
+        assert_eq!(ticker.ticks_for(Duration::from_millis(10)), 2);
+        assert_eq!(ticker.ticks_for(Duration::from_millis(15)), 3);
+        assert_eq!(ticker.ticks_for(Duration::MAX), MAX_TICKS);
+
+        // Dropping the last handle stops and joins the thread
+        drop(ticker);
+    }
+}
@@ -1,11 +1,16 @@
 //! Image - Represents a compiled WASM component (like a Docker image)
 
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
 use anyhow::{Context as AnyhowContext, Result};
 use bytes::Bytes;
+use sha2::{Digest, Sha256};
 
 use wasmtime::{component::Component, error::Context, Config, Engine};
 use wit_component::ComponentEncoder;
 
+use crate::EpochTicker;
+
 static WASI_ADAPTER: &[u8] = include_bytes!("../res/wasi_snapshot_preview1.reactor.wasm");
 
 /// An Image represents a compiled WASM component that can be instantiated
@@ -16,6 +21,13 @@ static WASI_ADAPTER: &[u8] = include_bytes!("../res/wasi_snapshot_preview1.react
 /// [`Image::new_with_config`] or [`Image::from_component_with_config`] with a
 /// suitably configured [`Config`].
 ///
+/// With epoch interruption enabled,
+/// [`start_epoch_ticker`](Image::start_epoch_ticker) drives the epoch from a
+/// background thread so guest calls can be given wall-clock timeouts.
+///
+/// Compilation happens on every call; to reuse compiled images across
+/// process restarts, load them through an [`ImageCache`](crate::ImageCache).
+///
 /// # Example — fuel metering
 /// ```ignore
 /// let mut config = tairitsu::Config::new();
@@ -32,12 +44,51 @@ static WASI_ADAPTER: &[u8] = include_bytes!("../res/wasi_snapshot_preview1.react
 pub struct Image {
     engine: Engine,
     component: Component,
+    /// SHA-256 of the bytes the image was created from, see [`Image::digest`]
+    digest: [u8; 32],
+    /// Shared by all clones, see [`Image::start_epoch_ticker`]
+    epoch_ticker: Arc<Mutex<Option<EpochTicker>>>,
+}
+
+/// What an image was created from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ImageSource {
+    /// A core module, wrapped with the WASI adapter before compiling
+    Module,
+    /// A component binary, compiled as is
+    Component,
+}
+
+/// Identify an image by its input bytes and how they are compiled
+pub(crate) fn source_digest(source: ImageSource, bytes: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    match source {
+        ImageSource::Module => {
+            hasher.update(b"module\0");
+            hasher.update(Sha256::digest(WASI_ADAPTER));
+        }
+        ImageSource::Component => hasher.update(b"component\0"),
+    }
+    hasher.update(bytes);
+    hasher.finalize().into()
 }
 
-fn apply_config_defaults(config: &mut Config) {
+pub(crate) fn apply_config_defaults(config: &mut Config) {
     config.wasm_component_model(true);
 }
 
+/// Wrap a core WASM module into a component with the WASI preview1 adapter.
+pub(crate) fn encode_module(wasm_binary: &[u8]) -> Result<Vec<u8>> {
+    ComponentEncoder::default()
+        .module(wasm_binary)
+        .context("Failed to parse WASM module")?
+        .validate(true)
+        .adapter("wasi_snapshot_preview1", WASI_ADAPTER)
+        .context("Failed to add WASI adapter")?
+        .encode()
+        .context("Failed to encode WASM component")
+}
+
 impl Image {
     /// Create a new Image from WASM binary with default engine configuration.
     pub fn new(wasm_binary: Bytes) -> Result<Self> {
@@ -56,19 +107,13 @@ impl Image {
 
         let engine = Engine::new(&config).context("Failed to create WASM engine")?;
 
-        let component_binary = ComponentEncoder::default()
-            .module(wasm_binary.as_ref())
-            .context("Failed to parse WASM module")?
-            .validate(true)
-            .adapter("wasi_snapshot_preview1", WASI_ADAPTER)
-            .context("Failed to add WASI adapter")?
-            .encode()
-            .context("Failed to encode WASM component")?;
+        let component_binary = encode_module(&wasm_binary)?;
 
         let component = Component::from_binary(&engine, &component_binary)
             .context("Failed to compile WASM component")?;
 
-        Ok(Self { engine, component })
+        let digest = source_digest(ImageSource::Module, &wasm_binary);
+        Ok(Self::from_parts(engine, component, digest))
     }
 
     /// Create a new Image from a pre-compiled WIT component binary with default
@@ -89,7 +134,27 @@ impl Image {
         let component = Component::from_binary(&engine, component_binary.as_ref())
             .context("Failed to compile WASM component")?;
 
-        Ok(Self { engine, component })
+        let digest = source_digest(ImageSource::Component, &component_binary);
+        Ok(Self::from_parts(engine, component, digest))
+    }
+
+    /// Assemble an image from an engine and a component compiled for it
+    pub(crate) fn from_parts(engine: Engine, component: Component, digest: [u8; 32]) -> Self {
+        Self {
+            engine,
+            component,
+            digest,
+            epoch_ticker: Arc::default(),
+        }
+    }
+
+    /// SHA-256 identifying the binary this image was created from
+    ///
+    /// Equal binaries give equal digests on every host, regardless of the
+    /// engine configuration. Snapshots record it to refuse restoring into a
+    /// different image.
+    pub fn digest(&self) -> [u8; 32] {
+        self.digest
     }
 
     /// Get the engine used by this image
@@ -101,6 +166,13 @@ impl Image {
     pub(crate) fn component(&self) -> &Component {
         &self.component
     }
+
+    /// The ticker shared by all clones of this image
+    pub(crate) fn epoch_ticker_slot(&self) -> MutexGuard<'_, Option<EpochTicker>> {
+        self.epoch_ticker
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
 }
 
 impl std::fmt::Debug for Image {
@@ -0,0 +1,359 @@
+//! ImageCache - On-disk cache of compiled components
+//!
+//! Compiling a component with Cranelift dominates the cost of creating an
+//! [`Image`]. [`ImageCache`] stores the compiled artifact (`.cwasm`) produced
+//! by [`Component::serialize`] and loads it with
+//! [`Component::deserialize_file`] on later runs.
+//!
+//! Entries are grouped by engine: each engine configuration gets its own
+//! directory named after [`Engine::precompile_compatibility_hash`], which
+//! covers the wasmtime version, the compiler settings and the enabled
+//! features. Within that directory an entry is named after the SHA-256 of
+//! its input, so changing the input bytes, the engine config or upgrading
+//! wasmtime never reuses an old artifact. Entries that fail to load anyway
+//! are recompiled and overwritten.
+//!
+//! # Layout
+//!
+//! ```text
+//! <dir>/<engine hash>/<content hash>.cwasm
+//! ```
+//!
+//! # Security
+//!
+//! Loading a compiled artifact runs native code from the cache directory
+//! without validation, so the directory must only be writable by the
+//! process that owns the cache.
+
+use std::{
+    fs,
+    hash::{Hash, Hasher},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use bytes::Bytes;
+use sha2::{Digest, Sha256};
+use tempfile::NamedTempFile;
+
+use wasmtime::{component::Component, Config, Engine};
+
+use crate::{
+    image::{apply_config_defaults, encode_module, source_digest, ImageSource},
+    Image,
+};
+
+/// File extension of cached artifacts
+const ARTIFACT_EXTENSION: &str = "cwasm";
+
+/// On-disk cache of compiled [`Image`]s
+///
+/// # Example
+/// ```ignore
+/// let cache = ImageCache::new("/var/cache/my-server/images")?;
+///
+/// // Compiles on the first start, loads the compiled artifact afterwards
+/// let image = cache.load_component(component_binary, Config::new())?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct ImageCache {
+    dir: PathBuf,
+}
+
+impl ImageCache {
+    /// Open a cache rooted at `dir`, creating the directory if needed.
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create image cache at {}", dir.display()))?;
+        Ok(Self { dir })
+    }
+
+    /// Root directory of the cache
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Cached equivalent of [`Image::new_with_config`]
+    pub fn load(&self, wasm_binary: Bytes, config: Config) -> Result<Image> {
+        self.load_source(ImageSource::Module, &wasm_binary, config)
+    }
+
+    /// Cached equivalent of [`Image::from_component_with_config`]
+    pub fn load_component(&self, component_binary: Bytes, config: Config) -> Result<Image> {
+        self.load_source(ImageSource::Component, &component_binary, config)
+    }
+
+    /// Remove cached artifacts of engines other than those `configs` create.
+    ///
+    /// Use this after a wasmtime upgrade or a config change to drop entries
+    /// that can no longer be used. Returns the number of files removed.
+    pub fn prune(&self, configs: &[Config]) -> Result<usize> {
+        let keep = configs
+            .iter()
+            .map(|config| {
+                let mut config = config.clone();
+                apply_config_defaults(&mut config);
+                let engine = Engine::new(&config)
+                    .map_err(anyhow::Error::from)
+                    .context("Failed to create WASM engine")?;
+                Ok(engine_key(&engine))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut removed = 0;
+        for entry in self.read_dir(&self.dir)? {
+            let name = entry.file_name();
+            if keep.iter().any(|key| name.to_str() == Some(key.as_str())) {
+                continue;
+            }
+            let path = entry.path();
+            removed += self.read_dir(&path).map_or(0, |entries| entries.count());
+            fs::remove_dir_all(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+        }
+        Ok(removed)
+    }
+
+    /// Remove every cached artifact.
+    pub fn clear(&self) -> Result<()> {
+        for entry in self.read_dir(&self.dir)? {
+            let path = entry.path();
+            fs::remove_dir_all(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Path of the artifact for an image with `digest` compiled by `engine`
+    fn artifact_path(&self, engine: &Engine, digest: &[u8; 32]) -> PathBuf {
+        self.dir
+            .join(engine_key(engine))
+            .join(format!("{}.{ARTIFACT_EXTENSION}", hex::encode(digest)))
+    }
+
+    fn load_source(&self, source: ImageSource, bytes: &[u8], mut config: Config) -> Result<Image> {
+        apply_config_defaults(&mut config);
+        let engine = Engine::new(&config)
+            .map_err(anyhow::Error::from)
+            .context("Failed to create WASM engine")?;
+        let digest = source_digest(source, bytes);
+        let path = self.artifact_path(&engine, &digest);
+
+        if path.is_file() {
+            // SAFETY: the file was written by `store` below from a component
+            // compiled by an engine with the same compatibility hash, and the
+            // cache directory is trusted (see the module docs). wasmtime
+            // still checks the artifact header and rejects mismatches.
+            match unsafe { Component::deserialize_file(&engine, &path) } {
+                Ok(component) => return Ok(Image::from_parts(engine, component, digest)),
+                // Stale or truncated entry: fall through and recompile
+                Err(_) => {
+                    let _ = fs::remove_file(&path);
+                }
+            }
+        }
+
+        let component = match source {
+            ImageSource::Module => Component::from_binary(&engine, &encode_module(bytes)?),
+            ImageSource::Component => Component::from_binary(&engine, bytes),
+        }
+        .map_err(anyhow::Error::from)
+        .context("Failed to compile WASM component")?;
+
+        self.store(&path, &component)?;
+        Ok(Image::from_parts(engine, component, digest))
+    }
+
+    /// Write `component`'s compiled artifact to `path`.
+    ///
+    /// The artifact is written to a temporary file and renamed into place,
+    /// so concurrent readers never see a partial file.
+    fn store(&self, path: &Path, component: &Component) -> Result<()> {
+        let serialized = component
+            .serialize()
+            .map_err(anyhow::Error::from)
+            .context("Failed to serialize compiled component")?;
+
+        let dir = path.parent().unwrap_or(&self.dir);
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        let mut tmp = NamedTempFile::new_in(dir)
+            .with_context(|| format!("Failed to create a temporary file in {}", dir.display()))?;
+        tmp.write_all(&serialized)
+            .with_context(|| format!("Failed to write {}", tmp.path().display()))?;
+        tmp.persist(path)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(())
+    }
+
+    fn read_dir(&self, dir: &Path) -> Result<impl Iterator<Item = fs::DirEntry>> {
+        let entries =
+            fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+        Ok(entries.filter_map(|entry| entry.ok()))
+    }
+}
+
+/// Directory name for artifacts compiled by `engine`
+fn engine_key(engine: &Engine) -> String {
+    let mut hasher = Sha256Hasher(Sha256::new());
+    engine.precompile_compatibility_hash().hash(&mut hasher);
+    let digest = format!("{:x}", hasher.0.finalize());
+    digest[..16].to_string()
+}
+
+/// Feeds [`Hash`] output into SHA-256, which unlike `DefaultHasher` is
+/// stable across Rust releases
+struct Sha256Hasher(Sha256);
+
+impl Hasher for Sha256Hasher {
+    /// The first 8 bytes of the digest of everything written so far
+    fn finish(&self) -> u64 {
+        let digest = self.0.clone().finalize();
+        u64::from_le_bytes(digest[..8].try_into().expect("SHA-256 digest is 32 bytes"))
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPONENT: &str = r#"
+        (component
+          (core module $m (func (export "f") (result i32) i32.const 7))
+          (core instance $i (instantiate $m))
+          (func $f (result u32) (canon lift (core func $i "f")))
+          (export "f" (func $f))
+        )
+    "#;
+
+    fn component_binary() -> Bytes {
+        wat::parse_str(COMPONENT).expect("valid WAT").into()
+    }
+
+    fn artifacts(cache: &ImageCache) -> Vec<PathBuf> {
+        let mut files: Vec<_> = walk(cache.dir());
+        files.sort();
+        files
+    }
+
+    fn walk(dir: &Path) -> Vec<PathBuf> {
+        fs::read_dir(dir)
+            .unwrap()
+            .flat_map(|entry| {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    walk(&path)
+                } else {
+                    vec![path]
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_component_is_cached_and_reused() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = ImageCache::new(tmp.path().join("images")).unwrap();
+
+        cache
+            .load_component(component_binary(), Config::new())
+            .unwrap();
+        let files = artifacts(&cache);
+        assert_eq!(files.len(), 1);
+        assert_eq!(
+            files[0].extension().and_then(|e| e.to_str()),
+            Some(ARTIFACT_EXTENSION)
+        );
+        let written = fs::metadata(&files[0]).unwrap().modified().unwrap();
+
+        let image = cache
+            .load_component(component_binary(), Config::new())
+            .unwrap();
+        assert_eq!(artifacts(&cache), files);
+        assert_eq!(
+            fs::metadata(&files[0]).unwrap().modified().unwrap(),
+            written
+        );
+        assert_eq!(
+            image
+                .component()
+                .component_type()
+                .exports(image.engine())
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_config_change_uses_separate_entry_and_prune_removes_it() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = ImageCache::new(tmp.path()).unwrap();
+        let mut fuel = Config::new();
+        fuel.consume_fuel(true);
+
+        cache
+            .load_component(component_binary(), Config::new())
+            .unwrap();
+        cache
+            .load_component(component_binary(), fuel.clone())
+            .unwrap();
+        assert_eq!(artifacts(&cache).len(), 2);
+
+        assert_eq!(cache.prune(&[fuel]).unwrap(), 1);
+        assert_eq!(artifacts(&cache).len(), 1);
+
+        cache.clear().unwrap();
+        assert!(artifacts(&cache).is_empty());
+    }
+
+    #[test]
+    fn test_corrupt_entry_is_recompiled() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = ImageCache::new(tmp.path()).unwrap();
+
+        cache
+            .load_component(component_binary(), Config::new())
+            .unwrap();
+        let path = artifacts(&cache).remove(0);
+        fs::write(&path, b"not a compiled component").unwrap();
+
+        cache
+            .load_component(component_binary(), Config::new())
+            .unwrap();
+        assert_ne!(fs::read(&path).unwrap(), b"not a compiled component");
+    }
+
+    #[test]
+    fn test_concurrent_stores_of_one_entry() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = ImageCache::new(tmp.path()).unwrap();
+
+        std::thread::scope(|scope| {
+            let loads: Vec<_> = (0..4)
+                .map(|_| scope.spawn(|| cache.load_component(component_binary(), Config::new())))
+                .collect();
+            for load in loads {
+                load.join().unwrap().unwrap();
+            }
+        });
+
+        // No temporary file is left behind next to the entry
+        assert_eq!(artifacts(&cache).len(), 1);
+    }
+
+    #[test]
+    fn test_sha256_hasher_finish() {
+        let mut a = Sha256Hasher(Sha256::new());
+        let mut b = Sha256Hasher(Sha256::new());
+        "engine".hash(&mut a);
+        "engine".hash(&mut b);
+        assert_eq!(a.finish(), b.finish());
+        "more".hash(&mut b);
+        assert_ne!(a.finish(), b.finish());
+    }
+}
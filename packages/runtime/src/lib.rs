@@ -6,7 +6,10 @@
 //! ## Architecture
 //!
 //! - [`Image`] - Represents a compiled WASM component (like a Docker image)
+//! - [`EpochTicker`] - Drives epoch interruption so guest calls can time out
+//! - [`ImageCache`] - Keeps compiled Images on disk across restarts (like a layer cache)
 //! - [`Container`] - Represents a running instance of an Image (like a Docker container)
+//! - [`ContainerPool`] - Hands out fresh containers pre-instantiated from a [`ContainerTemplate`]
 //! - [`Registry`] - Manages multiple Images and Containers (like a Docker daemon)
 //! - [`WitInterface`] - User-defined WIT interface trait
 //! - [`ContainerBuilder`] - Builder pattern for creating containers with custom WIT bindings
@@ -50,9 +53,16 @@
 //! ```
 
 pub mod container;
+mod epoch;
 mod image;
+mod image_cache;
+mod limits;
+mod logs;
+mod policy;
+pub mod pool;
 pub mod registry;
 pub mod ron;
+mod snapshot;
 pub mod wit;
 pub mod wit_helper;
 pub mod wit_registry;
@@ -62,18 +72,31 @@ pub mod wit_registry;
 pub mod dynamic;
 
 pub use container::{
-    Container, ContainerState, ExportInfo, GuestHandlerContext, GuestInstance, HostState,
-    HostStateImpl, ImportInfo,
+    BoxFuture, Container, ContainerError, ContainerState, ErrorCause, ExportInfo,
+    GuestHandlerContext, GuestInstance, HostState, HostStateImpl, ImportInfo,
 };
 // Dynamic invocation exports (requires 'dynamic' feature)
 #[cfg(feature = "dynamic")]
 pub use dynamic::host_imports::{HostImport, HostImportRegistry};
 #[cfg(feature = "dynamic")]
+pub use dynamic::wit_imports::WitImports;
+#[cfg(feature = "dynamic")]
 pub use dynamic::{ron_to_val, val_to_ron};
+pub use epoch::{EpochTicker, Timeout};
 pub use image::Image;
-pub use registry::Registry;
+pub use image_cache::ImageCache;
+pub use limits::{ContainerLimits, LimitKind};
+pub use logs::{ContainerLogs, LogEntry, LogLevel, LogSource, DEFAULT_LOG_CAPACITY};
+pub use policy::{
+    CapturePolicy, CapturedOutput, Cidr, DirMode, EnvPolicy, NetworkPolicy, Preopen, WasiPolicy,
+};
+pub use pool::{pooling_config, ContainerPool, ContainerTemplate, TemplateBuilder};
+pub use registry::{
+    ContainerInfo, ContainerStats, Registry, ResourceLimits, RestartPolicy, RunOptions,
+};
 // RON types (always exported, but only usable when 'ron' dependency is available)
 pub use ron::{typed_ron_tool, RonBinding, RonFunctionTool, RonTool, RonToolRegistry};
+pub use snapshot::{Snapshot, LOAD_STATE_EXPORT, SAVE_STATE_EXPORT, SNAPSHOT_VERSION};
 // Re-export procedural macros
 pub use tairitsu_macros::{
     export_wit, wit_guest_impl, wit_interface, wit_world, WitCommand as WitCommandDerive,
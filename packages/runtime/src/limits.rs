@@ -0,0 +1,169 @@
+//! Limits - Memory, table and instance limits for a container's store
+//!
+//! [`ContainerLimits`] wraps wasmtime's [`StoreLimits`] and remembers which
+//! limit a guest ran into, so a failed call can be reported as
+//! [`ErrorCause::ResourceLimit`](crate::container::ErrorCause::ResourceLimit)
+//! rather than a plain trap.
+
+use wasmtime::{ResourceLimiter, StoreLimits, StoreLimitsBuilder};
+
+/// A limit a guest can run into while executing
+///
+/// The instance limit is not listed: it is checked while instantiating, so
+/// exceeding it fails the container build instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LimitKind {
+    /// Linear memory size, see `ContainerBuilder::with_memory_limit`
+    Memory,
+    /// Table size, see `ContainerBuilder::with_table_limit`
+    Table,
+}
+
+impl std::fmt::Display for LimitKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LimitKind::Memory => "memory",
+            LimitKind::Table => "table",
+        })
+    }
+}
+
+/// Limits configured on a builder, applied to each new store
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct LimitConfig {
+    pub(crate) memory_size: Option<usize>,
+    pub(crate) table_elements: Option<usize>,
+    pub(crate) instances: Option<usize>,
+}
+
+impl LimitConfig {
+    pub(crate) fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Resource limiter installed in a container's store
+///
+/// The default [`HostState`](crate::HostState) holds one. A custom host
+/// state can support `with_memory_limit` and friends by embedding a
+/// `ContainerLimits` and returning it from
+/// [`HostStateImpl::limits`](crate::HostStateImpl::limits).
+#[derive(Default)]
+pub struct ContainerLimits {
+    config: LimitConfig,
+    limits: StoreLimits,
+    breached: Option<LimitKind>,
+}
+
+impl ContainerLimits {
+    pub(crate) fn new(config: LimitConfig) -> Self {
+        let mut builder = StoreLimitsBuilder::new();
+        if let Some(bytes) = config.memory_size {
+            builder = builder.memory_size(bytes);
+        }
+        if let Some(elements) = config.table_elements {
+            builder = builder.table_elements(elements);
+        }
+        if let Some(instances) = config.instances {
+            builder = builder.instances(instances);
+        }
+        Self {
+            config,
+            limits: builder.build(),
+            breached: None,
+        }
+    }
+
+    /// The limit the guest last ran into, if any
+    pub fn breached(&self) -> Option<LimitKind> {
+        self.breached
+    }
+
+    /// Return and clear the limit the guest last ran into
+    #[cfg(any(test, feature = "dynamic"))]
+    pub(crate) fn take_breached(&mut self) -> Option<LimitKind> {
+        self.breached.take()
+    }
+}
+
+impl std::fmt::Debug for ContainerLimits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ContainerLimits")
+            .field("config", &self.config)
+            .field("breached", &self.breached)
+            .finish()
+    }
+}
+
+impl ResourceLimiter for ContainerLimits {
+    fn memory_growing(
+        &mut self,
+        current: usize,
+        desired: usize,
+        maximum: Option<usize>,
+    ) -> wasmtime::Result<bool> {
+        let allowed = self.limits.memory_growing(current, desired, maximum)?;
+        // Growth past the memory's own declared maximum is not our limit
+        if !allowed && self.config.memory_size.is_some_and(|limit| desired > limit) {
+            self.breached = Some(LimitKind::Memory);
+        }
+        Ok(allowed)
+    }
+
+    fn table_growing(
+        &mut self,
+        current: usize,
+        desired: usize,
+        maximum: Option<usize>,
+    ) -> wasmtime::Result<bool> {
+        let allowed = self.limits.table_growing(current, desired, maximum)?;
+        if !allowed
+            && self
+                .config
+                .table_elements
+                .is_some_and(|limit| desired > limit)
+        {
+            self.breached = Some(LimitKind::Table);
+        }
+        Ok(allowed)
+    }
+
+    fn instances(&self) -> usize {
+        self.limits.instances()
+    }
+
+    fn tables(&self) -> usize {
+        self.limits.tables()
+    }
+
+    fn memories(&self) -> usize {
+        self.limits.memories()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breach_is_recorded_only_for_configured_limits() {
+        let mut limits = ContainerLimits::new(LimitConfig {
+            memory_size: Some(1 << 16),
+            ..Default::default()
+        });
+
+        assert!(limits.memory_growing(0, 1 << 16, None).unwrap());
+        assert_eq!(limits.breached(), None);
+
+        // The memory's own maximum is not a container limit
+        assert!(!limits.memory_growing(0, 1 << 15, Some(1 << 14)).unwrap());
+        assert_eq!(limits.breached(), None);
+
+        assert!(!limits.memory_growing(1 << 16, 1 << 17, None).unwrap());
+        assert_eq!(limits.take_breached(), Some(LimitKind::Memory));
+        assert_eq!(limits.breached(), None);
+
+        assert!(limits.table_growing(0, 1 << 20, None).unwrap());
+        assert_eq!(limits.breached(), None);
+    }
+}
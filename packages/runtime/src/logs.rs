@@ -0,0 +1,424 @@
+//! Logs - Guest stdout/stderr captured into a per-container ring buffer
+//!
+//! [`ContainerLogs`] is wired to the guest's stdout and stderr by
+//! [`HostState`](crate::HostState). Output is split into lines, each stored
+//! as a [`LogEntry`] with a timestamp and a level, and the oldest entries are
+//! dropped once the buffer is full. [`ContainerLogs::subscribe`] streams new
+//! entries as they arrive.
+//!
+//! A line starting with a level name (`ERROR`, `[warn]`, `Info:`, ...) gets
+//! that level; other lines are [`LogLevel::Info`] on stdout and
+//! [`LogLevel::Error`] on stderr.
+
+use std::{
+    collections::VecDeque,
+    io,
+    pin::Pin,
+    sync::{mpsc, Arc, Mutex, PoisonError},
+    task::{Context, Poll},
+    time::SystemTime,
+};
+
+use tokio::io::AsyncWrite;
+use wasmtime_wasi::cli::{IsTerminal, StdoutStream};
+
+/// Number of entries a [`ContainerLogs`] keeps by default
+pub const DEFAULT_LOG_CAPACITY: usize = 1000;
+
+/// Longest line kept in one entry; longer output is split
+const MAX_LINE_BYTES: usize = 16 * 1024;
+
+/// Severity of a [`LogEntry`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    fn parse(word: &str) -> Option<Self> {
+        Some(match word.to_ascii_lowercase().as_str() {
+            "trace" => LogLevel::Trace,
+            "debug" => LogLevel::Debug,
+            "info" => LogLevel::Info,
+            "warn" | "warning" => LogLevel::Warn,
+            "error" | "err" => LogLevel::Error,
+            _ => return None,
+        })
+    }
+}
+
+impl std::fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LogLevel::Trace => "TRACE",
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Info => "INFO",
+            LogLevel::Warn => "WARN",
+            LogLevel::Error => "ERROR",
+        })
+    }
+}
+
+/// Where a [`LogEntry`] came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LogSource {
+    /// The guest's standard output
+    Stdout,
+    /// The guest's standard error
+    Stderr,
+    /// A lifecycle event recorded by the runtime, e.g. a restart
+    Runtime,
+}
+
+/// A timestamped line of guest output or container lifecycle event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    pub timestamp: SystemTime,
+    pub level: LogLevel,
+    pub source: LogSource,
+    pub message: String,
+}
+
+impl LogEntry {
+    /// A line of guest output, with its level taken from a leading level name
+    fn from_output(source: LogSource, line: &str) -> Self {
+        let default = match source {
+            LogSource::Stderr => LogLevel::Error,
+            LogSource::Stdout | LogSource::Runtime => LogLevel::Info,
+        };
+        Self {
+            timestamp: SystemTime::now(),
+            level: detect_level(line).unwrap_or(default),
+            source,
+            message: line.to_string(),
+        }
+    }
+}
+
+/// The level named by the first word of `line`, if any
+fn detect_level(line: &str) -> Option<LogLevel> {
+    let word = line
+        .trim_start()
+        .split(|c: char| c.is_whitespace() || c == ':' || c == ']')
+        .next()?
+        .trim_start_matches('[');
+    LogLevel::parse(word)
+}
+
+/// Ring buffer of a container's log entries
+///
+/// Clones share the same buffer, so one can be kept to read the logs while
+/// the container owns another.
+#[derive(Clone)]
+pub struct ContainerLogs {
+    inner: Arc<Mutex<LogBuffer>>,
+}
+
+struct LogBuffer {
+    capacity: usize,
+    entries: VecDeque<LogEntry>,
+    subscribers: Vec<mpsc::Sender<LogEntry>>,
+}
+
+impl Default for ContainerLogs {
+    fn default() -> Self {
+        Self::new(DEFAULT_LOG_CAPACITY)
+    }
+}
+
+impl ContainerLogs {
+    /// Create a buffer keeping the last `capacity` entries
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(LogBuffer {
+                capacity,
+                entries: VecDeque::with_capacity(capacity.min(DEFAULT_LOG_CAPACITY)),
+                subscribers: Vec::new(),
+            })),
+        }
+    }
+
+    /// All buffered entries, oldest first
+    pub fn entries(&self) -> Vec<LogEntry> {
+        self.lock().entries.iter().cloned().collect()
+    }
+
+    /// Receive every entry added from now on
+    ///
+    /// The subscription ends when the receiver is dropped.
+    pub fn subscribe(&self) -> mpsc::Receiver<LogEntry> {
+        let (sender, receiver) = mpsc::channel();
+        self.lock().subscribers.push(sender);
+        receiver
+    }
+
+    /// Remove all buffered entries
+    pub fn clear(&self) {
+        self.lock().entries.clear();
+    }
+
+    /// Add an entry, dropping the oldest one if the buffer is full
+    pub fn push(&self, entry: LogEntry) {
+        let mut buffer = self.lock();
+        buffer
+            .subscribers
+            .retain(|subscriber| subscriber.send(entry.clone()).is_ok());
+        if buffer.capacity == 0 {
+            return;
+        }
+        if buffer.entries.len() == buffer.capacity {
+            buffer.entries.pop_front();
+        }
+        buffer.entries.push_back(entry);
+    }
+
+    /// Record a runtime lifecycle event
+    pub(crate) fn record(&self, level: LogLevel, message: String) {
+        self.push(LogEntry {
+            timestamp: SystemTime::now(),
+            level,
+            source: LogSource::Runtime,
+            message,
+        });
+    }
+
+    /// A WASI output stream appending to this buffer
+    pub(crate) fn stream(&self, source: LogSource) -> LogStream {
+        LogStream {
+            line: Arc::new(Mutex::new(LineBuffer {
+                logs: self.clone(),
+                source,
+                partial: Vec::new(),
+            })),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LogBuffer> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl std::fmt::Debug for ContainerLogs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let buffer = self.lock();
+        f.debug_struct("ContainerLogs")
+            .field("capacity", &buffer.capacity)
+            .field("entries", &buffer.entries.len())
+            .finish()
+    }
+}
+
+/// Guest stdout or stderr backed by a [`ContainerLogs`]
+///
+/// WASI asks for a new writer on every write call, so the unfinished line is
+/// kept here and shared by all of them. It is emitted once the stream and
+/// every writer are dropped.
+pub(crate) struct LogStream {
+    line: Arc<Mutex<LineBuffer>>,
+}
+
+impl LogStream {
+    fn writer(&self) -> LineWriter {
+        LineWriter {
+            line: self.line.clone(),
+        }
+    }
+}
+
+impl IsTerminal for LogStream {
+    fn is_terminal(&self) -> bool {
+        false
+    }
+}
+
+impl StdoutStream for LogStream {
+    fn async_stream(&self) -> Box<dyn AsyncWrite + Send + Sync> {
+        Box::new(self.writer())
+    }
+}
+
+/// Splits written bytes into lines and pushes each as a [`LogEntry`]
+struct LineBuffer {
+    logs: ContainerLogs,
+    source: LogSource,
+    /// Bytes after the last newline
+    partial: Vec<u8>,
+}
+
+impl LineBuffer {
+    fn write_bytes(&mut self, mut bytes: &[u8]) {
+        while let Some(newline) = bytes.iter().position(|b| *b == b'\n') {
+            self.partial.extend_from_slice(&bytes[..newline]);
+            self.emit();
+            bytes = &bytes[newline + 1..];
+        }
+        self.partial.extend_from_slice(bytes);
+        while self.partial.len() >= MAX_LINE_BYTES {
+            let rest = self.partial.split_off(line_split(&self.partial));
+            self.emit();
+            self.partial = rest;
+        }
+    }
+
+    fn emit(&mut self) {
+        let line = String::from_utf8_lossy(&self.partial);
+        let line = line.strip_suffix('\r').unwrap_or(&line);
+        self.logs.push(LogEntry::from_output(self.source, line));
+        self.partial.clear();
+    }
+
+    fn finish(&mut self) {
+        if !self.partial.is_empty() {
+            self.emit();
+        }
+    }
+}
+
+/// Where to cut an overlong line: [`MAX_LINE_BYTES`], moved back to the start
+/// of the UTF-8 character it falls in
+fn line_split(bytes: &[u8]) -> usize {
+    let is_continuation = |i: usize| bytes.get(i).is_some_and(|b| b & 0xC0 == 0x80);
+    (MAX_LINE_BYTES - 3..=MAX_LINE_BYTES)
+        .rev()
+        .find(|&i| !is_continuation(i))
+        .unwrap_or(MAX_LINE_BYTES)
+}
+
+impl Drop for LineBuffer {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// One of the writers handed out by a [`LogStream`]
+struct LineWriter {
+    line: Arc<Mutex<LineBuffer>>,
+}
+
+impl LineWriter {
+    fn write_bytes(&self, bytes: &[u8]) {
+        self.line
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .write_bytes(bytes);
+    }
+}
+
+impl AsyncWrite for LineWriter {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.write_bytes(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn writer(logs: &ContainerLogs, source: LogSource) -> LineWriter {
+        logs.stream(source).writer()
+    }
+
+    #[test]
+    fn test_output_is_split_into_leveled_lines() {
+        let logs = ContainerLogs::default();
+        let stdout = writer(&logs, LogSource::Stdout);
+        stdout.write_bytes(b"hello\r\n[WARN] low ");
+        stdout.write_bytes(b"disk\npartial");
+        let stderr = writer(&logs, LogSource::Stderr);
+        stderr.write_bytes(b"boom\ndebug: details\n");
+        drop(stdout);
+
+        let lines: Vec<_> = logs
+            .entries()
+            .into_iter()
+            .map(|e| (e.source, e.level, e.message))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                (LogSource::Stdout, LogLevel::Info, "hello".to_string()),
+                (
+                    LogSource::Stdout,
+                    LogLevel::Warn,
+                    "[WARN] low disk".to_string()
+                ),
+                (LogSource::Stderr, LogLevel::Error, "boom".to_string()),
+                (
+                    LogSource::Stderr,
+                    LogLevel::Debug,
+                    "debug: details".to_string()
+                ),
+                (LogSource::Stdout, LogLevel::Info, "partial".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_partial_lines_span_writers() {
+        let logs = ContainerLogs::default();
+        let stream = logs.stream(LogSource::Stderr);
+        // Like `eprint!("a"); eprint!("b\n")`, each write gets its own writer
+        stream.writer().write_bytes(b"a");
+        stream.writer().write_bytes(b"b\nc");
+        assert_eq!(logs.entries().len(), 1);
+        assert_eq!(logs.entries()[0].message, "ab");
+
+        drop(stream);
+        let messages: Vec<_> = logs.entries().into_iter().map(|e| e.message).collect();
+        assert_eq!(messages, vec!["ab", "c"]);
+    }
+
+    #[test]
+    fn test_long_lines_split_between_characters() {
+        let logs = ContainerLogs::default();
+        let stdout = writer(&logs, LogSource::Stdout);
+        // `é` straddles the limit, so the cut moves back before it
+        let line = format!("{}éb", "a".repeat(MAX_LINE_BYTES - 1));
+        stdout.write_bytes(line.as_bytes());
+        stdout.write_bytes(b"\n");
+
+        let messages: Vec<_> = logs.entries().into_iter().map(|e| e.message).collect();
+        assert_eq!(
+            messages,
+            vec!["a".repeat(MAX_LINE_BYTES - 1), "éb".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_ring_buffer_and_subscribers() {
+        let logs = ContainerLogs::new(2);
+        let subscriber = logs.subscribe();
+        for i in 0..3 {
+            logs.record(LogLevel::Info, format!("event {}", i));
+        }
+
+        let messages: Vec<_> = logs.entries().into_iter().map(|e| e.message).collect();
+        assert_eq!(messages, vec!["event 1", "event 2"]);
+        let streamed: Vec<_> = subscriber.try_iter().map(|e| e.message).collect();
+        assert_eq!(streamed, vec!["event 0", "event 1", "event 2"]);
+
+        drop(subscriber);
+        logs.record(LogLevel::Error, "unobserved".to_string());
+        assert!(logs.lock().subscribers.is_empty());
+
+        logs.clear();
+        assert!(logs.entries().is_empty());
+    }
+}
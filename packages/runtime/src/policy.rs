@@ -0,0 +1,486 @@
+//! Policy - Declarative WASI capabilities for a container
+//!
+//! A [`WasiPolicy`] lists what a guest may touch: preopened directories,
+//! environment variables, network ranges, and whether it sees the real clocks
+//! and randomness. Policies are plain data, so plugin permissions can live in
+//! a config file:
+//!
+//! ```toml
+//! clocks = true
+//! random = true
+//!
+//! [[preopens]]
+//! host = "./plugins/data"
+//! guest = "/data"
+//! mode = "read-only"
+//!
+//! [env]
+//! allow = ["LANG", "TZ"]
+//! vars = { PLUGIN_MODE = "production" }
+//!
+//! [capture]
+//! stdout = 65536
+//! stderr = 65536
+//!
+//! [network]
+//! allow = ["10.0.0.0/8", "192.168.1.10"]
+//! deny = ["10.0.0.1/32"]
+//! ip_name_lookup = false
+//! ```
+//!
+//! Load it with [`WasiPolicy::from_toml`] and apply it with
+//! [`HostState::with_policy`](crate::HostState::with_policy),
+//! [`ContainerBuilder::with_wasi_policy`](crate::ContainerBuilder::with_wasi_policy)
+//! or [`RunOptions::with_wasi_policy`](crate::RunOptions::with_wasi_policy).
+//! The default policy grants nothing beyond the real clocks and randomness.
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    net::{IpAddr, SocketAddr},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+use wasmtime_wasi::{
+    p2::pipe::MemoryOutputPipe, random::Deterministic, DirPerms, FilePerms, HostMonotonicClock,
+    HostWallClock, WasiCtxBuilder,
+};
+
+/// WASI capabilities granted to a guest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WasiPolicy {
+    /// Host directories visible to the guest
+    pub preopens: Vec<Preopen>,
+    /// Environment variables visible to the guest
+    pub env: EnvPolicy,
+    /// Sizes of the buffers capturing the guest's stdout and stderr
+    pub capture: CapturePolicy,
+    /// Addresses the guest may connect or bind to
+    pub network: NetworkPolicy,
+    /// Whether the guest sees the host clocks; if not, time stands still at
+    /// the Unix epoch
+    pub clocks: bool,
+    /// Whether the guest gets real randomness; if not, it receives a fixed
+    /// byte sequence, which makes runs reproducible
+    pub random: bool,
+}
+
+impl Default for WasiPolicy {
+    fn default() -> Self {
+        Self {
+            preopens: Vec::new(),
+            env: EnvPolicy::default(),
+            capture: CapturePolicy::default(),
+            network: NetworkPolicy::default(),
+            clocks: true,
+            random: true,
+        }
+    }
+}
+
+/// A host directory mounted into the guest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Preopen {
+    /// Directory on the host
+    pub host: PathBuf,
+    /// Path the guest opens it by
+    pub guest: String,
+    /// What the guest may do inside it
+    #[serde(default)]
+    pub mode: DirMode,
+}
+
+/// Access granted to a preopened directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DirMode {
+    /// List directories and read files
+    #[default]
+    ReadOnly,
+    /// Also create, write and remove files and directories
+    ReadWrite,
+}
+
+/// Environment variables passed to the guest
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EnvPolicy {
+    /// Host variables copied into the guest when they are set
+    pub allow: Vec<String>,
+    /// Variables set explicitly; these win over copied host variables
+    pub vars: BTreeMap<String, String>,
+}
+
+/// Buffers capturing guest output, in bytes
+///
+/// A stream with a buffer is kept in memory, read back through
+/// [`CapturedOutput`], and does not reach the container's
+/// [`ContainerLogs`](crate::ContainerLogs); streams without one are logged
+/// line by line. Once a buffer is full, further writes to it fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CapturePolicy {
+    pub stdout: Option<usize>,
+    pub stderr: Option<usize>,
+}
+
+/// Socket addresses the guest may use
+///
+/// An address is permitted if it is in an `allow` range and in no `deny`
+/// range, so an empty `allow` list denies all network access.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetworkPolicy {
+    pub allow: Vec<Cidr>,
+    pub deny: Vec<Cidr>,
+    /// Whether the guest may resolve host names
+    pub ip_name_lookup: bool,
+}
+
+impl NetworkPolicy {
+    /// Whether the guest may connect or bind to `addr`
+    pub fn permits(&self, addr: &IpAddr) -> bool {
+        self.allow.iter().any(|range| range.contains(addr))
+            && !self.deny.iter().any(|range| range.contains(addr))
+    }
+}
+
+/// An IP address range such as `10.0.0.0/8`
+///
+/// A bare address is a range holding just that address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Cidr {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    /// Create a range, failing if `prefix` is longer than the address
+    pub fn new(addr: IpAddr, prefix: u8) -> Result<Self> {
+        let max = max_prefix(&addr);
+        if prefix > max {
+            anyhow::bail!("Prefix /{} is longer than {} bits", prefix, max);
+        }
+        Ok(Self { addr, prefix })
+    }
+
+    /// Whether `addr` lies in this range
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        let bits = |addr: &IpAddr| match addr {
+            IpAddr::V4(v4) => u128::from(u32::from(*v4)) << 96,
+            IpAddr::V6(v6) => u128::from(*v6),
+        };
+        if self.addr.is_ipv4() != addr.is_ipv4() {
+            return false;
+        }
+        let mask = u128::MAX
+            .checked_shl(128 - u32::from(self.prefix))
+            .unwrap_or(0);
+        bits(&self.addr) & mask == bits(addr) & mask
+    }
+}
+
+fn max_prefix(addr: &IpAddr) -> u8 {
+    if addr.is_ipv4() {
+        32
+    } else {
+        128
+    }
+}
+
+impl FromStr for Cidr {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+        let addr: IpAddr = addr
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid address in range '{}'", s))?;
+        let prefix = match prefix {
+            Some(prefix) => prefix
+                .trim()
+                .parse()
+                .with_context(|| format!("Invalid prefix length in range '{}'", s))?,
+            None => max_prefix(&addr),
+        };
+        Self::new(addr, prefix)
+    }
+}
+
+impl TryFrom<String> for Cidr {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<Cidr> for String {
+    fn from(cidr: Cidr) -> Self {
+        cidr.to_string()
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+/// Guest output captured according to a [`CapturePolicy`]
+///
+/// Clones share the same buffers.
+#[derive(Debug, Clone, Default)]
+pub struct CapturedOutput {
+    stdout: Option<MemoryOutputPipe>,
+    stderr: Option<MemoryOutputPipe>,
+}
+
+impl CapturedOutput {
+    /// Everything the guest wrote to stdout, if stdout is captured
+    pub fn stdout(&self) -> Option<Bytes> {
+        self.stdout.as_ref().map(MemoryOutputPipe::contents)
+    }
+
+    /// Everything the guest wrote to stderr, if stderr is captured
+    pub fn stderr(&self) -> Option<Bytes> {
+        self.stderr.as_ref().map(MemoryOutputPipe::contents)
+    }
+}
+
+impl WasiPolicy {
+    /// Parse a policy from TOML
+    pub fn from_toml(source: &str) -> Result<Self> {
+        toml::from_str(source).context("Failed to parse WASI policy")
+    }
+
+    /// Read a policy from a TOML file
+    ///
+    /// Relative preopen paths are resolved against the file's directory.
+    pub fn from_toml_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read WASI policy {}", path.display()))?;
+        let mut policy = Self::from_toml(&source)
+            .with_context(|| format!("Invalid WASI policy {}", path.display()))?;
+        if let Some(base) = path.parent() {
+            for preopen in &mut policy.preopens {
+                if preopen.host.is_relative() {
+                    preopen.host = base.join(&preopen.host);
+                }
+            }
+        }
+        Ok(policy)
+    }
+
+    /// Configure `builder` with this policy's capabilities.
+    ///
+    /// Returns handles to the output buffers; read them after guest calls.
+    pub fn apply(&self, builder: &mut WasiCtxBuilder) -> Result<CapturedOutput> {
+        for preopen in &self.preopens {
+            let (dir_perms, file_perms) = match preopen.mode {
+                DirMode::ReadOnly => (DirPerms::READ, FilePerms::READ),
+                DirMode::ReadWrite => (DirPerms::all(), FilePerms::all()),
+            };
+            builder
+                .preopened_dir(&preopen.host, &preopen.guest, dir_perms, file_perms)
+                .map_err(anyhow::Error::from)
+                .with_context(|| {
+                    format!(
+                        "Failed to preopen {} as {}",
+                        preopen.host.display(),
+                        preopen.guest
+                    )
+                })?;
+        }
+
+        for name in &self.env.allow {
+            if self.env.vars.contains_key(name) {
+                continue;
+            }
+            if let Ok(value) = std::env::var(name) {
+                builder.env(name, value);
+            }
+        }
+        for (name, value) in &self.env.vars {
+            builder.env(name, value);
+        }
+
+        let captured = CapturedOutput {
+            stdout: self.capture.stdout.map(MemoryOutputPipe::new),
+            stderr: self.capture.stderr.map(MemoryOutputPipe::new),
+        };
+        if let Some(pipe) = &captured.stdout {
+            builder.stdout(pipe.clone());
+        }
+        if let Some(pipe) = &captured.stderr {
+            builder.stderr(pipe.clone());
+        }
+
+        if !self.network.allow.is_empty() {
+            let network = Arc::new(self.network.clone());
+            builder
+                .inherit_network()
+                .socket_addr_check(move |addr: SocketAddr, _| {
+                    let permitted = network.permits(&addr.ip());
+                    Box::pin(async move { permitted })
+                });
+        }
+        builder.allow_ip_name_lookup(self.network.ip_name_lookup);
+
+        if !self.clocks {
+            builder.wall_clock(FrozenClock).monotonic_clock(FrozenClock);
+        }
+        if !self.random {
+            builder
+                .secure_random(Deterministic::new(vec![0]))
+                .insecure_random(Deterministic::new(vec![0]))
+                .insecure_random_seed(0);
+        }
+
+        Ok(captured)
+    }
+}
+
+/// Clock reporting the Unix epoch forever, used when clocks are disabled
+struct FrozenClock;
+
+impl HostWallClock for FrozenClock {
+    fn resolution(&self) -> Duration {
+        Duration::from_secs(1)
+    }
+
+    fn now(&self) -> Duration {
+        Duration::ZERO
+    }
+}
+
+impl HostMonotonicClock for FrozenClock {
+    fn resolution(&self) -> u64 {
+        1_000_000_000
+    }
+
+    fn now(&self) -> u64 {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_from_toml() {
+        let policy = WasiPolicy::from_toml(
+            r#"
+            random = false
+
+            [[preopens]]
+            host = "/srv/data"
+            guest = "/data"
+
+            [[preopens]]
+            host = "/tmp/out"
+            guest = "/out"
+            mode = "read-write"
+
+            [env]
+            allow = ["LANG"]
+            vars = { MODE = "test" }
+
+            [capture]
+            stdout = 1024
+
+            [network]
+            allow = ["10.0.0.0/8"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(policy.preopens[0].mode, DirMode::ReadOnly);
+        assert_eq!(policy.preopens[1].mode, DirMode::ReadWrite);
+        assert_eq!(policy.env.vars["MODE"], "test");
+        assert_eq!(policy.capture.stdout, Some(1024));
+        assert_eq!(policy.capture.stderr, None);
+        assert!(policy.clocks);
+        assert!(!policy.random);
+        assert!(!policy.network.ip_name_lookup);
+
+        assert!(WasiPolicy::from_toml("unknown = 1").is_err());
+        assert!(WasiPolicy::from_toml("[network]\nallow = [\"10.0.0.0/33\"]").is_err());
+        assert_eq!(WasiPolicy::from_toml("").unwrap(), WasiPolicy::default());
+    }
+
+    #[test]
+    fn test_network_policy_permits() {
+        let network = NetworkPolicy {
+            allow: vec!["10.0.0.0/8".parse().unwrap(), "::1".parse().unwrap()],
+            deny: vec!["10.1.0.0/16".parse().unwrap()],
+            ip_name_lookup: false,
+        };
+        let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+
+        assert!(network.permits(&ip("10.2.3.4")));
+        assert!(!network.permits(&ip("10.1.3.4")));
+        assert!(!network.permits(&ip("11.0.0.1")));
+        assert!(network.permits(&ip("::1")));
+        assert!(!network.permits(&ip("::2")));
+        assert!(!NetworkPolicy::default().permits(&ip("127.0.0.1")));
+
+        assert!(Cidr::from_str("0.0.0.0/0")
+            .unwrap()
+            .contains(&ip("8.8.8.8")));
+        assert_eq!(
+            Cidr::from_str("192.168.1.10").unwrap().to_string(),
+            "192.168.1.10/32"
+        );
+    }
+
+    #[test]
+    fn test_apply_preopens_and_capture() {
+        let tmp = tempfile::tempdir().unwrap();
+        let policy = WasiPolicy {
+            preopens: vec![Preopen {
+                host: tmp.path().to_path_buf(),
+                guest: "/data".to_string(),
+                mode: DirMode::ReadWrite,
+            }],
+            capture: CapturePolicy {
+                stdout: Some(64),
+                stderr: None,
+            },
+            clocks: false,
+            random: false,
+            ..Default::default()
+        };
+
+        let mut builder = WasiCtxBuilder::new();
+        let captured = policy.apply(&mut builder).unwrap();
+        assert_eq!(captured.stdout(), Some(Bytes::new()));
+        assert_eq!(captured.stderr(), None);
+
+        let missing = WasiPolicy {
+            preopens: vec![Preopen {
+                host: tmp.path().join("missing"),
+                guest: "/missing".to_string(),
+                mode: DirMode::ReadOnly,
+            }],
+            ..Default::default()
+        };
+        assert!(missing.apply(&mut WasiCtxBuilder::new()).is_err());
+    }
+}
@@ -0,0 +1,598 @@
+//! Pool - Pre-instantiated container templates for high-throughput workloads
+//!
+//! [`ContainerBuilder`](crate::ContainerBuilder) links WASI, runs the host
+//! linker setup and resolves every import each time it builds a container.
+//! When the same image is instantiated over and over (one container per
+//! request, say), that work can be done once:
+//!
+//! - [`ContainerTemplate`] holds an [`InstancePre`] - a linker and component
+//!   with all imports already resolved - and turns it into a fresh
+//!   [`Container`] with a new store on every [`instantiate`](ContainerTemplate::instantiate).
+//! - [`ContainerPool`] keeps a number of containers instantiated ahead of
+//!   time, so [`acquire`](ContainerPool::acquire) only has to pop one.
+//! - [`pooling_config`] returns an engine [`Config`] using wasmtime's pooling
+//!   allocator, which reuses pre-reserved memory slots instead of mapping
+//!   fresh memory for every instance.
+//!
+//! # Example
+//! ```ignore
+//! let image = Image::from_component_with_config(bytes, tairitsu::pooling_config(256, 64 << 20))?;
+//! let template = image
+//!     .template()
+//!     .with_host_linker(|linker| MyHost::add_to_linker(linker, |s| s))
+//!     .build()?;
+//!
+//! let pool = ContainerPool::new(template, 32);
+//! pool.fill()?;
+//!
+//! // Per request
+//! let mut container = pool.acquire()?;
+//! let output = container.call_guest_raw_desc("handle", payload)?;
+//! ```
+
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context as AnyhowContext, Result};
+
+use wasmtime::{
+    component::{Instance, InstancePre, Linker},
+    error::Context,
+    Config, InstanceAllocationStrategy, PoolingAllocationConfig, Store,
+};
+
+#[cfg(feature = "dynamic")]
+use crate::container::Respawn;
+use crate::{
+    container::{add_wasi_to_linker, configure_store},
+    limits::LimitConfig,
+    Container, GuestInstance, HostState, HostStateImpl, Image,
+};
+
+/// Core instances reserved per container by [`pooling_config`].
+///
+/// A component built from a core module with the WASI adapter instantiates a
+/// handful of core modules (the guest, the adapter and shims); this leaves
+/// room for components that bundle more.
+pub const CORE_INSTANCES_PER_CONTAINER: u32 = 16;
+
+/// Linear memories reserved per container by [`pooling_config`].
+pub const MEMORIES_PER_CONTAINER: u32 = 4;
+
+/// Tables reserved per container by [`pooling_config`].
+pub const TABLES_PER_CONTAINER: u32 = 8;
+
+/// Engine config using the pooling instance allocator.
+///
+/// Reserves slots for `max_containers` live containers, each memory capped
+/// at `max_memory_bytes`. Instantiating beyond `max_containers` fails until
+/// a container is dropped. Pass the config to
+/// [`Image::new_with_config`] or [`Image::from_component_with_config`]; it
+/// can be adjusted further before that.
+pub fn pooling_config(max_containers: u32, max_memory_bytes: usize) -> Config {
+    let mut pooling = PoolingAllocationConfig::default();
+    pooling
+        .total_component_instances(max_containers)
+        .total_core_instances(max_containers.saturating_mul(CORE_INSTANCES_PER_CONTAINER))
+        .total_memories(max_containers.saturating_mul(MEMORIES_PER_CONTAINER))
+        .total_tables(max_containers.saturating_mul(TABLES_PER_CONTAINER))
+        .max_memory_size(max_memory_bytes);
+
+    let mut config = Config::new();
+    config.allocation_strategy(InstanceAllocationStrategy::Pooling(pooling));
+    config
+}
+
+/// Creates the host state of each container instantiated from a template.
+type HostStateFactory<T> = Box<dyn Fn() -> T + Send + Sync>;
+
+/// Wraps a fresh instance into the container's guest.
+type GuestFactory<T> =
+    Box<dyn Fn(&mut Store<T>, &Instance) -> Result<GuestInstance, anyhow::Error> + Send + Sync>;
+
+/// Host linker setup run once when the template is built.
+type HostLinkerInit<T> = Box<dyn FnOnce(&mut Linker<T>) -> Result<(), anyhow::Error> + Send>;
+
+impl Image {
+    /// Start building a [`ContainerTemplate`] for this image.
+    ///
+    /// Uses the default [`HostState`]; for a custom host state create the
+    /// builder with [`TemplateBuilder::new`].
+    pub fn template(&self) -> TemplateBuilder<HostState> {
+        TemplateBuilder::new(self.clone())
+    }
+}
+
+/// Builder for [`ContainerTemplate`]
+///
+/// Mirrors [`ContainerBuilder`](crate::ContainerBuilder), except that
+/// everything configured here runs once per template rather than once per
+/// container, and the guest is instantiated by the template itself.
+pub struct TemplateBuilder<T: HostStateImpl> {
+    image: Image,
+    host_state: HostStateFactory<T>,
+    fuel_limit: Option<u64>,
+    epoch_deadline: Option<u64>,
+    limits: LimitConfig,
+    host_linker_init: Option<HostLinkerInit<T>>,
+    guest_factory: Option<GuestFactory<T>>,
+}
+
+impl<T: HostStateImpl + Default> TemplateBuilder<T> {
+    /// Create builder from Image
+    pub fn new(image: Image) -> Self {
+        Self {
+            image,
+            host_state: Box::new(T::default),
+            fuel_limit: None,
+            epoch_deadline: None,
+            limits: LimitConfig::default(),
+            host_linker_init: None,
+            guest_factory: None,
+        }
+    }
+}
+
+impl<T: HostStateImpl> TemplateBuilder<T> {
+    /// Create the host state of each container with `factory`
+    pub fn with_host_state<F>(mut self, factory: F) -> Self
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+    {
+        self.host_state = Box::new(factory);
+        self
+    }
+
+    /// Set the fuel limit of each container's store.
+    ///
+    /// See [`ContainerBuilder::with_fuel_limit`](crate::ContainerBuilder::with_fuel_limit).
+    pub fn with_fuel_limit(mut self, limit: u64) -> Self {
+        self.fuel_limit = Some(limit);
+        self
+    }
+
+    /// Set the epoch deadline of each container's store.
+    ///
+    /// See [`ContainerBuilder::with_epoch_deadline`](crate::ContainerBuilder::with_epoch_deadline).
+    pub fn with_epoch_deadline(mut self, deadline: u64) -> Self {
+        self.epoch_deadline = Some(deadline);
+        self
+    }
+
+    /// Limit each linear memory of each container's guest to `bytes`.
+    ///
+    /// See [`ContainerBuilder::with_memory_limit`](crate::ContainerBuilder::with_memory_limit).
+    pub fn with_memory_limit(mut self, bytes: usize) -> Self {
+        self.limits.memory_size = Some(bytes);
+        self
+    }
+
+    /// Limit each table of each container's guest to `elements` entries.
+    ///
+    /// See [`ContainerBuilder::with_table_limit`](crate::ContainerBuilder::with_table_limit).
+    pub fn with_table_limit(mut self, elements: usize) -> Self {
+        self.limits.table_elements = Some(elements);
+        self
+    }
+
+    /// Limit the number of instances in each container's store.
+    ///
+    /// See [`ContainerBuilder::with_instance_limit`](crate::ContainerBuilder::with_instance_limit).
+    pub fn with_instance_limit(mut self, instances: usize) -> Self {
+        self.limits.instances = Some(instances);
+        self
+    }
+
+    /// Register additional host imports with the template's linker.
+    ///
+    /// Runs once, after WASI is added and before imports are resolved.
+    pub fn with_host_linker<F>(mut self, func: F) -> Self
+    where
+        F: FnOnce(&mut Linker<T>) -> Result<(), anyhow::Error> + Send + 'static,
+    {
+        self.host_linker_init = Some(Box::new(func));
+        self
+    }
+
+    /// Wrap each new instance into a guest, e.g. with WIT bindings.
+    ///
+    /// Without a factory the raw instance is used, which supports the
+    /// dynamic `call_guest_*` methods when the `dynamic` feature is enabled.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let template = image
+    ///     .template()
+    ///     .with_guest_factory(|store, instance| {
+    ///         Ok(GuestInstance::new(MyWorld::new(store, instance)?))
+    ///     })
+    ///     .build()?;
+    /// ```
+    pub fn with_guest_factory<F>(mut self, factory: F) -> Self
+    where
+        F: Fn(&mut Store<T>, &Instance) -> Result<GuestInstance, anyhow::Error>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.guest_factory = Some(Box::new(factory));
+        self
+    }
+
+    /// Build a template for synchronous containers
+    pub fn build(self) -> Result<ContainerTemplate<T>> {
+        self.build_with(false)
+    }
+
+    /// Build a template for async containers
+    pub fn build_async(self) -> Result<ContainerTemplate<T>> {
+        self.build_with(true)
+    }
+
+    fn build_with(self, async_support: bool) -> Result<ContainerTemplate<T>> {
+        let mut linker = Linker::new(self.image.engine());
+        add_wasi_to_linker(&mut linker, async_support)?;
+        if let Some(linker_init) = self.host_linker_init {
+            AnyhowContext::context(linker_init(&mut linker), "Failed to configure host linker")?;
+        }
+        let pre = linker
+            .instantiate_pre(self.image.component())
+            .context("Failed to resolve component imports")?;
+
+        let guest_factory: GuestFactory<T> = match self.guest_factory {
+            Some(factory) => factory,
+            None => Box::new(default_guest),
+        };
+
+        Ok(ContainerTemplate {
+            inner: Arc::new(TemplateInner {
+                image: self.image,
+                pre,
+                host_state: self.host_state,
+                guest_factory,
+                fuel_limit: self.fuel_limit,
+                epoch_deadline: self.epoch_deadline,
+                limits: self.limits,
+                async_support,
+            }),
+        })
+    }
+}
+
+#[cfg(feature = "dynamic")]
+fn default_guest<T>(_store: &mut Store<T>, instance: &Instance) -> Result<GuestInstance> {
+    Ok(GuestInstance::new_dynamic(*instance))
+}
+
+#[cfg(not(feature = "dynamic"))]
+fn default_guest<T>(_store: &mut Store<T>, instance: &Instance) -> Result<GuestInstance> {
+    Ok(GuestInstance::new(*instance))
+}
+
+/// A component with its imports resolved, ready to be instantiated into
+/// containers
+///
+/// Cloning is cheap; clones share the same pre-instantiated component.
+pub struct ContainerTemplate<T: HostStateImpl = HostState> {
+    inner: Arc<TemplateInner<T>>,
+}
+
+struct TemplateInner<T: HostStateImpl> {
+    image: Image,
+    pre: InstancePre<T>,
+    host_state: HostStateFactory<T>,
+    guest_factory: GuestFactory<T>,
+    fuel_limit: Option<u64>,
+    epoch_deadline: Option<u64>,
+    limits: LimitConfig,
+    async_support: bool,
+}
+
+impl<T: HostStateImpl> Clone for ContainerTemplate<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: HostStateImpl> ContainerTemplate<T> {
+    /// Whether containers are built in async mode, see [`Container::is_async`]
+    pub fn is_async(&self) -> bool {
+        self.inner.async_support
+    }
+
+    /// Create a fresh container
+    pub fn instantiate(&self) -> Result<Container<T>> {
+        if self.inner.async_support {
+            anyhow::bail!("This template was built with build_async(). Use instantiate_async().");
+        }
+        let mut store = self.new_store()?;
+        let instance = self
+            .inner
+            .pre
+            .instantiate(&mut store)
+            .context("Failed to instantiate component")?;
+        self.finish(store, instance)
+    }
+
+    /// Create a fresh async container
+    pub async fn instantiate_async(&self) -> Result<Container<T>> {
+        if !self.inner.async_support {
+            anyhow::bail!("This template was built with build(). Use instantiate().");
+        }
+        let mut store = self.new_store()?;
+        let instance = self
+            .inner
+            .pre
+            .instantiate_async(&mut store)
+            .await
+            .context("Failed to instantiate component")?;
+        self.finish(store, instance)
+    }
+
+    fn new_store(&self) -> Result<Store<T>> {
+        let mut store = Store::new(self.inner.image.engine(), (self.inner.host_state)());
+        configure_store(
+            &mut store,
+            self.inner.fuel_limit,
+            self.inner.epoch_deadline,
+            self.inner.limits,
+        )?;
+        Ok(store)
+    }
+
+    fn finish(&self, mut store: Store<T>, instance: Instance) -> Result<Container<T>> {
+        let guest = (self.inner.guest_factory)(&mut store, &instance)?;
+        let container = Container::from_parts(
+            store,
+            self.inner.image.clone(),
+            guest,
+            self.inner.async_support,
+        );
+
+        // Lets the container recover from a timeout
+        #[cfg(feature = "dynamic")]
+        let container = {
+            let (configure, guest) = (self.inner.clone(), self.inner.clone());
+            let respawn = Respawn::new(
+                self.inner.pre.clone(),
+                self.inner.async_support,
+                move |store| {
+                    configure_store(
+                        store,
+                        configure.fuel_limit,
+                        configure.epoch_deadline,
+                        configure.limits,
+                    )
+                },
+                move |store, instance| (guest.guest_factory)(store, instance),
+            );
+            container.with_recovery(self.inner.epoch_deadline, Some(respawn))
+        };
+
+        Ok(container)
+    }
+}
+
+/// Containers instantiated ahead of time from a [`ContainerTemplate`]
+///
+/// Containers handed out by [`acquire`](Self::acquire) are never returned
+/// to the pool: each one is fresh, and dropping it frees its resources.
+/// Call [`fill`](Self::fill) (for example from a background thread) to keep
+/// warm containers available.
+pub struct ContainerPool<T: HostStateImpl = HostState> {
+    template: ContainerTemplate<T>,
+    capacity: usize,
+    warm: Mutex<Warm<T>>,
+}
+
+struct Warm<T: HostStateImpl> {
+    containers: Vec<Container<T>>,
+    /// Slots claimed by fills that are still instantiating
+    reserved: usize,
+}
+
+impl<T: HostStateImpl> ContainerPool<T> {
+    /// Create an empty pool keeping up to `capacity` warm containers
+    pub fn new(template: ContainerTemplate<T>, capacity: usize) -> Self {
+        Self {
+            template,
+            capacity,
+            warm: Mutex::new(Warm {
+                containers: Vec::with_capacity(capacity),
+                reserved: 0,
+            }),
+        }
+    }
+
+    /// The template containers are instantiated from
+    pub fn template(&self) -> &ContainerTemplate<T> {
+        &self.template
+    }
+
+    /// Maximum number of warm containers
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of warm containers ready to be acquired
+    pub fn warm_count(&self) -> usize {
+        self.lock().containers.len()
+    }
+
+    /// Take a warm container, or instantiate one if none is ready
+    pub fn acquire(&self) -> Result<Container<T>> {
+        match self.take_warm() {
+            Some(container) => Ok(container),
+            None => self.template.instantiate(),
+        }
+    }
+
+    /// Take a warm container, or instantiate one if none is ready (async)
+    pub async fn acquire_async(&self) -> Result<Container<T>> {
+        match self.take_warm() {
+            Some(container) => Ok(container),
+            None => self.template.instantiate_async().await,
+        }
+    }
+
+    /// Instantiate containers until the pool is at capacity.
+    ///
+    /// Concurrent fills share the remaining capacity, so the pool never
+    /// holds more than `capacity` warm containers.
+    ///
+    /// Returns the number of containers added.
+    pub fn fill(&self) -> Result<usize> {
+        let mut added = 0;
+        while let Some(slot) = self.reserve() {
+            slot.fill(self.template.instantiate()?);
+            added += 1;
+        }
+        Ok(added)
+    }
+
+    /// Instantiate containers until the pool is at capacity (async).
+    ///
+    /// Returns the number of containers added.
+    pub async fn fill_async(&self) -> Result<usize> {
+        let mut added = 0;
+        while let Some(slot) = self.reserve() {
+            slot.fill(self.template.instantiate_async().await?);
+            added += 1;
+        }
+        Ok(added)
+    }
+
+    fn take_warm(&self) -> Option<Container<T>> {
+        self.lock().containers.pop()
+    }
+
+    /// Claim a slot for a container about to be instantiated, if the pool
+    /// is not already full counting the other fills in progress.
+    fn reserve(&self) -> Option<Reservation<'_, T>> {
+        let mut warm = self.lock();
+        if warm.containers.len() + warm.reserved >= self.capacity {
+            return None;
+        }
+        warm.reserved += 1;
+        Some(Reservation {
+            pool: self,
+            filled: false,
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Warm<T>> {
+        // A panic while holding the lock cannot leave the pool inconsistent
+        self.warm
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// A slot claimed by [`ContainerPool::reserve`]; released on drop if the
+/// instantiation failed or was cancelled.
+struct Reservation<'a, T: HostStateImpl> {
+    pool: &'a ContainerPool<T>,
+    filled: bool,
+}
+
+impl<T: HostStateImpl> Reservation<'_, T> {
+    fn fill(mut self, container: Container<T>) {
+        let mut warm = self.pool.lock();
+        warm.containers.push(container);
+        warm.reserved -= 1;
+        self.filled = true;
+    }
+}
+
+impl<T: HostStateImpl> Drop for Reservation<'_, T> {
+    fn drop(&mut self) {
+        if !self.filled {
+            self.pool.lock().reserved -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPONENT: &str = r#"
+        (component
+          (core module $m
+            (global $n (mut i32) (i32.const 0))
+            (func (export "next") (result i32)
+              global.get $n i32.const 1 i32.add global.set $n global.get $n))
+          (core instance $i (instantiate $m))
+          (func $next (result u32) (canon lift (core func $i "next")))
+          (export "next" (func $next))
+        )
+    "#;
+
+    fn image(config: Config) -> Image {
+        let binary = wat::parse_str(COMPONENT).expect("valid WAT");
+        Image::from_component_with_config(binary.into(), config).expect("valid component")
+    }
+
+    #[test]
+    fn test_template_instantiates_independent_containers() {
+        let template = image(Config::new()).template().build().unwrap();
+        let first = template.instantiate().unwrap();
+        let second = template.clone().instantiate().unwrap();
+        assert!(!first.is_async() && !second.is_async());
+        assert_eq!(first.list_guest_exports().unwrap()[0].name, "next");
+
+        #[cfg(feature = "dynamic")]
+        {
+            let (mut first, mut second) = (first, second);
+            assert_eq!(first.call_guest_raw_desc("next", "()").unwrap(), "1");
+            assert_eq!(first.call_guest_raw_desc("next", "()").unwrap(), "2");
+            assert_eq!(second.call_guest_raw_desc("next", "()").unwrap(), "1");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_template_mode_must_match() {
+        let template = image(Config::new()).template().build().unwrap();
+        let err = template.instantiate_async().await.unwrap_err();
+        assert!(err.to_string().contains("instantiate()"));
+    }
+
+    #[test]
+    fn test_pool_fill_and_acquire() {
+        let template = image(pooling_config(4, 1 << 20))
+            .template()
+            .build()
+            .unwrap();
+        let pool = ContainerPool::new(template, 2);
+        assert_eq!(pool.warm_count(), 0);
+
+        assert_eq!(pool.fill().unwrap(), 2);
+        assert_eq!(pool.fill().unwrap(), 0);
+
+        let first = pool.acquire().unwrap();
+        let second = pool.acquire().unwrap();
+        assert_eq!(pool.warm_count(), 0);
+        // Instantiated on demand once the pool is empty
+        let third = pool.acquire().unwrap();
+        drop((first, second, third));
+
+        assert_eq!(pool.fill().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_concurrent_fills_respect_capacity() {
+        let template = image(pooling_config(16, 1 << 20))
+            .template()
+            .build()
+            .unwrap();
+        let pool = ContainerPool::new(template, 3);
+
+        let added: usize = std::thread::scope(|scope| {
+            let fills: Vec<_> = (0..4).map(|_| scope.spawn(|| pool.fill())).collect();
+            fills.into_iter().map(|f| f.join().unwrap().unwrap()).sum()
+        });
+        assert_eq!(added, 3);
+        assert_eq!(pool.warm_count(), 3);
+    }
+}
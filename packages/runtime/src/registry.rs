@@ -1,20 +1,388 @@
 //! Registry - Manages Images and Containers (similar to Docker registry/daemon)
+//!
+//! Containers started with [`Registry::run_container`] are supervised: when a
+//! guest call leaves a container in [`ContainerState::Error`] (or, for
+//! [`RestartPolicy::Always`], stopped), the registry rebuilds it from its image
+//! according to its [`RestartPolicy`]. [`Registry::inspect`],
+//! [`Registry::logs`] and [`Registry::stats`] report on each container; the
+//! logs hold the guest's stdout and stderr alongside lifecycle events and are
+//! kept across restarts.
 
 use anyhow::{Context, Result};
 use bytes::Bytes;
 use std::{
     collections::HashMap,
-    sync::{Arc, Mutex},
+    sync::{mpsc, Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
 };
 
-use crate::{Container, Image};
+use wasmtime::component::Linker;
+
+pub use crate::logs::LogEntry;
+use crate::{
+    logs::LogLevel, Container, ContainerLogs, ContainerState, GuestHandlerContext, GuestInstance,
+    HostState, Image, WasiPolicy,
+};
+
+/// When the registry should rebuild a container that stopped working
+///
+/// Mirrors Docker's `--restart` flag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RestartPolicy {
+    /// Leave failed containers in their error state.
+    #[default]
+    Never,
+    /// Rebuild containers that enter [`ContainerState::Error`], optionally
+    /// giving up after `max_retries` restarts.
+    OnFailure { max_retries: Option<u32> },
+    /// Rebuild containers that fail or are stopped from inside
+    /// [`Registry::get_container_mut`].
+    Always,
+}
+
+impl RestartPolicy {
+    fn should_restart(&self, state: &ContainerState, restarts: u32) -> bool {
+        match (self, state) {
+            (RestartPolicy::Never, _) => false,
+            (RestartPolicy::OnFailure { max_retries }, ContainerState::Error(_)) => {
+                max_retries.is_none_or(|max| restarts < max)
+            }
+            (RestartPolicy::OnFailure { .. }, _) => false,
+            (RestartPolicy::Always, state) => {
+                matches!(state, ContainerState::Error(_) | ContainerState::Stopped)
+            }
+        }
+    }
+}
+
+/// Per-container resource limits
+///
+/// Each limit requires the matching engine feature on the image's
+/// [`Config`](wasmtime::Config); see [`ContainerBuilder`](crate::container::ContainerBuilder).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ResourceLimits {
+    /// Fuel granted on every (re)start, see `ContainerBuilder::with_fuel_limit`.
+    pub fuel: Option<u64>,
+    /// Epoch deadline set on every (re)start, see `ContainerBuilder::with_epoch_deadline`.
+    pub epoch_deadline: Option<u64>,
+    /// Linear memory size in bytes, see `ContainerBuilder::with_memory_limit`.
+    pub memory: Option<usize>,
+    /// Table size in elements, see `ContainerBuilder::with_table_limit`.
+    pub table_elements: Option<usize>,
+    /// Number of instances, see `ContainerBuilder::with_instance_limit`.
+    pub instances: Option<usize>,
+}
+
+/// Type alias for reusable host linker setup
+type HostLinkerFn = Arc<dyn Fn(&mut Linker<HostState>) -> Result<()> + Send + Sync>;
+
+/// Type alias for reusable guest initializers
+type GuestInitializerFn =
+    Arc<dyn for<'a> Fn(GuestHandlerContext<'a, HostState>) -> Result<GuestInstance> + Send + Sync>;
+
+/// Options for [`Registry::run_container`]
+///
+/// Unlike [`ContainerBuilder`](crate::container::ContainerBuilder), the
+/// initializers are `Fn` so the registry can rebuild the container on restart.
+/// Without a guest initializer the component is instantiated as-is and
+/// wrapped for dynamic invocation.
+#[derive(Clone, Default)]
+pub struct RunOptions {
+    restart_policy: RestartPolicy,
+    limits: ResourceLimits,
+    wasi_policy: Option<WasiPolicy>,
+    host_linker: Option<HostLinkerFn>,
+    guest_initializer: Option<GuestInitializerFn>,
+}
+
+impl RunOptions {
+    /// Create options with no restart policy and no limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the restart policy
+    pub fn with_restart_policy(mut self, policy: RestartPolicy) -> Self {
+        self.restart_policy = policy;
+        self
+    }
+
+    /// Set the resource limits applied on every (re)start
+    pub fn with_limits(mut self, limits: ResourceLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Grant the WASI capabilities of `policy` on every (re)start
+    pub fn with_wasi_policy(mut self, policy: WasiPolicy) -> Self {
+        self.wasi_policy = Some(policy);
+        self
+    }
+
+    /// Register host imports with the linker on every (re)start
+    pub fn with_host_linker<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&mut Linker<HostState>) -> Result<()> + Send + Sync + 'static,
+    {
+        self.host_linker = Some(Arc::new(f));
+        self
+    }
+
+    /// Set the guest initializer run on every (re)start
+    pub fn with_guest_initializer<F>(mut self, f: F) -> Self
+    where
+        F: for<'a> Fn(GuestHandlerContext<'a, HostState>) -> Result<GuestInstance>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.guest_initializer = Some(Arc::new(f));
+        self
+    }
+
+    fn build(&self, image: &Image, logs: &ContainerLogs) -> Result<Container> {
+        let policy = self.wasi_policy.clone().unwrap_or_default();
+        let mut builder = Container::builder(image.clone())
+            .with_host_state(HostState::with_policy_and_logs(&policy, logs.clone())?);
+        if let Some(fuel) = self.limits.fuel {
+            builder = builder.with_fuel_limit(fuel);
+        }
+        if let Some(deadline) = self.limits.epoch_deadline {
+            builder = builder.with_epoch_deadline(deadline);
+        }
+        if let Some(bytes) = self.limits.memory {
+            builder = builder.with_memory_limit(bytes);
+        }
+        if let Some(elements) = self.limits.table_elements {
+            builder = builder.with_table_limit(elements);
+        }
+        if let Some(instances) = self.limits.instances {
+            builder = builder.with_instance_limit(instances);
+        }
+        if let Some(host_linker) = self.host_linker.clone() {
+            builder = builder.with_host_linker(move |linker| host_linker(linker));
+        }
+        let initializer = self.guest_initializer.clone();
+        builder
+            .with_guest_initializer(move |ctx| match initializer {
+                Some(init) => init(ctx),
+                None => instantiate_default(ctx),
+            })
+            .build()
+    }
+}
+
+impl std::fmt::Debug for RunOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RunOptions")
+            .field("restart_policy", &self.restart_policy)
+            .field("limits", &self.limits)
+            .field("wasi_policy", &self.wasi_policy)
+            .finish()
+    }
+}
+
+/// Instantiate the component with no extra bindings.
+fn instantiate_default(ctx: GuestHandlerContext<'_, HostState>) -> Result<GuestInstance> {
+    let instance = ctx.linker.instantiate(&mut *ctx.store, ctx.component)?;
+    #[cfg(feature = "dynamic")]
+    let guest = GuestInstance::new_dynamic(instance);
+    #[cfg(not(feature = "dynamic"))]
+    let guest = GuestInstance::new(instance);
+    Ok(guest)
+}
+
+/// Snapshot of a container's configuration and state (like `docker inspect`)
+#[derive(Debug, Clone)]
+pub struct ContainerInfo {
+    pub name: String,
+    pub image: String,
+    pub state: ContainerState,
+    pub restart_policy: RestartPolicy,
+    pub limits: ResourceLimits,
+    pub restart_count: u32,
+    pub created_at: SystemTime,
+    /// When the current container instance was (re)built.
+    pub started_at: SystemTime,
+}
+
+/// Runtime counters for a container (like `docker stats`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContainerStats {
+    /// Guest calls made since the registry started the container, across restarts.
+    pub calls: u64,
+    /// Guest calls that returned an error, across restarts.
+    pub failed_calls: u64,
+    pub restart_count: u32,
+    /// Fuel left in the current instance, if fuel metering is enabled.
+    pub fuel_remaining: Option<u64>,
+    /// Time since the current instance was (re)built.
+    pub uptime: Duration,
+}
+
+/// A container together with the data needed to supervise it
+struct ManagedContainer {
+    container: Container,
+    image_name: String,
+    image: Image,
+    options: RunOptions,
+    restart_count: u32,
+    created_at: SystemTime,
+    started_at: SystemTime,
+    started: Instant,
+    /// Call counters of instances replaced by restarts
+    previous_calls: u64,
+    previous_failed_calls: u64,
+    /// Shared with each instance's host state, so output survives restarts
+    logs: ContainerLogs,
+    /// A replacement instance is being built outside the registry lock
+    restarting: bool,
+    /// The last rebuild failed; the next access tries again
+    retry_pending: bool,
+}
+
+impl ManagedContainer {
+    fn new(
+        container: Container,
+        image_name: String,
+        image: Image,
+        options: RunOptions,
+        logs: ContainerLogs,
+    ) -> Self {
+        let now = SystemTime::now();
+        let mut managed = Self {
+            container,
+            image_name,
+            image,
+            options,
+            restart_count: 0,
+            created_at: now,
+            started_at: now,
+            started: Instant::now(),
+            previous_calls: 0,
+            previous_failed_calls: 0,
+            logs,
+            restarting: false,
+            retry_pending: false,
+        };
+        managed.log(
+            LogLevel::Info,
+            format!("created from image '{}'", managed.image_name),
+        );
+        managed
+    }
+
+    fn log(&mut self, level: LogLevel, message: String) {
+        self.logs.record(level, message);
+    }
+
+    /// Apply the restart policy after the container's state changed.
+    ///
+    /// Returns what is needed to build the replacement when the policy
+    /// restarts the container; pass the result to [`restart`](Self::restart).
+    fn supervise(&mut self) -> Option<(Image, RunOptions, ContainerLogs)> {
+        let state = self.container.state().clone();
+        match &state {
+            ContainerState::Error(e) => self.log(LogLevel::Error, format!("failed: {}", e)),
+            ContainerState::Stopped => self.log(LogLevel::Info, "stopped".to_string()),
+            _ => return None,
+        }
+        self.plan_restart()
+    }
+
+    /// Check the restart policy against the current state and, if it allows
+    /// another attempt, mark the container as restarting.
+    fn plan_restart(&mut self) -> Option<(Image, RunOptions, ContainerLogs)> {
+        self.retry_pending = false;
+        if self.restarting {
+            return None;
+        }
+
+        let state = self.container.state().clone();
+        if !self
+            .options
+            .restart_policy
+            .should_restart(&state, self.restart_count)
+        {
+            if matches!(self.options.restart_policy, RestartPolicy::OnFailure { .. })
+                && matches!(state, ContainerState::Error(_))
+            {
+                self.log(
+                    LogLevel::Warn,
+                    format!(
+                        "not restarting: retry limit reached after {} restarts",
+                        self.restart_count
+                    ),
+                );
+            }
+            return None;
+        }
+
+        self.restarting = true;
+        Some((self.image.clone(), self.options.clone(), self.logs.clone()))
+    }
+
+    /// Swap in the instance built for a restart planned by
+    /// [`supervise`](Self::supervise).
+    ///
+    /// A failed rebuild counts as a restart attempt and leaves the old
+    /// instance in place; the policy is applied again on the next access.
+    fn restart(&mut self, built: Result<Container>) {
+        self.restarting = false;
+        match built {
+            Ok(container) => {
+                self.previous_calls += self.container.call_count();
+                self.previous_failed_calls += self.container.failed_call_count();
+                self.container = container;
+                self.restart_count += 1;
+                self.started_at = SystemTime::now();
+                self.started = Instant::now();
+                self.log(
+                    LogLevel::Info,
+                    format!("restarted (restart #{})", self.restart_count),
+                );
+            }
+            Err(e) => {
+                self.restart_count += 1;
+                self.retry_pending = true;
+                self.log(
+                    LogLevel::Error,
+                    format!("restart #{} failed: {:#}", self.restart_count, e),
+                );
+            }
+        }
+    }
+
+    fn info(&self, name: &str) -> ContainerInfo {
+        ContainerInfo {
+            name: name.to_string(),
+            image: self.image_name.clone(),
+            state: self.container.state().clone(),
+            restart_policy: self.options.restart_policy,
+            limits: self.options.limits,
+            restart_count: self.restart_count,
+            created_at: self.created_at,
+            started_at: self.started_at,
+        }
+    }
+
+    fn stats(&self) -> ContainerStats {
+        ContainerStats {
+            calls: self.previous_calls + self.container.call_count(),
+            failed_calls: self.previous_failed_calls + self.container.failed_call_count(),
+            restart_count: self.restart_count,
+            fuel_remaining: self.container.store().get_fuel().ok(),
+            uptime: self.started.elapsed(),
+        }
+    }
+}
 
 /// Registry manages images and containers
 ///
 /// Similar to Docker's daemon, it tracks available images and running containers
 pub struct Registry {
     images: Arc<Mutex<HashMap<String, Image>>>,
-    containers: Arc<Mutex<HashMap<String, Container>>>,
+    containers: Arc<Mutex<HashMap<String, ManagedContainer>>>,
 }
 
 impl Registry {
@@ -64,8 +432,52 @@ impl Registry {
         images.get(name).cloned()
     }
 
+    /// Create and start a container from a registered image (similar to docker run)
+    ///
+    /// # Arguments
+    /// * `image` - Name of a registered image
+    /// * `name` - Unique name for the container
+    /// * `options` - Initializers, restart policy and resource limits
+    pub fn run_container(
+        &self,
+        image: &str,
+        name: impl Into<String>,
+        options: RunOptions,
+    ) -> Result<()> {
+        let name = name.into();
+        let image_ref = self
+            .get_image(image)
+            .ok_or_else(|| anyhow::anyhow!("Image not found: {}", image))?;
+
+        if self.has_container(&name) {
+            anyhow::bail!("Container already exists: {}", name);
+        }
+
+        // Instantiating can be slow, so other containers stay usable meanwhile
+        let logs = ContainerLogs::default();
+        let container = options
+            .build(&image_ref, &logs)
+            .context(format!("Failed to start container '{}'", name))?;
+
+        let mut containers = self.containers.lock().unwrap_or_else(|e| e.into_inner());
+        if containers.contains_key(&name) {
+            anyhow::bail!("Container already exists: {}", name);
+        }
+        containers.insert(
+            name,
+            ManagedContainer::new(container, image.to_string(), image_ref, options, logs),
+        );
+
+        Ok(())
+    }
+
     /// Get mutable reference to container by name
     ///
+    /// If the callback leaves the container failed (or stopped), the
+    /// container's restart policy is applied before this returns. When the
+    /// previous rebuild failed, the policy is applied again on every call
+    /// until a rebuild succeeds or the policy gives up.
+    ///
     /// # Arguments
     /// * `name` - Container name
     /// * `f` - Callback function to execute on the container
@@ -73,17 +485,76 @@ impl Registry {
     where
         F: FnOnce(&mut Container) -> R,
     {
-        let mut containers = self.containers.lock().unwrap_or_else(|e| e.into_inner());
-        containers.get_mut(name).map(f)
+        let (result, restart) = {
+            let mut containers = self.containers.lock().unwrap_or_else(|e| e.into_inner());
+            let managed = containers.get_mut(name)?;
+
+            let before = managed.container.state().clone();
+            let result = f(&mut managed.container);
+            let restart = if *managed.container.state() != before {
+                managed.supervise()
+            } else if managed.retry_pending {
+                managed.plan_restart()
+            } else {
+                None
+            };
+            (result, restart)
+        };
+
+        // Build the replacement without holding the lock; the container may
+        // have been stopped and removed in the meantime.
+        if let Some((image, options, logs)) = restart {
+            let built = options.build(&image, &logs);
+            let mut containers = self.containers.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(managed) = containers.get_mut(name).filter(|m| m.restarting) {
+                managed.restart(built);
+            }
+        }
+        Some(result)
+    }
+
+    fn has_container(&self, name: &str) -> bool {
+        let containers = self.containers.lock().unwrap_or_else(|e| e.into_inner());
+        containers.contains_key(name)
     }
 
     /// Stop and remove container (similar to docker stop/rm)
+    ///
+    /// Restart policies do not apply to containers stopped this way.
     pub fn stop_container(&self, name: &str) -> Option<Container> {
         let mut containers = self.containers.lock().unwrap_or_else(|e| e.into_inner());
-        if let Some(container) = containers.get_mut(name) {
-            container.stop();
-        }
-        containers.remove(name)
+        let mut managed = containers.remove(name)?;
+        managed.container.stop();
+        Some(managed.container)
+    }
+
+    /// Configuration and state of a container (similar to docker inspect)
+    pub fn inspect(&self, name: &str) -> Option<ContainerInfo> {
+        let containers = self.containers.lock().unwrap_or_else(|e| e.into_inner());
+        containers.get(name).map(|managed| managed.info(name))
+    }
+
+    /// Guest output and lifecycle events of a container, oldest first
+    /// (similar to docker logs)
+    ///
+    /// At most [`DEFAULT_LOG_CAPACITY`](crate::DEFAULT_LOG_CAPACITY) entries are kept.
+    pub fn logs(&self, name: &str) -> Option<Vec<LogEntry>> {
+        let containers = self.containers.lock().unwrap_or_else(|e| e.into_inner());
+        containers.get(name).map(|managed| managed.logs.entries())
+    }
+
+    /// Stream new log entries of a container (similar to docker logs -f)
+    ///
+    /// The stream follows the container across restarts.
+    pub fn follow_logs(&self, name: &str) -> Option<mpsc::Receiver<LogEntry>> {
+        let containers = self.containers.lock().unwrap_or_else(|e| e.into_inner());
+        containers.get(name).map(|managed| managed.logs.subscribe())
+    }
+
+    /// Runtime counters of a container (similar to docker stats)
+    pub fn stats(&self, name: &str) -> Option<ContainerStats> {
+        let containers = self.containers.lock().unwrap_or_else(|e| e.into_inner());
+        containers.get(name).map(ManagedContainer::stats)
     }
 
     /// List all registered image names
@@ -125,6 +596,7 @@ mod tests {
     use bytes::Bytes;
 
     use super::*;
+    use crate::LogSource;
 
     const MINIMAL_WASM: &[u8] = b"\x00asm\x01\x00\x00\x00";
 
@@ -248,4 +720,154 @@ mod tests {
         assert!(result.is_err());
         assert!(reg.get_image("bad-comp").is_none());
     }
+
+    #[test]
+    fn test_restart_policy_decisions() {
+        let error = ContainerState::Error("trap".into());
+        let stopped = ContainerState::Stopped;
+
+        assert_eq!(RestartPolicy::default(), RestartPolicy::Never);
+        assert!(!RestartPolicy::Never.should_restart(&error, 0));
+
+        let on_failure = RestartPolicy::OnFailure {
+            max_retries: Some(2),
+        };
+        assert!(on_failure.should_restart(&error, 1));
+        assert!(!on_failure.should_restart(&error, 2));
+        assert!(!on_failure.should_restart(&stopped, 0));
+        assert!(RestartPolicy::OnFailure { max_retries: None }.should_restart(&error, 100));
+
+        assert!(RestartPolicy::Always.should_restart(&error, 100));
+        assert!(RestartPolicy::Always.should_restart(&stopped, 0));
+        assert!(!RestartPolicy::Always.should_restart(&ContainerState::Running, 0));
+    }
+
+    #[test]
+    fn test_run_container_unknown_image() {
+        let reg = Registry::new();
+        let result = reg.run_container("missing", "app", RunOptions::new());
+        assert!(result.is_err());
+        assert!(reg.list_containers().is_empty());
+    }
+
+    #[test]
+    fn test_inspect_logs_stats_not_found() {
+        let reg = Registry::new();
+        assert!(reg.inspect("nonexistent").is_none());
+        assert!(reg.logs("nonexistent").is_none());
+        assert!(reg.follow_logs("nonexistent").is_none());
+        assert!(reg.stats("nonexistent").is_none());
+    }
+
+    #[test]
+    fn test_run_container_and_restart() {
+        let reg = Registry::new();
+        reg.register_image("app", Bytes::from_static(MINIMAL_WASM))
+            .unwrap();
+        let options = RunOptions::new().with_restart_policy(RestartPolicy::Always);
+        reg.run_container("app", "web", options.clone()).unwrap();
+        assert!(reg.run_container("app", "web", options).is_err());
+
+        let info = reg.inspect("web").unwrap();
+        assert_eq!(info.image, "app");
+        assert_eq!(info.state, ContainerState::Created);
+        assert_eq!(info.restart_count, 0);
+
+        let follow = reg.follow_logs("web").unwrap();
+        reg.get_container_mut("web", |c| c.stop());
+
+        let info = reg.inspect("web").unwrap();
+        assert_eq!(info.state, ContainerState::Created);
+        assert_eq!(info.restart_count, 1);
+        assert_eq!(reg.stats("web").unwrap().restart_count, 1);
+
+        let logs = reg.logs("web").unwrap();
+        let messages: Vec<_> = logs.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "created from image 'app'",
+                "stopped",
+                "restarted (restart #1)"
+            ]
+        );
+        assert!(logs.iter().all(|e| e.source == LogSource::Runtime));
+        assert_eq!(follow.try_iter().count(), 2);
+
+        let stopped = reg.stop_container("web").unwrap();
+        assert_eq!(stopped.state(), &ContainerState::Stopped);
+        assert!(reg.inspect("web").is_none());
+    }
+
+    #[test]
+    fn test_containers_are_built_outside_the_registry_lock() {
+        let reg = Arc::new(Registry::new());
+        reg.register_image("app", Bytes::from_static(MINIMAL_WASM))
+            .unwrap();
+
+        // Building a container looks at the registry, which would deadlock
+        // if the registry held its lock while building.
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let options = {
+            let (reg, seen) = (Arc::downgrade(&reg), seen.clone());
+            RunOptions::new()
+                .with_restart_policy(RestartPolicy::Always)
+                .with_host_linker(move |_| {
+                    let reg = reg.upgrade().expect("registry is alive");
+                    seen.lock().unwrap().push(reg.list_containers());
+                    Ok(())
+                })
+        };
+        reg.run_container("app", "web", options).unwrap();
+        reg.get_container_mut("web", |c| c.stop());
+
+        assert_eq!(reg.inspect("web").unwrap().restart_count, 1);
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![Vec::<String>::new(), vec!["web".to_string()]]
+        );
+    }
+
+    #[test]
+    fn test_failed_rebuild_is_retried() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let reg = Registry::new();
+        reg.register_image("app", Bytes::from_static(MINIMAL_WASM))
+            .unwrap();
+
+        // The initial build succeeds and the first rebuild fails.
+        let builds = Arc::new(AtomicUsize::new(0));
+        let options = {
+            let builds = builds.clone();
+            RunOptions::new()
+                .with_restart_policy(RestartPolicy::Always)
+                .with_host_linker(move |_| match builds.fetch_add(1, Ordering::SeqCst) {
+                    1 => anyhow::bail!("linker unavailable"),
+                    _ => Ok(()),
+                })
+        };
+        reg.run_container("app", "web", options).unwrap();
+
+        reg.get_container_mut("web", |c| c.stop());
+        let info = reg.inspect("web").unwrap();
+        assert_eq!(info.state, ContainerState::Stopped);
+        assert_eq!(info.restart_count, 1);
+
+        reg.get_container_mut("web", |_| ());
+        let info = reg.inspect("web").unwrap();
+        assert_eq!(info.state, ContainerState::Created);
+        assert_eq!(info.restart_count, 2);
+        assert_eq!(builds.load(Ordering::SeqCst), 3);
+
+        let logs = reg.logs("web").unwrap();
+        let messages: Vec<_> = logs.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages[1], "stopped");
+        assert!(messages[2].starts_with("restart #1 failed: "));
+        assert_eq!(messages[3], "restarted (restart #2)");
+
+        // Once rebuilt, the container is no longer retried.
+        reg.get_container_mut("web", |_| ());
+        assert_eq!(builds.load(Ordering::SeqCst), 3);
+    }
 }
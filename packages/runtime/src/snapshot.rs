@@ -0,0 +1,334 @@
+//! Snapshot - Save a guest's state and restore it into a new container
+//!
+//! Snapshots are opt-in: the guest exports two functions that serialize and
+//! restore its own state,
+//!
+//! ```wit
+//! world plugin {
+//!     export save-state: func() -> list<u8>;
+//!     export load-state: func(state: list<u8>) -> result<_, string>;
+//! }
+//! ```
+//!
+//! (`load-state` may also return nothing). [`Container::snapshot`] wraps the
+//! saved bytes in a versioned envelope recording the image's
+//! [`digest`](crate::Image::digest), and [`Container::restore`] or
+//! [`ContainerBuilder::from_snapshot`](crate::ContainerBuilder::from_snapshot)
+//! refuse envelopes taken from a different image.
+//!
+//! # Envelope format
+//!
+//! ```text
+//! magic    b"TRSNAP"     6 bytes
+//! version  u16 LE        currently 1
+//! image    SHA-256      32 bytes
+//! length   u64 LE        length of the guest state
+//! state    bytes
+//! ```
+//!
+//! # Example
+//! ```ignore
+//! // Before shutting down
+//! std::fs::write("plugin.snap", container.snapshot()?)?;
+//!
+//! // After the restart
+//! let container = Container::builder(image)
+//!     .from_snapshot(std::fs::read("plugin.snap")?)
+//!     .with_guest_initializer(|ctx| { /* ... */ })
+//!     .build()?;
+//! ```
+
+use anyhow::{Context, Result};
+use bytes::{BufMut, Bytes, BytesMut};
+
+use wasmtime::component::Instance;
+
+use crate::{Container, HostStateImpl, Image};
+
+/// Guest export returning the guest's serialized state
+pub const SAVE_STATE_EXPORT: &str = "save-state";
+
+/// Guest export restoring state returned by [`SAVE_STATE_EXPORT`]
+pub const LOAD_STATE_EXPORT: &str = "load-state";
+
+/// Envelope version written by this release
+pub const SNAPSHOT_VERSION: u16 = 1;
+
+const MAGIC: &[u8; 6] = b"TRSNAP";
+const HEADER_LEN: usize = MAGIC.len() + 2 + 32 + 8;
+
+/// A decoded snapshot envelope
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    image_digest: [u8; 32],
+    state: Bytes,
+}
+
+impl Snapshot {
+    /// Wrap guest state saved from a container of `image`
+    pub fn new(image: &Image, state: Bytes) -> Self {
+        Self {
+            image_digest: image.digest(),
+            state,
+        }
+    }
+
+    /// Digest of the image the snapshot was taken from
+    pub fn image_digest(&self) -> [u8; 32] {
+        self.image_digest
+    }
+
+    /// The guest's saved state
+    pub fn state(&self) -> &Bytes {
+        &self.state
+    }
+
+    /// Serialize into the envelope format
+    pub fn encode(&self) -> Bytes {
+        let mut out = BytesMut::with_capacity(HEADER_LEN + self.state.len());
+        out.put_slice(MAGIC);
+        out.put_u16_le(SNAPSHOT_VERSION);
+        out.put_slice(&self.image_digest);
+        out.put_u64_le(self.state.len() as u64);
+        out.put_slice(&self.state);
+        out.freeze()
+    }
+
+    /// Parse an envelope produced by [`encode`](Self::encode)
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < HEADER_LEN || &bytes[..MAGIC.len()] != MAGIC {
+            anyhow::bail!("Not a container snapshot");
+        }
+        let (version, rest) = bytes[MAGIC.len()..].split_at(2);
+        let version = u16::from_le_bytes([version[0], version[1]]);
+        if version != SNAPSHOT_VERSION {
+            anyhow::bail!(
+                "Unsupported snapshot version {} (expected {})",
+                version,
+                SNAPSHOT_VERSION
+            );
+        }
+        let (digest, rest) = rest.split_at(32);
+        let (length, state) = rest.split_at(8);
+        let length = u64::from_le_bytes(length.try_into().expect("8-byte slice"));
+        if length != state.len() as u64 {
+            anyhow::bail!(
+                "Truncated snapshot: expected {} bytes of state, found {}",
+                length,
+                state.len()
+            );
+        }
+        Ok(Self {
+            image_digest: digest.try_into().expect("32-byte slice"),
+            state: Bytes::copy_from_slice(state),
+        })
+    }
+
+    /// Fail unless the snapshot was taken from `image`
+    pub fn check_image(&self, image: &Image) -> Result<()> {
+        if self.image_digest != image.digest() {
+            anyhow::bail!(
+                "Snapshot was taken from image {}, not {}",
+                hex::encode(self.image_digest),
+                hex::encode(image.digest())
+            );
+        }
+        Ok(())
+    }
+}
+
+impl<T: HostStateImpl> Container<T> {
+    /// Save the guest's state via its `save-state` export
+    ///
+    /// Returns an envelope for [`restore`](Self::restore) or
+    /// [`ContainerBuilder::from_snapshot`](crate::ContainerBuilder::from_snapshot).
+    /// Only synchronous containers whose guest is a raw component
+    /// [`Instance`] support snapshots.
+    pub fn snapshot(&mut self) -> Result<Bytes> {
+        let instance = self.snapshot_instance(SAVE_STATE_EXPORT)?;
+        let save = instance
+            .get_typed_func::<(), (Vec<u8>,)>(self.store_mut(), SAVE_STATE_EXPORT)
+            .map_err(anyhow::Error::from)
+            .context("save-state must have type `func() -> list<u8>`")?;
+        let (state,) = save
+            .call(self.store_mut(), ())
+            .map_err(anyhow::Error::from)
+            .context("Guest failed to save its state")?;
+
+        Ok(Snapshot::new(self.image(), state.into()).encode())
+    }
+
+    /// Restore guest state from a [`snapshot`](Self::snapshot) via the
+    /// guest's `load-state` export
+    ///
+    /// Fails without calling the guest if the envelope is malformed or was
+    /// taken from a different image.
+    pub fn restore(&mut self, snapshot: &[u8]) -> Result<()> {
+        let snapshot = Snapshot::decode(snapshot)?;
+        snapshot.check_image(self.image())?;
+
+        let instance = self.snapshot_instance(LOAD_STATE_EXPORT)?;
+        let state = snapshot.state().as_ref();
+        let store = self.store_mut();
+        if let Ok(load) = instance
+            .get_typed_func::<(&[u8],), (Result<(), String>,)>(&mut *store, LOAD_STATE_EXPORT)
+        {
+            let (result,) = load
+                .call(&mut *store, (state,))
+                .map_err(anyhow::Error::from)
+                .context("Guest failed to load its state")?;
+            return result.map_err(|e| anyhow::anyhow!("Guest rejected the snapshot: {}", e));
+        }
+
+        let load = instance
+            .get_typed_func::<(&[u8],), ()>(&mut *store, LOAD_STATE_EXPORT)
+            .map_err(anyhow::Error::from)
+            .context("load-state must have type `func(state: list<u8>)`, optionally returning `result<_, string>`")?;
+        load.call(&mut *store, (state,))
+            .map_err(anyhow::Error::from)
+            .context("Guest failed to load its state")
+    }
+
+    /// The guest instance, checked to export `name`
+    fn snapshot_instance(&mut self, name: &str) -> Result<Instance> {
+        if self.is_async() {
+            anyhow::bail!("Snapshots are only supported on containers built with build()");
+        }
+        let instance = self.guest().downcast_ref::<Instance>().copied().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Snapshots require a guest created from a raw component Instance, not typed bindings"
+            )
+        })?;
+        if instance.get_func(self.store_mut(), name).is_none() {
+            anyhow::bail!(
+                "Guest does not export `{}`, so it cannot be snapshotted",
+                name
+            );
+        }
+        Ok(instance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{container::ContainerBuilder, GuestInstance, HostState};
+
+    /// `bump()` increments a counter; the state is the counter's 4 bytes and
+    /// `load-state` rejects anything else with "bad state".
+    const COMPONENT: &str = r#"
+        (component
+          (core module $m
+            (memory (export "memory") 1)
+            (global $n (mut i32) (i32.const 0))
+            (global $heap (mut i32) (i32.const 1024))
+            (data (i32.const 48) "bad state")
+            (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+              (local $p i32)
+              (local.set $p (global.get $heap))
+              (global.set $heap (i32.add (global.get $heap) (local.get 3)))
+              (local.get $p))
+            (func (export "bump") (result i32)
+              (global.set $n (i32.add (global.get $n) (i32.const 1)))
+              (global.get $n))
+            (func (export "save-state") (result i32)
+              (i32.store (i32.const 16) (global.get $n))
+              (i32.store (i32.const 8) (i32.const 16))
+              (i32.store (i32.const 12) (i32.const 4))
+              (i32.const 8))
+            (func (export "load-state") (param $ptr i32) (param $len i32) (result i32)
+              (if (i32.eq (local.get $len) (i32.const 4))
+                (then
+                  (global.set $n (i32.load (local.get $ptr)))
+                  (i32.store8 (i32.const 32) (i32.const 0)))
+                (else
+                  (i32.store8 (i32.const 32) (i32.const 1))
+                  (i32.store (i32.const 36) (i32.const 48))
+                  (i32.store (i32.const 40) (i32.const 9))))
+              (i32.const 32)))
+          (core instance $i (instantiate $m))
+          (func $bump (result u32) (canon lift (core func $i "bump")))
+          (func $save (result (list u8))
+            (canon lift (core func $i "save-state") (memory (core memory $i "memory"))))
+          (func $load (param "state" (list u8)) (result (result (error string)))
+            (canon lift (core func $i "load-state") (memory (core memory $i "memory"))
+              (realloc (core func $i "realloc"))))
+          (export "bump" (func $bump))
+          (export "save-state" (func $save))
+          (export "load-state" (func $load))
+        )
+    "#;
+
+    fn image(wat: &str) -> Image {
+        let binary = wat::parse_str(wat).expect("valid WAT");
+        Image::from_component(binary.into()).expect("valid component")
+    }
+
+    fn build(builder: ContainerBuilder<HostState>) -> Result<Container> {
+        builder
+            .with_guest_initializer(|ctx| {
+                let instance = ctx.linker.instantiate(&mut *ctx.store, ctx.component)?;
+                Ok(GuestInstance::new(instance))
+            })
+            .build()
+    }
+
+    fn bump(container: &mut Container) -> u32 {
+        let instance = *container.guest().downcast_ref::<Instance>().unwrap();
+        let bump = instance
+            .get_typed_func::<(), (u32,)>(container.store_mut(), "bump")
+            .unwrap();
+        bump.call(container.store_mut(), ()).unwrap().0
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let image = image(COMPONENT);
+        let mut original = build(Container::builder(image.clone())).unwrap();
+        bump(&mut original);
+        assert_eq!(bump(&mut original), 2);
+
+        let snapshot = original.snapshot().unwrap();
+        let decoded = Snapshot::decode(&snapshot).unwrap();
+        assert_eq!(decoded.image_digest(), image.digest());
+        assert_eq!(decoded.state().as_ref(), 2u32.to_le_bytes());
+
+        let mut restored =
+            build(Container::builder(image.clone()).from_snapshot(snapshot)).unwrap();
+        assert_eq!(bump(&mut restored), 3);
+
+        let bad_state = Snapshot::new(&image, Bytes::from_static(b"bad")).encode();
+        let err = restored.restore(&bad_state).unwrap_err();
+        assert!(err.to_string().contains("bad state"), "{err}");
+        assert_eq!(bump(&mut restored), 4);
+    }
+
+    #[test]
+    fn test_snapshot_rejects_other_images_and_bad_envelopes() {
+        let image = image(COMPONENT);
+        let other = self::image(&COMPONENT.replace("bad state", "bad input"));
+        assert_ne!(image.digest(), other.digest());
+
+        let snapshot = build(Container::builder(image))
+            .unwrap()
+            .snapshot()
+            .unwrap();
+        let err =
+            build(Container::builder(other.clone()).from_snapshot(snapshot.clone())).unwrap_err();
+        assert!(
+            err.to_string().contains("Snapshot was taken from image"),
+            "{err:#}"
+        );
+
+        let mut container = build(Container::builder(other)).unwrap();
+        assert!(container.restore(b"not a snapshot").is_err());
+        assert!(container.restore(&snapshot[..snapshot.len() - 1]).is_err());
+
+        let mut future = snapshot.to_vec();
+        future[MAGIC.len()] = 2;
+        assert!(Snapshot::decode(&future)
+            .unwrap_err()
+            .to_string()
+            .contains("version 2"));
+    }
+}
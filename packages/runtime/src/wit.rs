@@ -182,6 +182,7 @@ impl WitLoader {
                             functions.push(FunctionInfo {
                                 name: name_str,
                                 params,
+                                docs: func.docs.contents.clone(),
                             });
                         }
                         wit_parser::WorldItem::Interface { id, .. } => {
@@ -206,6 +207,7 @@ impl WitLoader {
                                     functions.push(FunctionInfo {
                                         name: _func_name.clone(),
                                         params,
+                                        docs: func_item.docs.contents.clone(),
                                     });
                                 }
                             }
@@ -276,6 +278,7 @@ impl WitLoader {
                             functions.push(FunctionInfo {
                                 name: name_str,
                                 params,
+                                docs: func.docs.contents.clone(),
                             });
                         }
                         wit_parser::WorldItem::Interface { id, .. } => {
@@ -300,6 +303,7 @@ impl WitLoader {
                                     functions.push(FunctionInfo {
                                         name: func_name.clone(),
                                         params,
+                                        docs: func_item.docs.contents.clone(),
                                     });
                                 }
                             }
@@ -316,6 +320,66 @@ impl WitLoader {
         functions
     }
 
+    /// Get the documentation of a function
+    ///
+    /// # Arguments
+    /// * `interface` - Interface the function belongs to, either its full
+    ///   name (`package:name/interface@version`) or its bare name; `None` for
+    ///   functions declared directly in a world
+    /// * `function` - Function name
+    ///
+    /// # Returns
+    /// The function's doc comment, if the WIT source has one
+    pub fn function_docs(&self, interface: Option<&str>, function: &str) -> Option<String> {
+        match interface {
+            Some(interface) => {
+                // Inline interfaces (`export name: interface { ... }`) are only
+                // named by their world key
+                let named_in_world = |id: wit_parser::InterfaceId| {
+                    self.resolve.worlds.iter().any(|(_, world)| {
+                        world
+                            .exports
+                            .iter()
+                            .chain(&world.imports)
+                            .any(|(key, item)| {
+                                matches!(
+                                    (key, item),
+                                    (
+                                        wit_parser::WorldKey::Name(name),
+                                        wit_parser::WorldItem::Interface { id: item_id, .. },
+                                    ) if name == interface && *item_id == id
+                                )
+                            })
+                    })
+                };
+                self.resolve
+                    .interfaces
+                    .iter()
+                    .filter(|(id, iface)| {
+                        self.resolve.id_of(*id).as_deref() == Some(interface)
+                            || iface.name.as_deref() == Some(interface)
+                            || named_in_world(*id)
+                    })
+                    .find_map(|(_, iface)| iface.functions.get(function))
+                    .and_then(|func| func.docs.contents.clone())
+            }
+            None => {
+                self.resolve
+                    .worlds
+                    .iter()
+                    .flat_map(|(_, world)| world.exports.iter().chain(&world.imports))
+                    .find_map(|(key, item)| match (key, item) {
+                        (
+                            wit_parser::WorldKey::Name(name),
+                            wit_parser::WorldItem::Function(func),
+                        ) if name == function => Some(func),
+                        _ => None,
+                    })
+                    .and_then(|func| func.docs.contents.clone())
+            }
+        }
+    }
+
     /// Format a type as string
     fn format_type(&self, ty: &wit_parser::Type) -> String {
         match ty {
@@ -378,14 +442,61 @@ pub struct FunctionInfo {
     pub name: String,
     /// Parameters as (name, type) pairs
     pub params: Vec<(String, String)>,
+    /// Doc comment from the WIT source
+    pub docs: Option<String>,
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
     #[test]
     fn test_wit_loader_smoke() {
         // WIT file integration tests require actual .wit files on disk;
         // those are exercised by the tairitsu-e2e crate. This test ensures
         // the module compiles and is reachable.
     }
+
+    #[test]
+    fn test_function_docs() {
+        let mut resolve = Resolve::default();
+        resolve
+            .push_str(
+                "math.wit",
+                r#"
+                package example:math;
+
+                interface calc {
+                    /// Add two numbers
+                    add: func(a: u32, b: u32) -> u32;
+                    sub: func(a: u32, b: u32) -> u32;
+                }
+
+                world calculator {
+                    export calc;
+                    /// Check the guest is alive
+                    export ping: func() -> u32;
+                }
+                "#,
+            )
+            .unwrap();
+        let loader = WitLoader { resolve };
+
+        assert_eq!(
+            loader
+                .function_docs(Some("example:math/calc"), "add")
+                .as_deref(),
+            Some("Add two numbers")
+        );
+        assert_eq!(
+            loader.function_docs(Some("calc"), "add").as_deref(),
+            Some("Add two numbers")
+        );
+        assert_eq!(loader.function_docs(Some("calc"), "sub"), None);
+        assert_eq!(
+            loader.function_docs(None, "ping").as_deref(),
+            Some("Check the guest is alive")
+        );
+        assert_eq!(loader.function_docs(None, "add"), None);
+    }
 }
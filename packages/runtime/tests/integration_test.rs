@@ -3,8 +3,6 @@
 //! This test uses the actual compiled WASM component from wit-native-simple example
 //! to verify all dynamic invocation features work correctly.
 
-use std::path::PathBuf;
-
 #[test]
 #[cfg(feature = "dynamic")]
 fn test_real_wasm_component_dynamic_invocation() {
@@ -15,7 +13,7 @@ fn test_real_wasm_component_dynamic_invocation() {
     // Note: This test requires the WASM component to be pre-built
     // Run: cargo build --target wasm32-wasip2 --release --package tairitsu-example-wit-native-simple --lib
 
-    let wasm_path = PathBuf::from(
+    let wasm_path = std::path::PathBuf::from(
         "../../../target/wasm32-wasip2/release/tairitsu_example_wit_native_simple.wasm",
     );
 
@@ -274,6 +272,9 @@ fn test_serialization_capabilities_summary() {
     println!("     - Variant: CaseName(value)");
     println!("     - Result: Ok(value) / Err(error)");
     println!("     - Option: Some(value) / None");
+    println!("     - Enum: LightBlue (bare identifier)");
+    println!("     - Flags: [Read, Write] (set of identifiers)");
+    println!("     - Resource: Resource(n) (handle in the container's ResourceTable)");
 
     println!("\n  3. Special Values:");
     println!("     - Empty collections: [], (), {{}}");
@@ -309,3 +310,128 @@ fn test_serialization_capabilities_summary() {
 
     println!("\n✅ All serialization features verified!");
 }
+
+#[test]
+#[cfg(feature = "dynamic")]
+fn test_enum_and_flags_roundtrip() {
+    use tairitsu::dynamic::{ron_to_val, val_to_ron};
+    use wasmtime::{
+        component::{Component, Linker, Val},
+        Engine, Store,
+    };
+
+    // `f(c: color, p: perms) -> color` returns its first argument
+    let engine = Engine::default();
+    let component = Component::new(
+        &engine,
+        r#"
+        (component
+          (type $color' (enum "red" "light-blue"))
+          (export $color "color" (type $color'))
+          (type $perms' (flags "read" "write" "exec"))
+          (export $perms "perms" (type $perms'))
+          (core module $m
+            (func (export "f") (param i32 i32) (result i32) local.get 0))
+          (core instance $i (instantiate $m))
+          (func $f (param "c" $color) (param "p" $perms) (result $color)
+            (canon lift (core func $i "f")))
+          (export "f" (func $f))
+        )
+        "#,
+    )
+    .expect("Failed to compile test component");
+    let mut store = Store::new(&engine, ());
+    let instance = Linker::new(&engine)
+        .instantiate(&mut store, &component)
+        .expect("Failed to instantiate test component");
+    let func = instance.get_func(&mut store, "f").expect("f is exported");
+    let params: Vec<_> = func.ty(&store).params().map(|(_, ty)| ty).collect();
+    let (color_type, perms_type) = (&params[0], &params[1]);
+
+    println!("\n=== Testing Enum round trip ===");
+    let color = ron_to_val("LightBlue", color_type).expect("Failed to parse enum");
+    assert_eq!(color, Val::Enum("light-blue".to_string()));
+    assert_eq!(val_to_ron(&color).unwrap(), "LightBlue");
+    assert_eq!(
+        ron_to_val("\"light-blue\"", color_type).unwrap(),
+        Val::Enum("light-blue".to_string())
+    );
+    assert!(ron_to_val("Green", color_type).is_err());
+
+    println!("\n=== Testing Flags round trip ===");
+    let perms = ron_to_val("[Exec, Read, read]", perms_type).expect("Failed to parse flags");
+    assert_eq!(
+        perms,
+        Val::Flags(vec!["read".to_string(), "exec".to_string()])
+    );
+    assert_eq!(val_to_ron(&perms).unwrap(), "[Read, Exec]");
+    assert_eq!(ron_to_val("[]", perms_type).unwrap(), Val::Flags(vec![]));
+    assert!(ron_to_val("[Delete]", perms_type).is_err());
+
+    println!("\n=== Testing Enum and Flags through a call ===");
+    let mut results = vec![Val::Bool(false)];
+    func.call(&mut store, &[color, perms], &mut results)
+        .expect("Failed to call f");
+    assert_eq!(val_to_ron(&results[0]).unwrap(), "LightBlue");
+}
+
+#[test]
+#[cfg(feature = "dynamic")]
+fn test_variant_roundtrip() {
+    use tairitsu::dynamic::{ron_to_val, val_to_ron};
+    use wasmtime::{
+        component::{Component, Linker, Val},
+        Engine, Store,
+    };
+
+    let engine = Engine::default();
+    let component = Component::new(
+        &engine,
+        r#"
+        (component
+          (type $shape' (variant (case "light-blue" bool) (case "circle") (case "pair" (tuple u8 u8))))
+          (export $shape "shape" (type $shape'))
+          (core module $m
+            (func (export "f") (param i32 i32 i32 i32 i32 i32 i32)))
+          (core instance $i (instantiate $m))
+          (func $f (param "s" $shape) (param "r" (result u32 (error $shape)))
+            (canon lift (core func $i "f")))
+          (export "f" (func $f))
+        )
+        "#,
+    )
+    .expect("Failed to compile test component");
+    let mut store = Store::new(&engine, ());
+    let instance = Linker::new(&engine)
+        .instantiate(&mut store, &component)
+        .expect("Failed to instantiate test component");
+    let func = instance.get_func(&mut store, "f").expect("f is exported");
+    let params: Vec<_> = func.ty(&store).params().map(|(_, ty)| ty).collect();
+    let (shape_type, result_type) = (&params[0], &params[1]);
+
+    println!("\n=== Testing Variant round trip ===");
+    let shape = ron_to_val("LightBlue(true)", shape_type).expect("Failed to parse variant");
+    assert_eq!(
+        shape,
+        Val::Variant("light-blue".to_string(), Some(Box::new(Val::Bool(true))))
+    );
+    assert_eq!(val_to_ron(&shape).unwrap(), "LightBlue(true)");
+    assert_eq!(
+        ron_to_val("{\"light-blue\": true}", shape_type).unwrap(),
+        shape
+    );
+
+    let circle = ron_to_val("Circle", shape_type).expect("Failed to parse unit case");
+    assert_eq!(circle, Val::Variant("circle".to_string(), None));
+    assert_eq!(val_to_ron(&circle).unwrap(), "Circle");
+
+    let pair = ron_to_val("Pair((1, 2))", shape_type).expect("Failed to parse tuple case");
+    assert_eq!(val_to_ron(&pair).unwrap(), "Pair((1, 2))");
+    assert!(ron_to_val("Square(1)", shape_type).is_err());
+
+    println!("\n=== Testing Variant inside a Result ===");
+    for ron in ["Ok(7)", "Err(LightBlue(false))", "Err(Circle)"] {
+        let val = ron_to_val(ron, result_type).expect("Failed to parse result");
+        assert_eq!(val_to_ron(&val).unwrap(), ron);
+    }
+}
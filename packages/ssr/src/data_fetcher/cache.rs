@@ -138,6 +138,24 @@ impl Cache {
         cache.entries.clear();
     }
 
+    /// Entries that have not expired, as `(key, data)` pairs
+    pub fn entries(&self) -> Vec<(String, Vec<u8>)> {
+        let cache = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        cache
+            .entries
+            .iter()
+            .filter(|(_, entry)| !entry.is_expired())
+            .map(|(key, entry)| (key.clone(), entry.data.clone()))
+            .collect()
+    }
+
+    /// Insert `(key, data)` pairs with the default TTL
+    pub fn extend(&self, entries: impl IntoIterator<Item = (String, Vec<u8>)>) {
+        for (key, data) in entries {
+            self.insert(key, data);
+        }
+    }
+
     /// Get the number of entries in the cache
     pub fn len(&self) -> usize {
         let cache = self.inner.read().unwrap_or_else(|e| e.into_inner());
@@ -192,6 +210,46 @@ impl Cache {
     }
 }
 
+/// Sharing with the `use_query` cache of `tairitsu_hooks`, whose entries
+/// are JSON documents keyed by query key.
+#[cfg(feature = "data-fetcher")]
+impl Cache {
+    /// Copy the query cache of the current thread into this cache
+    ///
+    /// Call this after a server render has resolved its queries.
+    pub fn capture_queries(&self) {
+        self.extend(tairitsu_hooks::dehydrate_queries());
+    }
+
+    /// Seed the query cache of the current thread with this cache's entries
+    pub fn hydrate_queries(&self) {
+        tairitsu_hooks::hydrate_queries(self.entries());
+    }
+
+    /// A `<script type="application/json">` element carrying the JSON
+    /// entries of this cache, for `tairitsu_hooks::hydrate_queries_json` on
+    /// the client
+    ///
+    /// Entries that are not valid JSON are left out.
+    pub fn to_query_script(&self) -> String {
+        let queries: serde_json::Map<String, serde_json::Value> = self
+            .entries()
+            .into_iter()
+            .filter_map(|(key, data)| Some((key, serde_json::from_slice(&data).ok()?)))
+            .collect();
+        // `<` only appears inside JSON strings, where `\u003c` is
+        // equivalent and cannot close the script element.
+        let json = serde_json::Value::Object(queries)
+            .to_string()
+            .replace('<', "\\u003c");
+        format!(
+            r#"<script type="application/json" id="{}">{}</script>"#,
+            tairitsu_hooks::QUERY_CACHE_SCRIPT_ID,
+            json
+        )
+    }
+}
+
 /// Cache statistics
 #[derive(Debug, Clone)]
 pub struct CacheStats {
@@ -288,6 +346,37 @@ mod tests {
         assert_eq!(stats.total_access_count, 2);
     }
 
+    #[test]
+    fn test_cache_entries_extend() {
+        let source = Cache::new(Duration::from_secs(60));
+        source.insert("key1", b"data1".to_vec());
+        source.insert_with_ttl("key2", b"data2".to_vec(), Duration::ZERO);
+        std::thread::sleep(Duration::from_millis(5));
+
+        let target = Cache::new(Duration::from_secs(60));
+        target.extend(source.entries());
+        assert_eq!(target.len(), 1);
+        assert_eq!(target.get("key1"), Some(b"data1".to_vec()));
+    }
+
+    #[cfg(feature = "data-fetcher")]
+    #[test]
+    fn test_cache_query_round_trip() {
+        tairitsu_hooks::set_query_data("user", "</script>".to_string());
+
+        let cache = Cache::new(Duration::from_secs(60));
+        cache.capture_queries();
+        let script = cache.to_query_script();
+        assert!(script.contains(r#"{"user":"\u003c/script>"}"#));
+
+        tairitsu_hooks::clear_queries();
+        cache.hydrate_queries();
+        assert_eq!(
+            tairitsu_hooks::get_query_data::<String>("user").as_deref(),
+            Some("</script>")
+        );
+    }
+
     #[test]
     fn test_cache_lru_eviction() {
         let cache = Cache::with_limits(Duration::from_secs(60), 3);
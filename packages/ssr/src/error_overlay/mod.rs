@@ -6,11 +6,17 @@
 
 mod templates;
 
+use std::cell::RefCell;
+
 use serde::{Deserialize, Serialize};
 
-use tairitsu_vdom::{VElement, VNode};
+use tairitsu_vdom::{RenderError, VElement, VNode};
 pub use templates::Templates;
 
+thread_local! {
+    static REPORTED_ERRORS: RefCell<Vec<ErrorInfo>> = const { RefCell::new(Vec::new()) };
+}
+
 /// Information about an error that occurred in the application
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ErrorInfo {
@@ -68,6 +74,29 @@ impl ErrorInfo {
     }
 }
 
+impl From<&RenderError> for ErrorInfo {
+    fn from(error: &RenderError) -> Self {
+        Self::runtime(error.to_string())
+    }
+}
+
+/// Collect errors caught by VDOM error boundaries on this thread
+///
+/// Boundaries render their fallback either way; in debug builds the errors
+/// are also kept for [`ErrorOverlay::with_reported_errors`]. In release
+/// builds this does nothing.
+pub fn install_boundary_reporter() {
+    #[cfg(debug_assertions)]
+    tairitsu_vdom::set_error_reporter(|error| {
+        REPORTED_ERRORS.with(|errors| errors.borrow_mut().push(ErrorInfo::from(error)));
+    });
+}
+
+/// Remove and return the errors collected by [`install_boundary_reporter`]
+pub fn take_reported_errors() -> Vec<ErrorInfo> {
+    REPORTED_ERRORS.with(|errors| errors.borrow_mut().drain(..).collect())
+}
+
 /// Location information for an error
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ErrorLocation {
@@ -419,6 +448,11 @@ impl ErrorOverlay {
         self
     }
 
+    /// Add the errors reported by error boundaries since the last call
+    pub fn with_reported_errors(self) -> Self {
+        self.add_errors(take_reported_errors())
+    }
+
     /// Set whether to include inline styles
     pub fn with_styles(mut self, include: bool) -> Self {
         self.show_styles = include;
@@ -498,6 +532,26 @@ mod tests {
         assert_eq!(type_err.error_type, ErrorType::TypeError);
     }
 
+    #[cfg(debug_assertions)]
+    #[test]
+    fn test_boundary_errors_reach_the_overlay() {
+        install_boundary_reporter();
+        let boundary = tairitsu_vdom::ErrorBoundary::new(|_, _| VNode::empty());
+        boundary.render(|| panic!("widget failed"));
+
+        let reported = take_reported_errors();
+        assert_eq!(reported.len(), 1);
+        assert_eq!(reported[0].error_type, ErrorType::RuntimeError);
+        assert!(reported[0].message.contains("widget failed"));
+
+        boundary.reset();
+        boundary.render(|| panic!("widget failed again"));
+        let overlay = ErrorOverlay::new().with_reported_errors();
+        assert_eq!(overlay.errors.len(), 1);
+        assert!(take_reported_errors().is_empty());
+        tairitsu_vdom::clear_error_reporter();
+    }
+
     #[test]
     fn test_error_location_format() {
         let loc = ErrorLocation::new("src/main.rs", 42, 7);
@@ -18,8 +18,12 @@ pub struct SsrConfig {
     pub viewport_width: i32,
     /// Simulated viewport height (default 1080)
     pub viewport_height: i32,
-    /// Current URL path for route-aware rendering (default "/")
+    /// Current URL for route-aware rendering, including any query string and
+    /// fragment (default "/")
     pub current_route: String,
+    /// Origin reported as `location.origin`, e.g. `https://example.com`
+    /// (default empty)
+    pub origin: String,
 }
 
 impl Default for SsrConfig {
@@ -28,6 +32,7 @@ impl Default for SsrConfig {
             viewport_width: 1920,
             viewport_height: 1080,
             current_route: "/".to_string(),
+            origin: String::new(),
         }
     }
 }
@@ -39,6 +44,7 @@ impl SsrConfig {
             viewport_width,
             viewport_height,
             current_route: "/".to_string(),
+            origin: String::new(),
         }
     }
 
@@ -52,8 +58,66 @@ impl SsrConfig {
             } else {
                 route.to_string()
             },
+            origin: String::new(),
         }
     }
+
+    /// Report `origin` as `location.origin`
+    pub fn with_origin(mut self, origin: impl Into<String>) -> Self {
+        self.origin = origin.into();
+        self
+    }
+
+    /// Path part of the current route (`location.pathname`)
+    pub fn pathname(&self) -> &str {
+        let end = self
+            .current_route
+            .find(['?', '#'])
+            .unwrap_or(self.current_route.len());
+        &self.current_route[..end]
+    }
+
+    /// Query part of the current route including the `?` (`location.search`)
+    ///
+    /// Empty when there is no query string.
+    pub fn search(&self) -> &str {
+        let route = self.route_without_hash();
+        match route.find('?') {
+            Some(start) if start + 1 < route.len() => &route[start..],
+            _ => "",
+        }
+    }
+
+    /// Fragment of the current route including the `#` (`location.hash`)
+    ///
+    /// Empty when there is no fragment.
+    pub fn hash(&self) -> &str {
+        match self.current_route.find('#') {
+            Some(start) if start + 1 < self.current_route.len() => &self.current_route[start..],
+            _ => "",
+        }
+    }
+
+    /// Move to `url` as if by `history.pushState`
+    ///
+    /// A bare `?query` or `#hash` is resolved against the current path.
+    pub fn navigate(&mut self, url: &str) {
+        self.current_route = if url.starts_with('?') {
+            format!("{}{}", self.pathname(), url)
+        } else if url.starts_with('#') {
+            format!("{}{}", self.route_without_hash(), url)
+        } else if url.is_empty() {
+            return;
+        } else {
+            url.to_string()
+        };
+    }
+
+    fn route_without_hash(&self) -> &str {
+        self.current_route
+            .split_once('#')
+            .map_or(self.current_route.as_str(), |(route, _)| route)
+    }
 }
 
 /// SSR host state
@@ -340,7 +404,13 @@ impl crate::bindings::PlatformHelpersHost for SsrHostState {
 
     fn file_reader_read_as_array_buffer(&mut self, _blob: u64, _callback_id: u64) {}
 
-    fn idb_open(&mut self, _name: String, _version: Option<u64>, _callback_id: u64) -> u64 {
+    fn idb_open(
+        &mut self,
+        _name: String,
+        _version: Option<u64>,
+        _store_name: Option<String>,
+        _callback_id: u64,
+    ) -> u64 {
         0
     }
 
@@ -384,4 +454,33 @@ mod tests {
         assert_eq!(state.config.viewport_height, 720);
         assert_eq!(state.config.current_route, "/about");
     }
+
+    #[test]
+    fn test_config_location_parts() {
+        let config = SsrConfig::with_route(1280, 720, "/search?q=x&tag=a#top");
+        assert_eq!(config.pathname(), "/search");
+        assert_eq!(config.search(), "?q=x&tag=a");
+        assert_eq!(config.hash(), "#top");
+
+        let config = SsrConfig::with_route(1280, 720, "/about?#");
+        assert_eq!(config.pathname(), "/about");
+        assert_eq!(config.search(), "");
+        assert_eq!(config.hash(), "");
+        assert_eq!(config.origin, "");
+
+        let config = config.with_origin("https://example.com");
+        assert_eq!(config.origin, "https://example.com");
+    }
+
+    #[test]
+    fn test_config_navigate_keeps_location() {
+        let mut config = SsrConfig::with_route(1280, 720, "/search?q=x#top");
+        config.navigate("#results");
+        assert_eq!(config.current_route, "/search?q=x#results");
+        config.navigate("?q=y");
+        assert_eq!(config.current_route, "/search?q=y");
+        config.navigate("/users/1?tab=posts");
+        assert_eq!(config.pathname(), "/users/1");
+        assert_eq!(config.search(), "?tab=posts");
+    }
 }
@@ -1266,43 +1266,87 @@ fn register_core_imports(linker: &mut Linker<SsrHostState>) -> Result<()> {
         )?;
     }
 
-    // history: back/forward/push-state (no-ops)
+    // history: back/forward are no-ops; push/replace update the route
     {
         let mut hist = linker.instance("tairitsu-browser:full/history@0.2.0")?;
         hist.func_wrap(
             "back",
             |_caller: wasmtime::StoreContextMut<'_, SsrHostState>,
-             (_self,): (u64,)|
+             (): ()|
              -> Result<(), wasmtime::Error> { Ok(()) },
         )?;
         hist.func_wrap(
             "forward",
             |_caller: wasmtime::StoreContextMut<'_, SsrHostState>,
-             (_self,): (u64,)|
+             (): ()|
              -> Result<(), wasmtime::Error> { Ok(()) },
         )?;
         hist.func_wrap(
             "push-state",
-            |_caller: wasmtime::StoreContextMut<'_, SsrHostState>,
-             (_self, _data, _title, _url): (u64, String, String, Option<String>)|
-             -> Result<(), wasmtime::Error> { Ok(()) },
+            |mut caller: wasmtime::StoreContextMut<'_, SsrHostState>,
+             (_data, _title, url): (String, String, Option<String>)|
+             -> Result<(), wasmtime::Error> {
+                if let Some(url) = url {
+                    caller.data_mut().config.navigate(&url);
+                }
+                Ok(())
+            },
+        )?;
+        hist.func_wrap(
+            "replace-state",
+            |mut caller: wasmtime::StoreContextMut<'_, SsrHostState>,
+             (_data, _title, url): (String, String, Option<String>)|
+             -> Result<(), wasmtime::Error> {
+                if let Some(url) = url {
+                    caller.data_mut().config.navigate(&url);
+                }
+                Ok(())
+            },
         )?;
     }
 
-    // location: href, pathname, etc.
+    // location: derived from the configured route
     {
         let mut loc = linker.instance("tairitsu-browser:full/location@0.2.0")?;
         loc.func_wrap(
             "get-href",
-            |_caller: wasmtime::StoreContextMut<'_, SsrHostState>,
-             (_self,): (u64,)|
-             -> Result<(String,), wasmtime::Error> { Ok(("/".to_string(),)) },
+            |caller: wasmtime::StoreContextMut<'_, SsrHostState>,
+             (): ()|
+             -> Result<(String,), wasmtime::Error> {
+                Ok((caller.data().config.current_route.clone(),))
+            },
+        )?;
+        loc.func_wrap(
+            "get-origin",
+            |caller: wasmtime::StoreContextMut<'_, SsrHostState>,
+             (): ()|
+             -> Result<(String,), wasmtime::Error> {
+                Ok((caller.data().config.origin.clone(),))
+            },
         )?;
         loc.func_wrap(
             "get-pathname",
-            |_caller: wasmtime::StoreContextMut<'_, SsrHostState>,
-             (_self,): (u64,)|
-             -> Result<(String,), wasmtime::Error> { Ok(("/".to_string(),)) },
+            |caller: wasmtime::StoreContextMut<'_, SsrHostState>,
+             (): ()|
+             -> Result<(String,), wasmtime::Error> {
+                Ok((caller.data().config.pathname().to_string(),))
+            },
+        )?;
+        loc.func_wrap(
+            "get-search",
+            |caller: wasmtime::StoreContextMut<'_, SsrHostState>,
+             (): ()|
+             -> Result<(String,), wasmtime::Error> {
+                Ok((caller.data().config.search().to_string(),))
+            },
+        )?;
+        loc.func_wrap(
+            "get-hash",
+            |caller: wasmtime::StoreContextMut<'_, SsrHostState>,
+             (): ()|
+             -> Result<(String,), wasmtime::Error> {
+                Ok((caller.data().config.hash().to_string(),))
+            },
         )?;
     }
 
@@ -0,0 +1,426 @@
+//! Error boundaries for component subtrees.
+//!
+//! An [`ErrorBoundary`] catches panics raised while rendering its children,
+//! while re-rendering components registered inside it, and while running
+//! their effects, and renders a fallback [`VNode`] instead of taking down
+//! the whole app. [`ErrorBoundary::try_render`] also turns an `Err` returned
+//! by the children into a fallback.
+//!
+//! Panics are only caught where unwinding is available; with
+//! `panic = "abort"` (the usual wasm setting) they still abort.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let boundary = ErrorBoundary::new(|error, boundary| {
+//!     let boundary = boundary.clone();
+//!     el("div")
+//!         .child(txt(&format!("Something went wrong: {}", error.message)))
+//!         .child(el("button").on_click(move |_| boundary.reset()).child(txt("Retry")))
+//!         .into()
+//! });
+//!
+//! boundary.render(|| dashboard())
+//! ```
+
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    rc::Rc,
+};
+
+use tracing::warn;
+
+use crate::{runtime, ComponentId, VNode};
+
+type Fallback = Box<dyn Fn(&RenderError, &ErrorBoundary) -> VNode>;
+type ErrorReporter = Rc<dyn Fn(&RenderError)>;
+
+thread_local! {
+    /// Boundaries whose children are currently rendering, innermost last.
+    static BOUNDARY_STACK: RefCell<Vec<ErrorBoundary>> = const { RefCell::new(Vec::new()) };
+    /// The boundary each component was registered inside.
+    static COMPONENT_BOUNDARIES: RefCell<HashMap<ComponentId, ErrorBoundary>> = RefCell::new(HashMap::new());
+    static ERROR_REPORTER: RefCell<Option<ErrorReporter>> = const { RefCell::new(None) };
+}
+
+/// What was running when a [`RenderError`] happened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorPhase {
+    Render,
+    Effect,
+}
+
+/// A failure caught by an [`ErrorBoundary`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderError {
+    /// The panic message or the `Err`'s `Display` output
+    pub message: String,
+    /// The component that was rendering or owned the effect, if any
+    pub component: Option<ComponentId>,
+    pub phase: ErrorPhase,
+}
+
+impl RenderError {
+    pub fn new(
+        message: impl Into<String>,
+        component: Option<ComponentId>,
+        phase: ErrorPhase,
+    ) -> Self {
+        Self {
+            message: message.into(),
+            component,
+            phase,
+        }
+    }
+
+    fn from_panic(
+        payload: Box<dyn Any + Send>,
+        component: Option<ComponentId>,
+        phase: ErrorPhase,
+    ) -> Self {
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "component panicked".to_string()
+        };
+        Self::new(message, component, phase)
+    }
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let phase = match self.phase {
+            ErrorPhase::Render => "render",
+            ErrorPhase::Effect => "effect",
+        };
+        match self.component {
+            Some(id) => write!(f, "{} error in component {}: {}", phase, id, self.message),
+            None => write!(f, "{} error: {}", phase, self.message),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+/// Catches render and effect failures in a subtree and renders a fallback.
+///
+/// Clones share the same state. The boundary remembers the component that
+/// rendered it and re-renders that component when an error is caught later
+/// or when it is [reset](Self::reset).
+#[derive(Clone)]
+pub struct ErrorBoundary {
+    inner: Rc<BoundaryInner>,
+}
+
+struct BoundaryInner {
+    error: RefCell<Option<RenderError>>,
+    owner: Cell<Option<ComponentId>>,
+    fallback: Fallback,
+}
+
+impl ErrorBoundary {
+    /// Create a boundary rendering `fallback` while it holds an error.
+    ///
+    /// The fallback receives the boundary so it can offer a
+    /// [reset](Self::reset).
+    pub fn new(fallback: impl Fn(&RenderError, &ErrorBoundary) -> VNode + 'static) -> Self {
+        Self {
+            inner: Rc::new(BoundaryInner {
+                error: RefCell::new(None),
+                owner: Cell::new(None),
+                fallback: Box::new(fallback),
+            }),
+        }
+    }
+
+    /// Render `children`, or the fallback if they panic or an error was
+    /// caught earlier.
+    pub fn render(&self, children: impl FnOnce() -> VNode) -> VNode {
+        self.try_render(|| Ok::<_, std::convert::Infallible>(children()))
+    }
+
+    /// Like [`render`](Self::render), also falling back when `children`
+    /// returns an `Err`.
+    pub fn try_render<E: fmt::Display>(
+        &self,
+        children: impl FnOnce() -> Result<VNode, E>,
+    ) -> VNode {
+        let component = runtime::active_component_id();
+        if component.is_some() {
+            self.inner.owner.set(component);
+        }
+        if let Some(error) = self.error() {
+            return (self.inner.fallback)(&error, self);
+        }
+
+        BOUNDARY_STACK.with(|stack| stack.borrow_mut().push(self.clone()));
+        let result = panic::catch_unwind(AssertUnwindSafe(children));
+        BOUNDARY_STACK.with(|stack| stack.borrow_mut().pop());
+
+        let error = match result {
+            Ok(Ok(vnode)) => return vnode,
+            Ok(Err(err)) => RenderError::new(err.to_string(), component, ErrorPhase::Render),
+            Err(payload) => RenderError::from_panic(payload, component, ErrorPhase::Render),
+        };
+        self.record(error.clone());
+        (self.inner.fallback)(&error, self)
+    }
+
+    /// The caught error, if any.
+    pub fn error(&self) -> Option<RenderError> {
+        self.inner.error.borrow().clone()
+    }
+
+    pub fn has_error(&self) -> bool {
+        self.inner.error.borrow().is_some()
+    }
+
+    /// Clear the caught error and re-render the owning component so the
+    /// children are tried again.
+    pub fn reset(&self) {
+        if self.inner.error.borrow_mut().take().is_none() {
+            return;
+        }
+        if let Some(owner) = self.inner.owner.get() {
+            runtime::mark_dirty(owner);
+        }
+    }
+
+    /// Catch an error raised outside of [`render`](Self::render) and switch
+    /// to the fallback.
+    ///
+    /// Only the first error is kept until the boundary is reset.
+    pub fn catch(&self, error: RenderError) {
+        if self.has_error() {
+            return;
+        }
+        self.record(error);
+        if let Some(owner) = self.inner.owner.get() {
+            runtime::mark_dirty(owner);
+        }
+    }
+
+    fn record(&self, error: RenderError) {
+        warn!("Error boundary caught {}", error);
+        *self.inner.error.borrow_mut() = Some(error.clone());
+        report(&error);
+    }
+}
+
+impl fmt::Debug for ErrorBoundary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErrorBoundary")
+            .field("error", &self.inner.error.borrow())
+            .field("owner", &self.inner.owner.get())
+            .finish()
+    }
+}
+
+/// Call `reporter` with every error caught by any boundary on this thread,
+/// replacing the previous reporter.
+///
+/// Dev tooling such as the SSR error overlay uses this to surface errors
+/// that were already handled by a fallback.
+pub fn set_error_reporter(reporter: impl Fn(&RenderError) + 'static) {
+    ERROR_REPORTER.with(|slot| *slot.borrow_mut() = Some(Rc::new(reporter)));
+}
+
+pub fn clear_error_reporter() {
+    ERROR_REPORTER.with(|slot| slot.borrow_mut().take());
+}
+
+fn report(error: &RenderError) {
+    let reporter = ERROR_REPORTER.with(|slot| slot.borrow().clone());
+    if let Some(reporter) = reporter {
+        reporter(error);
+    }
+}
+
+/// The boundary whose children are rendering right now, if any.
+pub(crate) fn innermost() -> Option<ErrorBoundary> {
+    BOUNDARY_STACK.with(|stack| stack.borrow().last().cloned())
+}
+
+/// The boundary responsible for work started now: the innermost rendering
+/// boundary, else the one enclosing the active component.
+pub(crate) fn current() -> Option<ErrorBoundary> {
+    innermost().or_else(|| runtime::active_component_id().and_then(boundary_for))
+}
+
+/// Record that `component` was registered inside `boundary`.
+pub(crate) fn adopt(component: ComponentId, boundary: ErrorBoundary) {
+    COMPONENT_BOUNDARIES.with(|map| map.borrow_mut().insert(component, boundary));
+}
+
+pub(crate) fn forget(component: ComponentId) {
+    COMPONENT_BOUNDARIES.with(|map| map.borrow_mut().remove(&component));
+}
+
+/// The nearest boundary enclosing `component`, walking up the tree.
+///
+/// A boundary is never responsible for the component that owns it, since
+/// that component renders the fallback.
+pub(crate) fn boundary_for(component: ComponentId) -> Option<ErrorBoundary> {
+    let mut current = Some(component);
+    while let Some(id) = current {
+        let found = COMPONENT_BOUNDARIES.with(|map| map.borrow().get(&id).cloned());
+        if let Some(boundary) = found {
+            if boundary.inner.owner.get() != Some(component) {
+                return Some(boundary);
+            }
+        }
+        current = runtime::parent_component(id);
+    }
+    None
+}
+
+/// Run `f`, routing a panic to `boundary` instead of unwinding further.
+///
+/// Returns `None` if `f` panicked.
+pub(crate) fn guard<T>(
+    boundary: &ErrorBoundary,
+    component: Option<ComponentId>,
+    phase: ErrorPhase,
+    f: impl FnOnce() -> T,
+) -> Option<T> {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(value) => Some(value),
+        Err(payload) => {
+            boundary.catch(RenderError::from_panic(payload, component, phase));
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_effect, txt, Signal};
+
+    fn fallback() -> ErrorBoundary {
+        ErrorBoundary::new(|error, _| txt(&error.message))
+    }
+
+    fn text(vnode: &VNode) -> String {
+        match vnode {
+            VNode::Text(text) => text.text.clone(),
+            other => panic!("expected a text node, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_render_panic_and_err_fall_back_until_reset() {
+        let boundary = fallback();
+
+        let vnode = boundary.render(|| panic!("render failed"));
+        assert_eq!(text(&vnode), "render failed");
+        assert_eq!(boundary.error().unwrap().phase, ErrorPhase::Render);
+
+        // Children are not retried until the boundary is reset.
+        let vnode = boundary.render(|| txt("ok"));
+        assert_eq!(text(&vnode), "render failed");
+
+        boundary.reset();
+        assert!(!boundary.has_error());
+        let vnode = boundary.try_render(|| Err::<VNode, _>("not found"));
+        assert_eq!(text(&vnode), "not found");
+    }
+
+    #[test]
+    fn test_component_and_effect_failures_reach_the_enclosing_boundary() {
+        let reported = Rc::new(RefCell::new(Vec::new()));
+        let sink = reported.clone();
+        set_error_reporter(move |error| sink.borrow_mut().push(error.clone()));
+
+        let boundary = fallback();
+        let owner = runtime::use_component(VNode::empty);
+        let fail = Signal::new(false);
+        let (child, _effect) = runtime::with_component(owner, || {
+            let mut child = None;
+            let mut effect = None;
+            boundary.render(|| {
+                let fail_render = fail.clone();
+                child = Some(runtime::use_component(move || {
+                    if fail_render.get() {
+                        panic!("child render failed");
+                    }
+                    VNode::empty()
+                }));
+                let fail_effect = fail.clone();
+                effect = Some(create_effect(move || {
+                    if fail_effect.get() {
+                        panic!("effect failed");
+                    }
+                }));
+                VNode::empty()
+            });
+            (child.unwrap(), effect.unwrap())
+        });
+
+        fail.set(true);
+        let error = boundary.error().unwrap();
+        assert_eq!(error.phase, ErrorPhase::Effect);
+        assert_eq!(error.message, "effect failed");
+
+        boundary.reset();
+        runtime::mark_dirty(child);
+        let error = boundary.error().unwrap();
+        assert_eq!(error.phase, ErrorPhase::Render);
+        assert_eq!(error.component, Some(child));
+
+        let messages: Vec<_> = reported
+            .borrow()
+            .iter()
+            .map(|e| e.message.clone())
+            .collect();
+        assert_eq!(messages, vec!["effect failed", "child render failed"]);
+        clear_error_reporter();
+        runtime::cleanup_component(child);
+        runtime::cleanup_component(owner);
+    }
+
+    #[test]
+    fn test_caught_panic_restores_the_active_scope() {
+        use std::any::TypeId;
+
+        let provide = |name: &'static str| {
+            let scope = runtime::current_scope().unwrap();
+            runtime::provide_context_slot(scope, TypeId::of::<&str>(), Rc::new(name));
+        };
+        let lookup = || {
+            let slot = runtime::find_context_slot(
+                runtime::current_scope().unwrap(),
+                TypeId::of::<&str>(),
+            )?;
+            slot.downcast_ref::<&str>().copied()
+        };
+
+        let boundary = fallback();
+        let owner = runtime::use_component(VNode::empty);
+        let child = runtime::use_component(VNode::empty);
+        let sibling = runtime::with_component(owner, || {
+            provide("owner");
+            boundary.render(|| {
+                runtime::with_scope(|| {
+                    provide("failed scope");
+                    runtime::with_component(child, || panic!("child render failed"))
+                })
+            });
+            assert_eq!(runtime::active_component_id(), Some(owner));
+            runtime::with_scope(lookup)
+        });
+
+        assert!(boundary.has_error());
+        assert_eq!(sibling, Some("owner"));
+        assert_eq!(runtime::active_component_id(), None);
+        assert_eq!(runtime::current_scope(), None);
+        runtime::cleanup_component(child);
+        runtime::cleanup_component(owner);
+    }
+}
@@ -34,28 +34,53 @@ fn call_stop_propagation(handle: u64) {
 #[derive(Debug, Clone)]
 pub struct EventWitHandle {
     handle: Option<u64>,
+    /// `prevent_default` / `stop_propagation` for this handle, instead of
+    /// the registered platform functions
+    controls: Option<(EventControlFn, EventControlFn)>,
 }
 
 impl EventWitHandle {
     pub fn from_wit(handle: u64) -> Self {
         Self {
             handle: Some(handle),
+            controls: None,
+        }
+    }
+
+    /// A handle whose `prevent_default` and `stop_propagation` call the
+    /// given functions rather than the registered ones, for platforms that
+    /// dispatch events themselves.
+    pub fn with_controls(
+        handle: u64,
+        prevent_default: EventControlFn,
+        stop_propagation: EventControlFn,
+    ) -> Self {
+        Self {
+            handle: Some(handle),
+            controls: Some((prevent_default, stop_propagation)),
         }
     }
 
     pub fn placeholder() -> Self {
-        Self { handle: None }
+        Self {
+            handle: None,
+            controls: None,
+        }
     }
 
     pub fn prevent_default(&self) {
-        if let Some(handle) = self.handle {
-            call_prevent_default(handle);
+        match (self.handle, self.controls) {
+            (Some(handle), Some((prevent_default, _))) => prevent_default(handle),
+            (Some(handle), None) => call_prevent_default(handle),
+            (None, _) => {}
         }
     }
 
     pub fn stop_propagation(&self) {
-        if let Some(handle) = self.handle {
-            call_stop_propagation(handle);
+        match (self.handle, self.controls) {
+            (Some(handle), Some((_, stop_propagation))) => stop_propagation(handle),
+            (Some(handle), None) => call_stop_propagation(handle),
+            (None, _) => {}
         }
     }
 }
@@ -554,6 +579,27 @@ impl Default for GenericEvent {
     }
 }
 
+/// A `storage` event, fired on `window` when another document changes
+/// `localStorage` or `sessionStorage`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StorageEvent {
+    /// The changed key, or `None` when the storage area was cleared
+    pub key: Option<String>,
+    pub old_value: Option<String>,
+    /// The new value, or `None` when the key was removed
+    pub new_value: Option<String>,
+    /// URL of the document that made the change
+    pub url: String,
+    /// Whether `sessionStorage` changed rather than `localStorage`
+    pub session: bool,
+}
+
+impl EventData for StorageEvent {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
 /// Represents keyboard keys for keyboard event handling.
 /// This enum provides a Dioxus-compatible API for matching keyboard keys.
 #[derive(Debug, Clone, PartialEq, Eq)]
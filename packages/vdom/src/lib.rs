@@ -7,6 +7,7 @@
 //!
 //! - [`VNode`] / [`VElement`] — Virtual DOM nodes with attributes, styles, events
 //! - [`Signal`] — Fine-grained reactive state with automatic dependency tracking
+//! - [`ErrorBoundary`] — Fallback rendering for panicking or failing subtrees
 //! - [`Patch`] — Diff result operations (create, replace, update, reorder, etc.)
 //! - [`Platform`] — Trait abstraction for DOM/Canvas/Clipboard/File/Geo/IDB/Media ops
 //!
@@ -21,10 +22,13 @@
 //! ]);
 //! ```
 
+pub mod boundary;
 pub mod callback;
 pub mod diff;
 pub mod dom_ops;
 pub mod events;
+#[cfg(not(target_family = "wasm"))]
+pub mod mock;
 pub mod patch;
 pub mod platform;
 pub mod portal;
@@ -35,6 +39,9 @@ pub mod vnode;
 #[cfg(target_family = "wasm")]
 mod wasm_export;
 
+pub use boundary::{
+    clear_error_reporter, set_error_reporter, ErrorBoundary, ErrorPhase, RenderError,
+};
 pub use callback::{Callback, EventHandler};
 pub use dom_ops::{
     get_bounding_client_rect, register_dom_functions, register_ref_resolver,
@@ -43,9 +50,11 @@ pub use dom_ops::{
 pub use events::{
     AnimationEvent, ChangeEvent, DataTransfer, DragEvent, Event, EventData, EventWitHandle,
     FileData, FocusEvent, FormData, FormEvent, GenericEvent, InputEvent, Key, KeyboardEvent,
-    MouseData, MouseEvent, PointerEvent, PointerType, SubmitEvent, TouchEvent, TouchPoint,
-    TransitionEvent, WheelEvent,
+    MouseData, MouseEvent, PointerEvent, PointerType, StorageEvent, SubmitEvent, TouchEvent,
+    TouchPoint, TransitionEvent, WheelEvent,
 };
+#[cfg(not(target_family = "wasm"))]
+pub use mock::{MockElement, MockPlatform};
 pub use patch::Patch;
 pub use platform::{
     CanvasContext, CanvasOps, ClipboardOps, ContentEditableOps, ContentEditableState, DomOps,
@@ -55,12 +64,14 @@ pub use platform::{
 };
 pub use portal::{FixedPosition, Portal, PortalManager, PortalMaskMode, PortalPosition};
 pub use reactive::{
-    batch, create_effect, take_dependencies, DependencyEntry, EffectHandle, Signal,
+    batch, create_effect, create_effect_with_cleanup, take_dependencies, DependencyEntry,
+    EffectHandle, Signal,
 };
 pub use runtime::{
     cleanup_component, flush_render, mark_dirty, notify_signal, on_element_removed,
-    register_effect_handle, register_element, request_rerender, rerender, store_initial_vnode,
-    subscribe_component, use_component, with_component, ComponentId,
+    parent_component, register_effect_handle, register_element, register_persistent_effect_handle,
+    request_rerender, rerender, set_parent_component, store_initial_vnode, subscribe_component,
+    use_component, with_component, with_scope, ComponentId,
 };
 pub use svg::SafeSvg;
 pub use vnode::{
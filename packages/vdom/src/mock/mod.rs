@@ -0,0 +1,916 @@
+//! Headless in-memory [`Platform`](crate::Platform) for native tests.
+//!
+//! [`MockPlatform`] implements every `*Ops` trait against an in-memory node
+//! tree, so component logic can be mounted, driven and inspected with plain
+//! `cargo test`:
+//!
+//! - **Tree** — elements, text nodes, attributes, inline styles and listeners
+//! - **Virtual clock** — `set_timeout`, `set_interval` and
+//!   `request_animation_frame` only run when the test calls
+//!   [`advance_time`](MockPlatform::advance_time) or
+//!   [`run_frame`](MockPlatform::run_frame)
+//! - **Events** — synthetic dispatch with bubbling, `once` listeners,
+//!   `prevent_default` and `stop_propagation`
+//! - **Queries** — a subset of CSS selectors plus text lookup
+//!
+//! Everything else (layout, clipboard, IndexedDB, geolocation, …) is backed
+//! by simple in-memory state that tests can preset.
+//!
+//! # Example
+//!
+//! ```
+//! use tairitsu_vdom::{mock::MockPlatform, VElement, VNode, VText};
+//!
+//! let platform = MockPlatform::new();
+//! let button = VElement::new("button")
+//!     .on_click(|_| println!("clicked"))
+//!     .child(VNode::Text(VText::new("Save")));
+//! platform.mount(&VNode::Element(Box::new(button)));
+//!
+//! let button = platform.get_by_text("Save").unwrap();
+//! platform.click(&button);
+//! assert_eq!(platform.html(), "<button>Save</button>");
+//! ```
+
+mod ops;
+mod render;
+mod selector;
+mod tree;
+
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use selector::Selector;
+use tree::{MockNode, MockTree, NodeKind};
+
+use crate::{
+    events::EventWitHandle,
+    platform::{
+        DomRect, ElementHandle, EventHandle, GeoPosition, GeoPositionError, MutationRecord,
+        ResizeObserverEntry,
+    },
+    ChangeEvent, EventData, FocusEvent, InputEvent, KeyboardEvent, MouseEvent, SubmitEvent,
+};
+
+/// Interval between animation frames on the virtual clock, in milliseconds.
+pub const FRAME_MS: f64 = 16.0;
+
+/// Handle to a node in a [`MockPlatform`] tree.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MockElement(u64);
+
+impl MockElement {
+    /// Construct from a raw handle.
+    pub const fn from_raw(id: u64) -> Self {
+        Self(id)
+    }
+
+    /// The raw handle, as passed in `MouseEvent::target` and friends.
+    pub const fn as_raw(&self) -> u64 {
+        self.0
+    }
+}
+
+impl ElementHandle for MockElement {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Handle to a dispatched mock event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MockEvent(u64);
+
+impl EventHandle for MockEvent {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Context passed to the event factory of [`MockPlatform::dispatch_event`].
+#[derive(Clone, Debug)]
+pub struct EventInit {
+    /// Element the event was dispatched on
+    pub target: u64,
+    /// Element whose listener is being invoked
+    pub current_target: u64,
+    /// Handle wired to this dispatch's `prevent_default` / `stop_propagation`
+    pub handle: EventWitHandle,
+}
+
+type RepeatFn = Rc<RefCell<Box<dyn FnMut()>>>;
+type FrameFn = Box<dyn FnOnce(f64)>;
+type MediaListener = Rc<RefCell<dyn FnMut(bool)>>;
+type ScrollCallback = Rc<RefCell<dyn FnMut(f64, f64)>>;
+type ResizeCallback = Rc<RefCell<dyn FnMut(i32, i32)>>;
+type MutationCallback = Rc<RefCell<dyn FnMut(Vec<MutationRecord>)>>;
+
+enum TimerCallback {
+    Once(Box<dyn FnOnce()>),
+    Repeat(RepeatFn),
+}
+
+struct Timer {
+    id: i32,
+    due: f64,
+    seq: u64,
+    interval: Option<f64>,
+    callback: TimerCallback,
+}
+
+struct MediaQueryList {
+    query: String,
+    listeners: Vec<(u64, MediaListener)>,
+}
+
+struct ResizeObserver {
+    callback: Rc<RefCell<dyn FnMut(Vec<ResizeObserverEntry>)>>,
+    targets: Vec<u64>,
+}
+
+/// Playback state recorded for a media element by the `MediaOps` calls.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VideoState {
+    pub playing: bool,
+    pub current_time: f64,
+    pub duration: f64,
+    pub muted: bool,
+    pub volume: f64,
+}
+
+type IdbStore = std::collections::BTreeMap<String, String>;
+
+struct MockState {
+    tree: MockTree,
+    body: u64,
+    now: f64,
+    seq: u64,
+    timers: Vec<Timer>,
+    next_timer_id: i32,
+    frames: Vec<(u32, FrameFn)>,
+    next_frame_id: u32,
+    frame_due: Option<f64>,
+    next_handle: u64,
+    viewport: (i32, i32),
+    scroll_y: f64,
+    dark_mode: bool,
+    clipboard: Option<String>,
+    scroll_callbacks: Vec<ScrollCallback>,
+    resize_callbacks: Vec<ResizeCallback>,
+    media_matches: HashMap<String, bool>,
+    media_lists: HashMap<u64, MediaQueryList>,
+    resize_observers: HashMap<u64, ResizeObserver>,
+    mutation_observers: HashMap<u64, MutationCallback>,
+    canvas_log: Vec<String>,
+    videos: HashMap<u64, VideoState>,
+    idb_names: HashMap<String, u64>,
+    idb: HashMap<u64, HashMap<String, IdbStore>>,
+    blobs: HashMap<u64, Vec<u8>>,
+    geolocation: Option<Result<GeoPosition, GeoPositionError>>,
+    exec_commands: Vec<(String, Option<String>)>,
+    focused: Option<u64>,
+    fullscreen: Option<u64>,
+}
+
+impl MockState {
+    fn new() -> Self {
+        let mut tree = MockTree::new();
+        let body = tree.insert(MockNode::new(NodeKind::Element("body".to_string())));
+        Self {
+            tree,
+            body,
+            now: 0.0,
+            seq: 0,
+            timers: Vec::new(),
+            next_timer_id: 1,
+            frames: Vec::new(),
+            next_frame_id: 1,
+            frame_due: None,
+            next_handle: 1,
+            viewport: (1024, 768),
+            scroll_y: 0.0,
+            dark_mode: false,
+            clipboard: None,
+            scroll_callbacks: Vec::new(),
+            resize_callbacks: Vec::new(),
+            media_matches: HashMap::new(),
+            media_lists: HashMap::new(),
+            resize_observers: HashMap::new(),
+            mutation_observers: HashMap::new(),
+            canvas_log: Vec::new(),
+            videos: HashMap::new(),
+            idb_names: HashMap::new(),
+            idb: HashMap::new(),
+            blobs: HashMap::new(),
+            geolocation: None,
+            exec_commands: Vec::new(),
+            focused: None,
+            fullscreen: None,
+        }
+    }
+
+    fn next_handle(&mut self) -> u64 {
+        let id = self.next_handle;
+        self.next_handle += 1;
+        id
+    }
+
+    fn next_seq(&mut self) -> u64 {
+        self.seq += 1;
+        self.seq
+    }
+}
+
+thread_local! {
+    static NEXT_EVENT: Cell<u64> = const { Cell::new(1) };
+    static PREVENTED: RefCell<HashSet<u64>> = RefCell::new(HashSet::new());
+    static STOPPED: RefCell<HashSet<u64>> = RefCell::new(HashSet::new());
+}
+
+fn mock_prevent_default(handle: u64) {
+    PREVENTED.with(|p| p.borrow_mut().insert(handle));
+}
+
+fn mock_stop_propagation(handle: u64) {
+    STOPPED.with(|s| s.borrow_mut().insert(handle));
+}
+
+/// Events that do not bubble to ancestors.
+fn is_non_bubbling(event: &str) -> bool {
+    matches!(
+        event,
+        "focus" | "blur" | "mouseenter" | "mouseleave" | "load" | "error" | "scroll"
+    )
+}
+
+/// In-memory implementation of every platform trait.
+///
+/// Cloning is cheap and yields a handle to the same tree and clock.
+#[derive(Clone)]
+pub struct MockPlatform {
+    state: Rc<RefCell<MockState>>,
+}
+
+impl Default for MockPlatform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockPlatform {
+    /// Create an empty document with a `<body>` root.
+    pub fn new() -> Self {
+        Self {
+            state: Rc::new(RefCell::new(MockState::new())),
+        }
+    }
+
+    /// The `<body>` element that [`mount`](Self::mount) renders into.
+    pub fn body(&self) -> MockElement {
+        MockElement(self.state.borrow().body)
+    }
+
+    // -- Tree inspection ------------------------------------------------------
+
+    /// Serialized HTML of the body's children.
+    pub fn html(&self) -> String {
+        let state = self.state.borrow();
+        state.tree.inner_html(state.body)
+    }
+
+    /// Serialized HTML of `element` including its own tag.
+    pub fn outer_html(&self, element: &MockElement) -> String {
+        self.state.borrow().tree.outer_html(element.0)
+    }
+
+    /// Concatenated text of `element` and its descendants.
+    pub fn text_content(&self, element: &MockElement) -> String {
+        self.state.borrow().tree.text_content(element.0)
+    }
+
+    /// Replace the text of a text node, or the children of an element.
+    pub fn set_text_content(&self, node: &MockElement, text: &str) {
+        self.state.borrow_mut().tree.set_text_content(node.0, text);
+    }
+
+    /// Lowercase tag name, or `None` for text nodes and removed elements.
+    pub fn tag_name(&self, element: &MockElement) -> Option<String> {
+        self.state
+            .borrow()
+            .tree
+            .get(element.0)
+            .and_then(|n| n.tag().map(str::to_string))
+    }
+
+    /// Value of an inline style property.
+    pub fn style(&self, element: &MockElement, name: &str) -> Option<String> {
+        self.state.borrow().tree.get(element.0).and_then(|n| {
+            n.styles
+                .iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.clone())
+        })
+    }
+
+    /// Parent node, if attached.
+    pub fn parent(&self, element: &MockElement) -> Option<MockElement> {
+        self.state
+            .borrow()
+            .tree
+            .get(element.0)
+            .and_then(|n| n.parent)
+            .map(MockElement)
+    }
+
+    /// Child nodes, including text nodes.
+    pub fn children(&self, element: &MockElement) -> Vec<MockElement> {
+        self.state
+            .borrow()
+            .tree
+            .get(element.0)
+            .map(|n| n.children.iter().copied().map(MockElement).collect())
+            .unwrap_or_default()
+    }
+
+    /// Whether the node still exists in the tree.
+    pub fn contains(&self, element: &MockElement) -> bool {
+        self.state.borrow().tree.get(element.0).is_some()
+    }
+
+    /// Names of the events `element` currently listens to.
+    pub fn listeners(&self, element: &MockElement) -> Vec<String> {
+        self.state
+            .borrow()
+            .tree
+            .get(element.0)
+            .map(|n| n.listeners.iter().map(|l| l.event.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    /// All elements under the body matching `selector`, in document order.
+    ///
+    /// Supports `tag`, `*`, `#id`, `.class`, `[attr]` and `[attr=value]`
+    /// compounds joined by whitespace or commas; anything else matches
+    /// nothing.
+    pub fn query_all(&self, selector: &str) -> Vec<MockElement> {
+        let body = self.body();
+        self.query_all_in(&body, selector)
+    }
+
+    /// Elements under `root` matching `selector`, in document order.
+    pub fn query_all_in(&self, root: &MockElement, selector: &str) -> Vec<MockElement> {
+        let Some(selector) = Selector::parse(selector) else {
+            tracing::warn!("MockPlatform: unsupported selector '{}'", selector);
+            return Vec::new();
+        };
+        let state = self.state.borrow();
+        state
+            .tree
+            .descendants(root.0)
+            .into_iter()
+            .filter(|&id| selector.matches(&state.tree, id))
+            .map(MockElement)
+            .collect()
+    }
+
+    /// Innermost elements whose trimmed text content equals `text`.
+    pub fn get_all_by_text(&self, text: &str) -> Vec<MockElement> {
+        let state = self.state.borrow();
+        let matching: Vec<u64> = state
+            .tree
+            .descendants(state.body)
+            .into_iter()
+            .filter(|&id| state.tree.text_content(id).trim() == text)
+            .collect();
+        // Drop ancestors whose text only matches through a matching child.
+        matching
+            .iter()
+            .copied()
+            .filter(|&id| {
+                !matching
+                    .iter()
+                    .any(|&other| other != id && state.tree.ancestry(other).contains(&id))
+            })
+            .map(MockElement)
+            .collect()
+    }
+
+    /// First innermost element whose trimmed text content equals `text`.
+    pub fn get_by_text(&self, text: &str) -> Option<MockElement> {
+        self.get_all_by_text(text).into_iter().next()
+    }
+
+    // -- Virtual clock --------------------------------------------------------
+
+    /// Current virtual time in milliseconds.
+    pub fn now(&self) -> f64 {
+        self.state.borrow().now
+    }
+
+    /// Number of pending timeouts and intervals.
+    pub fn pending_timers(&self) -> usize {
+        self.state.borrow().timers.len()
+    }
+
+    /// Number of queued animation frame callbacks.
+    pub fn pending_frames(&self) -> usize {
+        self.state.borrow().frames.len()
+    }
+
+    /// Advance the virtual clock by `ms`, running due timers and animation
+    /// frames in time order.
+    pub fn advance_time(&self, ms: u64) {
+        let target = self.now() + ms as f64;
+        loop {
+            let (next_timer, next_frame) = {
+                let state = self.state.borrow();
+                let next_timer = state
+                    .timers
+                    .iter()
+                    .min_by(|a, b| a.due.total_cmp(&b.due).then(a.seq.cmp(&b.seq)))
+                    .map(|t| (t.id, t.due));
+                (next_timer, state.frame_due)
+            };
+
+            match (next_timer, next_frame) {
+                (Some((_, due)), Some(frame)) if frame < due && frame <= target => {
+                    self.run_frames_at(frame)
+                }
+                (Some((id, due)), _) if due <= target => self.run_timer(id, due),
+                (_, Some(frame)) if frame <= target => self.run_frames_at(frame),
+                _ => break,
+            }
+        }
+        self.state.borrow_mut().now = target;
+    }
+
+    /// Advance to the next animation frame and run its callbacks.
+    ///
+    /// Timers due before the frame run first.
+    pub fn run_frame(&self) {
+        let now = self.now();
+        let due = self
+            .state
+            .borrow()
+            .frame_due
+            .unwrap_or(((now / FRAME_MS).floor() + 1.0) * FRAME_MS);
+        self.advance_time((due - now).max(0.0) as u64);
+    }
+
+    fn run_timer(&self, id: i32, due: f64) {
+        let callback = {
+            let mut state = self.state.borrow_mut();
+            state.now = due;
+            let Some(index) = state.timers.iter().position(|t| t.id == id) else {
+                return;
+            };
+            match state.timers[index].interval {
+                Some(interval) => {
+                    let seq = state.next_seq();
+                    let timer = &mut state.timers[index];
+                    timer.due = due + interval;
+                    timer.seq = seq;
+                    match &timer.callback {
+                        TimerCallback::Repeat(f) => TimerCallback::Repeat(f.clone()),
+                        TimerCallback::Once(_) => return,
+                    }
+                }
+                None => state.timers.remove(index).callback,
+            }
+        };
+        match callback {
+            TimerCallback::Once(f) => f(),
+            TimerCallback::Repeat(f) => (f.borrow_mut())(),
+        }
+    }
+
+    fn run_frames_at(&self, due: f64) {
+        let frames = {
+            let mut state = self.state.borrow_mut();
+            state.now = due;
+            state.frame_due = None;
+            std::mem::take(&mut state.frames)
+        };
+        for (_, callback) in frames {
+            callback(due);
+        }
+    }
+
+    // -- Events ---------------------------------------------------------------
+
+    /// Dispatch an event on `target`, bubbling to its ancestors.
+    ///
+    /// `make_event` builds the event object for each invoked listener.
+    /// Returns `false` if a listener called `prevent_default`, like the DOM's
+    /// `dispatchEvent`.
+    pub fn dispatch_event(
+        &self,
+        target: &MockElement,
+        event: &str,
+        make_event: impl Fn(&EventInit) -> Box<dyn EventData>,
+    ) -> bool {
+        let handle_id = NEXT_EVENT.with(|n| n.replace(n.get() + 1));
+        let path = {
+            let state = self.state.borrow();
+            let path = state.tree.ancestry(target.0);
+            if is_non_bubbling(event) {
+                path.into_iter().take(1).collect()
+            } else {
+                path
+            }
+        };
+
+        for node in path {
+            let handlers = {
+                let mut state = self.state.borrow_mut();
+                let Some(node) = state.tree.get_mut(node) else {
+                    continue;
+                };
+                let handlers: Vec<_> = node
+                    .listeners
+                    .iter()
+                    .filter(|l| l.event == event)
+                    .map(|l| l.handler.clone())
+                    .collect();
+                node.listeners
+                    .retain(|l| l.event != event || !l.options.once);
+                handlers
+            };
+
+            let init = EventInit {
+                target: target.0,
+                current_target: node,
+                handle: EventWitHandle::with_controls(
+                    handle_id,
+                    mock_prevent_default,
+                    mock_stop_propagation,
+                ),
+            };
+            for handler in handlers {
+                (handler.borrow_mut())(make_event(&init));
+            }
+
+            if STOPPED.with(|s| s.borrow_mut().remove(&handle_id)) {
+                break;
+            }
+        }
+
+        !PREVENTED.with(|p| p.borrow_mut().remove(&handle_id))
+    }
+
+    /// Dispatch a primary-button `click`.
+    pub fn click(&self, target: &MockElement) -> bool {
+        self.dispatch_event(target, "click", |init| {
+            Box::new(
+                MouseEvent::new()
+                    .target(init.target)
+                    .current_target(init.current_target)
+                    .event_handle(init.handle.clone()),
+            )
+        })
+    }
+
+    /// Set the element's `value` and dispatch `input`.
+    pub fn input(&self, target: &MockElement, value: &str) -> bool {
+        self.set_value(target, value);
+        self.dispatch_event(target, "input", |init| {
+            Box::new(
+                InputEvent::new()
+                    .data(value)
+                    .event_handle(init.handle.clone()),
+            )
+        })
+    }
+
+    /// Set the element's `value` and dispatch `change`.
+    pub fn change(&self, target: &MockElement, value: &str) -> bool {
+        self.set_value(target, value);
+        self.dispatch_event(target, "change", |init| {
+            let mut event = ChangeEvent::new()
+                .value(value)
+                .with_event_handle(init.handle.clone());
+            event.target = Some(init.target);
+            Box::new(event)
+        })
+    }
+
+    /// Dispatch `keydown` with the given key.
+    pub fn key_down(&self, target: &MockElement, key: &str) -> bool {
+        self.dispatch_event(target, "keydown", |init| {
+            Box::new(
+                KeyboardEvent::new()
+                    .key(key)
+                    .event_handle(init.handle.clone()),
+            )
+        })
+    }
+
+    /// Move focus to `target` and dispatch `focus`.
+    pub fn focus(&self, target: &MockElement) -> bool {
+        self.state.borrow_mut().focused = Some(target.0);
+        self.dispatch_event(target, "focus", |init| {
+            Box::new(FocusEvent::new().event_handle(init.handle.clone()))
+        })
+    }
+
+    /// Clear focus from `target` and dispatch `blur`.
+    pub fn blur(&self, target: &MockElement) -> bool {
+        {
+            let mut state = self.state.borrow_mut();
+            if state.focused == Some(target.0) {
+                state.focused = None;
+            }
+        }
+        self.dispatch_event(target, "blur", |init| {
+            Box::new(FocusEvent::new().event_handle(init.handle.clone()))
+        })
+    }
+
+    /// Dispatch `submit` on a form.
+    pub fn submit(&self, target: &MockElement) -> bool {
+        self.dispatch_event(target, "submit", |init| {
+            let mut event = SubmitEvent::new().with_event_handle(init.handle.clone());
+            event.target = Some(init.target);
+            Box::new(event)
+        })
+    }
+
+    /// Currently focused element.
+    pub fn focused(&self) -> Option<MockElement> {
+        self.state.borrow().focused.map(MockElement)
+    }
+
+    fn set_value(&self, target: &MockElement, value: &str) {
+        if let Some(node) = self.state.borrow_mut().tree.get_mut(target.0) {
+            node.attributes
+                .insert("value".to_string(), value.to_string());
+        }
+    }
+
+    // -- Environment presets --------------------------------------------------
+
+    /// Set the viewport size and notify `on_resize` callbacks.
+    pub fn set_viewport(&self, width: i32, height: i32) {
+        let callbacks = {
+            let mut state = self.state.borrow_mut();
+            state.viewport = (width, height);
+            state.resize_callbacks.clone()
+        };
+        for callback in callbacks {
+            (callback.borrow_mut())(width, height);
+        }
+    }
+
+    /// Set the window scroll position and notify `on_scroll` callbacks.
+    pub fn set_scroll_y(&self, y: f64) {
+        let callbacks = {
+            let mut state = self.state.borrow_mut();
+            state.scroll_y = y;
+            state.scroll_callbacks.clone()
+        };
+        for callback in callbacks {
+            (callback.borrow_mut())(0.0, y);
+        }
+    }
+
+    /// Set the layout rectangle of `element` and notify resize observers.
+    pub fn set_rect(&self, element: &MockElement, rect: DomRect) {
+        let observers: Vec<_> = {
+            let mut state = self.state.borrow_mut();
+            if let Some(node) = state.tree.get_mut(element.0) {
+                node.rect = rect;
+            }
+            state
+                .resize_observers
+                .values()
+                .filter(|o| o.targets.contains(&element.0))
+                .map(|o| o.callback.clone())
+                .collect()
+        };
+        for callback in observers {
+            (callback.borrow_mut())(vec![resize_entry(element.0, rect)]);
+        }
+    }
+
+    /// Set whether a media query matches and notify its listeners.
+    pub fn set_media_matches(&self, query: &str, matches: bool) {
+        let listeners: Vec<_> = {
+            let mut state = self.state.borrow_mut();
+            state.media_matches.insert(query.to_string(), matches);
+            state
+                .media_lists
+                .values()
+                .filter(|l| l.query == query)
+                .flat_map(|l| l.listeners.iter().map(|(_, f)| f.clone()))
+                .collect()
+        };
+        for listener in listeners {
+            (listener.borrow_mut())(matches);
+        }
+    }
+
+    /// Set the result of `prefers_dark_mode`.
+    pub fn set_dark_mode(&self, dark: bool) {
+        self.state.borrow_mut().dark_mode = dark;
+    }
+
+    /// Set the result of geolocation requests.
+    pub fn set_geolocation(&self, result: Result<GeoPosition, GeoPositionError>) {
+        self.state.borrow_mut().geolocation = Some(result);
+    }
+
+    /// Register a blob for the `FileOps` readers and return its handle.
+    pub fn add_blob(&self, bytes: impl Into<Vec<u8>>) -> u64 {
+        let mut state = self.state.borrow_mut();
+        let id = state.next_handle();
+        state.blobs.insert(id, bytes.into());
+        id
+    }
+
+    /// Current clipboard contents.
+    pub fn clipboard(&self) -> Option<String> {
+        self.state.borrow().clipboard.clone()
+    }
+
+    /// Canvas operations recorded so far, e.g. `fill_rect 0 0 10 10`.
+    pub fn canvas_log(&self) -> Vec<String> {
+        self.state.borrow().canvas_log.clone()
+    }
+
+    /// Commands passed to `exec_command`.
+    pub fn exec_commands(&self) -> Vec<(String, Option<String>)> {
+        self.state.borrow().exec_commands.clone()
+    }
+
+    /// Playback state of a media element.
+    pub fn video_state(&self, element: &MockElement) -> VideoState {
+        self.state
+            .borrow()
+            .videos
+            .get(&element.0)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Element passed to the last `request_fullscreen`.
+    pub fn fullscreen_element(&self) -> Option<MockElement> {
+        self.state.borrow().fullscreen.map(MockElement)
+    }
+}
+
+fn resize_entry(target: u64, rect: DomRect) -> ResizeObserverEntry {
+    use crate::platform::ResizeObserverSize;
+    ResizeObserverEntry {
+        target,
+        content_rect: rect,
+        border_box_size: vec![ResizeObserverSize {
+            inline_size: rect.width,
+            block_size: rect.height,
+        }],
+        content_box_size: vec![ResizeObserverSize {
+            inline_size: rect.width,
+            block_size: rect.height,
+        }],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DomOps, TimerOps};
+
+    #[test]
+    fn test_timers_run_in_due_order() {
+        let platform = MockPlatform::new();
+        let log = Rc::new(RefCell::new(Vec::new()));
+
+        for (label, ms) in [("b", 20), ("a", 10), ("c", 20)] {
+            let log = log.clone();
+            platform.set_timeout(Box::new(move || log.borrow_mut().push(label)), ms);
+        }
+        let cancelled = {
+            let log = log.clone();
+            platform.set_timeout(Box::new(move || log.borrow_mut().push("x")), 5)
+        };
+        platform.clear_timeout(cancelled);
+
+        platform.advance_time(15);
+        assert_eq!(*log.borrow(), vec!["a"]);
+        platform.advance_time(5);
+        assert_eq!(*log.borrow(), vec!["a", "b", "c"]);
+        assert_eq!(platform.now(), 20.0);
+        assert_eq!(platform.pending_timers(), 0);
+    }
+
+    #[test]
+    fn test_interval_and_animation_frames() {
+        let platform = MockPlatform::new();
+        let ticks = Rc::new(Cell::new(0));
+        let frames = Rc::new(RefCell::new(Vec::new()));
+
+        let interval = {
+            let ticks = ticks.clone();
+            platform.set_interval(Box::new(move || ticks.set(ticks.get() + 1)), 10)
+        };
+        {
+            let frames = frames.clone();
+            platform.request_animation_frame(Box::new(move |t| frames.borrow_mut().push(t)));
+        }
+
+        platform.run_frame();
+        assert_eq!(*frames.borrow(), vec![FRAME_MS]);
+        assert_eq!(ticks.get(), 1);
+
+        platform.advance_time(34);
+        assert_eq!(ticks.get(), 5);
+        platform.clear_interval(interval);
+        platform.advance_time(100);
+        assert_eq!(ticks.get(), 5);
+    }
+
+    #[test]
+    fn test_dispatch_bubbles_and_honours_stop_propagation() {
+        let platform = MockPlatform::new();
+        let outer = platform.create_element("div");
+        let inner = platform.create_element("button");
+        platform.append_child(&platform.body(), &outer);
+        platform.append_child(&outer, &inner);
+
+        let log = Rc::new(RefCell::new(Vec::new()));
+        {
+            let log = log.clone();
+            platform.add_event_listener(
+                &outer,
+                "click",
+                Box::new(move |_| log.borrow_mut().push("outer")),
+            );
+        }
+        {
+            let log = log.clone();
+            platform.add_event_listener(
+                &inner,
+                "click",
+                Box::new(move |e| {
+                    log.borrow_mut().push("inner");
+                    let e = e.as_any().downcast_ref::<MouseEvent>().unwrap();
+                    if e.target == e.current_target {
+                        e.prevent_default();
+                    }
+                }),
+            );
+        }
+
+        assert!(!platform.click(&inner));
+        assert_eq!(*log.borrow(), vec!["inner", "outer"]);
+
+        platform.remove_event_listener(&outer, "click");
+        {
+            let log = log.clone();
+            platform.add_event_listener(
+                &outer,
+                "click",
+                Box::new(move |_| log.borrow_mut().push("never")),
+            );
+        }
+        platform.add_event_listener(
+            &inner,
+            "click",
+            Box::new(|e| {
+                e.as_any()
+                    .downcast_ref::<MouseEvent>()
+                    .unwrap()
+                    .stop_propagation()
+            }),
+        );
+        log.borrow_mut().clear();
+        platform.click(&inner);
+        assert_eq!(*log.borrow(), vec!["inner"]);
+    }
+
+    #[test]
+    fn test_once_listener_fires_once() {
+        let platform = MockPlatform::new();
+        let button = platform.create_element("button");
+        platform.append_child(&platform.body(), &button);
+
+        let count = Rc::new(Cell::new(0));
+        let counter = count.clone();
+        platform.add_event_listener_with_options(
+            &button,
+            "click",
+            Box::new(move |_| counter.set(counter.get() + 1)),
+            crate::ListenerOptions {
+                once: true,
+                ..Default::default()
+            },
+        );
+
+        platform.click(&button);
+        platform.click(&button);
+        assert_eq!(count.get(), 1);
+    }
+}
@@ -0,0 +1,939 @@
+//! Platform trait implementations for [`MockPlatform`].
+//!
+//! Asynchronous completions (clipboard, file readers, IndexedDB,
+//! geolocation) are queued as zero-delay timers, so they run on the next
+//! [`advance_time`](MockPlatform::advance_time) call just like a browser task.
+
+use std::{cell::RefCell, rc::Rc};
+
+use super::{
+    resize_entry,
+    tree::{Listener, MockNode, NodeKind},
+    MediaQueryList, MockElement, MockEvent, MockPlatform, ResizeObserver, Selector, Timer,
+    TimerCallback,
+};
+use crate::{
+    platform::{
+        CanvasContext, CanvasOps, ClipboardOps, ContentEditableOps, ContentEditableState, DomOps,
+        DomRect, FileOps, GeoOps, GeoPosition, GeoPositionError, IdbOps, LayoutOps,
+        ListenerOptions, MediaOps, MediaQueryOps, MutationObserverInit, MutationRecord,
+        ObserverOps, QueryOps, ResizeObserverEntry, ScrollOps, TimerOps,
+    },
+    EventData,
+};
+
+impl MockPlatform {
+    /// Run `f` on the next tick of the virtual clock.
+    fn defer(&self, f: impl FnOnce() + 'static) {
+        self.set_timeout(Box::new(f), 0);
+    }
+
+    fn find(&self, root: u64, selector: &str) -> Option<MockElement> {
+        self.query_all_in(&MockElement(root), selector)
+            .into_iter()
+            .next()
+    }
+}
+
+impl DomOps for MockPlatform {
+    type Element = MockElement;
+    type Event = MockEvent;
+
+    fn create_element(&self, tag: &str) -> MockElement {
+        let node = MockNode::new(NodeKind::Element(tag.to_ascii_lowercase()));
+        MockElement(self.state.borrow_mut().tree.insert(node))
+    }
+
+    fn create_text_node(&self, text: &str) -> MockElement {
+        let node = MockNode::new(NodeKind::Text(text.to_string()));
+        MockElement(self.state.borrow_mut().tree.insert(node))
+    }
+
+    fn append_child(&self, parent: &MockElement, child: &MockElement) {
+        self.state
+            .borrow_mut()
+            .tree
+            .insert_before(parent.0, child.0, None);
+    }
+
+    fn remove_child(&self, parent: &MockElement, child: &MockElement) {
+        self.state.borrow_mut().tree.remove(parent.0, child.0);
+    }
+
+    fn set_attribute(&self, element: &MockElement, name: &str, value: &str) {
+        let mut state = self.state.borrow_mut();
+        let Some(node) = state.tree.get_mut(element.0) else {
+            return;
+        };
+        if name == "style" {
+            node.styles.clear();
+            for decl in value.split(';') {
+                if let Some((k, v)) = decl.split_once(':') {
+                    node.set_style(k.trim(), v.trim());
+                }
+            }
+        } else {
+            node.attributes.insert(name.to_string(), value.to_string());
+        }
+    }
+
+    fn remove_attribute(&self, element: &MockElement, name: &str) {
+        if let Some(node) = self.state.borrow_mut().tree.get_mut(element.0) {
+            if name == "style" {
+                node.styles.clear();
+            } else {
+                node.attributes.remove(name);
+            }
+        }
+    }
+
+    fn set_style(&self, element: &MockElement, name: &str, value: &str) {
+        if let Some(node) = self.state.borrow_mut().tree.get_mut(element.0) {
+            if value.is_empty() {
+                node.styles.retain(|(n, _)| n != name);
+            } else {
+                node.set_style(name, value);
+            }
+        }
+    }
+
+    fn set_class(&self, element: &MockElement, class: &str) {
+        self.set_attribute(element, "class", class);
+    }
+
+    fn add_event_listener(
+        &self,
+        element: &MockElement,
+        event: &str,
+        handler: Box<dyn FnMut(Box<dyn EventData>)>,
+    ) {
+        self.add_event_listener_with_options(element, event, handler, ListenerOptions::default());
+    }
+
+    fn remove_event_listener(&self, element: &MockElement, event: &str) {
+        if let Some(node) = self.state.borrow_mut().tree.get_mut(element.0) {
+            node.listeners.retain(|l| l.event != event);
+        }
+    }
+
+    fn add_event_listener_with_options(
+        &self,
+        element: &MockElement,
+        event: &str,
+        handler: Box<dyn FnMut(Box<dyn EventData>)>,
+        options: ListenerOptions,
+    ) {
+        if let Some(node) = self.state.borrow_mut().tree.get_mut(element.0) {
+            node.listeners.push(Listener {
+                event: event.to_string(),
+                handler: Rc::new(RefCell::new(handler)),
+                options,
+            });
+        }
+    }
+
+    fn get_attribute(&self, element: &MockElement, name: &str) -> Option<String> {
+        let state = self.state.borrow();
+        let node = state.tree.get(element.0)?;
+        if name == "style" {
+            let style = node.style_attr();
+            return (!style.is_empty()).then_some(style);
+        }
+        node.attributes.get(name).cloned()
+    }
+
+    fn class_list_add(&self, element: &MockElement, tokens: &[&str]) {
+        if let Some(node) = self.state.borrow_mut().tree.get_mut(element.0) {
+            let mut classes: Vec<String> = node.classes().map(str::to_string).collect();
+            for token in tokens {
+                if !classes.iter().any(|c| c == token) {
+                    classes.push(token.to_string());
+                }
+            }
+            node.attributes
+                .insert("class".to_string(), classes.join(" "));
+        }
+    }
+
+    fn class_list_remove(&self, element: &MockElement, tokens: &[&str]) {
+        if let Some(node) = self.state.borrow_mut().tree.get_mut(element.0) {
+            let classes: Vec<&str> = node.classes().filter(|c| !tokens.contains(c)).collect();
+            let classes = classes.join(" ");
+            node.attributes.insert("class".to_string(), classes);
+        }
+    }
+
+    fn class_list_contains(&self, element: &MockElement, token: &str) -> bool {
+        self.state
+            .borrow()
+            .tree
+            .get(element.0)
+            .is_some_and(|n| n.classes().any(|c| c == token))
+    }
+
+    fn first_child(&self, element: &MockElement) -> Option<MockElement> {
+        self.state
+            .borrow()
+            .tree
+            .get(element.0)
+            .and_then(|n| n.children.first().copied())
+            .map(MockElement)
+    }
+
+    fn insert_before(
+        &self,
+        parent: &MockElement,
+        new_node: &MockElement,
+        reference_node: Option<&MockElement>,
+    ) {
+        self.state.borrow_mut().tree.insert_before(
+            parent.0,
+            new_node.0,
+            reference_node.map(|r| r.0),
+        );
+    }
+
+    fn query_selector_on(&self, element: &MockElement, selector: &str) -> Option<MockElement> {
+        self.find(element.0, selector)
+    }
+
+    fn get_inner_html(&self, element: &MockElement) -> String {
+        self.state.borrow().tree.inner_html(element.0)
+    }
+
+    fn set_inner_html(&self, element: &MockElement, html: String) {
+        let mut state = self.state.borrow_mut();
+        state.tree.clear_children(element.0);
+        if let Some(node) = state.tree.get_mut(element.0) {
+            node.inner_html = Some(html);
+        }
+    }
+}
+
+impl TimerOps for MockPlatform {
+    fn set_timeout(&self, callback: Box<dyn FnOnce()>, ms: i32) -> i32 {
+        let mut state = self.state.borrow_mut();
+        let id = state.next_timer_id;
+        state.next_timer_id += 1;
+        let seq = state.next_seq();
+        let due = state.now + ms.max(0) as f64;
+        state.timers.push(Timer {
+            id,
+            due,
+            seq,
+            interval: None,
+            callback: TimerCallback::Once(callback),
+        });
+        id
+    }
+
+    fn clear_timeout(&self, id: i32) {
+        self.state.borrow_mut().timers.retain(|t| t.id != id);
+    }
+
+    fn set_interval(&self, callback: Box<dyn FnMut()>, ms: i32) -> i32 {
+        let mut state = self.state.borrow_mut();
+        let id = state.next_timer_id;
+        state.next_timer_id += 1;
+        let seq = state.next_seq();
+        // Browsers clamp zero-delay intervals; avoid spinning forever.
+        let interval = ms.max(1) as f64;
+        let due = state.now + interval;
+        state.timers.push(Timer {
+            id,
+            due,
+            seq,
+            interval: Some(interval),
+            callback: TimerCallback::Repeat(Rc::new(RefCell::new(callback))),
+        });
+        id
+    }
+
+    fn clear_interval(&self, id: i32) {
+        self.clear_timeout(id);
+    }
+
+    fn request_animation_frame(&self, callback: Box<dyn FnOnce(f64)>) -> u32 {
+        let mut state = self.state.borrow_mut();
+        let id = state.next_frame_id;
+        state.next_frame_id += 1;
+        if state.frame_due.is_none() {
+            state.frame_due = Some(((state.now / super::FRAME_MS).floor() + 1.0) * super::FRAME_MS);
+        }
+        state.frames.push((id, callback));
+        id
+    }
+
+    fn cancel_animation_frame(&self, id: u32) {
+        let mut state = self.state.borrow_mut();
+        state.frames.retain(|(frame, _)| *frame != id);
+        if state.frames.is_empty() {
+            state.frame_due = None;
+        }
+    }
+}
+
+impl LayoutOps for MockPlatform {
+    fn get_bounding_client_rect(&self, element: &MockElement) -> DomRect {
+        let state = self.state.borrow();
+        let mut rect = state
+            .tree
+            .get(element.0)
+            .map(|n| n.rect)
+            .unwrap_or(DomRect {
+                x: 0.0,
+                y: 0.0,
+                width: 0.0,
+                height: 0.0,
+            });
+        rect.y -= state.scroll_y;
+        rect
+    }
+
+    fn inner_width(&self) -> i32 {
+        self.state.borrow().viewport.0
+    }
+
+    fn inner_height(&self) -> i32 {
+        self.state.borrow().viewport.1
+    }
+
+    fn get_element_scroll_top(&self, element: &MockElement) -> f64 {
+        self.state
+            .borrow()
+            .tree
+            .get(element.0)
+            .map_or(0.0, |n| n.scroll_top)
+    }
+
+    fn set_element_scroll_top(&self, element: &MockElement, value: f64) {
+        if let Some(node) = self.state.borrow_mut().tree.get_mut(element.0) {
+            node.scroll_top = value.max(0.0);
+        }
+    }
+
+    fn get_element_scroll_height(&self, element: &MockElement) -> i32 {
+        self.state
+            .borrow()
+            .tree
+            .get(element.0)
+            .map_or(0, |n| n.scroll_height.max(n.rect.height as i32))
+    }
+
+    fn get_element_client_height(&self, element: &MockElement) -> i32 {
+        self.state
+            .borrow()
+            .tree
+            .get(element.0)
+            .map_or(0, |n| n.rect.height as i32)
+    }
+
+    fn get_element_client_width(&self, element: &MockElement) -> i32 {
+        self.state
+            .borrow()
+            .tree
+            .get(element.0)
+            .map_or(0, |n| n.rect.width as i32)
+    }
+}
+
+impl ObserverOps for MockPlatform {
+    fn create_resize_observer(&self, callback: Box<dyn FnMut(Vec<ResizeObserverEntry>)>) -> u64 {
+        let mut state = self.state.borrow_mut();
+        let id = state.next_handle();
+        state.resize_observers.insert(
+            id,
+            ResizeObserver {
+                callback: Rc::new(RefCell::new(callback)),
+                targets: Vec::new(),
+            },
+        );
+        id
+    }
+
+    fn observe_resize(&self, observer: u64, element: &MockElement) {
+        // Like the browser, report the initial size once observation starts.
+        let (callback, rect) = {
+            let mut state = self.state.borrow_mut();
+            let rect = state.tree.get(element.0).map(|n| n.rect);
+            let Some(entry) = state.resize_observers.get_mut(&observer) else {
+                return;
+            };
+            if !entry.targets.contains(&element.0) {
+                entry.targets.push(element.0);
+            }
+            (entry.callback.clone(), rect)
+        };
+        if let Some(rect) = rect {
+            let target = element.0;
+            self.defer(move || (callback.borrow_mut())(vec![resize_entry(target, rect)]));
+        }
+    }
+
+    fn unobserve_resize(&self, observer: u64, element: &MockElement) {
+        if let Some(entry) = self.state.borrow_mut().resize_observers.get_mut(&observer) {
+            entry.targets.retain(|&t| t != element.0);
+        }
+    }
+
+    fn disconnect_resize(&self, observer: u64) {
+        self.state.borrow_mut().resize_observers.remove(&observer);
+    }
+
+    fn create_mutation_observer(&self, callback: Box<dyn FnMut(Vec<MutationRecord>)>) -> u64 {
+        let mut state = self.state.borrow_mut();
+        let id = state.next_handle();
+        state
+            .mutation_observers
+            .insert(id, Rc::new(RefCell::new(callback)));
+        id
+    }
+
+    fn observe_mutations(
+        &self,
+        _observer: u64,
+        _element: &MockElement,
+        _options: Option<MutationObserverInit>,
+    ) {
+        // Mutation records are not generated by the mock tree.
+    }
+
+    fn disconnect_mutation(&self, observer: u64) {
+        self.state.borrow_mut().mutation_observers.remove(&observer);
+    }
+}
+
+impl MediaQueryOps for MockPlatform {
+    fn match_media(&self, query: &str) -> u64 {
+        let mut state = self.state.borrow_mut();
+        let id = state.next_handle();
+        state.media_lists.insert(
+            id,
+            MediaQueryList {
+                query: query.to_string(),
+                listeners: Vec::new(),
+            },
+        );
+        id
+    }
+
+    fn media_query_list_get_media(&self, list: u64) -> String {
+        self.state
+            .borrow()
+            .media_lists
+            .get(&list)
+            .map(|l| l.query.clone())
+            .unwrap_or_default()
+    }
+
+    fn media_query_list_get_matches(&self, list: u64) -> bool {
+        let state = self.state.borrow();
+        state
+            .media_lists
+            .get(&list)
+            .and_then(|l| state.media_matches.get(&l.query))
+            .copied()
+            .unwrap_or(false)
+    }
+
+    fn media_query_list_add_listener(&self, list: u64, callback: Box<dyn FnMut(bool)>) -> u64 {
+        let mut state = self.state.borrow_mut();
+        let id = state.next_handle();
+        if let Some(entry) = state.media_lists.get_mut(&list) {
+            entry.listeners.push((id, Rc::new(RefCell::new(callback))));
+        }
+        id
+    }
+
+    fn media_query_list_remove_listener(&self, list: u64, listener_id: u64) {
+        if let Some(entry) = self.state.borrow_mut().media_lists.get_mut(&list) {
+            entry.listeners.retain(|(id, _)| *id != listener_id);
+        }
+    }
+}
+
+impl ClipboardOps for MockPlatform {
+    fn copy_to_clipboard(&self, text: &str) -> bool {
+        self.state.borrow_mut().clipboard = Some(text.to_string());
+        true
+    }
+
+    fn read_clipboard(&self) -> Option<String> {
+        self.state.borrow().clipboard.clone()
+    }
+
+    fn clipboard_write_text_async(
+        &self,
+        text: &str,
+        on_complete: Box<dyn FnOnce(Result<(), String>)>,
+    ) {
+        self.copy_to_clipboard(text);
+        self.defer(move || on_complete(Ok(())));
+    }
+
+    fn clipboard_read_text_async(&self, on_complete: Box<dyn FnOnce(Result<String, String>)>) {
+        let text = self.read_clipboard();
+        self.defer(move || on_complete(text.ok_or_else(|| "clipboard is empty".to_string())));
+    }
+}
+
+impl ContentEditableOps for MockPlatform {
+    fn get_contenteditable_state(&self, element: &MockElement) -> Option<ContentEditableState> {
+        let state = self.state.borrow();
+        let node = state.tree.get(element.0)?;
+        Some(ContentEditableState {
+            editable: node.content_editable,
+            focused: state.focused == Some(element.0),
+        })
+    }
+
+    fn exec_command(&self, command: &str, value: Option<&str>) -> bool {
+        self.state
+            .borrow_mut()
+            .exec_commands
+            .push((command.to_string(), value.map(str::to_string)));
+        true
+    }
+
+    fn get_selection_start(&self, element: &MockElement) -> Option<u32> {
+        self.get_selection_end(element).map(|_| 0)
+    }
+
+    fn get_selection_end(&self, element: &MockElement) -> Option<u32> {
+        let state = self.state.borrow();
+        let node = state.tree.get(element.0)?;
+        let len = match node.attributes.get("value") {
+            Some(value) => value.chars().count(),
+            None => state.tree.text_content(element.0).chars().count(),
+        };
+        Some(len as u32)
+    }
+
+    fn set_content_editable(&self, element: &MockElement, editable: bool) {
+        if let Some(node) = self.state.borrow_mut().tree.get_mut(element.0) {
+            node.content_editable = editable;
+        }
+    }
+}
+
+impl ScrollOps for MockPlatform {
+    fn get_scroll_y(&self) -> f64 {
+        self.state.borrow().scroll_y
+    }
+
+    fn scroll_to(&self, top: f64, _behavior: &str) {
+        self.set_scroll_y(top.max(0.0));
+    }
+
+    fn on_scroll(&self, callback: Box<dyn FnMut(f64, f64)>) {
+        self.state
+            .borrow_mut()
+            .scroll_callbacks
+            .push(Rc::new(RefCell::new(callback)));
+    }
+
+    fn on_resize(&self, callback: Box<dyn FnMut(i32, i32)>) {
+        self.state
+            .borrow_mut()
+            .resize_callbacks
+            .push(Rc::new(RefCell::new(callback)));
+    }
+
+    fn prefers_dark_mode(&self) -> bool {
+        self.state.borrow().dark_mode
+    }
+
+    fn request_fullscreen(&self, element: &MockElement) {
+        self.state.borrow_mut().fullscreen = Some(element.0);
+    }
+
+    fn get_scroll_top_from_point(&self, x: i32, y: i32) -> f64 {
+        self.element_from_point(x, y)
+            .map_or(0.0, |el| self.get_element_scroll_top(&el))
+    }
+
+    fn get_scroll_top_by_selector(&self, selector: &str) -> f64 {
+        self.query_selector(selector)
+            .map_or(0.0, |el| self.get_element_scroll_top(&el))
+    }
+
+    fn get_target_element_from_event(&self, client_x: i32, client_y: i32) -> Option<MockElement> {
+        self.element_from_point(client_x, client_y)
+    }
+}
+
+impl QueryOps for MockPlatform {
+    fn get_element_by_id(&self, id: &str) -> Option<MockElement> {
+        let state = self.state.borrow();
+        state
+            .tree
+            .descendants(state.body)
+            .into_iter()
+            .find(|&node| {
+                state
+                    .tree
+                    .get(node)
+                    .and_then(|n| n.attributes.get("id"))
+                    .is_some_and(|v| v == id)
+            })
+            .map(MockElement)
+    }
+
+    fn query_selector(&self, selector: &str) -> Option<MockElement> {
+        let body = self.state.borrow().body;
+        self.find(body, selector)
+    }
+
+    fn query_selector_all(&self, selector: &str) -> Vec<MockElement> {
+        self.query_all(selector)
+    }
+
+    /// Topmost (last in document order) element whose rectangle contains the
+    /// point.
+    fn element_from_point(&self, x: i32, y: i32) -> Option<MockElement> {
+        let state = self.state.borrow();
+        let (x, y) = (x as f64, y as f64 + state.scroll_y);
+        state
+            .tree
+            .descendants(state.body)
+            .into_iter()
+            .rev()
+            .find(|&id| {
+                state.tree.get(id).is_some_and(|n| {
+                    let r = n.rect;
+                    r.width > 0.0
+                        && r.height > 0.0
+                        && x >= r.x
+                        && x < r.x + r.width
+                        && y >= r.y
+                        && y < r.y + r.height
+                })
+            })
+            .map(MockElement)
+    }
+
+    fn element_closest(&self, element: &MockElement, selector: &str) -> Option<MockElement> {
+        let selector = Selector::parse(selector)?;
+        let state = self.state.borrow();
+        state
+            .tree
+            .ancestry(element.0)
+            .into_iter()
+            .find(|&id| selector.matches(&state.tree, id))
+            .map(MockElement)
+    }
+
+    fn get_element_rect_by_id(&self, id: &str) -> Option<DomRect> {
+        self.get_element_by_id(id)
+            .map(|el| self.get_bounding_client_rect(&el))
+    }
+
+    fn get_bounding_rect_by_class(
+        &self,
+        class_name: &str,
+        element: &MockElement,
+    ) -> Option<DomRect> {
+        let found = self.find(element.0, &format!(".{}", class_name))?;
+        Some(self.get_bounding_client_rect(&found))
+    }
+}
+
+impl CanvasOps for MockPlatform {
+    fn get_canvas_context(
+        &self,
+        element: &MockElement,
+        context_type: &str,
+    ) -> Option<CanvasContext> {
+        let is_canvas = self
+            .state
+            .borrow()
+            .tree
+            .get(element.0)
+            .is_some_and(|n| n.tag() == Some("canvas"));
+        (is_canvas && context_type == "2d").then_some(element.0)
+    }
+
+    fn canvas_set_fill_style(&self, ctx: CanvasContext, color: &str) {
+        self.state
+            .borrow_mut()
+            .canvas_log
+            .push(format!("{} fill_style {}", ctx, color));
+    }
+
+    fn canvas_fill_rect(&self, ctx: CanvasContext, x: f64, y: f64, w: f64, h: f64) {
+        self.state
+            .borrow_mut()
+            .canvas_log
+            .push(format!("{} fill_rect {} {} {} {}", ctx, x, y, w, h));
+    }
+
+    fn canvas_clear_rect(&self, ctx: CanvasContext, x: f64, y: f64, w: f64, h: f64) {
+        self.state
+            .borrow_mut()
+            .canvas_log
+            .push(format!("{} clear_rect {} {} {} {}", ctx, x, y, w, h));
+    }
+
+    fn draw_qrcode_on_canvas_by_id(
+        &self,
+        canvas_id: &str,
+        matrix: &[Vec<bool>],
+        modules: u64,
+        color: &str,
+        background: &str,
+    ) -> bool {
+        if self.get_element_by_id(canvas_id).is_none() {
+            return false;
+        }
+        let dark = matrix.iter().flatten().filter(|&&m| m).count();
+        self.state.borrow_mut().canvas_log.push(format!(
+            "qrcode #{} {} modules {} dark {} on {}",
+            canvas_id, modules, dark, color, background
+        ));
+        true
+    }
+}
+
+impl MediaOps for MockPlatform {
+    fn video_play(&self, element: &MockElement) {
+        self.state
+            .borrow_mut()
+            .videos
+            .entry(element.0)
+            .or_default()
+            .playing = true;
+    }
+
+    fn video_pause(&self, element: &MockElement) {
+        self.state
+            .borrow_mut()
+            .videos
+            .entry(element.0)
+            .or_default()
+            .playing = false;
+    }
+
+    fn video_get_current_time(&self, element: &MockElement) -> f64 {
+        self.video_state(element).current_time
+    }
+
+    fn video_get_duration(&self, element: &MockElement) -> f64 {
+        self.video_state(element).duration
+    }
+
+    fn video_seek(&self, element: &MockElement, time: f64) {
+        self.state
+            .borrow_mut()
+            .videos
+            .entry(element.0)
+            .or_default()
+            .current_time = time;
+    }
+
+    fn video_set_muted(&self, element: &MockElement, muted: bool) {
+        self.state
+            .borrow_mut()
+            .videos
+            .entry(element.0)
+            .or_default()
+            .muted = muted;
+    }
+
+    fn video_set_volume(&self, element: &MockElement, volume: f64) {
+        self.state
+            .borrow_mut()
+            .videos
+            .entry(element.0)
+            .or_default()
+            .volume = volume.clamp(0.0, 1.0);
+    }
+
+    fn create_audio_context(&self) -> u64 {
+        self.state.borrow_mut().next_handle()
+    }
+
+    fn create_analyser_node(&self, _audio_context: u64) -> u64 {
+        self.state.borrow_mut().next_handle()
+    }
+
+    fn create_media_element_source(&self, _audio_context: u64, _element: u64) -> u64 {
+        self.state.borrow_mut().next_handle()
+    }
+
+    fn analyser_node_get_frequency_data(&self, _analyser: u64) -> Vec<f32> {
+        Vec::new()
+    }
+
+    fn analyser_node_get_time_domain_data(&self, _analyser: u64) -> Vec<f32> {
+        Vec::new()
+    }
+}
+
+impl GeoOps for MockPlatform {
+    fn get_current_position(
+        &self,
+        on_success: Box<dyn FnOnce(GeoPosition)>,
+        on_error: Box<dyn FnOnce(GeoPositionError)>,
+        _enable_high_accuracy: bool,
+        _timeout: u32,
+        _maximum_age: u32,
+    ) {
+        let result = self.state.borrow().geolocation.clone().unwrap_or_else(|| {
+            Err(GeoPositionError {
+                code: 2,
+                message: "position unavailable".to_string(),
+            })
+        });
+        self.defer(move || match result {
+            Ok(position) => on_success(position),
+            Err(error) => on_error(error),
+        });
+    }
+}
+
+impl FileOps for MockPlatform {
+    fn file_reader_sync_read_as_text(
+        &self,
+        blob: u64,
+        _encoding: Option<&str>,
+    ) -> Result<String, String> {
+        let bytes = self.file_reader_sync_read_as_array_buffer(blob)?;
+        String::from_utf8(bytes).map_err(|e| e.to_string())
+    }
+
+    fn file_reader_sync_read_as_array_buffer(&self, blob: u64) -> Result<Vec<u8>, String> {
+        self.state
+            .borrow()
+            .blobs
+            .get(&blob)
+            .cloned()
+            .ok_or_else(|| format!("unknown blob {}", blob))
+    }
+
+    fn file_reader_read_as_text(
+        &self,
+        blob: u64,
+        encoding: Option<&str>,
+        on_complete: Box<dyn FnOnce(Result<String, String>)>,
+    ) {
+        let result = self.file_reader_sync_read_as_text(blob, encoding);
+        self.defer(move || on_complete(result));
+    }
+
+    fn file_reader_read_as_array_buffer(
+        &self,
+        blob: u64,
+        on_complete: Box<dyn FnOnce(Result<Vec<u8>, String>)>,
+    ) {
+        let result = self.file_reader_sync_read_as_array_buffer(blob);
+        self.defer(move || on_complete(result));
+    }
+}
+
+impl MockPlatform {
+    fn with_idb_store<R>(
+        &self,
+        db: u64,
+        store_name: &str,
+        f: impl FnOnce(&mut super::IdbStore) -> R,
+    ) -> Result<R, String> {
+        let mut state = self.state.borrow_mut();
+        let stores = state
+            .idb
+            .get_mut(&db)
+            .ok_or_else(|| format!("unknown database {}", db))?;
+        // Like a browser, only stores created when opening the database exist
+        let store = stores
+            .get_mut(store_name)
+            .ok_or_else(|| format!("NotFoundError: object store '{}' not found", store_name))?;
+        Ok(f(store))
+    }
+}
+
+impl IdbOps for MockPlatform {
+    fn idb_open(
+        &self,
+        name: &str,
+        _version: Option<u64>,
+        store_name: Option<&str>,
+        on_complete: Box<dyn FnOnce(Result<u64, String>)>,
+    ) -> u64 {
+        let db = {
+            let mut state = self.state.borrow_mut();
+            let db = match state.idb_names.get(name) {
+                Some(&db) => db,
+                None => {
+                    let db = state.next_handle();
+                    state.idb_names.insert(name.to_string(), db);
+                    state.idb.insert(db, Default::default());
+                    db
+                }
+            };
+            if let (Some(store_name), Some(stores)) = (store_name, state.idb.get_mut(&db)) {
+                stores.entry(store_name.to_string()).or_default();
+            }
+            db
+        };
+        self.defer(move || on_complete(Ok(db)));
+        db
+    }
+
+    fn idb_put(
+        &self,
+        db: u64,
+        store_name: &str,
+        value: &str,
+        key: Option<&str>,
+        on_complete: Box<dyn FnOnce(Result<(), String>)>,
+    ) {
+        let result = self.with_idb_store(db, store_name, |store| {
+            let key = key.map_or_else(|| (store.len() + 1).to_string(), str::to_string);
+            store.insert(key, value.to_string());
+        });
+        self.defer(move || on_complete(result));
+    }
+
+    fn idb_get(
+        &self,
+        db: u64,
+        store_name: &str,
+        key: &str,
+        on_complete: Box<dyn FnOnce(Result<Option<String>, String>)>,
+    ) {
+        let result = self.with_idb_store(db, store_name, |store| store.get(key).cloned());
+        self.defer(move || on_complete(result));
+    }
+
+    fn idb_delete(
+        &self,
+        db: u64,
+        store_name: &str,
+        key: &str,
+        on_complete: Box<dyn FnOnce(Result<(), String>)>,
+    ) {
+        let result = self.with_idb_store(db, store_name, |store| {
+            store.remove(key);
+        });
+        self.defer(move || on_complete(result));
+    }
+
+    fn idb_get_all(
+        &self,
+        db: u64,
+        store_name: &str,
+        on_complete: Box<dyn FnOnce(Result<Vec<String>, String>)>,
+    ) {
+        let result = self.with_idb_store(db, store_name, |store| store.values().cloned().collect());
+        self.defer(move || on_complete(result));
+    }
+
+    fn idb_clear(
+        &self,
+        db: u64,
+        store_name: &str,
+        on_complete: Box<dyn FnOnce(Result<(), String>)>,
+    ) {
+        let result = self.with_idb_store(db, store_name, |store| store.clear());
+        self.defer(move || on_complete(result));
+    }
+}
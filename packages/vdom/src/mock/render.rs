@@ -0,0 +1,245 @@
+//! Rendering [`VNode`]s and applying [`Patch`]es to the mock tree.
+//!
+//! Mirrors the browser platform's `render_vnode` / `apply_patches`, including
+//! `element_ref` population, reactive bindings for dynamic attributes and
+//! text, and element registration with the runtime for component cleanup.
+
+use std::any::Any;
+
+use super::{MockElement, MockPlatform};
+use crate::{
+    platform::DomOps,
+    reactive::{create_effect, EffectHandle},
+    runtime, Patch, Style, VNode,
+};
+
+/// Create an effect whose lifetime is tied to the rendering component.
+fn create_tracked_effect(f: impl FnMut() + 'static) -> EffectHandle {
+    let handle = create_effect(f);
+    if let Some(id) = runtime::active_component_id() {
+        runtime::register_effect_handle(id, handle.clone());
+    }
+    handle
+}
+
+impl MockPlatform {
+    /// Replace the body's content with `vnode`.
+    ///
+    /// Returns the first rendered node, or the body for an empty fragment.
+    pub fn mount(&self, vnode: &VNode) -> MockElement {
+        let body = self.body();
+        self.clear(&body);
+        self.render_into(&body, vnode)
+            .into_iter()
+            .next()
+            .unwrap_or(body)
+    }
+
+    /// Render `vnode` and append the resulting nodes to `parent`.
+    pub fn render_into(&self, parent: &MockElement, vnode: &VNode) -> Vec<MockElement> {
+        let nodes = self.build(vnode);
+        for node in &nodes {
+            self.append_child(parent, node);
+        }
+        nodes
+    }
+
+    /// Apply `patches` to `target`, the node rendered for the diffed root.
+    ///
+    /// Root-level `CreateNode` appends into `target`, so a component's
+    /// initial render can be applied to its (empty) container.
+    pub fn apply_patches(&self, target: &MockElement, patches: &[Patch]) {
+        for patch in patches {
+            self.apply_patch(target, patch);
+        }
+    }
+
+    fn apply_patch(&self, element: &MockElement, patch: &Patch) {
+        match patch {
+            Patch::CreateNode { node } => {
+                self.render_into(element, node);
+            }
+            // Removal happens at the parent level via RemoveChild.
+            Patch::RemoveNode => {}
+            Patch::ReplaceNode { node } => self.replace_node(element, node),
+            Patch::UpdateText { text } => {
+                self.state
+                    .borrow_mut()
+                    .tree
+                    .set_text_content(element.0, text);
+            }
+            Patch::UpdateAttribute { name, value } | Patch::AddAttribute { name, value } => {
+                self.set_attribute(element, name, value);
+            }
+            Patch::RemoveAttribute { name } => self.remove_attribute(element, name),
+            Patch::UpdateStyle { style } => {
+                self.remove_attribute(element, "style");
+                self.apply_style(element, style);
+            }
+            Patch::UpdateClass { class } => {
+                if class.static_classes.is_empty() {
+                    self.remove_attribute(element, "class");
+                } else {
+                    self.set_class(element, &class.static_classes);
+                }
+            }
+            Patch::InsertChild { index, node } => {
+                let reference = self.children(element).get(*index).copied();
+                for child in self.build(node) {
+                    self.insert_before(element, &child, reference.as_ref());
+                }
+            }
+            Patch::RemoveChild { index } => {
+                if let Some(child) = self.children(element).get(*index) {
+                    self.remove_node(element, child);
+                }
+            }
+            Patch::UpdateChild { index, patches } => {
+                if let Some(child) = self.children(element).get(*index) {
+                    self.apply_patches(child, patches);
+                }
+            }
+            Patch::AddEvent { name, handler } | Patch::UpdateEvent { name, handler } => {
+                self.remove_event_listener(element, name);
+                let handler = handler.clone();
+                self.add_event_listener(
+                    element,
+                    name,
+                    Box::new(move |event| (handler.borrow_mut())(event)),
+                );
+            }
+            Patch::RemoveEvent { name } => self.remove_event_listener(element, name),
+        }
+    }
+
+    /// Replace `element` in its parent, or its content if it is the body.
+    fn replace_node(&self, element: &MockElement, node: &VNode) {
+        match self.parent(element) {
+            Some(parent) => {
+                for new_node in self.build(node) {
+                    self.insert_before(&parent, &new_node, Some(element));
+                }
+                self.remove_node(&parent, element);
+            }
+            None => {
+                self.clear(element);
+                self.render_into(element, node);
+            }
+        }
+    }
+
+    fn remove_node(&self, parent: &MockElement, child: &MockElement) {
+        let removed: Vec<u64> = {
+            let state = self.state.borrow();
+            std::iter::once(child.0)
+                .chain(state.tree.descendants(child.0))
+                .collect()
+        };
+        for id in removed {
+            runtime::on_element_removed(id);
+        }
+        self.remove_child(parent, child);
+    }
+
+    fn clear(&self, element: &MockElement) {
+        for child in self.children(element) {
+            self.remove_node(element, &child);
+        }
+        self.state.borrow_mut().tree.clear_children(element.0);
+    }
+
+    fn apply_style(&self, element: &MockElement, style: &Style) {
+        for part in style.static_styles.split(';') {
+            if let Some((name, value)) = part.split_once(':') {
+                self.set_style(element, name.trim(), value.trim());
+            }
+        }
+        for (name, value) in &style.css_variables {
+            self.set_style(element, name, value);
+        }
+    }
+
+    /// Create detached nodes for `vnode` (several for a fragment).
+    fn build(&self, vnode: &VNode) -> Vec<MockElement> {
+        match vnode {
+            VNode::Element(velement) => {
+                let element = self.create_element(&velement.tag);
+                runtime::register_element(element.0);
+
+                if let Some(element_ref) = &velement.element_ref {
+                    *element_ref.borrow_mut() = Some(Box::new(element) as Box<dyn Any>);
+                }
+
+                for (name, value) in &velement.attributes {
+                    self.set_attribute(&element, name, value);
+                }
+                if !velement.class.static_classes.is_empty() {
+                    self.set_class(&element, &velement.class.static_classes);
+                }
+                self.apply_style(&element, &velement.style);
+
+                for (event_name, handler) in &velement.event_handlers {
+                    let handler = handler.clone();
+                    self.add_event_listener(
+                        &element,
+                        event_name,
+                        Box::new(move |event| (handler.borrow_mut())(event)),
+                    );
+                }
+
+                if let Some(inner) = &velement.inner_html {
+                    self.set_inner_html(&element, inner.clone());
+                } else {
+                    for child in &velement.children {
+                        self.render_into(&element, child);
+                    }
+                }
+
+                for (name, compute) in &velement.dynamic_attributes {
+                    let platform = self.clone();
+                    let name = name.clone();
+                    let compute = compute.clone();
+                    create_tracked_effect(move || {
+                        let value = (compute.borrow_mut())();
+                        platform.set_attribute(&element, &name, &value);
+                    });
+                }
+                for (name, compute) in &velement.dynamic_styles {
+                    let platform = self.clone();
+                    let name = name.clone();
+                    let compute = compute.clone();
+                    create_tracked_effect(move || {
+                        let value = (compute.borrow_mut())();
+                        platform.set_style(&element, &name, &value);
+                    });
+                }
+                for compute in &velement.dynamic_classes {
+                    let platform = self.clone();
+                    let compute = compute.clone();
+                    create_tracked_effect(move || {
+                        let value = (compute.borrow_mut())();
+                        platform.set_class(&element, &value);
+                    });
+                }
+
+                vec![element]
+            }
+            VNode::Text(vtext) => vec![self.create_text_node(&vtext.text)],
+            VNode::DynamicText(dt) => {
+                let text_node = self.create_text_node(&dt.initial);
+                let platform = self.clone();
+                let compute = dt.compute.clone();
+                create_tracked_effect(move || {
+                    let text = (compute.borrow_mut())();
+                    platform
+                        .state
+                        .borrow_mut()
+                        .tree
+                        .set_text_content(text_node.0, &text);
+                });
+                vec![text_node]
+            }
+            VNode::Fragment(children) => children.iter().flat_map(|c| self.build(c)).collect(),
+        }
+    }
+}
@@ -0,0 +1,186 @@
+//! Minimal CSS selector matching for the mock tree.
+//!
+//! Supports compound selectors built from `tag`, `*`, `#id`, `.class`,
+//! `[attr]` and `[attr=value]`, joined by the descendant combinator
+//! (whitespace), and comma-separated selector lists.
+
+use super::tree::MockTree;
+
+#[derive(Debug, Default, PartialEq)]
+struct Compound {
+    tag: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+    attributes: Vec<(String, Option<String>)>,
+}
+
+/// A parsed selector list.
+#[derive(Debug, PartialEq)]
+pub(super) struct Selector {
+    alternatives: Vec<Vec<Compound>>,
+}
+
+impl Selector {
+    /// Parse a selector, returning `None` for unsupported syntax.
+    pub fn parse(selector: &str) -> Option<Self> {
+        let alternatives = selector
+            .split(',')
+            .map(|part| {
+                let compounds: Option<Vec<_>> =
+                    part.split_whitespace().map(parse_compound).collect();
+                compounds.filter(|c| !c.is_empty())
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self { alternatives })
+    }
+
+    /// Whether the element `id` matches this selector.
+    pub fn matches(&self, tree: &MockTree, id: u64) -> bool {
+        self.alternatives
+            .iter()
+            .any(|chain| matches_chain(tree, id, chain))
+    }
+}
+
+fn parse_compound(input: &str) -> Option<Compound> {
+    let mut compound = Compound::default();
+    let mut rest = input;
+
+    let tag_end = rest.find(['#', '.', '[']).unwrap_or(rest.len());
+    let tag = &rest[..tag_end];
+    if !tag.is_empty() && tag != "*" {
+        if !is_ident(tag) {
+            return None;
+        }
+        compound.tag = Some(tag.to_ascii_lowercase());
+    }
+    rest = &rest[tag_end..];
+
+    while !rest.is_empty() {
+        let body = rest.strip_prefix(['#', '.', '['])?;
+        match &rest[..1] {
+            prefix @ ("#" | ".") => {
+                let end = body.find(['#', '.', '[']).unwrap_or(body.len());
+                let name = &body[..end];
+                if !is_ident(name) {
+                    return None;
+                }
+                if prefix == "#" {
+                    compound.id = Some(name.to_string());
+                } else {
+                    compound.classes.push(name.to_string());
+                }
+                rest = &body[end..];
+            }
+            "[" => {
+                let end = body.find(']')?;
+                let inner = &body[..end];
+                let attr = match inner.split_once('=') {
+                    Some((name, value)) => (
+                        name.trim().to_string(),
+                        Some(value.trim().trim_matches(['"', '\'']).to_string()),
+                    ),
+                    None => (inner.trim().to_string(), None),
+                };
+                compound.attributes.push(attr);
+                rest = &body[end + 1..];
+            }
+            _ => unreachable!("prefix is one of '#', '.', '['"),
+        }
+    }
+
+    Some(compound)
+}
+
+fn is_ident(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn matches_compound(tree: &MockTree, id: u64, compound: &Compound) -> bool {
+    let Some(node) = tree.get(id) else {
+        return false;
+    };
+    let Some(tag) = node.tag() else {
+        return false;
+    };
+
+    if compound
+        .tag
+        .as_deref()
+        .is_some_and(|t| !t.eq_ignore_ascii_case(tag))
+    {
+        return false;
+    }
+    if compound
+        .id
+        .as_deref()
+        .is_some_and(|want| node.attributes.get("id").map(String::as_str) != Some(want))
+    {
+        return false;
+    }
+    if !compound
+        .classes
+        .iter()
+        .all(|class| node.classes().any(|c| c == class))
+    {
+        return false;
+    }
+    compound
+        .attributes
+        .iter()
+        .all(|(name, value)| match (node.attributes.get(name), value) {
+            (Some(_), None) => true,
+            (Some(actual), Some(want)) => actual == want,
+            (None, _) => false,
+        })
+}
+
+fn matches_chain(tree: &MockTree, id: u64, chain: &[Compound]) -> bool {
+    let Some((last, ancestors)) = chain.split_last() else {
+        return false;
+    };
+    if !matches_compound(tree, id, last) {
+        return false;
+    }
+
+    // Match the remaining compounds against ancestors, innermost first.
+    let mut remaining = ancestors.iter().rev().peekable();
+    for ancestor in tree.ancestry(id).into_iter().skip(1) {
+        match remaining.peek() {
+            Some(compound) if matches_compound(tree, ancestor, compound) => {
+                remaining.next();
+            }
+            Some(_) => {}
+            None => break,
+        }
+    }
+    remaining.peek().is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_compound() {
+        let selector = Selector::parse("button.primary#go[type=submit]").unwrap();
+        assert_eq!(
+            selector.alternatives,
+            vec![vec![Compound {
+                tag: Some("button".to_string()),
+                id: Some("go".to_string()),
+                classes: vec!["primary".to_string()],
+                attributes: vec![("type".to_string(), Some("submit".to_string()))],
+            }]]
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_unsupported() {
+        assert!(Selector::parse("div > span").is_none());
+        assert!(Selector::parse("a:hover").is_none());
+        assert!(Selector::parse("[unterminated").is_none());
+    }
+}
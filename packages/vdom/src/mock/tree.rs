@@ -0,0 +1,326 @@
+//! In-memory node tree backing [`MockPlatform`](super::MockPlatform).
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    rc::Rc,
+};
+
+use crate::{
+    platform::{DomRect, ListenerOptions},
+    vnode::{html_escape_attr_into, html_escape_into, is_void_element},
+    EventData,
+};
+
+/// Shared event handler stored on a node.
+pub(super) type ListenerFn = Rc<RefCell<Box<dyn FnMut(Box<dyn EventData>)>>>;
+
+pub(super) enum NodeKind {
+    Element(String),
+    Text(String),
+}
+
+pub(super) struct Listener {
+    pub event: String,
+    pub handler: ListenerFn,
+    pub options: ListenerOptions,
+}
+
+pub(super) struct MockNode {
+    pub kind: NodeKind,
+    pub attributes: BTreeMap<String, String>,
+    pub styles: Vec<(String, String)>,
+    pub children: Vec<u64>,
+    pub parent: Option<u64>,
+    pub inner_html: Option<String>,
+    pub listeners: Vec<Listener>,
+    pub rect: DomRect,
+    pub scroll_top: f64,
+    pub scroll_height: i32,
+    pub content_editable: bool,
+}
+
+impl MockNode {
+    pub fn new(kind: NodeKind) -> Self {
+        Self {
+            kind,
+            attributes: BTreeMap::new(),
+            styles: Vec::new(),
+            children: Vec::new(),
+            parent: None,
+            inner_html: None,
+            listeners: Vec::new(),
+            rect: DomRect {
+                x: 0.0,
+                y: 0.0,
+                width: 0.0,
+                height: 0.0,
+            },
+            scroll_top: 0.0,
+            scroll_height: 0,
+            content_editable: false,
+        }
+    }
+
+    pub fn tag(&self) -> Option<&str> {
+        match &self.kind {
+            NodeKind::Element(tag) => Some(tag),
+            NodeKind::Text(_) => None,
+        }
+    }
+
+    pub fn set_style(&mut self, name: &str, value: &str) {
+        if let Some(entry) = self.styles.iter_mut().find(|(n, _)| n == name) {
+            entry.1 = value.to_string();
+        } else {
+            self.styles.push((name.to_string(), value.to_string()));
+        }
+    }
+
+    pub fn style_attr(&self) -> String {
+        self.styles
+            .iter()
+            .map(|(name, value)| format!("{}: {}", name, value))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    pub fn classes(&self) -> impl Iterator<Item = &str> {
+        self.attributes
+            .get("class")
+            .map(|c| c.split_whitespace())
+            .into_iter()
+            .flatten()
+    }
+}
+
+/// Arena of nodes keyed by handle.
+pub(super) struct MockTree {
+    pub nodes: HashMap<u64, MockNode>,
+    next_id: u64,
+}
+
+impl MockTree {
+    pub fn new() -> Self {
+        Self {
+            nodes: HashMap::new(),
+            // Handle 0 is reserved for "no element" / the window target.
+            next_id: 1,
+        }
+    }
+
+    pub fn insert(&mut self, node: MockNode) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.nodes.insert(id, node);
+        id
+    }
+
+    pub fn get(&self, id: u64) -> Option<&MockNode> {
+        self.nodes.get(&id)
+    }
+
+    pub fn get_mut(&mut self, id: u64) -> Option<&mut MockNode> {
+        self.nodes.get_mut(&id)
+    }
+
+    /// Detach `child` from its current parent, if any.
+    pub fn detach(&mut self, child: u64) {
+        let parent = self.nodes.get(&child).and_then(|n| n.parent);
+        if let Some(parent) = parent.and_then(|p| self.nodes.get_mut(&p)) {
+            parent.children.retain(|&c| c != child);
+        }
+        if let Some(node) = self.nodes.get_mut(&child) {
+            node.parent = None;
+        }
+    }
+
+    /// Insert `child` into `parent` before `reference` (or at the end).
+    pub fn insert_before(&mut self, parent: u64, child: u64, reference: Option<u64>) {
+        if parent == child || !self.nodes.contains_key(&parent) {
+            return;
+        }
+        self.detach(child);
+        let Some(node) = self.nodes.get_mut(&parent) else {
+            return;
+        };
+        node.inner_html = None;
+        let index = reference
+            .and_then(|r| node.children.iter().position(|&c| c == r))
+            .unwrap_or(node.children.len());
+        node.children.insert(index, child);
+        if let Some(child) = self.nodes.get_mut(&child) {
+            child.parent = Some(parent);
+        }
+    }
+
+    /// Remove `child` from `parent` and drop its subtree.
+    pub fn remove(&mut self, parent: u64, child: u64) {
+        let is_child = self
+            .nodes
+            .get(&parent)
+            .is_some_and(|p| p.children.contains(&child));
+        if !is_child {
+            return;
+        }
+        self.detach(child);
+        self.drop_subtree(child);
+    }
+
+    /// Remove all children of `parent`.
+    pub fn clear_children(&mut self, parent: u64) {
+        let children = self
+            .nodes
+            .get_mut(&parent)
+            .map(|n| std::mem::take(&mut n.children))
+            .unwrap_or_default();
+        for child in children {
+            self.drop_subtree(child);
+        }
+    }
+
+    fn drop_subtree(&mut self, id: u64) {
+        if let Some(node) = self.nodes.remove(&id) {
+            for child in node.children {
+                self.drop_subtree(child);
+            }
+        }
+    }
+
+    /// Handles of `id` and its ancestors, innermost first.
+    pub fn ancestry(&self, id: u64) -> Vec<u64> {
+        let mut path = Vec::new();
+        let mut current = Some(id);
+        while let Some(node_id) = current {
+            path.push(node_id);
+            current = self.nodes.get(&node_id).and_then(|n| n.parent);
+        }
+        path
+    }
+
+    /// Element descendants of `id` in document order (excluding `id`).
+    pub fn descendants(&self, id: u64) -> Vec<u64> {
+        let mut out = Vec::new();
+        self.collect_descendants(id, &mut out);
+        out
+    }
+
+    fn collect_descendants(&self, id: u64, out: &mut Vec<u64>) {
+        if let Some(node) = self.nodes.get(&id) {
+            for &child in &node.children {
+                if self.nodes.get(&child).is_some_and(|c| c.tag().is_some()) {
+                    out.push(child);
+                    self.collect_descendants(child, out);
+                }
+            }
+        }
+    }
+
+    pub fn text_content(&self, id: u64) -> String {
+        let mut buf = String::new();
+        self.write_text(id, &mut buf);
+        buf
+    }
+
+    fn write_text(&self, id: u64, buf: &mut String) {
+        let Some(node) = self.nodes.get(&id) else {
+            return;
+        };
+        match &node.kind {
+            NodeKind::Text(text) => buf.push_str(text),
+            NodeKind::Element(_) => {
+                for &child in &node.children {
+                    self.write_text(child, buf);
+                }
+            }
+        }
+    }
+
+    pub fn set_text_content(&mut self, id: u64, text: &str) {
+        match self.nodes.get_mut(&id).map(|n| &mut n.kind) {
+            Some(NodeKind::Text(current)) => *current = text.to_string(),
+            Some(NodeKind::Element(_)) => {
+                self.clear_children(id);
+                if let Some(node) = self.nodes.get_mut(&id) {
+                    node.inner_html = None;
+                }
+                if !text.is_empty() {
+                    let child = self.insert(MockNode::new(NodeKind::Text(text.to_string())));
+                    self.insert_before(id, child, None);
+                }
+            }
+            None => {}
+        }
+    }
+
+    /// Serialize the children of `id` to HTML.
+    pub fn inner_html(&self, id: u64) -> String {
+        let mut buf = String::new();
+        if let Some(node) = self.nodes.get(&id) {
+            if let Some(raw) = &node.inner_html {
+                buf.push_str(raw);
+            } else {
+                for &child in &node.children {
+                    self.write_html(child, &mut buf);
+                }
+            }
+        }
+        buf
+    }
+
+    /// Serialize `id` and its subtree to HTML.
+    ///
+    /// Attributes are sorted, followed by `class` and `style`, matching
+    /// [`VNode::render_to_html`](crate::VNode::render_to_html).
+    pub fn outer_html(&self, id: u64) -> String {
+        let mut buf = String::new();
+        self.write_html(id, &mut buf);
+        buf
+    }
+
+    fn write_html(&self, id: u64, buf: &mut String) {
+        let Some(node) = self.nodes.get(&id) else {
+            return;
+        };
+        let tag = match &node.kind {
+            NodeKind::Text(text) => {
+                html_escape_into(buf, text);
+                return;
+            }
+            NodeKind::Element(tag) => tag,
+        };
+
+        buf.push('<');
+        buf.push_str(tag);
+        for (name, value) in &node.attributes {
+            if name == "class" || name == "style" {
+                continue;
+            }
+            buf.push(' ');
+            buf.push_str(name);
+            buf.push_str("=\"");
+            html_escape_attr_into(buf, value);
+            buf.push('"');
+        }
+        if let Some(class) = node.attributes.get("class") {
+            buf.push_str(" class=\"");
+            html_escape_attr_into(buf, class);
+            buf.push('"');
+        }
+        let style = node.style_attr();
+        if !style.is_empty() {
+            buf.push_str(" style=\"");
+            html_escape_attr_into(buf, &style);
+            buf.push('"');
+        }
+        buf.push('>');
+
+        if is_void_element(tag) {
+            return;
+        }
+        buf.push_str(&self.inner_html(id));
+        buf.push_str("</");
+        buf.push_str(tag);
+        buf.push('>');
+    }
+}
@@ -1,8 +1,10 @@
 pub trait IdbOps: Sized + 'static {
+    /// Open the database `name`, creating `store_name` in it if it is missing.
     fn idb_open(
         &self,
         name: &str,
         version: Option<u64>,
+        store_name: Option<&str>,
         on_complete: Box<dyn FnOnce(Result<u64, String>)>,
     ) -> u64;
     fn idb_put(
@@ -6,6 +6,11 @@ use std::{
 
 use tracing::trace;
 
+use crate::{
+    boundary::{ErrorBoundary, ErrorPhase},
+    runtime::ComponentId,
+};
+
 type SubscribeFn = Box<dyn Fn(Rc<dyn Fn()>)>;
 
 pub struct DependencyEntry {
@@ -109,6 +114,11 @@ impl<T: Clone + 'static> Signal<T> {
         self.inner.borrow().value.clone()
     }
 
+    /// Read the current value without tracking this signal as a dependency.
+    pub fn peek(&self) -> T {
+        self.inner.borrow().value.clone()
+    }
+
     /// Write a new value and notify all subscribers. If not inside a [`batch`],
     /// subscribers are called synchronously.
     pub fn set(&self, value: T) {
@@ -196,26 +206,45 @@ impl<T: Clone + 'static> Signal<T> {
     }
 }
 
+/// Teardown registered by an effect, run before it re-runs or when it stops
+type Cleanup = Rc<RefCell<Option<Box<dyn FnOnce()>>>>;
+
 /// A handle to a reactive effect created by [`create_effect`].
 ///
 /// Drop this handle to allow the effect to be cleaned up, or call [`stop()`](EffectHandle::stop)
 /// to deactivate the effect without dropping it.
 pub struct EffectHandle {
     stopped: Rc<Cell<bool>>,
+    cleanup: Cleanup,
 }
 
 impl Clone for EffectHandle {
     fn clone(&self) -> Self {
         Self {
             stopped: self.stopped.clone(),
+            cleanup: self.cleanup.clone(),
         }
     }
 }
 
 impl EffectHandle {
+    /// A handle that only runs `teardown` when stopped.
+    ///
+    /// Useful for effects driven by something other than signal tracking,
+    /// such as explicit dependency lists.
+    pub fn with_cleanup<C: FnOnce() + 'static>(teardown: C) -> Self {
+        Self {
+            stopped: Rc::new(Cell::new(false)),
+            cleanup: Rc::new(RefCell::new(Some(Box::new(teardown)))),
+        }
+    }
+
     /// Stop the effect. It will no longer re-run when tracked signals change.
+    ///
+    /// The teardown returned by the effect's last run, if any, runs now.
     pub fn stop(&self) {
         self.stopped.set(true);
+        run_cleanup(&self.cleanup);
     }
 
     /// Returns `true` if the effect has been stopped.
@@ -224,6 +253,16 @@ impl EffectHandle {
     }
 }
 
+/// Run and clear a pending teardown without tracking its signal reads.
+fn run_cleanup(cleanup: &Cleanup) {
+    let teardown = cleanup.borrow_mut().take();
+    if let Some(teardown) = teardown {
+        let tracking = TRACKING_ACTIVE.with(|t| t.replace(false));
+        teardown();
+        TRACKING_ACTIVE.with(|t| t.set(tracking));
+    }
+}
+
 /// Create a reactive effect that auto-tracks signal dependencies.
 ///
 /// The closure runs immediately. Any [`Signal::get()`] calls inside the closure
@@ -245,22 +284,78 @@ impl EffectHandle {
 /// count.set(1); // prints "count = 1"
 /// ```
 pub fn create_effect<F>(f: F) -> EffectHandle
+where
+    F: FnMut() + 'static,
+{
+    spawn_effect(f, Rc::new(RefCell::new(None)))
+}
+
+/// Create a reactive effect whose closure returns a teardown.
+///
+/// Behaves like [`create_effect`]; the teardown returned by one run is
+/// called before the next run and when the effect is
+/// [stopped](EffectHandle::stop).
+///
+/// # Example
+///
+/// ```no_run
+/// use tairitsu_vdom::{Signal, create_effect_with_cleanup};
+///
+/// let room = Signal::new("lobby");
+/// let room_clone = room.clone();
+///
+/// let effect = create_effect_with_cleanup(move || {
+///     let name = room_clone.get();
+///     println!("joined {}", name);
+///     move || println!("left {}", name)
+/// });
+///
+/// room.set("kitchen"); // prints "left lobby", then "joined kitchen"
+/// effect.stop(); // prints "left kitchen"
+/// ```
+pub fn create_effect_with_cleanup<F, C>(mut f: F) -> EffectHandle
+where
+    F: FnMut() -> C + 'static,
+    C: FnOnce() + 'static,
+{
+    let cleanup: Cleanup = Rc::new(RefCell::new(None));
+    let slot = cleanup.clone();
+    spawn_effect(
+        move || {
+            run_cleanup(&slot);
+            let teardown = f();
+            *slot.borrow_mut() = Some(Box::new(teardown));
+        },
+        cleanup,
+    )
+}
+
+fn spawn_effect<F>(f: F, cleanup: Cleanup) -> EffectHandle
 where
     F: FnMut() + 'static,
 {
     let callback: Rc<RefCell<dyn FnMut()>> = Rc::new(RefCell::new(f));
     let stopped = Rc::new(Cell::new(false));
     let generation = Rc::new(Cell::new(0u64));
+    let boundary = crate::boundary::current().map(|boundary| {
+        let component = crate::runtime::active_component_id();
+        (boundary, component)
+    });
 
-    execute_effect(&callback, &stopped, &generation);
+    execute_effect(&callback, &stopped, &generation, &boundary);
 
-    EffectHandle { stopped }
+    EffectHandle { stopped, cleanup }
 }
 
+/// The error boundary an effect reports to, with the component that
+/// created it.
+type EffectBoundary = Option<(ErrorBoundary, Option<ComponentId>)>;
+
 fn execute_effect(
     callback: &Rc<RefCell<dyn FnMut()>>,
     stopped: &Rc<Cell<bool>>,
     generation: &Rc<Cell<u64>>,
+    boundary: &EffectBoundary,
 ) {
     if stopped.get() {
         return;
@@ -274,12 +369,28 @@ fn execute_effect(
         DEPENDENCIES.with(|deps| deps.borrow_mut().drain(..).collect());
 
     TRACKING_ACTIVE.with(|t| t.set(true));
-    callback.borrow_mut()();
+    let completed = match boundary {
+        None => {
+            callback.borrow_mut()();
+            true
+        }
+        Some((boundary, component)) => {
+            crate::boundary::guard(boundary, *component, ErrorPhase::Effect, || {
+                callback.borrow_mut()()
+            })
+            .is_some()
+        }
+    };
     TRACKING_ACTIVE.with(|t| t.set(false));
 
     let deps: Vec<DependencyEntry> =
         DEPENDENCIES.with(|deps| deps.borrow_mut().drain(..).collect());
 
+    // A failed effect stays unsubscribed until its boundary re-renders it.
+    if !completed {
+        return;
+    }
+
     if deps.is_empty() {
         return;
     }
@@ -287,6 +398,7 @@ fn execute_effect(
     let cb = callback.clone();
     let stopped_clone = stopped.clone();
     let gen_clone = generation.clone();
+    let boundary = boundary.clone();
     let rerun: Rc<dyn Fn()> = Rc::new(move || {
         if stopped_clone.get() {
             return;
@@ -294,7 +406,7 @@ fn execute_effect(
         if gen_clone.get() != my_gen {
             return;
         }
-        execute_effect(&cb, &stopped_clone, &gen_clone);
+        execute_effect(&cb, &stopped_clone, &gen_clone, &boundary);
     });
 
     for dep in deps {
@@ -650,4 +762,38 @@ mod tests {
         s.set(1);
         assert_eq!(*count.borrow(), 2, "effect should re-run once per set");
     }
+
+    #[test]
+    fn test_effect_cleanup_runs_before_rerun_and_on_stop() {
+        let signal = Signal::new(1);
+        let log = Rc::new(RefCell::new(Vec::new()));
+
+        let effect = create_effect_with_cleanup({
+            let signal = signal.clone();
+            let log = log.clone();
+            move || {
+                let value = signal.get();
+                log.borrow_mut().push(format!("run {}", value));
+                let log = log.clone();
+                move || log.borrow_mut().push(format!("cleanup {}", value))
+            }
+        });
+        signal.set(2);
+        effect.stop();
+        signal.set(3);
+        effect.stop();
+
+        assert_eq!(
+            *log.borrow(),
+            vec!["run 1", "cleanup 1", "run 2", "cleanup 2"]
+        );
+
+        let stopped = Rc::new(Cell::new(false));
+        let handle = EffectHandle::with_cleanup({
+            let stopped = stopped.clone();
+            move || stopped.set(true)
+        });
+        handle.clone().stop();
+        assert!(stopped.get() && handle.is_stopped());
+    }
 }
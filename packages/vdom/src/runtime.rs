@@ -3,11 +3,17 @@
 //! This module provides the runtime infrastructure for tracking signal
 //! dependencies and scheduling re-renders when signals change.
 
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+    rc::Rc,
+};
 
 use tracing::trace;
 
 use crate::{
+    boundary::ErrorPhase,
     patch::Patch,
     reactive::{EffectHandle, SignalId},
     VNode,
@@ -63,6 +69,61 @@ pub fn reset_hook_slots() {
     HOOK_SLOT.with(|slots| slots.borrow_mut().clear());
 }
 
+thread_local! {
+    static CONTEXT_SLOT: RefCell<HashMap<(ComponentId, TypeId), Rc<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+/// Store a context value provided by `component_id`, replacing any value of
+/// the same type it provided before.
+pub fn provide_context_slot(component_id: ComponentId, type_id: TypeId, value: Rc<dyn Any>) {
+    CONTEXT_SLOT.with(|slots| {
+        slots.borrow_mut().insert((component_id, type_id), value);
+    });
+}
+
+/// The context value of `type_id` provided by `component_id` itself.
+pub fn context_slot(component_id: ComponentId, type_id: TypeId) -> Option<Rc<dyn Any>> {
+    CONTEXT_SLOT.with(|slots| slots.borrow().get(&(component_id, type_id)).cloned())
+}
+
+/// The context value of `type_id` provided by the nearest of
+/// `component_id` and its ancestors.
+pub fn find_context_slot(component_id: ComponentId, type_id: TypeId) -> Option<Rc<dyn Any>> {
+    let mut current = Some(component_id);
+    while let Some(id) = current {
+        if let Some(value) = context_slot(id, type_id) {
+            return Some(value);
+        }
+        current = parent_component(id);
+    }
+    None
+}
+
+/// Remove the context value of `type_id` provided by `component_id`.
+/// Returns `true` if a value was removed.
+pub fn remove_context_slot(component_id: ComponentId, type_id: TypeId) -> bool {
+    CONTEXT_SLOT.with(|slots| {
+        slots
+            .borrow_mut()
+            .remove(&(component_id, type_id))
+            .is_some()
+    })
+}
+
+/// Clear all context values provided by a component (called during cleanup).
+pub fn clear_context_slots(component_id: ComponentId) {
+    CONTEXT_SLOT.with(|slots| {
+        slots
+            .borrow_mut()
+            .retain(|(cid, _), _| *cid != component_id);
+    });
+}
+
+/// Clear every provided context value (useful for testing).
+pub fn reset_context_slots() {
+    CONTEXT_SLOT.with(|slots| slots.borrow_mut().clear());
+}
+
 /// Inner state of the reactive runtime
 struct RuntimeInner {
     next_id: ComponentId,
@@ -76,7 +137,17 @@ struct RuntimeInner {
     scheduled: bool,
     raf_id: Option<u32>,
     effect_handles: HashMap<ComponentId, Vec<EffectHandle>>,
+    persistent_effect_handles: HashMap<ComponentId, Vec<EffectHandle>>,
     element_to_component: HashMap<u64, ComponentId>,
+    component_parents: HashMap<ComponentId, ComponentId>,
+    /// Innermost scope entered with [`with_scope`] during the current render
+    active_scope: Option<ComponentId>,
+    /// Scopes by parent and the order they were entered in its render
+    scope_ids: HashMap<(ComponentId, usize), ComponentId>,
+    /// Scopes entered so far in the current render of each parent
+    scope_cursors: HashMap<ComponentId, usize>,
+    /// Scope ids count down, so they never collide with component ids
+    next_scope_id: ComponentId,
 }
 
 impl RuntimeInner {
@@ -93,9 +164,21 @@ impl RuntimeInner {
             scheduled: false,
             raf_id: None,
             effect_handles: HashMap::new(),
+            persistent_effect_handles: HashMap::new(),
             element_to_component: HashMap::new(),
+            component_parents: HashMap::new(),
+            active_scope: None,
+            scope_ids: HashMap::new(),
+            scope_cursors: HashMap::new(),
+            next_scope_id: ComponentId::MAX,
         }
     }
+
+    fn new_scope_id(&mut self) -> ComponentId {
+        let id = self.next_scope_id;
+        self.next_scope_id -= 1;
+        id
+    }
 }
 
 thread_local! {
@@ -124,15 +207,32 @@ pub fn set_apply_patches_callback<F: FnMut(ComponentId, Vec<Patch>) + 'static>(c
     });
 }
 
+/// Remove the callbacks installed by [`set_schedule_callback`] and
+/// [`set_apply_patches_callback`].
+///
+/// Renders scheduled afterwards wait for a new schedule callback, and
+/// patches are dropped until a new apply callback is set.
+pub fn clear_callbacks() {
+    RUNTIME.with(|runtime| {
+        let mut rt = runtime.borrow_mut();
+        rt.schedule_callback = None;
+        rt.apply_patches_callback = None;
+    });
+}
+
 /// Register a component with the runtime.
 ///
 /// This creates a new component instance and tracks its render function.
 /// The component will be automatically re-rendered when its dependencies change.
+///
+/// A component registered while another one is active (rendering) becomes
+/// its child, see [`parent_component`].
 pub fn use_component<F>(render_fn: F) -> ComponentId
 where
     F: FnMut() -> VNode + 'static,
 {
-    RUNTIME.with(|runtime| {
+    let boundary = crate::boundary::innermost();
+    let id = RUNTIME.with(|runtime| {
         let mut rt = runtime.borrow_mut();
 
         let id = rt.next_id;
@@ -140,11 +240,37 @@ where
 
         rt.render_functions
             .insert(id, Rc::new(RefCell::new(render_fn)));
+        if let Some(parent) = rt.active_component {
+            rt.component_parents.insert(id, parent);
+        }
 
         trace!("Registered component {}", id);
 
         id
-    })
+    });
+    if let Some(boundary) = boundary {
+        crate::boundary::adopt(id, boundary);
+    }
+    id
+}
+
+/// The component `id` was registered under, if any.
+pub fn parent_component(id: ComponentId) -> Option<ComponentId> {
+    RUNTIME.with(|runtime| runtime.borrow().component_parents.get(&id).copied())
+}
+
+/// Attach `id` to `parent` in the component tree, or detach it with `None`.
+///
+/// [`use_component`] records the parent automatically; this is for
+/// components registered outside their parent's render.
+pub fn set_parent_component(id: ComponentId, parent: Option<ComponentId>) {
+    RUNTIME.with(|runtime| {
+        let mut rt = runtime.borrow_mut();
+        match parent {
+            Some(parent) => rt.component_parents.insert(id, parent),
+            None => rt.component_parents.remove(&id),
+        };
+    });
 }
 
 /// Update a component's render function.
@@ -216,17 +342,37 @@ pub fn mark_dirty(id: ComponentId) {
 
 /// Set the active component for dependency tracking.
 pub fn with_component<T>(id: ComponentId, f: impl FnOnce() -> T) -> T {
-    RUNTIME.with(|runtime| {
+    let _restore = RUNTIME.with(|runtime| {
         let mut rt = runtime.borrow_mut();
-        let prev = rt.active_component;
-        rt.active_component = Some(id);
-        drop(rt);
-        let result = f();
-        RUNTIME.with(|runtime| {
-            runtime.borrow_mut().active_component = prev;
+        let prev_scope = rt.active_scope.take();
+        let prev = rt.active_component.replace(id);
+        RestoreActive {
+            component: Some(prev),
+            scope: prev_scope,
+        }
+    });
+    f()
+}
+
+/// Restores the active component and scope when dropped, so a panic caught
+/// by an [`ErrorBoundary`](crate::ErrorBoundary) does not leave the failed
+/// child's component or scope active.
+struct RestoreActive {
+    /// `None` when only the scope changed
+    component: Option<Option<ComponentId>>,
+    scope: Option<ComponentId>,
+}
+
+impl Drop for RestoreActive {
+    fn drop(&mut self) {
+        let _ = RUNTIME.try_with(|runtime| {
+            let mut rt = runtime.borrow_mut();
+            if let Some(component) = self.component {
+                rt.active_component = component;
+            }
+            rt.active_scope = self.scope;
         });
-        result
-    })
+    }
 }
 
 /// Get the ID of the currently active component, if any.
@@ -234,6 +380,68 @@ pub fn active_component_id() -> Option<ComponentId> {
     RUNTIME.with(|runtime| runtime.borrow().active_component)
 }
 
+/// Run `f` in a child scope of the current scope.
+///
+/// `#[component]` functions render in their own scope, so contexts they
+/// provide reach only their subtree, even though nested components render
+/// within the same runtime component. A scope is matched to the previous
+/// render by the order scopes are entered in, like hooks; it is cleaned up
+/// with its parent. A scope entered outside of any component has no parent
+/// and is cleaned up when `f` returns. Signal tracking stays with the active
+/// component.
+pub fn with_scope<T>(f: impl FnOnce() -> T) -> T {
+    let (root, prev) = RUNTIME.with(|runtime| {
+        let mut rt = runtime.borrow_mut();
+        let parent = rt.active_scope.or(rt.active_component);
+        let id = match parent {
+            Some(parent) => {
+                let cursor = rt.scope_cursors.entry(parent).or_default();
+                let key = (parent, *cursor);
+                *cursor += 1;
+                match rt.scope_ids.get(&key) {
+                    Some(&id) => id,
+                    None => {
+                        let id = rt.new_scope_id();
+                        rt.scope_ids.insert(key, id);
+                        rt.component_parents.insert(id, parent);
+                        id
+                    }
+                }
+            }
+            // Outside of any component there is no render to match against
+            None => rt.new_scope_id(),
+        };
+        rt.scope_cursors.insert(id, 0);
+        let root = parent.is_none().then(|| RootScope(id));
+        (root, rt.active_scope.replace(id))
+    });
+    let _cleanup = root;
+    let _restore = RestoreActive {
+        component: None,
+        scope: prev,
+    };
+    f()
+}
+
+/// Cleans up a scope entered outside of any component once it is left, as no
+/// component render owns it
+struct RootScope(ComponentId);
+
+impl Drop for RootScope {
+    fn drop(&mut self) {
+        cleanup_component(self.0);
+    }
+}
+
+/// The innermost scope entered with [`with_scope`], or else the active
+/// component. Contexts are provided and looked up from here.
+pub fn current_scope() -> Option<ComponentId> {
+    RUNTIME.with(|runtime| {
+        let rt = runtime.borrow();
+        rt.active_scope.or(rt.active_component)
+    })
+}
+
 /// Track a signal dependency for the current component.
 pub fn track_signal(signal_id: SignalId) {
     RUNTIME.with(|runtime| {
@@ -309,6 +517,7 @@ fn render_component(id: ComponentId) {
         render_fn: RenderFn,
         old_vnode: Option<VNode>,
         apply_patches_cb: Option<ApplyPatchesCallback>,
+        effect_handles: Vec<EffectHandle>,
     }
 
     let extracted: Option<Extracted> = RUNTIME.with(|runtime| {
@@ -324,15 +533,10 @@ fn render_component(id: ComponentId) {
 
         let prev = rt.active_component;
         rt.active_component = Some(id);
+        rt.active_scope = None;
+        rt.scope_cursors.insert(id, 0);
         let _ = prev;
 
-        // Stop previous render's effect handles so they don't accumulate.
-        if let Some(handles) = rt.effect_handles.remove(&id) {
-            for handle in handles {
-                handle.stop();
-            }
-        }
-
         let old_vnode = rt.component_vnodes.get(&id).cloned();
         let apply_patches_cb = rt.apply_patches_callback.clone();
 
@@ -340,6 +544,7 @@ fn render_component(id: ComponentId) {
             render_fn,
             old_vnode,
             apply_patches_cb,
+            effect_handles: rt.effect_handles.remove(&id).unwrap_or_default(),
         })
     });
 
@@ -347,8 +552,29 @@ fn render_component(id: ComponentId) {
         return;
     };
 
-    // Phase 2: call the render function — no borrow held.
-    let new_vnode = (ext.render_fn.borrow_mut())();
+    // Stop previous render's effect handles so they don't accumulate.
+    // Their teardowns are user code, so this runs without a borrow held.
+    for handle in ext.effect_handles {
+        handle.stop();
+    }
+
+    // Phase 2: call the render function — no borrow held. Inside an error
+    // boundary a panic switches the boundary to its fallback instead.
+    let render = || (ext.render_fn.borrow_mut())();
+    let new_vnode = match crate::boundary::boundary_for(id) {
+        None => render(),
+        Some(boundary) => {
+            match crate::boundary::guard(&boundary, Some(id), ErrorPhase::Render, render) {
+                Some(vnode) => vnode,
+                None => {
+                    RUNTIME.with(|runtime| {
+                        runtime.borrow_mut().active_component = None;
+                    });
+                    return;
+                }
+            }
+        }
+    };
 
     // Phase 3: store the new VNode (brief borrow).
     // NOTE: active_component stays Some(id) so that patch application
@@ -422,29 +648,49 @@ pub fn notify_signal(signal_id: SignalId) {
 }
 
 /// Cleanup resources for a component.
+///
+/// Effect handles registered for the component are stopped, running their
+/// teardowns, after the runtime state is released. Scopes entered during
+/// its renders are cleaned up with it.
 pub fn cleanup_component(id: ComponentId) {
-    RUNTIME.with(|runtime| {
+    let (handles, scopes) = RUNTIME.with(|runtime| {
         let mut rt = runtime.borrow_mut();
         rt.render_functions.remove(&id);
         rt.component_vnodes.remove(&id);
         rt.dirty_components.retain(|&c| c != id);
         rt.element_to_component.retain(|_, &mut c| c != id);
+        rt.component_parents.remove(&id);
 
         rt.signal_dependencies.retain(|_, deps| {
             deps.retain(|&c| c != id);
             !deps.is_empty()
         });
 
-        if let Some(handles) = rt.effect_handles.remove(&id) {
-            for handle in handles {
-                handle.stop();
+        trace!("Cleaned up component {}", id);
+
+        let mut scopes = Vec::new();
+        rt.scope_ids.retain(|&(parent, _), &mut scope| {
+            if parent == id {
+                scopes.push(scope);
             }
-        }
+            parent != id && scope != id
+        });
+        rt.scope_cursors.remove(&id);
 
-        trace!("Cleaned up component {}", id);
+        let mut handles = rt.effect_handles.remove(&id).unwrap_or_default();
+        handles.extend(rt.persistent_effect_handles.remove(&id).unwrap_or_default());
+        (handles, scopes)
     });
 
+    for handle in handles {
+        handle.stop();
+    }
     clear_hook_slots(id);
+    clear_context_slots(id);
+    crate::boundary::forget(id);
+    for scope in scopes {
+        cleanup_component(scope);
+    }
 }
 
 pub fn register_effect_handle(id: ComponentId, handle: EffectHandle) {
@@ -454,6 +700,20 @@ pub fn register_effect_handle(id: ComponentId, handle: EffectHandle) {
     });
 }
 
+/// Register an effect handle that lives as long as the component.
+///
+/// Unlike [`register_effect_handle`], the handle is not stopped when the
+/// component re-renders, only by [`cleanup_component`]. Handles that were
+/// already stopped are dropped from the list.
+pub fn register_persistent_effect_handle(id: ComponentId, handle: EffectHandle) {
+    RUNTIME.with(|runtime| {
+        let mut rt = runtime.borrow_mut();
+        let handles = rt.persistent_effect_handles.entry(id).or_default();
+        handles.retain(|handle| !handle.is_stopped());
+        handles.push(handle);
+    });
+}
+
 /// Register a DOM element handle as belonging to the currently active component.
 ///
 /// Called from the platform layer when `render_vnode` creates a new element.
@@ -712,4 +972,91 @@ mod tests {
             assert!(!rt.render_functions.contains_key(&id));
         });
     }
+
+    #[test]
+    fn test_context_slots_follow_component_tree() {
+        let root = use_component(VNode::empty);
+        let child = with_component(root, || use_component(VNode::empty));
+        let sibling = with_component(root, || use_component(VNode::empty));
+        let grandchild = with_component(child, || use_component(VNode::empty));
+        assert_eq!(parent_component(grandchild), Some(child));
+        assert_eq!(parent_component(root), None);
+
+        let key = TypeId::of::<&str>();
+        let value =
+            |slot: Option<Rc<dyn Any>>| slot.and_then(|v| v.downcast_ref::<&str>().copied());
+        provide_context_slot(root, key, Rc::new("root"));
+        provide_context_slot(child, key, Rc::new("child"));
+
+        assert_eq!(value(find_context_slot(grandchild, key)), Some("child"));
+        assert_eq!(value(find_context_slot(sibling, key)), Some("root"));
+        assert_eq!(value(context_slot(grandchild, key)), None);
+
+        cleanup_component(child);
+        assert_eq!(value(context_slot(child, key)), None);
+        assert_eq!(value(find_context_slot(sibling, key)), Some("root"));
+        assert!(remove_context_slot(root, key));
+        assert_eq!(value(find_context_slot(sibling, key)), None);
+    }
+
+    #[test]
+    fn test_scopes_are_stable_across_renders() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let id = use_component({
+            let seen = seen.clone();
+            move || {
+                let first = with_scope(|| (current_scope(), with_scope(current_scope)));
+                let second = with_scope(current_scope);
+                seen.borrow_mut().push((first, second));
+                VNode::empty()
+            }
+        });
+
+        render_component(id);
+        render_component(id);
+        let seen = seen.borrow();
+        assert_eq!(seen[0], seen[1]);
+
+        let ((Some(first), Some(nested)), Some(second)) = seen[0] else {
+            panic!("scopes missing: {:?}", seen[0]);
+        };
+        assert_ne!(first, second);
+        assert_eq!(parent_component(nested), Some(first));
+        assert_eq!(parent_component(second), Some(id));
+
+        cleanup_component(id);
+        assert_eq!(parent_component(first), None);
+        assert_eq!(parent_component(nested), None);
+    }
+
+    #[test]
+    fn test_root_scopes_are_released() {
+        let sizes = || {
+            let runtime = RUNTIME.with(|runtime| {
+                let rt = runtime.borrow();
+                (
+                    rt.scope_ids.len(),
+                    rt.scope_cursors.len(),
+                    rt.component_parents.len(),
+                )
+            });
+            (runtime, CONTEXT_SLOT.with(|slots| slots.borrow().len()))
+        };
+        // Like a router or SSR render calling components outside of a
+        // registered component
+        let render = || {
+            with_scope(|| {
+                let scope = current_scope().unwrap();
+                provide_context_slot(scope, TypeId::of::<&str>(), Rc::new("root"));
+                with_scope(|| with_scope(current_scope))
+            })
+        };
+
+        let empty = sizes();
+        assert!(render().is_some());
+        assert_eq!(sizes(), empty);
+        render();
+        assert_eq!(sizes(), empty);
+        assert_eq!(current_scope(), None);
+    }
 }
@@ -1050,7 +1050,7 @@ impl VNode {
 }
 
 /// HTML-escape text content.
-fn html_escape_into(buf: &mut String, s: &str) {
+pub(crate) fn html_escape_into(buf: &mut String, s: &str) {
     for ch in s.chars() {
         match ch {
             '&' => buf.push_str("&amp;"),
@@ -1062,7 +1062,7 @@ fn html_escape_into(buf: &mut String, s: &str) {
 }
 
 /// HTML-escape an attribute value.
-fn html_escape_attr_into(buf: &mut String, s: &str) {
+pub(crate) fn html_escape_attr_into(buf: &mut String, s: &str) {
     for ch in s.chars() {
         match ch {
             '&' => buf.push_str("&amp;"),
@@ -1076,7 +1076,7 @@ fn html_escape_attr_into(buf: &mut String, s: &str) {
 }
 
 /// Returns true for HTML void elements that must not have a closing tag.
-fn is_void_element(tag: &str) -> bool {
+pub(crate) fn is_void_element(tag: &str) -> bool {
     matches!(
         tag,
         "area"
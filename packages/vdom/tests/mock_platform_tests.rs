@@ -0,0 +1,232 @@
+use std::{cell::RefCell, rc::Rc};
+
+use tairitsu_macros::rsx;
+use tairitsu_vdom::{
+    diff::diff, mock::MockPlatform, DomOps, FileOps, IdbOps, QueryOps, Signal, TimerOps, VNode,
+};
+
+#[test]
+fn test_mount_matches_render_to_html() {
+    let platform = MockPlatform::new();
+    let node = rsx! {
+        div {
+            class: "card",
+            h1 { "Title" }
+            p { id: "body", "Hello & welcome" }
+        }
+    };
+
+    platform.mount(&node);
+
+    assert_eq!(platform.html(), node.render_to_html());
+}
+
+#[test]
+#[allow(unused_braces)]
+fn test_click_updates_dynamic_text() {
+    let platform = MockPlatform::new();
+    let count = Signal::new(0);
+    let on_click = {
+        let count = count.clone();
+        move |_| count.set(count.get() + 1)
+    };
+
+    platform.mount(&rsx! {
+        div {
+            button { onclick: on_click, "Increment" }
+            span { id: "count", {count.clone()} }
+        }
+    });
+
+    let button = platform.get_by_text("Increment").unwrap();
+    platform.click(&button);
+    platform.click(&button);
+
+    let span = platform.get_element_by_id("count").unwrap();
+    assert_eq!(platform.text_content(&span), "2");
+}
+
+#[test]
+fn test_apply_patches_from_diff() {
+    let platform = MockPlatform::new();
+    let old = rsx! {
+        ul {
+            li { "one" }
+            li { "two" }
+        }
+    };
+    let new = rsx! {
+        ul {
+            class: "list",
+            li { "one" }
+            li { "deux" }
+            li { "three" }
+        }
+    };
+
+    let root = platform.mount(&old);
+    platform.apply_patches(&root, &diff(Some(&old), &new));
+
+    assert_eq!(platform.html(), new.render_to_html());
+    assert_eq!(platform.query_selector_all("ul.list li").len(), 3);
+}
+
+#[test]
+fn test_replace_root_node() {
+    let platform = MockPlatform::new();
+    let old = rsx! { div { "old" } };
+    let new = rsx! { section { "new" } };
+
+    let root = platform.mount(&old);
+    platform.apply_patches(&root, &diff(Some(&old), &new));
+
+    assert_eq!(platform.html(), "<section>new</section>");
+    assert!(!platform.contains(&root));
+}
+
+#[test]
+fn test_query_helpers() {
+    let platform = MockPlatform::new();
+    platform.mount(&rsx! {
+        form {
+            id: "login",
+            input { class: "field", name: "user" }
+            input { class: "field wide", name: "pass" }
+            button { "Sign in" }
+        }
+    });
+
+    assert_eq!(platform.query_selector_all(".field").len(), 2);
+    let pass = platform.query_selector("#login .wide").unwrap();
+    assert_eq!(
+        platform.get_attribute(&pass, "name").as_deref(),
+        Some("pass")
+    );
+    let form = platform.element_closest(&pass, "form").unwrap();
+    assert_eq!(platform.tag_name(&form).as_deref(), Some("form"));
+    assert!(platform.query_selector("input[name=user]").is_some());
+}
+
+#[test]
+fn test_async_ops_complete_on_next_tick() {
+    let platform = MockPlatform::new();
+    let blob = platform.add_blob("hello");
+    let results = Rc::new(RefCell::new(Vec::new()));
+
+    {
+        let results = results.clone();
+        platform.file_reader_read_as_text(
+            blob,
+            None,
+            Box::new(move |r| results.borrow_mut().push(r.unwrap())),
+        );
+    }
+    {
+        let platform2 = platform.clone();
+        let results = results.clone();
+        platform.idb_open(
+            "app",
+            None,
+            Some("kv"),
+            Box::new(move |db| {
+                let db = db.unwrap();
+                platform2.idb_put(db, "kv", "v", Some("k"), Box::new(|r| r.unwrap()));
+                platform2.idb_get(
+                    db,
+                    "kv",
+                    "k",
+                    Box::new(move |r| results.borrow_mut().push(r.unwrap().unwrap())),
+                );
+            }),
+        );
+    }
+
+    assert!(results.borrow().is_empty());
+    platform.advance_time(0);
+    assert_eq!(*results.borrow(), vec!["hello", "v"]);
+}
+
+#[test]
+fn test_idb_stores_must_be_created_on_open() {
+    let platform = MockPlatform::new();
+    let results = Rc::new(RefCell::new(Vec::new()));
+    let put = |db: u64| {
+        let results = results.clone();
+        platform.idb_put(
+            db,
+            "kv",
+            "v",
+            Some("k"),
+            Box::new(move |r| results.borrow_mut().push(r)),
+        );
+    };
+
+    let db = platform.idb_open("app", None, None, Box::new(|_| {}));
+    put(db);
+    platform.advance_time(0);
+    let err = results.borrow_mut().remove(0).unwrap_err();
+    assert!(err.starts_with("NotFoundError"), "{}", err);
+
+    let reopened = platform.idb_open("app", None, Some("kv"), Box::new(|_| {}));
+    assert_eq!(reopened, db);
+    put(db);
+    platform.advance_time(0);
+    assert_eq!(*results.borrow(), vec![Ok(())]);
+}
+
+#[test]
+fn test_debounced_input() {
+    let platform = MockPlatform::new();
+    let committed = Rc::new(RefCell::new(None));
+    let pending = Rc::new(RefCell::new(None));
+
+    let input = platform.create_element("input");
+    platform.append_child(&platform.body(), &input);
+    {
+        let platform2 = platform.clone();
+        let committed = committed.clone();
+        platform.add_event_listener(
+            &input,
+            "input",
+            Box::new(move |e| {
+                let value = e
+                    .as_any()
+                    .downcast_ref::<tairitsu_vdom::InputEvent>()
+                    .unwrap()
+                    .data
+                    .clone();
+                if let Some(id) = pending.borrow_mut().take() {
+                    platform2.clear_timeout(id);
+                }
+                let committed = committed.clone();
+                let id = platform2
+                    .set_timeout(Box::new(move || *committed.borrow_mut() = Some(value)), 300);
+                *pending.borrow_mut() = Some(id);
+            }),
+        );
+    }
+
+    platform.input(&input, "he");
+    platform.advance_time(200);
+    platform.input(&input, "hello");
+    platform.advance_time(200);
+    assert_eq!(*committed.borrow(), None);
+    platform.advance_time(100);
+    assert_eq!(committed.borrow().as_deref(), Some("hello"));
+    assert_eq!(
+        platform.get_attribute(&input, "value").as_deref(),
+        Some("hello")
+    );
+}
+
+#[test]
+fn test_mount_replaces_previous_tree() {
+    let platform = MockPlatform::new();
+    platform.mount(&VNode::Fragment(vec![
+        rsx! { p { "a" } },
+        rsx! { p { "b" } },
+    ]));
+    assert_eq!(platform.html(), "<p>a</p><p>b</p>");
+    platform.mount(&rsx! { p { "c" } });
+    assert_eq!(platform.html(), "<p>c</p>");
+}
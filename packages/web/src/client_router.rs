@@ -1,31 +1,45 @@
 //! Client-side router for SPA-style navigation in tairitsu-web apps.
 //!
 //! This router integrates with the browser's History API for URL-based routing.
-//! Click interception is expected to be handled by JS-level code (e.g., in index.html)
-//! which calls preventDefault() + pushState(). The Rust-side router handles:
+//! The Rust-side router handles:
 //! - Initial render based on current URL
-//! - Re-rendering on URL change (polled every 200ms)
-//! - Programmatic navigation via navigate()
+//! - Re-rendering on `popstate` (back/forward) and `hashchange` events
+//! - Programmatic navigation via [`navigate`] / [`replace`]
+//!
+//! Navigation diffs the new route tree against the previous one and patches
+//! `#app` in place, so unchanged parts of the page (layout shells, focused
+//! inputs) keep their DOM nodes.
+//!
+//! Locations include the query string and fragment (`/search?q=x#top`); they
+//! are passed to the render function as-is and survive navigation.
+//!
+//! Same-origin link clicks are intercepted by the [`Link`](crate::router::Link)
+//! component, so no hand-written JS is needed in `index.html`.
 
 use std::cell::{Cell, RefCell};
 
-use tairitsu_vdom::TimerOps;
+use tairitsu_vdom::DomOps;
 
-use crate::wit_platform::{self, WitPlatform};
+use crate::wit_platform::{self, WitElement, WitPlatform};
+
+/// Event-target handle that the browser glue resolves to the global `window`.
+const WINDOW_TARGET: u64 = 0;
 
 thread_local! {
     static INSTANCE: RefCell<Option<RouterServiceInner>> = RefCell::new(None);
     static CURRENT_PATH: RefCell<String> = RefCell::new("/".to_string());
-    static POLLING_ACTIVE: Cell<bool> = const { Cell::new(false) };
+    static LISTENING: Cell<bool> = const { Cell::new(false) };
 }
 
 struct RouterServiceInner {
     platform: WitPlatform,
     render_fn: Box<dyn Fn(&str) -> tairitsu_vdom::VNode + Send + Sync>,
+    /// Tree currently mounted in `#app`, diffed against on navigation
+    rendered: RefCell<Option<tairitsu_vdom::VNode>>,
 }
 
 /// Initialize the router: read current URL, render initial route,
-/// and start polling for URL changes.
+/// and subscribe to `popstate` / `hashchange` on `window`.
 pub fn init_router(
     platform: WitPlatform,
     render_fn: impl Fn(&str) -> tairitsu_vdom::VNode + Send + Sync + 'static,
@@ -33,19 +47,25 @@ pub fn init_router(
     let inner = RouterServiceInner {
         platform,
         render_fn: Box::new(render_fn),
+        rendered: RefCell::new(None),
     };
-    let path = wit_platform::get_pathname();
+    let path = browser_location();
     do_render(&inner, &path);
+    subscribe_history_events(&inner.platform);
     INSTANCE.with(|i| *i.borrow_mut() = Some(inner));
     set_current_path(&path);
-    POLLING_ACTIVE.with(|a| a.set(true));
-    schedule_poll();
 }
 
-/// Stop the router and clean up polling.
+/// Stop the router and remove its `window` listeners.
 pub fn stop_router() {
-    POLLING_ACTIVE.with(|a| a.set(false));
-    INSTANCE.with(|i| *i.borrow_mut() = None);
+    let inner = INSTANCE.with(|i| i.borrow_mut().take());
+    if let Some(inner) = inner {
+        if LISTENING.with(|l| l.replace(false)) {
+            let window = WitElement::from_raw(WINDOW_TARGET);
+            inner.platform.remove_event_listener(&window, "popstate");
+            inner.platform.remove_event_listener(&window, "hashchange");
+        }
+    }
 }
 
 /// Programmatically navigate to a path (pushState + re-render).
@@ -58,7 +78,17 @@ pub fn navigate(path: &str) {
     do_navigate(&normalized);
 }
 
-/// Handle a popstate event (back/forward button) with new pathname.
+/// Navigate to a path without adding a history entry (replaceState + re-render).
+pub fn replace(path: &str) {
+    let normalized = normalize_path(path);
+    if normalized == current_path() {
+        return;
+    }
+    wit_platform::replace_state(&normalized);
+    do_navigate(&normalized);
+}
+
+/// Handle a popstate event (back/forward button) with the new location.
 pub fn on_popstate(new_path: &str) {
     let normalized = normalize_path(new_path);
     if normalized == current_path() {
@@ -67,6 +97,35 @@ pub fn on_popstate(new_path: &str) {
     do_navigate(&normalized);
 }
 
+/// Handle a hashchange event.
+///
+/// The pathname may be unchanged, so the current route is always re-rendered
+/// to let fragment-aware views update.
+pub fn on_hashchange() {
+    do_navigate(&browser_location());
+}
+
+/// Current browser location as `pathname?search#hash`.
+fn browser_location() -> String {
+    let mut location = wit_platform::get_pathname();
+    location.push_str(&wit_platform::get_search());
+    location.push_str(&wit_platform::get_hash());
+    normalize_path(&location)
+}
+
+fn subscribe_history_events(platform: &WitPlatform) {
+    if LISTENING.with(|l| l.replace(true)) {
+        return;
+    }
+    let window = WitElement::from_raw(WINDOW_TARGET);
+    platform.add_event_listener(
+        &window,
+        "popstate",
+        Box::new(|_| on_popstate(&browser_location())),
+    );
+    platform.add_event_listener(&window, "hashchange", Box::new(|_| on_hashchange()));
+}
+
 fn do_navigate(path: &str) {
     INSTANCE.with(|i| {
         if let Some(ref inner) = *i.borrow() {
@@ -78,53 +137,57 @@ fn do_navigate(path: &str) {
 
 fn do_render(inner: &RouterServiceInner, path: &str) {
     let vnode = (inner.render_fn)(path);
-    if let Err(e) = inner.platform.mount_vnode_to_app(vnode) {
-        tracing::error!("Failed to mount route '{}': {}", path, e);
+    let previous = inner.rendered.borrow_mut().take();
+
+    let result = match previous {
+        Some(old) => wit_platform::wasm_impl::app_element().and_then(|app| {
+            let patches = tairitsu_vdom::diff::diff(Some(&old), &vnode);
+            inner.platform.apply_patches(&app, &patches)
+        }),
+        None => inner.platform.mount_vnode_to_app(vnode.clone()),
+    };
+
+    match result {
+        Ok(()) => *inner.rendered.borrow_mut() = Some(vnode),
+        // The DOM no longer matches any known tree; remount on the next route.
+        Err(e) => tracing::error!("Failed to render route '{}': {}", path, e),
     }
 }
 
-fn schedule_poll() {
-    INSTANCE.with(|i| {
-        if let Some(ref inner) = *i.borrow() {
-            let _ = inner.platform.set_timeout(
-                Box::new(|| {
-                    poll_url_change();
-                }),
-                200,
-            );
-        }
-    });
-}
+/// Normalize the path part of a location, keeping its query and fragment.
+///
+/// A bare `?query` replaces the current query and fragment, and a bare
+/// `#hash` only the current fragment, as `SsrConfig::navigate` does.
+fn normalize_path(location: &str) -> String {
+    let resolved;
+    let location = if location.starts_with(['?', '#']) {
+        let current = current_path();
+        let keep = if location.starts_with('#') {
+            current.find('#')
+        } else {
+            current.find(['?', '#'])
+        };
+        resolved = format!("{}{}", &current[..keep.unwrap_or(current.len())], location);
+        resolved.as_str()
+    } else {
+        location
+    };
 
-fn poll_url_change() {
-    if !POLLING_ACTIVE.with(|a| a.get()) {
-        return;
-    }
-    let current = wit_platform::get_pathname();
-    if current != current_path() {
-        let normalized = normalize_path(&current);
-        set_current_path(&normalized);
-        INSTANCE.with(|i| {
-            if let Some(ref inner) = *i.borrow() {
-                do_render(inner, &normalized);
-            }
-        });
-    }
-    schedule_poll();
-}
+    let split = location.find(['?', '#']).unwrap_or(location.len());
+    let (path, suffix) = location.split_at(split);
 
-fn normalize_path(path: &str) -> String {
     let mut p = path.to_string();
     if p.ends_with('/') && p.len() > 1 {
         p.pop();
     }
     if p.is_empty() {
-        "/".to_string()
-    } else {
-        p
+        p.push('/');
     }
+    p.push_str(suffix);
+    p
 }
 
+/// Current location, including query string and fragment.
 pub fn current_path() -> String {
     CURRENT_PATH.with(|p| p.borrow().clone())
 }
@@ -0,0 +1,762 @@
+//! Client-side hydration of server-rendered markup.
+//!
+//! Instead of clearing `#app` and rebuilding the DOM, hydration walks the
+//! nodes produced by `tairitsu_ssr::render_to_html` alongside the `VNode`
+//! tree, adopts the existing element handles and only attaches listeners
+//! and reactive effects. Any divergence is repaired in place and recorded
+//! in a [`HydrationReport`].
+//!
+//! The walk itself is written against [`HydrationDom`], so the same code
+//! runs over the browser DOM and, in tests, over the mock platform.
+
+use std::fmt;
+
+use anyhow::Result;
+use tairitsu_vdom::{DynamicText, VElement, VNode};
+
+/// How [`ComponentRenderer::mount`](crate::runtime_integration::ComponentRenderer::mount)
+/// attaches a `VNode` tree to the root element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MountMode {
+    /// Clear the root element and render the tree from scratch.
+    #[default]
+    Replace,
+    /// Adopt the existing (server-rendered) DOM under the root element.
+    Hydrate,
+}
+
+/// The kind of divergence found between the DOM and the `VNode` tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MismatchKind {
+    /// The DOM node has a different tag (or node type) than expected.
+    Tag { expected: String, found: String },
+    /// A text node's content differs from the expected text.
+    Text { expected: String, found: String },
+    /// An attribute value differs (`found` is `None` when it is absent).
+    Attribute {
+        name: String,
+        expected: String,
+        found: Option<String>,
+    },
+    /// The `VNode` has no corresponding DOM node; it was created.
+    MissingNode { expected: String },
+    /// The DOM has a node the `VNode` tree does not describe; it was removed.
+    ExtraNode { found: String },
+}
+
+/// A single mismatch found during hydration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HydrationMismatch {
+    /// Child-index path from the root element, e.g. `"0/2/1"`.
+    pub path: String,
+    /// What differed.
+    pub kind: MismatchKind,
+}
+
+impl fmt::Display for HydrationMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            MismatchKind::Tag { expected, found } => {
+                write!(
+                    f,
+                    "[{}] expected <{}>, found {}",
+                    self.path, expected, found
+                )
+            }
+            MismatchKind::Text { expected, found } => {
+                write!(
+                    f,
+                    "[{}] expected text {:?}, found {:?}",
+                    self.path, expected, found
+                )
+            }
+            MismatchKind::Attribute {
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "[{}] attribute `{}` expected {:?}, found {:?}",
+                self.path, name, expected, found
+            ),
+            MismatchKind::MissingNode { expected } => {
+                write!(f, "[{}] missing node, expected {}", self.path, expected)
+            }
+            MismatchKind::ExtraNode { found } => {
+                write!(f, "[{}] unexpected node {}", self.path, found)
+            }
+        }
+    }
+}
+
+/// Result of a hydration pass.
+///
+/// Mismatches are repaired as they are found, so the DOM always ends up
+/// consistent with the `VNode` tree; the report is for diagnostics.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HydrationReport {
+    /// Number of existing DOM nodes that were adopted.
+    pub adopted: usize,
+    /// All mismatches, in document order.
+    pub mismatches: Vec<HydrationMismatch>,
+}
+
+impl HydrationReport {
+    /// Returns `true` if the server markup matched the `VNode` tree exactly.
+    pub fn is_clean(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    /// Record a mismatch at `path`.
+    pub fn push(&mut self, path: &str, kind: MismatchKind) {
+        let mismatch = HydrationMismatch {
+            path: path.to_string(),
+            kind,
+        };
+        tracing::warn!("hydration mismatch: {}", mismatch);
+        self.mismatches.push(mismatch);
+    }
+}
+
+/// Build the path of the `index`-th child below `parent`.
+pub(crate) fn child_path(parent: &str, index: usize) -> String {
+    if parent.is_empty() {
+        index.to_string()
+    } else {
+        format!("{}/{}", parent, index)
+    }
+}
+
+/// What hydration needs to know about an existing DOM node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    not(all(feature = "wit-bindings", target_family = "wasm")),
+    allow(dead_code)
+)]
+pub(crate) enum DomNodeKind {
+    /// An element, with its lower-case tag name.
+    Element(String),
+    /// A text node and its content.
+    Text(String),
+    /// Comments and other node types, by node name.
+    Other(String),
+}
+
+/// DOM access used by [`hydrate_children`].
+pub(crate) trait HydrationDom {
+    type Node: Clone;
+
+    fn first_child(&self, parent: &Self::Node) -> Option<Self::Node>;
+    fn next_sibling(&self, node: &Self::Node) -> Option<Self::Node>;
+    fn node_kind(&self, node: &Self::Node) -> DomNodeKind;
+    fn get_attribute(&self, element: &Self::Node, name: &str) -> Option<String>;
+    fn set_attribute(&self, element: &Self::Node, name: &str, value: &str);
+    /// Replace the content of a text node.
+    fn set_text(&self, text_node: &Self::Node, text: &str);
+    /// Create a text node and insert it before `reference`.
+    fn insert_text(&self, parent: &Self::Node, text: &str, reference: &Self::Node) -> Self::Node;
+    /// Render `vnode` from scratch and append it to `parent`.
+    fn append_vnode(&self, parent: &Self::Node, vnode: &VNode) -> Result<()>;
+    /// Render `vnode` from scratch in place of `old`, which is removed.
+    fn replace_with_vnode(
+        &self,
+        parent: &Self::Node,
+        old: &Self::Node,
+        vnode: &VNode,
+    ) -> Result<()>;
+    /// Remove `node` and release what the runtime registered for it.
+    fn remove(&self, parent: &Self::Node, node: &Self::Node);
+    /// Register an adopted element and attach its ref, listeners and
+    /// reactive attributes, styles and classes.
+    fn bind_element(&self, velement: &VElement, element: &Self::Node);
+    /// Keep an adopted text node in sync with `text`.
+    fn bind_text(&self, text_node: &Self::Node, text: &DynamicText);
+}
+
+/// Hydrate the children of `parent` against `vnodes`.
+#[cfg_attr(
+    not(all(feature = "wit-bindings", target_family = "wasm")),
+    allow(dead_code)
+)]
+pub(crate) fn hydrate_children<D: HydrationDom>(
+    dom: &D,
+    parent: &D::Node,
+    vnodes: &[VNode],
+    path: &str,
+    report: &mut HydrationReport,
+) -> Result<()> {
+    let mut children = Vec::new();
+    flatten_children(vnodes, &mut children);
+    let mut cursor = dom.first_child(parent);
+
+    for (index, vnode) in children.iter().enumerate() {
+        let expects_text = matches!(vnode, VNode::Text(_) | VNode::DynamicText(_));
+        while let Some(node) = &cursor {
+            let kind = dom.node_kind(node);
+            let skip = if expects_text {
+                matches!(kind, DomNodeKind::Other(_))
+            } else {
+                is_ignorable(&kind)
+            };
+            if !skip {
+                break;
+            }
+            cursor = dom.next_sibling(node);
+        }
+
+        cursor = hydrate_node(dom, parent, vnode, cursor, &child_path(path, index), report)?;
+    }
+
+    // Anything left over was not produced by this VNode tree.
+    let mut index = children.len();
+    while let Some(node) = cursor {
+        let next = dom.next_sibling(&node);
+        let kind = dom.node_kind(&node);
+        if !is_ignorable(&kind) {
+            report.push(
+                &child_path(path, index),
+                MismatchKind::ExtraNode {
+                    found: describe_node(&kind),
+                },
+            );
+            dom.remove(parent, &node);
+            index += 1;
+        }
+        cursor = next;
+    }
+
+    Ok(())
+}
+
+/// Inline fragments, since they produce no DOM node of their own.
+fn flatten_children<'a>(nodes: &'a [VNode], out: &mut Vec<&'a VNode>) {
+    for node in nodes {
+        match node {
+            VNode::Fragment(children) => flatten_children(children, out),
+            other => out.push(other),
+        }
+    }
+}
+
+/// Comments and whitespace-only text between elements carry no meaning.
+fn is_ignorable(kind: &DomNodeKind) -> bool {
+    match kind {
+        DomNodeKind::Element(_) => false,
+        DomNodeKind::Text(text) => text.trim().is_empty(),
+        DomNodeKind::Other(_) => true,
+    }
+}
+
+fn describe_node(kind: &DomNodeKind) -> String {
+    match kind {
+        DomNodeKind::Element(tag) => format!("<{}>", tag),
+        DomNodeKind::Text(_) => "#text".to_string(),
+        DomNodeKind::Other(name) => name.clone(),
+    }
+}
+
+fn describe_vnode(vnode: &VNode) -> String {
+    match vnode {
+        VNode::Element(velement) => format!("<{}>", velement.tag),
+        _ => "#text".to_string(),
+    }
+}
+
+/// Hydrate a single node and return the DOM node following it.
+fn hydrate_node<D: HydrationDom>(
+    dom: &D,
+    parent: &D::Node,
+    vnode: &VNode,
+    existing: Option<D::Node>,
+    path: &str,
+    report: &mut HydrationReport,
+) -> Result<Option<D::Node>> {
+    let Some(node) = existing else {
+        let is_empty_text = matches!(vnode, VNode::Text(t) if t.text.is_empty());
+        if !is_empty_text {
+            report.push(
+                path,
+                MismatchKind::MissingNode {
+                    expected: describe_vnode(vnode),
+                },
+            );
+        }
+        dom.append_vnode(parent, vnode)?;
+        return Ok(None);
+    };
+
+    match vnode {
+        VNode::Element(velement) => {
+            let kind = dom.node_kind(&node);
+            let tag_matches = matches!(
+                &kind,
+                DomNodeKind::Element(tag) if tag.eq_ignore_ascii_case(&velement.tag)
+            );
+
+            if !tag_matches {
+                report.push(
+                    path,
+                    MismatchKind::Tag {
+                        expected: velement.tag.clone(),
+                        found: describe_node(&kind),
+                    },
+                );
+                let next = dom.next_sibling(&node);
+                dom.replace_with_vnode(parent, &node, vnode)?;
+                return Ok(next);
+            }
+
+            hydrate_element(dom, velement, &node, path, report)?;
+            report.adopted += 1;
+            Ok(dom.next_sibling(&node))
+        }
+        VNode::Text(vtext) => {
+            let (_, next) = hydrate_text(dom, parent, &vtext.text, node, path, report);
+            Ok(next)
+        }
+        VNode::DynamicText(dt) => {
+            let (text_node, next) = hydrate_text(dom, parent, &dt.initial, node, path, report);
+            dom.bind_text(&text_node, dt);
+            Ok(next)
+        }
+        // Fragments are flattened by the caller.
+        VNode::Fragment(_) => Ok(Some(node)),
+    }
+}
+
+/// Adopt (or create) a text node; returns it and the DOM node following it.
+fn hydrate_text<D: HydrationDom>(
+    dom: &D,
+    parent: &D::Node,
+    expected: &str,
+    node: D::Node,
+    path: &str,
+    report: &mut HydrationReport,
+) -> (D::Node, Option<D::Node>) {
+    let DomNodeKind::Text(found) = dom.node_kind(&node) else {
+        if !expected.is_empty() {
+            report.push(
+                path,
+                MismatchKind::MissingNode {
+                    expected: "#text".to_string(),
+                },
+            );
+        }
+        let text_node = dom.insert_text(parent, expected, &node);
+        return (text_node, Some(node));
+    };
+
+    if found == expected {
+        report.adopted += 1;
+        let next = dom.next_sibling(&node);
+        return (node, next);
+    }
+
+    // The HTML parser merges adjacent text VNodes into one DOM text
+    // node, so split off our prefix and leave the rest for the next VNode.
+    if found.len() > expected.len() && found.starts_with(expected) {
+        let text_node = dom.insert_text(parent, expected, &node);
+        dom.set_text(&node, &found[expected.len()..]);
+        report.adopted += 1;
+        return (text_node, Some(node));
+    }
+
+    report.push(
+        path,
+        MismatchKind::Text {
+            expected: expected.to_string(),
+            found,
+        },
+    );
+    dom.set_text(&node, expected);
+    let next = dom.next_sibling(&node);
+    (node, next)
+}
+
+/// Bind an existing element to its `VElement` and hydrate its children.
+///
+/// Static attributes and the static class are compared before binding.
+/// Dynamic attributes, and `class` when dynamic classes own it, are read
+/// before and after [`HydrationDom::bind_element`] so that each compute
+/// runs only once, inside its effect; the effect has already repaired a
+/// mismatch by the time it is reported. Inline styles are not compared:
+/// static ones come from the same `VNode` as the markup and dynamic ones
+/// are rewritten by their effects.
+fn hydrate_element<D: HydrationDom>(
+    dom: &D,
+    velement: &VElement,
+    element: &D::Node,
+    path: &str,
+    report: &mut HydrationReport,
+) -> Result<()> {
+    let dynamic_class = !velement.dynamic_classes.is_empty();
+
+    let mut expected_attrs: Vec<(&str, &str)> = velement
+        .attributes
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .collect();
+    if !dynamic_class && !velement.class.static_classes.is_empty() {
+        expected_attrs.push(("class", velement.class.static_classes.as_str()));
+    }
+    for (name, value) in expected_attrs {
+        let found = dom.get_attribute(element, name);
+        if found.as_deref() != Some(value) {
+            report.push(
+                path,
+                MismatchKind::Attribute {
+                    name: name.to_string(),
+                    expected: value.to_string(),
+                    found,
+                },
+            );
+            dom.set_attribute(element, name, value);
+        }
+    }
+
+    let mut bound: Vec<&str> = velement
+        .dynamic_attributes
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect();
+    if dynamic_class {
+        bound.push("class");
+    }
+    let server_values: Vec<Option<String>> = bound
+        .iter()
+        .map(|name| dom.get_attribute(element, name))
+        .collect();
+
+    dom.bind_element(velement, element);
+
+    for (name, found) in bound.into_iter().zip(server_values) {
+        let expected = dom.get_attribute(element, name);
+        if expected != found {
+            report.push(
+                path,
+                MismatchKind::Attribute {
+                    name: name.to_string(),
+                    expected: expected.unwrap_or_default(),
+                    found,
+                },
+            );
+        }
+    }
+
+    if velement.inner_html.is_none() {
+        hydrate_children(dom, element, &velement.children, path, report)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use tairitsu_vdom::{
+        create_effect, el, txt, DomOps, EffectHandle, MockElement, MockPlatform, Signal,
+    };
+
+    use super::*;
+
+    /// [`HydrationDom`] over the mock platform, keeping its effects alive.
+    #[derive(Default)]
+    struct MockDom {
+        platform: MockPlatform,
+        effects: RefCell<Vec<EffectHandle>>,
+    }
+
+    impl HydrationDom for MockDom {
+        type Node = MockElement;
+
+        fn first_child(&self, parent: &MockElement) -> Option<MockElement> {
+            self.platform.first_child(parent)
+        }
+
+        fn next_sibling(&self, node: &MockElement) -> Option<MockElement> {
+            let siblings = self.platform.children(&self.platform.parent(node)?);
+            let index = siblings.iter().position(|sibling| sibling == node)?;
+            siblings.get(index + 1).copied()
+        }
+
+        fn node_kind(&self, node: &MockElement) -> DomNodeKind {
+            match self.platform.tag_name(node) {
+                Some(tag) => DomNodeKind::Element(tag),
+                None => DomNodeKind::Text(self.platform.text_content(node)),
+            }
+        }
+
+        fn get_attribute(&self, element: &MockElement, name: &str) -> Option<String> {
+            self.platform.get_attribute(element, name)
+        }
+
+        fn set_attribute(&self, element: &MockElement, name: &str, value: &str) {
+            self.platform.set_attribute(element, name, value);
+        }
+
+        fn set_text(&self, text_node: &MockElement, text: &str) {
+            self.platform.set_text_content(text_node, text);
+        }
+
+        fn insert_text(
+            &self,
+            parent: &MockElement,
+            text: &str,
+            reference: &MockElement,
+        ) -> MockElement {
+            let text_node = self.platform.create_text_node(text);
+            self.platform
+                .insert_before(parent, &text_node, Some(reference));
+            text_node
+        }
+
+        fn append_vnode(&self, parent: &MockElement, vnode: &VNode) -> Result<()> {
+            self.platform.render_into(parent, vnode);
+            Ok(())
+        }
+
+        fn replace_with_vnode(
+            &self,
+            parent: &MockElement,
+            old: &MockElement,
+            vnode: &VNode,
+        ) -> Result<()> {
+            for node in self.platform.render_into(parent, vnode) {
+                self.platform.insert_before(parent, &node, Some(old));
+            }
+            self.remove(parent, old);
+            Ok(())
+        }
+
+        fn remove(&self, parent: &MockElement, node: &MockElement) {
+            self.platform.remove_child(parent, node);
+        }
+
+        fn bind_element(&self, velement: &VElement, element: &MockElement) {
+            let bindings = velement
+                .dynamic_attributes
+                .iter()
+                .map(|(name, compute)| (name.clone(), compute.clone()))
+                .chain(
+                    velement
+                        .dynamic_classes
+                        .iter()
+                        .map(|compute| ("class".to_string(), compute.clone())),
+                );
+            for (name, compute) in bindings {
+                let (platform, element) = (self.platform.clone(), *element);
+                self.effects.borrow_mut().push(create_effect(move || {
+                    let value = (compute.borrow_mut())();
+                    platform.set_attribute(&element, &name, &value);
+                }));
+            }
+        }
+
+        fn bind_text(&self, text_node: &MockElement, text: &DynamicText) {
+            let (platform, text_node) = (self.platform.clone(), *text_node);
+            let compute = text.compute.clone();
+            self.effects.borrow_mut().push(create_effect(move || {
+                let value = (compute.borrow_mut())();
+                platform.set_text_content(&text_node, &value);
+            }));
+        }
+    }
+
+    impl MockDom {
+        /// Mount `server` as the existing markup, then hydrate it with `client`.
+        fn hydrate(&self, server: VNode, client: &VNode) -> HydrationReport {
+            self.platform.mount(&server);
+            let mut report = HydrationReport::default();
+            hydrate_children(
+                self,
+                &self.platform.body(),
+                std::slice::from_ref(client),
+                "",
+                &mut report,
+            )
+            .unwrap();
+            report
+        }
+    }
+
+    fn list(items: &[&str]) -> VNode {
+        let items = items
+            .iter()
+            .map(|item| VNode::Element(Box::new(el("li").child(txt(item)))))
+            .collect();
+        VNode::Element(Box::new(el("ul").children(items)))
+    }
+
+    fn kinds(report: &HydrationReport) -> Vec<(&str, &MismatchKind)> {
+        report
+            .mismatches
+            .iter()
+            .map(|m| (m.path.as_str(), &m.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_hydrate_adopts_matching_markup() {
+        let dom = MockDom::default();
+        let report = dom.hydrate(list(&["a", "b"]), &list(&["a", "b"]));
+
+        assert!(report.is_clean(), "{:?}", report.mismatches);
+        // ul, two li and two text nodes
+        assert_eq!(report.adopted, 5);
+    }
+
+    #[test]
+    fn test_hydrate_repairs_mismatched_text() {
+        let dom = MockDom::default();
+        let ul = dom.platform.mount(&list(&["a", "stale"]));
+        let client = list(&["a", "fresh"]);
+        let mut report = HydrationReport::default();
+        hydrate_children(
+            &dom,
+            &dom.platform.body(),
+            std::slice::from_ref(&client),
+            "",
+            &mut report,
+        )
+        .unwrap();
+
+        assert_eq!(
+            kinds(&report),
+            [(
+                "0/1/0",
+                &MismatchKind::Text {
+                    expected: "fresh".to_string(),
+                    found: "stale".to_string(),
+                }
+            )]
+        );
+        assert_eq!(dom.platform.children(&dom.platform.body()), [ul]);
+        assert_eq!(dom.platform.html(), "<ul><li>a</li><li>fresh</li></ul>");
+    }
+
+    #[test]
+    fn test_hydrate_removes_extra_and_creates_missing_children() {
+        let dom = MockDom::default();
+        let report = dom.hydrate(list(&["a", "b", "c"]), &list(&["a", "b"]));
+        assert_eq!(
+            kinds(&report),
+            [(
+                "0/2",
+                &MismatchKind::ExtraNode {
+                    found: "<li>".to_string(),
+                }
+            )]
+        );
+        assert_eq!(dom.platform.html(), "<ul><li>a</li><li>b</li></ul>");
+
+        let report = dom.hydrate(list(&["a"]), &list(&["a", "b"]));
+        assert_eq!(
+            kinds(&report),
+            [(
+                "0/1",
+                &MismatchKind::MissingNode {
+                    expected: "<li>".to_string(),
+                }
+            )]
+        );
+        assert_eq!(dom.platform.html(), "<ul><li>a</li><li>b</li></ul>");
+    }
+
+    #[test]
+    fn test_hydrate_checks_dynamic_attributes() {
+        let dom = MockDom::default();
+        let href = Signal::new("/new".to_string());
+        let client = VNode::Element(Box::new(
+            el("a")
+                .dynamic_attr("href", {
+                    let href = href.clone();
+                    move || href.get()
+                })
+                .dynamic_attr("title", || "Home".to_string())
+                .class("link")
+                .dynamic_class(|| "link active".to_string()),
+        ));
+        let server = VNode::Element(Box::new(
+            el("a")
+                .attr("href", "/old")
+                .attr("title", "Home")
+                .class("link"),
+        ));
+
+        let report = dom.hydrate(server, &client);
+        assert_eq!(
+            kinds(&report),
+            [
+                (
+                    "0",
+                    &MismatchKind::Attribute {
+                        name: "href".to_string(),
+                        expected: "/new".to_string(),
+                        found: Some("/old".to_string()),
+                    }
+                ),
+                (
+                    "0",
+                    &MismatchKind::Attribute {
+                        name: "class".to_string(),
+                        expected: "link active".to_string(),
+                        found: Some("link".to_string()),
+                    }
+                ),
+            ]
+        );
+
+        // The adopted element stays bound to the signal.
+        let a = dom.platform.children(&dom.platform.body())[0];
+        href.set("/newer".to_string());
+        assert_eq!(
+            dom.platform.get_attribute(&a, "href").as_deref(),
+            Some("/newer")
+        );
+    }
+
+    #[test]
+    fn test_mount_mode_default_is_replace() {
+        assert_eq!(MountMode::default(), MountMode::Replace);
+    }
+
+    #[test]
+    fn test_report_is_clean() {
+        let mut report = HydrationReport::default();
+        assert!(report.is_clean());
+
+        report.push(
+            "0/1",
+            MismatchKind::Tag {
+                expected: "span".to_string(),
+                found: "<div>".to_string(),
+            },
+        );
+        assert!(!report.is_clean());
+        assert_eq!(report.mismatches[0].path, "0/1");
+    }
+
+    #[test]
+    fn test_child_path() {
+        assert_eq!(child_path("", 0), "0");
+        assert_eq!(child_path("0", 2), "0/2");
+        assert_eq!(child_path("0/2", 1), "0/2/1");
+    }
+
+    #[test]
+    fn test_mismatch_display() {
+        let mismatch = HydrationMismatch {
+            path: "1".to_string(),
+            kind: MismatchKind::Attribute {
+                name: "href".to_string(),
+                expected: "/a".to_string(),
+                found: None,
+            },
+        };
+        assert_eq!(
+            mismatch.to_string(),
+            "[1] attribute `href` expected \"/a\", found None"
+        );
+    }
+}
@@ -6,6 +6,8 @@ pub mod browser;
 pub mod client_router;
 #[cfg(feature = "wit-bindings")]
 pub mod handle_cache;
+#[cfg(feature = "vdom")]
+pub mod hydration;
 #[cfg(feature = "wit-bindings")]
 pub mod navigation;
 pub mod prelude;
@@ -20,11 +22,17 @@ pub mod wit_platform;
 
 #[cfg(feature = "browser")]
 pub use browser::BrowserPlatform;
+#[cfg(feature = "vdom")]
+pub use hydration::{HydrationMismatch, HydrationReport, MismatchKind, MountMode};
 #[cfg(feature = "wit-bindings")]
 pub use navigation::{current_path, navigate, replace};
 pub use prelude::*;
 #[cfg(feature = "router")]
 pub use router::*;
+// The router's URL query types take precedence over the data-fetching
+// `tairitsu_hooks::{query, Query}` from the prelude.
+#[cfg(feature = "router")]
+pub use router::{query, Query};
 #[cfg(feature = "wit-bindings")]
 pub use runtime_integration::init_runtime;
 #[cfg(feature = "ssr")]
@@ -35,7 +43,26 @@ pub use wit_platform::prevent_event_default;
 pub use wit_platform::{fetch_text, fetch_text_with_options};
 #[cfg(feature = "wit-bindings")]
 pub use wit_platform::{
-    get_pathname, push_state, replace_state, WitElement, WitEvent, WitPlatform,
+    get_hash, get_origin, get_pathname, get_search, push_state, replace_state, WitElement,
+    WitEvent, WitPlatform,
 };
 #[cfg(all(feature = "wit-bindings", target_family = "wasm"))]
 pub use wit_platform::{ws_close, ws_connect, ws_send, WsConnection};
+
+/// Expanded by `#[derive(Routable)]` so that a missing `router` feature is
+/// reported as such instead of as an unresolved path.
+#[cfg(feature = "router")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __require_router {
+    () => {};
+}
+
+#[cfg(not(feature = "router"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __require_router {
+    () => {
+        compile_error!("#[derive(Routable)] requires the `router` feature of tairitsu-web");
+    };
+}
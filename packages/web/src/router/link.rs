@@ -0,0 +1,282 @@
+//! Client-side navigation links.
+//!
+//! [`Link`] renders a plain `<a href>` (so SSR output and crawlers see a real
+//! link) and intercepts same-origin clicks to navigate without a page load.
+//!
+//! # Example
+//!
+//! ```ignore
+//! rsx! {
+//!     nav {
+//!         Link { href: "/about".to_string(), "About" }
+//!         Link { href: "/login".to_string(), replace: true, "Log in" }
+//!     }
+//! }
+//! ```
+
+use tairitsu_vdom::{MouseEvent, VElement, VNode};
+
+/// Props for [`Link`].
+#[derive(Debug, Clone, Default)]
+pub struct LinkProps {
+    /// Target URL; same-origin targets are navigated client-side.
+    pub href: String,
+    /// CSS classes for the `<a>` element.
+    pub class: String,
+    /// Use `replaceState` instead of `pushState`.
+    pub replace: bool,
+    /// Link content.
+    pub children: VNode,
+}
+
+/// An `<a>` element that navigates through the client router.
+///
+/// Clicks with a modifier key or a non-primary button, and links to other
+/// origins, are left to the browser.
+#[allow(non_snake_case)]
+pub fn Link(props: LinkProps) -> VNode {
+    let href = props.href.clone();
+    let replace = props.replace;
+
+    let mut anchor = VElement::new("a").attr("href", props.href.as_str());
+    if !props.class.is_empty() {
+        anchor = anchor.class(props.class.as_str());
+    }
+
+    let anchor = anchor
+        .on_click(move |event: MouseEvent| {
+            if event.button != 0
+                || event.ctrl_key
+                || event.meta_key
+                || event.shift_key
+                || event.alt_key
+            {
+                return;
+            }
+            let Some(path) = local_href(&href, &current_origin(), &current_location()) else {
+                return;
+            };
+            event.prevent_default();
+            navigate_to(&path, replace);
+        })
+        .child(props.children);
+
+    VNode::Element(Box::new(anchor))
+}
+
+/// Resolve `href` to an in-app location if it points at `origin`.
+///
+/// Relative references (`#top`, `?q=x`, `edit`, `../list`) are resolved
+/// against `current`, the location the link is rendered at. Returns `None`
+/// for cross-origin URLs and non-HTTP schemes, which are left to the browser.
+pub fn local_href(href: &str, origin: &str, current: &str) -> Option<String> {
+    let href = href.trim();
+
+    if href.is_empty() || href.starts_with("//") {
+        return None;
+    }
+    if href.starts_with('/') {
+        return Some(resolve_dot_segments(href));
+    }
+
+    let current = if current.starts_with('/') {
+        current
+    } else {
+        "/"
+    };
+    if href.starts_with('#') {
+        let base = current.find('#').map_or(current, |i| &current[..i]);
+        return Some(format!("{base}{href}"));
+    }
+    if href.starts_with('?') {
+        let base = current.find(['?', '#']).map_or(current, |i| &current[..i]);
+        return Some(format!("{base}{href}"));
+    }
+
+    if !origin.is_empty() {
+        if let Some(rest) = href.strip_prefix(origin) {
+            if rest.is_empty() {
+                return Some("/".to_string());
+            }
+            if rest.starts_with(['/', '?', '#']) {
+                return local_href(rest, "", current);
+            }
+        }
+    }
+
+    if has_scheme(href) {
+        return None;
+    }
+
+    let path = current.find(['?', '#']).map_or(current, |i| &current[..i]);
+    let directory = &path[..=path.rfind('/').unwrap_or(0)];
+    Some(resolve_dot_segments(&format!("{directory}{href}")))
+}
+
+/// Whether `href` starts with a URL scheme such as `https:` or `mailto:`.
+fn has_scheme(href: &str) -> bool {
+    let end = href.find([':', '/', '?', '#']);
+    match end {
+        Some(i) if href[i..].starts_with(':') => {
+            let scheme = &href[..i];
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        _ => false,
+    }
+}
+
+/// Remove `.` and `..` segments from the path part of an absolute location.
+fn resolve_dot_segments(location: &str) -> String {
+    let split = location.find(['?', '#']).unwrap_or(location.len());
+    let (path, suffix) = location.split_at(split);
+
+    let mut segments: Vec<&str> = Vec::new();
+    let mut parts = path[1..].split('/').peekable();
+    while let Some(segment) = parts.next() {
+        let last = parts.peek().is_none();
+        match segment {
+            "." => {}
+            ".." => {
+                segments.pop();
+            }
+            _ => {
+                segments.push(segment);
+                continue;
+            }
+        }
+        // A trailing `.` or `..` names a directory.
+        if last {
+            segments.push("");
+        }
+    }
+
+    format!("/{}{}", segments.join("/"), suffix)
+}
+
+fn current_origin() -> String {
+    #[cfg(feature = "wit-bindings")]
+    {
+        crate::wit_platform::get_origin()
+    }
+    #[cfg(not(feature = "wit-bindings"))]
+    {
+        String::new()
+    }
+}
+
+fn current_location() -> String {
+    #[cfg(all(feature = "wit-bindings", target_family = "wasm"))]
+    {
+        crate::client_router::current_path()
+    }
+    #[cfg(not(all(feature = "wit-bindings", target_family = "wasm")))]
+    {
+        "/".to_string()
+    }
+}
+
+fn navigate_to(path: &str, replace: bool) {
+    #[cfg(all(feature = "wit-bindings", target_family = "wasm"))]
+    {
+        if replace {
+            crate::client_router::replace(path);
+        } else {
+            crate::client_router::navigate(path);
+        }
+    }
+    #[cfg(not(all(feature = "wit-bindings", target_family = "wasm")))]
+    {
+        let _ = (path, replace);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_href_absolute_path() {
+        assert_eq!(
+            local_href("/about", "https://example.com", "/"),
+            Some("/about".to_string())
+        );
+        assert_eq!(local_href("/", "", "/"), Some("/".to_string()));
+        assert_eq!(local_href("/a/./b/../c", "", "/"), Some("/a/c".to_string()));
+    }
+
+    #[test]
+    fn test_local_href_same_origin_url() {
+        let origin = "https://example.com";
+        assert_eq!(
+            local_href("https://example.com/users/1?tab=2", origin, "/"),
+            Some("/users/1?tab=2".to_string())
+        );
+        assert_eq!(
+            local_href("https://example.com", origin, "/"),
+            Some("/".to_string())
+        );
+        assert_eq!(
+            local_href("https://example.com#top", origin, "/docs?v=1"),
+            Some("/docs?v=1#top".to_string())
+        );
+    }
+
+    #[test]
+    fn test_local_href_rejects_external() {
+        let origin = "https://example.com";
+        assert_eq!(local_href("https://other.com/", origin, "/"), None);
+        assert_eq!(
+            local_href("https://example.com.evil.io/", origin, "/"),
+            None
+        );
+        assert_eq!(local_href("//cdn.example.com/x", origin, "/"), None);
+        assert_eq!(local_href("mailto:a@b.c", origin, "/"), None);
+        assert_eq!(local_href("", origin, "/"), None);
+    }
+
+    #[test]
+    fn test_local_href_fragment_and_query() {
+        let current = "/docs/intro?v=1#old";
+        assert_eq!(
+            local_href("#top", "", current),
+            Some("/docs/intro?v=1#top".to_string())
+        );
+        assert_eq!(
+            local_href("?q=x", "", current),
+            Some("/docs/intro?q=x".to_string())
+        );
+    }
+
+    #[test]
+    fn test_local_href_relative_path() {
+        let current = "/docs/intro?v=1";
+        assert_eq!(
+            local_href("setup", "", current),
+            Some("/docs/setup".to_string())
+        );
+        assert_eq!(
+            local_href("../blog/post:1?x#y", "", current),
+            Some("/blog/post:1?x#y".to_string())
+        );
+        assert_eq!(local_href("./", "", current), Some("/docs/".to_string()));
+        assert_eq!(local_href("..", "", current), Some("/".to_string()));
+        assert_eq!(local_href("../../..", "", "/a/b"), Some("/".to_string()));
+    }
+
+    #[test]
+    fn test_link_renders_anchor() {
+        let vnode = Link(LinkProps {
+            href: "/about".to_string(),
+            class: "nav".to_string(),
+            children: VNode::Text(tairitsu_vdom::VText::new("About")),
+            ..Default::default()
+        });
+        assert_eq!(
+            vnode.render_to_html(),
+            r#"<a href="/about" class="nav">About</a>"#
+        );
+    }
+}
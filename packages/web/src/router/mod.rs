@@ -4,6 +4,9 @@
 //! - Static routes (e.g., `/about`, `/contact`)
 //! - Dynamic routes (e.g., `/users/:id`, `/posts/:slug`)
 //! - Route guards/middleware
+//! - Nested layouts rendered through an [`Outlet`]
+//! - Query strings and fragments (`/search?q=x#top`)
+//! - Typed routes via [`Routable`]
 //! - 404 fallback
 //!
 //! # Example
@@ -19,11 +22,31 @@
 //!
 //! let vnode = router.render("/users/123");
 //! ```
+//!
+//! Nested layouts:
+//!
+//! ```ignore
+//! let router = Router::new().nest(
+//!     Route::layout("/admin", admin_shell) // renders `Outlet()` somewhere
+//!         .middleware(require_admin)
+//!         .child(Route::new("", admin_home))
+//!         .child(Route::new("users/:id", admin_user))
+//!         .fallback(admin_not_found),
+//! );
+//! ```
 
+pub mod link;
+pub mod outlet;
+pub mod query;
+pub mod routable;
 pub mod segment;
 
-use std::{collections::HashMap, sync::Arc};
+use std::{cell::RefCell, collections::HashMap, sync::Arc};
 
+pub use link::{local_href, Link, LinkProps};
+pub use outlet::{fill_outlet, Outlet, OUTLET_TAG};
+pub use query::{split_url, Query};
+pub use routable::Routable;
 pub use segment::{RouteSegment, SegmentType};
 use tairitsu_vdom::VNode;
 use thiserror::Error;
@@ -41,6 +64,10 @@ pub struct Route {
     pub middleware: Vec<RouteMiddleware>,
     /// Whether this is an exact match or prefix match
     pub exact: bool,
+    /// Nested routes rendered into this route's [`Outlet`]
+    pub children: Vec<Route>,
+    /// Rendered into the [`Outlet`] when no child matches
+    pub fallback: Option<RouteHandler>,
 }
 
 impl Route {
@@ -52,6 +79,8 @@ impl Route {
             name: None,
             middleware: Vec::new(),
             exact: true,
+            children: Vec::new(),
+            fallback: None,
         }
     }
 
@@ -63,9 +92,37 @@ impl Route {
             name: None,
             middleware: Vec::new(),
             exact: false,
+            children: Vec::new(),
+            fallback: None,
         }
     }
 
+    /// Create a layout route
+    ///
+    /// The handler renders the shell and places an [`Outlet`] where the
+    /// matched child should appear. Child paths are relative to `path`, and
+    /// children see the params as left by this route's middleware.
+    pub fn layout(path: impl Into<String>, handler: impl Into<RouteHandler>) -> Self {
+        Self::prefix(path, handler)
+    }
+
+    /// Add a nested child route
+    pub fn child(mut self, route: Route) -> Self {
+        self.children.push(route);
+        self
+    }
+
+    /// Set the handler rendered into the outlet when no child matches
+    pub fn fallback(mut self, handler: impl Into<RouteHandler>) -> Self {
+        self.fallback = Some(handler.into());
+        self
+    }
+
+    /// Whether this route renders nested routes through an outlet
+    pub fn is_layout(&self) -> bool {
+        !self.children.is_empty() || self.fallback.is_some()
+    }
+
     /// Set the route name
     pub fn name(mut self, name: impl Into<String>) -> Self {
         self.name = Some(name.into());
@@ -109,6 +166,21 @@ pub struct RouteMatch {
     pub route: Route,
     /// The extracted parameters
     pub params: Params,
+    /// Enclosing layouts, outermost first
+    pub layouts: Vec<RouteLevel>,
+    /// Parsed query string parameters
+    pub query: Query,
+    /// URL fragment without the leading `#`
+    pub fragment: Option<String>,
+}
+
+/// A layout level of a nested route match
+#[derive(Clone)]
+pub struct RouteLevel {
+    /// The layout route
+    pub route: Route,
+    /// Parameters captured up to and including this level
+    pub params: Params,
 }
 
 /// A router that matches paths to routes
@@ -164,6 +236,18 @@ impl Router {
         self
     }
 
+    /// Add a route tree (typically a [`Route::layout`] with children)
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// router.nest(Route::layout("/app", shell).child(Route::new("settings", settings)));
+    /// ```
+    pub fn nest(mut self, route: Route) -> Self {
+        self.routes.push(route);
+        self
+    }
+
     /// Add a named route
     ///
     /// # Example
@@ -227,64 +311,97 @@ impl Router {
     /// }
     /// ```
     pub fn match_route(&self, path: impl AsRef<str>) -> Option<RouteMatch> {
-        let path = path.as_ref().trim_start_matches('/');
-        let path = path.trim_end_matches('/');
-
-        for route in &self.routes {
-            let pattern = route.path.trim_start_matches('/');
-            let pattern = pattern.trim_end_matches('/');
-
-            if let Some(params) = self.match_pattern(pattern, path) {
-                return Some(RouteMatch {
-                    route: route.clone(),
-                    params,
-                });
-            }
-        }
-
-        None
+        let (path, query, fragment) = split_url(path.as_ref());
+        let path_segments = split_segments(path);
+        let mut matched = Self::match_routes(&self.routes, &path_segments, &Params::new())?;
+        matched.query = query.map(Query::parse).unwrap_or_default();
+        matched.fragment = fragment.map(str::to_string);
+        Some(matched)
     }
 
-    /// Match a pattern against a path
-    fn match_pattern(&self, pattern: &str, path: &str) -> Option<Params> {
-        let pattern_segments: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
-        let path_segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
-
-        let mut params = Params::new();
-
-        // For exact match, segment counts must match
-        if pattern_segments.len() != path_segments.len() {
-            return None;
-        }
+    /// Match path segments against a list of (possibly nested) routes
+    fn match_routes(routes: &[Route], segments: &[&str], inherited: &Params) -> Option<RouteMatch> {
+        for route in routes {
+            let pattern = split_segments(&route.path);
+
+            if !route.is_layout() {
+                if let Some(own) = match_pattern(&pattern, segments) {
+                    let mut params = inherited.clone();
+                    params.extend(own);
+                    return Some(RouteMatch {
+                        route: route.clone(),
+                        params,
+                        layouts: Vec::new(),
+                        query: Query::default(),
+                        fragment: None,
+                    });
+                }
+                continue;
+            }
 
-        for (pattern_seg, path_seg) in pattern_segments.iter().zip(path_segments.iter()) {
-            if let Some(param_name) = pattern_seg.strip_prefix(':') {
-                // Dynamic segment - extract parameter
-                params.insert(param_name.to_string(), path_seg.to_string());
-            } else if **pattern_seg == *"*" {
-                // Wildcard - match anything
-            } else if pattern_seg != path_seg {
-                // Static segment - must match exactly
-                return None;
+            // Layouts match a prefix; the rest is resolved by their children.
+            if pattern.len() > segments.len() {
+                continue;
+            }
+            let Some(own) = match_pattern(&pattern, &segments[..pattern.len()]) else {
+                continue;
+            };
+            let mut params = inherited.clone();
+            params.extend(own);
+
+            let rest = &segments[pattern.len()..];
+            let leaf = Self::match_routes(&route.children, rest, &params).or_else(|| {
+                route.fallback.as_ref().map(|fallback| RouteMatch {
+                    route: Route::new(route.path.clone(), fallback.clone()),
+                    params: params.clone(),
+                    layouts: Vec::new(),
+                    query: Query::default(),
+                    fragment: None,
+                })
+            });
+
+            if let Some(mut matched) = leaf {
+                matched.layouts.insert(
+                    0,
+                    RouteLevel {
+                        route: route.clone(),
+                        params,
+                    },
+                );
+                return Some(matched);
             }
         }
 
-        Some(params)
+        None
     }
 
     /// Render a route by matching the path
     ///
     /// Returns the VNode for the matched route, or the fallback if no match.
+    /// The query and fragment of `path` are available to handlers through
+    /// [`current_query`] and [`current_fragment`] while rendering.
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let vnode = router.render("/users/123");
+    /// let vnode = router.render("/users/123?tab=posts");
     /// ```
     pub fn render(&self, path: impl AsRef<str>) -> VNode {
+        let (_, query, fragment) = split_url(path.as_ref());
+        let location = (
+            query.map(Query::parse).unwrap_or_default(),
+            fragment.map(str::to_string),
+        );
+        let previous = CURRENT_LOCATION.with(|l| l.replace(location));
+        let vnode = self.render_matched(path.as_ref());
+        CURRENT_LOCATION.with(|l| *l.borrow_mut() = previous);
+        vnode
+    }
+
+    fn render_matched(&self, path: &str) -> VNode {
         if let Some(matched) = self.match_route(path) {
             // Apply global middleware first
-            let mut params = matched.params;
+            let mut params = matched.params.clone();
 
             for middleware in &self.global_middleware {
                 if let Err(e) = middleware(&mut params) {
@@ -292,6 +409,26 @@ impl Router {
                 }
             }
 
+            // Apply layout middleware, outermost first. Whatever global or
+            // layout middleware does to the params is inherited by the
+            // levels below.
+            let mut layouts = Vec::with_capacity(matched.layouts.len());
+            let mut parent_matched = matched.params.clone();
+            let mut parent_params = params;
+            for level in matched.layouts {
+                let mut level_params =
+                    rebase_params(&level.params, &parent_matched, &parent_params);
+                for middleware in &level.route.middleware {
+                    if let Err(e) = middleware(&mut level_params) {
+                        return self.render_error(e);
+                    }
+                }
+                parent_matched = level.params;
+                parent_params = level_params.clone();
+                layouts.push((level.route.handler, level_params));
+            }
+            let mut params = rebase_params(&matched.params, &parent_matched, &parent_params);
+
             // Apply route-specific middleware
             for middleware in &matched.route.middleware {
                 if let Err(e) = middleware(&mut params) {
@@ -299,8 +436,12 @@ impl Router {
                 }
             }
 
-            // Render the route
-            (matched.route.handler)(params)
+            // Render the route, then wrap it in its layouts innermost first
+            let mut vnode = (matched.route.handler)(params);
+            for (handler, level_params) in layouts.into_iter().rev() {
+                vnode = fill_outlet(handler(level_params), vnode);
+            }
+            vnode
         } else if let Some(fallback) = &self.fallback {
             (fallback)(Params::new())
         } else {
@@ -354,16 +495,119 @@ impl Router {
     /// // Returns: Some("/users/123".to_string())
     /// ```
     pub fn url_for(&self, name: &str, params: &[(&str, &str)]) -> Option<String> {
-        for route in &self.routes {
-            if route.name.as_deref() == Some(name) {
-                let mut url = route.path.clone();
-                for (key, value) in params {
-                    url = url.replace(&format!(":{}", key), value);
-                }
-                return Some(url);
-            }
+        let mut url = find_named_path(&self.routes, name, "")?;
+        for (key, value) in params {
+            url = url.replace(&format!(":{}", key), value);
         }
-        None
+        Some(url)
+    }
+
+    /// Generate a URL for a named route with parameters and a query string
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let url = router.url_for_with_query("search", &[], &[("q", "rust wasm"), ("tag", "a")]);
+    /// // Returns: Some("/search?q=rust%20wasm&tag=a".to_string())
+    /// ```
+    pub fn url_for_with_query(
+        &self,
+        name: &str,
+        params: &[(&str, &str)],
+        query: &[(&str, &str)],
+    ) -> Option<String> {
+        let url = self.url_for(name, params)?;
+        if query.is_empty() {
+            return Some(url);
+        }
+        Some(format!(
+            "{}?{}",
+            url,
+            Query::from_pairs(query).to_query_string()
+        ))
+    }
+}
+
+thread_local! {
+    static CURRENT_LOCATION: RefCell<(Query, Option<String>)> = RefCell::new(Default::default());
+}
+
+/// Query parameters of the URL being rendered by [`Router::render`]
+pub fn current_query() -> Query {
+    CURRENT_LOCATION.with(|l| l.borrow().0.clone())
+}
+
+/// Fragment of the URL being rendered by [`Router::render`]
+pub fn current_fragment() -> Option<String> {
+    CURRENT_LOCATION.with(|l| l.borrow().1.clone())
+}
+
+/// Split a path into its non-empty segments
+fn split_segments(path: &str) -> Vec<&str> {
+    path.split('/').filter(|s| !s.is_empty()).collect()
+}
+
+/// Match pattern segments against path segments (segment counts must match)
+fn match_pattern(pattern_segments: &[&str], path_segments: &[&str]) -> Option<Params> {
+    if pattern_segments.len() != path_segments.len() {
+        return None;
+    }
+
+    let mut params = Params::new();
+
+    for (pattern_seg, path_seg) in pattern_segments.iter().zip(path_segments.iter()) {
+        if let Some(param_name) = pattern_seg.strip_prefix(':') {
+            // Dynamic segment - extract parameter
+            params.insert(param_name.to_string(), path_seg.to_string());
+        } else if *pattern_seg == "*" {
+            // Wildcard - match anything
+        } else if pattern_seg != path_seg {
+            // Static segment - must match exactly
+            return None;
+        }
+    }
+
+    Some(params)
+}
+
+/// Move `params`, matched below a level whose params were `parent_matched`,
+/// onto `parent_params`, the parent's params after its middleware ran
+///
+/// Only entries captured (or changed) below the parent are carried over.
+fn rebase_params(params: &Params, parent_matched: &Params, parent_params: &Params) -> Params {
+    let mut rebased = parent_params.clone();
+    rebased.extend(
+        params
+            .iter()
+            .filter(|(key, value)| parent_matched.get(*key) != Some(*value))
+            .map(|(key, value)| (key.clone(), value.clone())),
+    );
+    rebased
+}
+
+/// Find the full path of a named route, descending into nested routes
+fn find_named_path(routes: &[Route], name: &str, base: &str) -> Option<String> {
+    for route in routes {
+        let full = join_paths(base, &route.path);
+        if route.name.as_deref() == Some(name) {
+            return Some(full);
+        }
+        if let Some(path) = find_named_path(&route.children, name, &full) {
+            return Some(path);
+        }
+    }
+    None
+}
+
+/// Join a parent path and a relative child path
+fn join_paths(base: &str, path: &str) -> String {
+    let base = base.trim_end_matches('/');
+    let path = path.trim_matches('/');
+    match (base.is_empty(), path.is_empty()) {
+        (true, true) => "/".to_string(),
+        (true, false) => format!("/{}", path),
+        (false, true) => base.to_string(),
+        (false, false) => format!("{}/{}", base, path),
     }
 }
 
@@ -456,4 +700,229 @@ mod tests {
         assert_eq!(segments[2].to_string(), "posts");
         assert!(segments[3].is_dynamic());
     }
+
+    fn text(s: &str) -> VNode {
+        VNode::Text(tairitsu_vdom::VText::new(s))
+    }
+
+    fn shell(tag: &'static str) -> RouteHandler {
+        Arc::new(move |_params| {
+            VNode::Element(Box::new(tairitsu_vdom::VElement::new(tag).child(Outlet())))
+        })
+    }
+
+    fn nested_router() -> Router {
+        Router::new().nest(
+            Route::layout("/admin", shell("section"))
+                .child(Route::new("", wrap_handler(|_| text("home"))))
+                .child(
+                    Route::layout("users/:id", shell("article")).child(Route::new(
+                        "posts/:post",
+                        Arc::new(|params: Params| {
+                            text(&format!("{}:{}", params["id"], params["post"]))
+                        }) as RouteHandler,
+                    )),
+                )
+                .fallback(wrap_handler(|_| text("admin 404"))),
+        )
+    }
+
+    #[test]
+    fn test_nested_layout_render() {
+        let router = nested_router();
+
+        assert_eq!(
+            router.render("/admin").render_to_html(),
+            "<section>home</section>"
+        );
+        assert_eq!(
+            router.render("/admin/users/7/posts/9").render_to_html(),
+            "<section><article>7:9</article></section>"
+        );
+    }
+
+    #[test]
+    fn test_nested_layout_params_per_level() {
+        let router = nested_router();
+        let matched = router.match_route("/admin/users/7/posts/9").unwrap();
+
+        assert_eq!(matched.layouts.len(), 2);
+        assert!(matched.layouts[0].params.is_empty());
+        assert_eq!(matched.layouts[1].params.get("id"), Some(&"7".to_string()));
+        assert_eq!(matched.params.get("post"), Some(&"9".to_string()));
+        assert_eq!(matched.params.get("id"), Some(&"7".to_string()));
+    }
+
+    #[test]
+    fn test_nested_layout_fallback() {
+        let router = nested_router().fallback(wrap_handler(|_| text("root 404")));
+
+        assert_eq!(
+            router.render("/admin/unknown").render_to_html(),
+            "<section>admin 404</section>"
+        );
+        assert_eq!(router.render("/other").render_to_html(), "root 404");
+    }
+
+    #[test]
+    fn test_nested_layout_middleware() {
+        let deny: RouteMiddleware = Arc::new(|_params| Err(MiddlewareError::Forbidden));
+        let router = Router::new().nest(
+            Route::layout("/private", shell("div"))
+                .middleware(deny)
+                .child(Route::new("page", wrap_handler(|_| text("secret")))),
+        );
+
+        let html = router.render("/private/page").render_to_html();
+        assert!(html.contains("Error: Forbidden"));
+        assert!(!html.contains("secret"));
+    }
+
+    #[test]
+    fn test_layout_middleware_params_reach_children() {
+        // Resolve `/users/me/...` to the signed-in user before the children run.
+        let resolve_me: RouteMiddleware = Arc::new(|params| {
+            if params.get("id").map(String::as_str) == Some("me") {
+                params.insert("id".to_string(), "42".to_string());
+            }
+            params.insert("viewer".to_string(), "42".to_string());
+            Ok(())
+        });
+        let require_viewer: RouteMiddleware = Arc::new(|params| match params.get("viewer") {
+            Some(_) => Ok(()),
+            None => Err(MiddlewareError::Unauthorized),
+        });
+        let router = Router::new().nest(
+            Route::layout("/users/:id", shell("section"))
+                .middleware(resolve_me)
+                .child(
+                    Route::layout(
+                        "posts",
+                        Arc::new(|params: Params| {
+                            VNode::Element(Box::new(
+                                tairitsu_vdom::VElement::new("article")
+                                    .attr("data-user", params["id"].as_str())
+                                    .child(Outlet()),
+                            ))
+                        }) as RouteHandler,
+                    )
+                    .child(
+                        Route::new(
+                            ":post",
+                            Arc::new(|params: Params| {
+                                text(&format!("{}:{}", params["id"], params["post"]))
+                            }) as RouteHandler,
+                        )
+                        .middleware(require_viewer),
+                    ),
+                ),
+        );
+
+        assert_eq!(
+            router.render("/users/me/posts/9").render_to_html(),
+            "<section><article data-user=\"42\">42:9</article></section>"
+        );
+        assert_eq!(
+            router.render("/users/7/posts/9").render_to_html(),
+            "<section><article data-user=\"7\">7:9</article></section>"
+        );
+    }
+
+    #[test]
+    fn test_global_middleware_params_reach_layouts() {
+        let lowercase_lang: RouteMiddleware = Arc::new(|params| {
+            if let Some(lang) = params.get_mut("lang") {
+                *lang = lang.to_lowercase();
+            }
+            Ok(())
+        });
+        let require_known_lang: RouteMiddleware =
+            Arc::new(|params| match params.get("lang").map(String::as_str) {
+                Some("en") | Some("ja") => Ok(()),
+                _ => Err(MiddlewareError::Custom("unknown language".to_string())),
+            });
+        let router = Router::new().middleware(lowercase_lang).nest(
+            Route::layout(
+                "/:lang",
+                Arc::new(|params: Params| {
+                    VNode::Element(Box::new(
+                        tairitsu_vdom::VElement::new("main")
+                            .attr("lang", params["lang"].as_str())
+                            .child(Outlet()),
+                    ))
+                }) as RouteHandler,
+            )
+            .middleware(require_known_lang)
+            .child(Route::new(
+                "docs/:page",
+                Arc::new(|params: Params| text(&format!("{}/{}", params["lang"], params["page"])))
+                    as RouteHandler,
+            )),
+        );
+
+        assert_eq!(
+            router.render("/EN/docs/intro").render_to_html(),
+            "<main lang=\"en\">en/intro</main>"
+        );
+    }
+
+    #[test]
+    fn test_nested_url_for() {
+        let router = Router::new().nest(
+            Route::layout("/admin", shell("div"))
+                .child(Route::new("users/:id", wrap_handler(mock_handler)).name("admin_user")),
+        );
+
+        assert_eq!(
+            router.url_for("admin_user", &[("id", "5")]),
+            Some("/admin/users/5".to_string())
+        );
+    }
+
+    #[test]
+    fn test_match_route_with_query_and_fragment() {
+        let router = Router::new().route("/search", wrap_handler(mock_handler));
+
+        let matched = router.match_route("/search?q=x&tag=a&tag=b#top").unwrap();
+        assert_eq!(matched.query.get("q"), Some("x"));
+        assert_eq!(matched.query.get_all("tag"), vec!["a", "b"]);
+        assert_eq!(matched.fragment.as_deref(), Some("top"));
+
+        let matched = router.match_route("/search").unwrap();
+        assert!(matched.query.is_empty());
+        assert_eq!(matched.fragment, None);
+    }
+
+    #[test]
+    fn test_render_exposes_query() {
+        let router = Router::new().route(
+            "/items",
+            wrap_handler(|_| {
+                let page = current_query().get_as::<u32>("page").unwrap_or(1);
+                let fragment = current_fragment().unwrap_or_default();
+                text(&format!("page {} #{}", page, fragment))
+            }),
+        );
+
+        assert_eq!(
+            router.render("/items?page=3#list").render_to_html(),
+            "page 3 #list"
+        );
+        assert_eq!(router.render("/items").render_to_html(), "page 1 #");
+        assert!(current_query().is_empty());
+    }
+
+    #[test]
+    fn test_url_for_with_query() {
+        let router = Router::new().named_route("user", "/users/:id", wrap_handler(mock_handler));
+
+        assert_eq!(
+            router.url_for_with_query("user", &[("id", "1")], &[("tab", "a b"), ("x", "1")]),
+            Some("/users/1?tab=a%20b&x=1".to_string())
+        );
+        assert_eq!(
+            router.url_for_with_query("user", &[("id", "1")], &[]),
+            Some("/users/1".to_string())
+        );
+    }
 }
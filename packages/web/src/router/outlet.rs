@@ -0,0 +1,89 @@
+//! Outlet placeholder for nested layouts.
+//!
+//! A layout route renders its shell with an [`Outlet`] where the matched
+//! child route should appear. After the child is rendered, the router
+//! replaces the placeholder with the child's `VNode`.
+
+use tairitsu_vdom::{VElement, VNode};
+
+/// Tag name of the placeholder element emitted by [`Outlet`].
+pub const OUTLET_TAG: &str = "tairitsu-outlet";
+
+/// Placeholder for the matched child route inside a layout.
+///
+/// # Example
+///
+/// ```ignore
+/// Route::layout("/admin", |_params| rsx! {
+///     div { class: "admin-shell",
+///         nav { "Admin" }
+///         main { Outlet() }
+///     }
+/// })
+/// ```
+#[allow(non_snake_case)]
+pub fn Outlet() -> VNode {
+    VNode::Element(Box::new(VElement::new(OUTLET_TAG)))
+}
+
+/// Replace the first [`Outlet`] in `layout` with `content`.
+///
+/// If the layout contains no outlet, `content` is dropped and a warning is
+/// logged.
+pub fn fill_outlet(mut layout: VNode, content: VNode) -> VNode {
+    let mut content = Some(content);
+    if !replace_outlet(&mut layout, &mut content) {
+        tracing::warn!("layout rendered without an Outlet; child route content is discarded");
+    }
+    layout
+}
+
+fn replace_outlet(node: &mut VNode, content: &mut Option<VNode>) -> bool {
+    match node {
+        VNode::Element(el) if el.tag == OUTLET_TAG => {
+            *node = content.take().unwrap_or_default();
+            true
+        }
+        VNode::Element(el) => el
+            .children
+            .iter_mut()
+            .any(|child| replace_outlet(child, content)),
+        VNode::Fragment(children) => children
+            .iter_mut()
+            .any(|child| replace_outlet(child, content)),
+        VNode::Text(_) | VNode::DynamicText(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tairitsu_vdom::VText;
+
+    use super::*;
+
+    #[test]
+    fn test_fill_outlet_nested() {
+        let layout = VNode::Element(Box::new(VElement::new("div").child(VNode::Element(
+            Box::new(VElement::new("main").child(Outlet())),
+        ))));
+        let filled = fill_outlet(layout, VNode::Text(VText::new("page")));
+        assert_eq!(filled.render_to_html(), "<div><main>page</main></div>");
+    }
+
+    #[test]
+    fn test_fill_outlet_only_first() {
+        let layout = VNode::Fragment(vec![Outlet(), Outlet()]);
+        let filled = fill_outlet(layout, VNode::Text(VText::new("x")));
+        assert_eq!(
+            filled.render_to_html(),
+            "x<tairitsu-outlet></tairitsu-outlet>"
+        );
+    }
+
+    #[test]
+    fn test_fill_outlet_missing() {
+        let layout = VNode::Text(VText::new("shell"));
+        let filled = fill_outlet(layout, VNode::Text(VText::new("page")));
+        assert_eq!(filled.render_to_html(), "shell");
+    }
+}
@@ -0,0 +1,229 @@
+//! Query string and fragment handling for route URLs.
+
+use std::str::FromStr;
+
+/// Parsed query string parameters.
+///
+/// Keys may repeat (`?tag=a&tag=b`); insertion order is preserved.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Query {
+    pairs: Vec<(String, String)>,
+}
+
+impl Query {
+    /// Create an empty query
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a query string, with or without the leading `?`
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let query = Query::parse("?q=rust+wasm&tag=a&tag=b");
+    /// assert_eq!(query.get("q"), Some("rust wasm"));
+    /// assert_eq!(query.get_all("tag"), vec!["a", "b"]);
+    /// ```
+    pub fn parse(query: &str) -> Self {
+        let query = query.strip_prefix('?').unwrap_or(query);
+        let pairs = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (decode_component(key), decode_component(value))
+            })
+            .collect();
+        Self { pairs }
+    }
+
+    /// Build a query from key/value pairs
+    pub fn from_pairs(pairs: &[(&str, &str)]) -> Self {
+        Self {
+            pairs: pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    /// Append a value for `key`
+    pub fn append(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.pairs.push((key.into(), value.into()));
+    }
+
+    /// First value for `key`
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.pairs
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// All values for `key`, in order
+    pub fn get_all(&self, key: &str) -> Vec<&str> {
+        self.pairs
+            .iter()
+            .filter(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+            .collect()
+    }
+
+    /// First value for `key` parsed as `T`
+    ///
+    /// Returns `None` if the key is missing or the value does not parse.
+    pub fn get_as<T: FromStr>(&self, key: &str) -> Option<T> {
+        self.get(key).and_then(|v| v.parse().ok())
+    }
+
+    /// All values for `key` that parse as `T`
+    pub fn get_all_as<T: FromStr>(&self, key: &str) -> Vec<T> {
+        self.get_all(key)
+            .into_iter()
+            .filter_map(|v| v.parse().ok())
+            .collect()
+    }
+
+    /// Whether `key` is present
+    pub fn contains(&self, key: &str) -> bool {
+        self.pairs.iter().any(|(k, _)| k == key)
+    }
+
+    /// Whether there are no parameters
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Iterate over all key/value pairs
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.pairs.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Encode as a query string without the leading `?`
+    pub fn to_query_string(&self) -> String {
+        self.pairs
+            .iter()
+            .map(|(k, v)| format!("{}={}", encode_component(k), encode_component(v)))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+/// Split a URL into its path, query (without `?`) and fragment (without `#`)
+pub fn split_url(url: &str) -> (&str, Option<&str>, Option<&str>) {
+    let (rest, fragment) = match url.split_once('#') {
+        Some((rest, fragment)) => (rest, Some(fragment)),
+        None => (url, None),
+    };
+    let (path, query) = match rest.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (rest, None),
+    };
+    (path, query, fragment)
+}
+
+/// Percent-encode a query component
+pub fn encode_component(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+/// Decode a percent-encoded query component (`+` is a space)
+pub fn decode_component(s: &str) -> String {
+    percent_decode(s, true)
+}
+
+/// Decode a percent-encoded path segment (`+` stays a literal `+`)
+pub fn decode_path_segment(s: &str) -> String {
+    percent_decode(s, false)
+}
+
+fn percent_decode(s: &str, plus_is_space: bool) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' if plus_is_space => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                match (hex_value(bytes[i + 1]), hex_value(bytes[i + 2])) {
+                    (Some(hi), Some(lo)) => {
+                        out.push(hi << 4 | lo);
+                        i += 2;
+                    }
+                    _ => out.push(b'%'),
+                }
+            }
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    match byte {
+        b'0'..=b'9' => Some(byte - b'0'),
+        b'a'..=b'f' => Some(byte - b'a' + 10),
+        b'A'..=b'F' => Some(byte - b'A' + 10),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_multi_valued() {
+        let query = Query::parse("?tag=a&tag=b&page=2");
+        assert_eq!(query.get("tag"), Some("a"));
+        assert_eq!(query.get_all("tag"), vec!["a", "b"]);
+        assert_eq!(query.get_as::<u32>("page"), Some(2));
+        assert_eq!(query.get_as::<u32>("tag"), None);
+        assert!(!query.contains("missing"));
+    }
+
+    #[test]
+    fn test_parse_decoding() {
+        let query = Query::parse("q=rust+wasm&path=%2Fa%2Fb&flag&bad=%zz");
+        assert_eq!(query.get("q"), Some("rust wasm"));
+        assert_eq!(query.get("path"), Some("/a/b"));
+        assert_eq!(query.get("flag"), Some(""));
+        assert_eq!(query.get("bad"), Some("%zz"));
+    }
+
+    #[test]
+    fn test_decode_path_segment_keeps_plus() {
+        assert_eq!(decode_path_segment("c++"), "c++");
+        assert_eq!(decode_path_segment("a%2Bb%20c"), "a+b c");
+        assert_eq!(decode_component("c++"), "c  ");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let query = Query::from_pairs(&[("q", "a b&c"), ("n", "1")]);
+        let encoded = query.to_query_string();
+        assert_eq!(encoded, "q=a%20b%26c&n=1");
+        assert_eq!(Query::parse(&encoded), query);
+    }
+
+    #[test]
+    fn test_split_url() {
+        assert_eq!(
+            split_url("/search?q=x#top"),
+            ("/search", Some("q=x"), Some("top"))
+        );
+        assert_eq!(split_url("/a#b?c"), ("/a", None, Some("b?c")));
+        assert_eq!(split_url("/plain"), ("/plain", None, None));
+    }
+}
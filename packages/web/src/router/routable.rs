@@ -0,0 +1,40 @@
+//! Typed routes.
+//!
+//! [`Routable`] is usually derived with `#[derive(Routable)]` from
+//! `tairitsu-macros`, so a typo in a route or a parameter name is a compile
+//! error instead of a `None` from [`Router::url_for`](super::Router::url_for).
+//!
+//! # Example
+//!
+//! ```ignore
+//! #[derive(Routable)]
+//! enum AppRoute {
+//!     #[route("/")]
+//!     Home,
+//!     #[route("/users/:id")]
+//!     User { id: u64 },
+//! }
+//!
+//! let view = match AppRoute::from_path(&current_path()) {
+//!     Some(AppRoute::Home) => rsx! { h1 { "Home" } },
+//!     Some(AppRoute::User { id }) => rsx! { h1 { "User {id}" } },
+//!     None => not_found(),
+//! };
+//!
+//! rsx! { Link { href: AppRoute::User { id: 7 }.to_path(), "Profile" } }
+//! ```
+
+/// A set of routes that can be parsed from and rendered back to a path.
+pub trait Routable: Sized {
+    /// Parse a path into a route
+    ///
+    /// Any query string or fragment is ignored. Returns `None` if no route
+    /// matches or a parameter fails to parse.
+    fn from_path(path: &str) -> Option<Self>;
+
+    /// Render the route back to its path
+    fn to_path(&self) -> String;
+
+    /// All route patterns, in declaration order
+    fn patterns() -> &'static [&'static str];
+}
@@ -37,7 +37,8 @@ impl RouteSegment {
     pub fn parse_path(path: &str) -> Vec<Self> {
         let mut segments = Vec::new();
 
-        // Skip leading slash and split
+        // Ignore query and fragment, skip leading slash and split
+        let (path, _, _) = super::split_url(path);
         let path = path.trim_start_matches('/');
         let path = path.trim_end_matches('/');
 
@@ -160,6 +161,13 @@ mod tests {
         assert_eq!(segments[3].segment_type, SegmentType::Dynamic);
     }
 
+    #[test]
+    fn test_parse_path_ignores_query_and_fragment() {
+        let segments = RouteSegment::parse_path("/search/?q=a/b#top");
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].raw, "search");
+    }
+
     #[test]
     fn test_static_segment_matches() {
         let segment = RouteSegment {
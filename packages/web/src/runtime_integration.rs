@@ -8,7 +8,10 @@ use std::{cell::RefCell, rc::Rc};
 
 use tairitsu_vdom::Patch;
 
-use crate::WitElement;
+use crate::{
+    hydration::{HydrationReport, MountMode},
+    WitElement,
+};
 
 /// Initialize the reactive runtime with web platform callbacks.
 ///
@@ -104,6 +107,7 @@ pub fn init_runtime(root_element: WitElement) {
 #[cfg(feature = "wit-bindings")]
 pub struct ComponentRenderer {
     root_element: Rc<RefCell<WitElement>>,
+    mode: MountMode,
 }
 
 #[cfg(feature = "wit-bindings")]
@@ -116,9 +120,24 @@ impl ComponentRenderer {
     pub fn new(root_element: WitElement) -> Self {
         Self {
             root_element: Rc::new(RefCell::new(root_element)),
+            mode: MountMode::default(),
         }
     }
 
+    /// Set how [`mount`](Self::mount) attaches the tree.
+    ///
+    /// Use [`MountMode::Hydrate`] when `#app` already contains markup
+    /// produced by `tairitsu_ssr::render_to_html`.
+    pub fn with_mode(mut self, mode: MountMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Return the configured mount mode.
+    pub fn mode(&self) -> MountMode {
+        self.mode
+    }
+
     /// Initialize the runtime with this renderer's root element.
     pub fn init_runtime(&self) {
         let root = *self.root_element.borrow();
@@ -126,7 +145,15 @@ impl ComponentRenderer {
     }
 
     /// Mount a VNode to the root element.
+    ///
+    /// In [`MountMode::Hydrate`] the existing DOM is adopted instead of
+    /// replaced; mismatches are logged. Use [`hydrate`](Self::hydrate) to
+    /// inspect them.
     pub fn mount(&self, vnode: tairitsu_vdom::VNode) -> Result<()> {
+        if self.mode == MountMode::Hydrate {
+            return self.hydrate(vnode).map(|_| ());
+        }
+
         #[cfg(target_family = "wasm")]
         {
             if let Ok(platform) = crate::WitPlatform::new() {
@@ -141,6 +168,26 @@ impl ComponentRenderer {
             anyhow::bail!("mount is only available on wasm32 targets")
         }
     }
+
+    /// Hydrate server-rendered markup under the root element.
+    ///
+    /// Adopts existing DOM nodes, attaches event listeners and reactive
+    /// effects, and returns a report of any mismatches that were repaired.
+    pub fn hydrate(&self, vnode: tairitsu_vdom::VNode) -> Result<HydrationReport> {
+        #[cfg(target_family = "wasm")]
+        {
+            if let Ok(platform) = crate::WitPlatform::new() {
+                platform.hydrate_vnode_to_app(vnode)
+            } else {
+                anyhow::bail!("Failed to create platform for hydration")
+            }
+        }
+        #[cfg(not(target_family = "wasm"))]
+        {
+            let _ = vnode;
+            anyhow::bail!("hydrate is only available on wasm32 targets")
+        }
+    }
 }
 
 #[cfg(test)]
@@ -154,4 +201,13 @@ mod tests {
         // In a real environment, this would require an actual WASM context
         // For now, we just test that the function compiles
     }
+
+    #[test]
+    fn test_component_renderer_mode() {
+        let renderer = ComponentRenderer::new(WitElement::from_raw(1));
+        assert_eq!(renderer.mode(), MountMode::Replace);
+
+        let renderer = renderer.with_mode(MountMode::Hydrate);
+        assert_eq!(renderer.mode(), MountMode::Hydrate);
+    }
 }
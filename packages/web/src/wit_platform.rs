@@ -155,6 +155,24 @@ impl WitPlatform {
         }
     }
 
+    /// Hydrate server-rendered markup inside `#app` with a VNode tree.
+    ///
+    /// Unlike [`mount_vnode_to_app`](Self::mount_vnode_to_app) this keeps the
+    /// existing DOM, adopting its nodes and attaching event listeners and
+    /// reactive effects. Mismatches are repaired and returned in the report.
+    pub fn hydrate_vnode_to_app(
+        &self,
+        _vnode: tairitsu_vdom::VNode,
+    ) -> Result<crate::hydration::HydrationReport> {
+        #[cfg(not(target_family = "wasm"))]
+        anyhow::bail!("hydrate_vnode_to_app is only available on wasm32 targets (wasm32-wasip2)");
+
+        #[cfg(target_family = "wasm")]
+        {
+            wasm_impl::hydrate_vnode_to_app(self, _vnode)
+        }
+    }
+
     /// Apply a list of patches to update the DOM.
     ///
     /// This method applies patches generated by the diff algorithm to update
@@ -276,6 +294,9 @@ pub mod wasm_impl {
     };
 
     use super::{WitElement, WitEvent, WitPlatform};
+    use crate::hydration::{
+        hydrate_children, DomNodeKind, HydrationDom, HydrationReport, MismatchKind,
+    };
 
     thread_local! {
         static CURRENT_RENDER_COMPONENT: RefCell<Option<tairitsu_vdom::ComponentId>> = RefCell::new(None);
@@ -502,6 +523,18 @@ pub mod wasm_impl {
         bindings::tairitsu_browser::full::history::replace_state("", "", Some(url));
     }
 
+    pub fn wasm_get_search() -> String {
+        bindings::tairitsu_browser::full::location::get_search()
+    }
+
+    pub fn wasm_get_hash() -> String {
+        bindings::tairitsu_browser::full::location::get_hash()
+    }
+
+    pub fn wasm_get_origin() -> String {
+        bindings::tairitsu_browser::full::location::get_origin()
+    }
+
     // -- Component export implementation ---------------------------------
 
     /// Implements the `event-callbacks` WIT export interface.
@@ -616,6 +649,18 @@ pub mod wasm_impl {
                     evt.target = target;
                     Box::new(evt)
                 }
+                "storage" => {
+                    use bindings::tairitsu_browser::full::{storage_event, window_session_storage};
+                    let area = storage_event::get_storage_area(event_handle);
+                    Box::new(tairitsu_vdom::StorageEvent {
+                        key: storage_event::get_key(event_handle),
+                        old_value: storage_event::get_old_value(event_handle),
+                        new_value: storage_event::get_new_value(event_handle),
+                        url: storage_event::get_url(event_handle),
+                        session: area
+                            == Some(window_session_storage::get_session_storage(WINDOW_TARGET)),
+                    })
+                }
                 _ => Box::new(
                     GenericEvent::new()
                         .event_type(&event_type)
@@ -939,6 +984,50 @@ pub mod wasm_impl {
         }
     }
 
+    /// Handle the browser glue resolves to the global `window`.
+    const WINDOW_TARGET: u64 = 0;
+
+    /// Back `tairitsu_hooks::LocalStorage` with the browser's `localStorage`.
+    #[cfg(feature = "hooks")]
+    fn register_local_storage() {
+        use bindings::tairitsu_browser::full::{storage, window_local_storage};
+
+        fn local_storage() -> u64 {
+            window_local_storage::get_local_storage(WINDOW_TARGET)
+        }
+
+        tairitsu_hooks::register_local_storage(tairitsu_hooks::LocalStorageFuncs {
+            get_item: |key| storage::get_item(local_storage(), key),
+            set_item: |key, value| storage::set_item(local_storage(), key, value),
+            remove_item: |key| storage::remove_item(local_storage(), key),
+            listen: |handler| {
+                let listener_id =
+                    bindings::tairitsu_browser::full::event_target::add_event_listener(
+                        WINDOW_TARGET,
+                        "storage",
+                        false,
+                    )
+                    .map_err(|e| anyhow::anyhow!("add_event_listener failed: {}", e))?;
+                EVENT_CALLBACKS.with(|m| {
+                    m.borrow_mut().insert(
+                        listener_id,
+                        Box::new(move |event: Box<dyn EventData>| {
+                            // sessionStorage changes fire the same event.
+                            if let Some(event) =
+                                event.as_any().downcast_ref::<tairitsu_vdom::StorageEvent>()
+                            {
+                                if !event.session {
+                                    handler(event);
+                                }
+                            }
+                        }),
+                    );
+                });
+                Ok(())
+            },
+        });
+    }
+
     /// Register WIT functions for use in event handlers.
     #[cfg(target_family = "wasm")]
     fn register_dom_ops_functions() {
@@ -1074,6 +1163,9 @@ pub mod wasm_impl {
             any.downcast_ref::<super::WitElement>().map(|w| w.as_raw())
         });
 
+        #[cfg(feature = "hooks")]
+        register_local_storage();
+
         log_info("DOM operations functions registered for event handlers");
     }
 
@@ -1882,6 +1974,7 @@ pub mod wasm_impl {
             &self,
             name: &str,
             version: Option<u64>,
+            store_name: Option<&str>,
             on_complete: Box<dyn FnOnce(Result<u64, String>)>,
         ) -> u64 {
             let callback_id = next_callback_id();
@@ -1893,7 +1986,12 @@ pub mod wasm_impl {
                     }),
                 );
             });
-            bindings::tairitsu_browser::full::platform_helpers::idb_open(name, version, callback_id)
+            bindings::tairitsu_browser::full::platform_helpers::idb_open(
+                name,
+                version,
+                store_name,
+                callback_id,
+            )
         }
 
         fn idb_put(
@@ -2023,6 +2121,15 @@ pub mod wasm_impl {
     }
 
     pub(super) fn mount_vnode_to_app(platform: &WitPlatform, vnode: VNode) -> Result<()> {
+        let app = app_element()?;
+
+        bindings::tairitsu_browser::full::node::set_text_content(app.as_raw(), Some(""));
+
+        render_vnode(platform, &vnode, &app)
+    }
+
+    /// The `#app` element, created under `<body>` when it is missing.
+    pub(crate) fn app_element() -> Result<WitElement> {
         let doc_handle: u64 = 0;
 
         let app = if let Some(handle) =
@@ -2038,10 +2145,215 @@ pub mod wasm_impl {
             let _ = bindings::tairitsu_browser::full::node::append_child(body, div);
             WitElement::from_raw(div)
         };
+        Ok(app)
+    }
 
-        bindings::tairitsu_browser::full::node::set_text_content(app.as_raw(), Some(""));
+    const ELEMENT_NODE: u16 = 1;
+    const TEXT_NODE: u16 = 3;
 
-        render_vnode(platform, &vnode, &app)
+    /// Hydrate the server-rendered markup inside `#app` against `vnode`.
+    ///
+    /// Existing DOM nodes are adopted in place; mismatches are repaired and
+    /// returned in the report. Falls back to a full mount when `#app` is absent.
+    pub(super) fn hydrate_vnode_to_app(
+        platform: &WitPlatform,
+        vnode: VNode,
+    ) -> Result<HydrationReport> {
+        let mut report = HydrationReport::default();
+
+        let Some(app) =
+            bindings::tairitsu_browser::full::non_element_parent_node::get_element_by_id(0, "app")
+                .map(WitElement::from_raw)
+        else {
+            report.push(
+                "",
+                MismatchKind::MissingNode {
+                    expected: "#app".to_string(),
+                },
+            );
+            mount_vnode_to_app(platform, vnode)?;
+            return Ok(report);
+        };
+
+        let dom = BrowserDom { platform };
+        hydrate_children(&dom, &app, std::slice::from_ref(&vnode), "", &mut report)?;
+
+        if !report.is_clean() {
+            log_warning(&format!(
+                "Hydration finished with {} mismatch(es)",
+                report.mismatches.len()
+            ));
+        }
+
+        Ok(report)
+    }
+
+    /// [`HydrationDom`] over the browser DOM.
+    struct BrowserDom<'a> {
+        platform: &'a WitPlatform,
+    }
+
+    impl HydrationDom for BrowserDom<'_> {
+        type Node = WitElement;
+
+        fn first_child(&self, parent: &WitElement) -> Option<WitElement> {
+            bindings::tairitsu_browser::full::node::get_first_child(parent.as_raw())
+                .map(WitElement::from_raw)
+        }
+
+        fn next_sibling(&self, node: &WitElement) -> Option<WitElement> {
+            bindings::tairitsu_browser::full::node::get_next_sibling(node.as_raw())
+                .map(WitElement::from_raw)
+        }
+
+        fn node_kind(&self, node: &WitElement) -> DomNodeKind {
+            let handle = node.as_raw();
+            match bindings::tairitsu_browser::full::node::get_node_type(handle) {
+                ELEMENT_NODE => DomNodeKind::Element(
+                    bindings::tairitsu_browser::full::element::get_tag_name(handle).to_lowercase(),
+                ),
+                TEXT_NODE => DomNodeKind::Text(
+                    bindings::tairitsu_browser::full::node::get_node_value(handle)
+                        .unwrap_or_default(),
+                ),
+                _ => DomNodeKind::Other(bindings::tairitsu_browser::full::node::get_node_name(
+                    handle,
+                )),
+            }
+        }
+
+        fn get_attribute(&self, element: &WitElement, name: &str) -> Option<String> {
+            self.platform.get_attribute(element, name)
+        }
+
+        fn set_attribute(&self, element: &WitElement, name: &str, value: &str) {
+            self.platform.set_attribute(element, name, value);
+        }
+
+        fn set_text(&self, text_node: &WitElement, text: &str) {
+            bindings::tairitsu_browser::full::node::set_node_value(text_node.as_raw(), Some(text));
+        }
+
+        fn insert_text(
+            &self,
+            parent: &WitElement,
+            text: &str,
+            reference: &WitElement,
+        ) -> WitElement {
+            let text_node = self.platform.create_text_node(text);
+            self.platform
+                .insert_before(parent, &text_node, Some(reference));
+            text_node
+        }
+
+        fn append_vnode(&self, parent: &WitElement, vnode: &VNode) -> Result<()> {
+            render_vnode(self.platform, vnode, parent)
+        }
+
+        fn replace_with_vnode(
+            &self,
+            parent: &WitElement,
+            old: &WitElement,
+            vnode: &VNode,
+        ) -> Result<()> {
+            let new_node = create_vnode_element(self.platform, vnode)?;
+            if let VNode::Element(velement) = vnode {
+                if let Some(ref inner) = velement.inner_html {
+                    self.platform.set_inner_html(&new_node, inner.clone());
+                } else {
+                    for child in &velement.children {
+                        render_vnode(self.platform, child, &new_node)?;
+                    }
+                }
+            }
+            self.platform.insert_before(parent, &new_node, Some(old));
+            self.remove(parent, old);
+            Ok(())
+        }
+
+        fn remove(&self, parent: &WitElement, node: &WitElement) {
+            tairitsu_vdom::runtime::on_element_removed(node.as_raw());
+            self.platform.remove_child(parent, node);
+        }
+
+        fn bind_element(&self, velement: &tairitsu_vdom::VElement, element: &WitElement) {
+            bind_element(self.platform, velement, element);
+        }
+
+        fn bind_text(&self, text_node: &WitElement, text: &tairitsu_vdom::DynamicText) {
+            bind_dynamic_text(text_node, text);
+        }
+    }
+
+    /// Wire a `VElement` to its DOM element: runtime registration, ref,
+    /// listeners and the effects behind dynamic attributes, styles and
+    /// classes.
+    ///
+    /// Shared by fresh renders, patch-created elements and hydration.
+    fn bind_element(
+        platform: &WitPlatform,
+        velement: &tairitsu_vdom::VElement,
+        element: &WitElement,
+    ) {
+        tairitsu_vdom::runtime::register_element(element.as_raw());
+
+        if let Some(ref element_ref) = velement.element_ref {
+            use std::any::Any;
+            let mut ref_mut = element_ref.borrow_mut();
+            *ref_mut = Some(Box::new(element.clone()) as Box<dyn Any>);
+        }
+
+        for (event_name, handler) in &velement.event_handlers {
+            let handler = handler.clone();
+            platform.add_event_listener(
+                element,
+                event_name,
+                Box::new(move |event| {
+                    (handler.borrow_mut())(event);
+                }),
+            );
+        }
+
+        for (name, compute) in &velement.dynamic_attributes {
+            let raw = element.as_raw();
+            let name = name.clone();
+            let compute = compute.clone();
+            create_tracked_effect(move || {
+                let value = (compute.borrow_mut())();
+                bindings::tairitsu_browser::full::element::set_attribute(raw, &name, &value);
+            });
+        }
+
+        for (name, compute) in &velement.dynamic_styles {
+            let raw = element.as_raw();
+            let name = name.clone();
+            let compute = compute.clone();
+            let platform = platform.clone();
+            create_tracked_effect(move || {
+                let value = (compute.borrow_mut())();
+                let el = WitElement::from_raw(raw);
+                platform.set_style(&el, &name, &value);
+            });
+        }
+
+        for compute in &velement.dynamic_classes {
+            let raw = element.as_raw();
+            let compute = compute.clone();
+            create_tracked_effect(move || {
+                let value = (compute.borrow_mut())();
+                bindings::tairitsu_browser::full::element::set_attribute(raw, "class", &value);
+            });
+        }
+    }
+
+    /// Keep a text node in sync with its `DynamicText`.
+    fn bind_dynamic_text(text_node: &WitElement, text: &tairitsu_vdom::DynamicText) {
+        let raw = text_node.as_raw();
+        let compute = text.compute.clone();
+        create_tracked_effect(move || {
+            let new_text = (compute.borrow_mut())();
+            bindings::tairitsu_browser::full::node::set_text_content(raw, Some(&new_text));
+        });
     }
 
     fn render_vnode(platform: &WitPlatform, vnode: &VNode, parent: &WitElement) -> Result<()> {
@@ -2049,16 +2361,6 @@ pub mod wasm_impl {
             VNode::Element(velement) => {
                 let element = platform.create_element(&velement.tag);
 
-                tairitsu_vdom::runtime::register_element(element.as_raw());
-
-                // Populate element_ref if present
-                if let Some(ref element_ref) = velement.element_ref {
-                    use std::any::Any;
-                    // Store the WitElement in the ref
-                    let mut ref_mut = element_ref.borrow_mut();
-                    *ref_mut = Some(Box::new(element.clone()) as Box<dyn Any>);
-                }
-
                 for (name, value) in &velement.attributes {
                     platform.set_attribute(&element, name, value);
                 }
@@ -2083,17 +2385,6 @@ pub mod wasm_impl {
                     platform.set_style(&element, name, value);
                 }
 
-                for (event_name, handler) in &velement.event_handlers {
-                    let handler = handler.clone();
-                    platform.add_event_listener(
-                        &element,
-                        event_name,
-                        Box::new(move |event| {
-                            (handler.borrow_mut())(event);
-                        }),
-                    );
-                }
-
                 if let Some(ref inner) = velement.inner_html {
                     platform.set_inner_html(&element, inner.clone());
                 } else {
@@ -2102,40 +2393,9 @@ pub mod wasm_impl {
                     }
                 }
 
-                for (name, compute) in &velement.dynamic_attributes {
-                    let raw = element.as_raw();
-                    let name = name.clone();
-                    let compute = compute.clone();
-                    create_tracked_effect(move || {
-                        let value = (compute.borrow_mut())();
-                        bindings::tairitsu_browser::full::element::set_attribute(
-                            raw, &name, &value,
-                        );
-                    });
-                }
-
-                for (name, compute) in &velement.dynamic_styles {
-                    let raw = element.as_raw();
-                    let name = name.clone();
-                    let compute = compute.clone();
-                    let platform = platform.clone();
-                    create_tracked_effect(move || {
-                        let value = (compute.borrow_mut())();
-                        let el = WitElement::from_raw(raw);
-                        platform.set_style(&el, &name, &value);
-                    });
-                }
-
-                for compute in &velement.dynamic_classes {
-                    let raw = element.as_raw();
-                    let compute = compute.clone();
-                    create_tracked_effect(move || {
-                        let value = (compute.borrow_mut())();
-                        bindings::tairitsu_browser::full::element::set_attribute(
-                            raw, "class", &value,
-                        );
-                    });
-                }
+                // Dynamic attributes (e.g. a `<select>`'s `value`) may
+                // depend on the children, so bind after rendering them.
+                bind_element(platform, velement, &element);
 
                 platform.append_child(parent, &element);
             }
@@ -2147,12 +2407,7 @@ pub mod wasm_impl {
                 let text_node = platform.create_text_node(&dt.initial);
                 platform.append_child(parent, &text_node);
 
-                let raw = text_node.as_raw();
-                let compute = dt.compute.clone();
-                create_tracked_effect(move || {
-                    let new_text = (compute.borrow_mut())();
-                    bindings::tairitsu_browser::full::node::set_text_content(raw, Some(&new_text));
-                });
+                bind_dynamic_text(&text_node, dt);
             }
             VNode::Fragment(children) => {
                 for child in children {
@@ -2311,15 +2566,6 @@ pub mod wasm_impl {
             VNode::Element(velement) => {
                 let element = platform.create_element(&velement.tag);
 
-                tairitsu_vdom::runtime::register_element(element.as_raw());
-
-                // Populate element_ref if present (for patch-created elements)
-                if let Some(ref element_ref) = velement.element_ref {
-                    use std::any::Any;
-                    let mut ref_mut = element_ref.borrow_mut();
-                    *ref_mut = Some(Box::new(element.clone()) as Box<dyn Any>);
-                }
-
                 for (name, value) in &velement.attributes {
                     platform.set_attribute(&element, name, value);
                 }
@@ -2330,51 +2576,7 @@ pub mod wasm_impl {
 
                 apply_style(platform, &element, &velement.style)?;
 
-                for (event_name, handler) in &velement.event_handlers {
-                    let handler = handler.clone();
-                    platform.add_event_listener(
-                        &element,
-                        event_name,
-                        Box::new(move |event| {
-                            (handler.borrow_mut())(event);
-                        }),
-                    );
-                }
-
-                for (name, compute) in &velement.dynamic_attributes {
-                    let raw = element.as_raw();
-                    let name = name.clone();
-                    let compute = compute.clone();
-                    create_tracked_effect(move || {
-                        let value = (compute.borrow_mut())();
-                        bindings::tairitsu_browser::full::element::set_attribute(
-                            raw, &name, &value,
-                        );
-                    });
-                }
-
-                for (name, compute) in &velement.dynamic_styles {
-                    let raw = element.as_raw();
-                    let name = name.clone();
-                    let compute = compute.clone();
-                    let p = platform.clone();
-                    create_tracked_effect(move || {
-                        let value = (compute.borrow_mut())();
-                        let el = WitElement::from_raw(raw);
-                        p.set_style(&el, &name, &value);
-                    });
-                }
-
-                for compute in &velement.dynamic_classes {
-                    let raw = element.as_raw();
-                    let compute = compute.clone();
-                    create_tracked_effect(move || {
-                        let value = (compute.borrow_mut())();
-                        bindings::tairitsu_browser::full::element::set_attribute(
-                            raw, "class", &value,
-                        );
-                    });
-                }
+                bind_element(platform, velement, &element);
 
                 Ok(element)
             }
@@ -2382,12 +2584,7 @@ pub mod wasm_impl {
             VNode::DynamicText(dt) => {
                 let text_node = platform.create_text_node(&dt.initial);
 
-                let raw = text_node.as_raw();
-                let compute = dt.compute.clone();
-                create_tracked_effect(move || {
-                    let new_text = (compute.borrow_mut())();
-                    bindings::tairitsu_browser::full::node::set_text_content(raw, Some(&new_text));
-                });
+                bind_dynamic_text(&text_node, dt);
 
                 Ok(text_node)
             }
@@ -2581,6 +2778,42 @@ pub fn get_pathname() -> String {
     }
 }
 
+/// Get the current URL query string including the leading `?` (empty if none).
+pub fn get_search() -> String {
+    #[cfg(not(target_family = "wasm"))]
+    {
+        String::new()
+    }
+    #[cfg(all(feature = "wit-bindings", target_family = "wasm"))]
+    {
+        wasm_impl::wasm_get_search()
+    }
+}
+
+/// Get the current URL fragment including the leading `#` (empty if none).
+pub fn get_hash() -> String {
+    #[cfg(not(target_family = "wasm"))]
+    {
+        String::new()
+    }
+    #[cfg(all(feature = "wit-bindings", target_family = "wasm"))]
+    {
+        wasm_impl::wasm_get_hash()
+    }
+}
+
+/// Get the origin of the current page (e.g., `https://example.com`).
+pub fn get_origin() -> String {
+    #[cfg(not(target_family = "wasm"))]
+    {
+        String::new()
+    }
+    #[cfg(all(feature = "wit-bindings", target_family = "wasm"))]
+    {
+        wasm_impl::wasm_get_origin()
+    }
+}
+
 /// Push a new URL onto the browser history stack (client-side navigation).
 pub fn push_state(url: &str) {
     #[cfg(all(feature = "wit-bindings", target_family = "wasm"))]
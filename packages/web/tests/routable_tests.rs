@@ -0,0 +1,107 @@
+#![cfg(all(feature = "router", feature = "macros"))]
+
+use tairitsu_macros::Routable;
+use tairitsu_web::router::Routable;
+
+#[derive(Debug, PartialEq, Routable)]
+enum AppRoute {
+    #[route("/")]
+    Home,
+    #[route("/users/:id")]
+    User { id: u64 },
+    #[route("/posts/:slug/comments/:comment")]
+    Comment { slug: String, comment: u32 },
+    #[route("/files/:name")]
+    File { name: String },
+}
+
+#[test]
+fn test_routable_from_path() {
+    assert_eq!(AppRoute::from_path("/"), Some(AppRoute::Home));
+    assert_eq!(
+        AppRoute::from_path("/users/42"),
+        Some(AppRoute::User { id: 42 })
+    );
+    assert_eq!(
+        AppRoute::from_path("/posts/hello/comments/3"),
+        Some(AppRoute::Comment {
+            slug: "hello".to_string(),
+            comment: 3
+        })
+    );
+    assert_eq!(
+        AppRoute::from_path("/files/readme"),
+        Some(AppRoute::File {
+            name: "readme".to_string()
+        })
+    );
+}
+
+#[test]
+fn test_routable_rejects_bad_params() {
+    assert_eq!(AppRoute::from_path("/users/abc"), None);
+    assert_eq!(AppRoute::from_path("/users"), None);
+    assert_eq!(AppRoute::from_path("/unknown"), None);
+}
+
+#[test]
+fn test_routable_ignores_query_and_fragment() {
+    assert_eq!(
+        AppRoute::from_path("/users/7/?tab=posts#top"),
+        Some(AppRoute::User { id: 7 })
+    );
+}
+
+#[test]
+fn test_routable_to_path_round_trip() {
+    let routes = [
+        AppRoute::Home,
+        AppRoute::User { id: 42 },
+        AppRoute::Comment {
+            slug: "hello".to_string(),
+            comment: 3,
+        },
+        AppRoute::File {
+            name: "readme".to_string(),
+        },
+    ];
+    for route in routes {
+        assert_eq!(AppRoute::from_path(&route.to_path()), Some(route));
+    }
+    assert_eq!(AppRoute::User { id: 42 }.to_path(), "/users/42");
+}
+
+#[test]
+fn test_routable_params_with_reserved_characters_round_trip() {
+    let route = AppRoute::Comment {
+        slug: "a/b?c#d e%f+g".to_string(),
+        comment: 1,
+    };
+    let path = route.to_path();
+    assert_eq!(path, "/posts/a%2Fb%3Fc%23d%20e%25f%2Bg/comments/1");
+    assert_eq!(AppRoute::from_path(&path), Some(route));
+}
+
+#[test]
+fn test_routable_keeps_plus_in_path_segments() {
+    assert_eq!(
+        AppRoute::from_path("/posts/c++/comments/2"),
+        Some(AppRoute::Comment {
+            slug: "c++".to_string(),
+            comment: 2
+        })
+    );
+}
+
+#[test]
+fn test_routable_patterns() {
+    assert_eq!(
+        AppRoute::patterns(),
+        &[
+            "/",
+            "/users/:id",
+            "/posts/:slug/comments/:comment",
+            "/files/:name"
+        ]
+    );
+}